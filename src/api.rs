@@ -1,129 +1,668 @@
 use anyhow::{Context, Result};
+use colored::Colorize;
 use reqwest::Client;
+use serde::Serialize;
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
 
-use crate::config;
+use crate::config::{self, Credential};
+use crate::oauth;
+use crate::retry::{self, FailureKind, RetryPolicy};
 
+/// Default number of nodes requested per page
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Default GraphQL endpoint, overridable via `LINEAR_API_URL` or a workspace's `api_url`
 const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
 
-/// Resolves a team key (like "SCW") or name to a team UUID.
-/// If the input is already a UUID (36 characters with dashes), returns it as-is.
-pub async fn resolve_team_id(client: &LinearClient, team: &str) -> Result<String> {
-    // If already a UUID (36 chars with dashes pattern), return as-is
-    if team.len() == 36 && team.chars().filter(|c| *c == '-').count() == 4 {
-        return Ok(team.to_string());
-    }
-
-    // Query to find team by key or name
-    let query = r#"
-        query {
-            teams(first: 100) {
-                nodes {
-                    id
-                    key
-                    name
-                }
+/// Controls how many nodes a paginated query fetches
+#[derive(Debug, Clone, Copy)]
+pub struct PageOptions {
+    /// Maximum number of nodes to fetch in total (`None` fetches every page)
+    pub limit: Option<usize>,
+    /// Number of nodes requested per page
+    pub page_size: usize,
+}
+
+impl PageOptions {
+    /// Fetch at most `limit` nodes
+    pub fn limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Fetch every page
+    pub fn all() -> Self {
+        Self {
+            limit: None,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Fetch every page when `all` is set, otherwise at most `limit` nodes
+    pub fn new(limit: usize, all: bool) -> Self {
+        if all {
+            Self::all()
+        } else {
+            Self::limit(limit)
+        }
+    }
+}
+
+/// Process exit codes for each kind of failure. 1 is any other error, 2 is a usage error.
+pub mod exit_code {
+    pub const AUTH: i32 = 3;
+    pub const NOT_FOUND: i32 = 4;
+    pub const VALIDATION: i32 = 5;
+    pub const RATE_LIMITED: i32 = 6;
+    pub const NETWORK: i32 = 7;
+    pub const SERVER: i32 = 8;
+}
+
+/// A problem with one field or argument of a request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Dotted path to the offending field, e.g. "issueCreate.input.title"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+}
+
+/// Errors returned by the Linear API, classified so callers and scripts can branch on them
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinearError {
+    /// Missing, invalid or insufficiently privileged API key
+    Auth { message: String },
+    /// The requested entity doesn't exist (or isn't visible to this key)
+    NotFound { message: String },
+    /// The request was rejected; `errors` points at the offending fields
+    Validation {
+        message: String,
+        errors: Vec<FieldError>,
+    },
+    /// Too many requests; `retry_after` is when the limit resets, if known
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// The request didn't complete. `connect` is set when it never reached the server.
+    Network { message: String, connect: bool },
+    /// Linear failed to handle the request
+    Server {
+        status: Option<u16>,
+        message: String,
+    },
+}
+
+impl LinearError {
+    /// Short machine-readable name, used in JSON error output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LinearError::Auth { .. } => "auth",
+            LinearError::NotFound { .. } => "not_found",
+            LinearError::Validation { .. } => "validation",
+            LinearError::RateLimited { .. } => "rate_limited",
+            LinearError::Network { .. } => "network",
+            LinearError::Server { .. } => "server",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LinearError::Auth { .. } => exit_code::AUTH,
+            LinearError::NotFound { .. } => exit_code::NOT_FOUND,
+            LinearError::Validation { .. } => exit_code::VALIDATION,
+            LinearError::RateLimited { .. } => exit_code::RATE_LIMITED,
+            LinearError::Network { .. } => exit_code::NETWORK,
+            LinearError::Server { .. } => exit_code::SERVER,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            LinearError::Auth { message }
+            | LinearError::NotFound { message }
+            | LinearError::Validation { message, .. }
+            | LinearError::RateLimited { message, .. }
+            | LinearError::Network { message, .. }
+            | LinearError::Server { message, .. } => message,
+        }
+    }
+
+    /// How the retry loop should treat this error
+    pub fn failure_kind(&self) -> FailureKind {
+        match self {
+            LinearError::RateLimited { .. } => FailureKind::RateLimited,
+            LinearError::Network { connect: true, .. } => FailureKind::Connect,
+            LinearError::Network { .. } => FailureKind::Server,
+            LinearError::Server { status, .. } => match status {
+                Some(status) => FailureKind::from_status(*status),
+                None => FailureKind::Server,
+            },
+            _ => FailureKind::Permanent,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            LinearError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Structured form for `--output json`
+    pub fn to_json(&self) -> Value {
+        let mut error = json!({
+            "kind": self.kind(),
+            "message": self.message(),
+            "exitCode": self.exit_code(),
+        });
+        match self {
+            LinearError::Validation { errors, .. } if !errors.is_empty() => {
+                error["errors"] = json!(errors);
+            }
+            LinearError::RateLimited {
+                retry_after: Some(wait),
+                ..
+            } => {
+                error["retryAfterSeconds"] = json!(wait.as_secs_f64());
             }
+            LinearError::Server {
+                status: Some(status),
+                ..
+            } => {
+                error["status"] = json!(status);
+            }
+            _ => {}
         }
-    "#;
+        json!({ "error": error })
+    }
 
-    let result = client.query(query, None).await?;
-    let empty = vec![];
-    let teams = result["data"]["teams"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    fn from_reqwest(err: reqwest::Error) -> Self {
+        let message = if err.is_connect() {
+            format!("Failed to connect to Linear API: {}", err)
+        } else if err.is_timeout() {
+            format!("Request to Linear API timed out: {}", err)
+        } else {
+            err.to_string()
+        };
+        LinearError::Network {
+            message,
+            connect: err.is_connect(),
+        }
+    }
 
-    // First try exact key match (case-insensitive)
-    if let Some(team_data) = teams
-        .iter()
-        .find(|t| t["key"].as_str().map(|k| k.eq_ignore_ascii_case(team)) == Some(true))
-    {
-        if let Some(id) = team_data["id"].as_str() {
-            return Ok(id.to_string());
+    /// Classify a non-success HTTP status without a GraphQL error body
+    fn from_status(status: u16, retry_after: Option<Duration>) -> Self {
+        let reason = reqwest::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("Unknown error");
+        match status {
+            401 => LinearError::Auth {
+                message: "Authentication failed - check your API key".to_string(),
+            },
+            403 => LinearError::Auth {
+                message: "Access denied".to_string(),
+            },
+            404 => LinearError::NotFound {
+                message: "Not found".to_string(),
+            },
+            429 => LinearError::RateLimited {
+                message: "Rate limited by Linear API".to_string(),
+                retry_after,
+            },
+            400..=499 => LinearError::Validation {
+                message: format!("HTTP {} {}", status, reason),
+                errors: Vec::new(),
+            },
+            _ => LinearError::Server {
+                status: Some(status),
+                message: format!("HTTP {} {}", status, reason),
+            },
         }
     }
 
-    // Then try exact name match (case-insensitive)
-    if let Some(team_data) = teams
-        .iter()
-        .find(|t| t["name"].as_str().map(|n| n.eq_ignore_ascii_case(team)) == Some(true))
-    {
-        if let Some(id) = team_data["id"].as_str() {
-            return Ok(id.to_string());
+    /// Classify the `errors` array of a GraphQL response
+    pub fn from_graphql(
+        errors: &Value,
+        status: Option<u16>,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        let empty = vec![];
+        let errors = errors.as_array().unwrap_or(&empty);
+
+        let message = errors
+            .iter()
+            .map(|e| {
+                e["extensions"]["userPresentableMessage"]
+                    .as_str()
+                    .or_else(|| e["message"].as_str())
+                    .unwrap_or("Unknown error")
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        let message = if message.is_empty() {
+            "Unknown GraphQL error".to_string()
+        } else {
+            message
+        };
+
+        let codes: Vec<String> = errors
+            .iter()
+            .filter_map(|e| {
+                e["extensions"]["code"]
+                    .as_str()
+                    .or_else(|| e["extensions"]["type"].as_str())
+            })
+            .map(|c| c.to_ascii_uppercase().replace(' ', "_"))
+            .collect();
+        let has_code = |wanted: &[&str]| codes.iter().any(|c| wanted.contains(&c.as_str()));
+
+        if has_code(&["RATELIMITED", "RATE_LIMITED"]) || status == Some(429) {
+            return LinearError::RateLimited {
+                message,
+                retry_after,
+            };
+        }
+        if has_code(&[
+            "AUTHENTICATION_ERROR",
+            "UNAUTHENTICATED",
+            "FORBIDDEN",
+            "AUTHORIZATION_ERROR",
+        ]) || matches!(status, Some(401) | Some(403))
+        {
+            return LinearError::Auth { message };
+        }
+        if has_code(&["NOT_FOUND", "ENTITY_NOT_FOUND"])
+            || message.to_ascii_lowercase().contains("not found")
+        {
+            return LinearError::NotFound { message };
+        }
+        if let Some(status) = status.filter(|s| *s >= 500) {
+            return LinearError::Server {
+                status: Some(status),
+                message,
+            };
+        }
+        if has_code(&["INTERNAL_SERVER_ERROR", "INTERNAL_ERROR"]) {
+            return LinearError::Server {
+                status: None,
+                message,
+            };
+        }
+
+        let field_errors = errors
+            .iter()
+            .map(|e| {
+                let mut path: Vec<String> = e["path"]
+                    .as_array()
+                    .map(|p| {
+                        p.iter()
+                            .map(|seg| match seg.as_str() {
+                                Some(s) => s.to_string(),
+                                None => seg.to_string(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if let Some(arg) = e["extensions"]["argumentName"]
+                    .as_str()
+                    .or_else(|| e["extensions"]["field"].as_str())
+                {
+                    path.push(arg.to_string());
+                }
+                FieldError {
+                    path: (!path.is_empty()).then(|| path.join(".")),
+                    message: e["message"].as_str().unwrap_or("Unknown error").to_string(),
+                }
+            })
+            .collect();
+
+        LinearError::Validation {
+            message,
+            errors: field_errors,
         }
     }
+}
 
-    anyhow::bail!(
-        "Team not found: '{}'. Use 'linear-cli t list' to see available teams.",
-        team
-    )
+impl std::fmt::Display for LinearError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinearError::Auth { message } => write!(f, "Authentication error: {}", message),
+            LinearError::NotFound { message } => write!(f, "Not found: {}", message),
+            LinearError::Validation { message, errors } => {
+                write!(f, "Invalid request: {}", message)?;
+                let paths: Vec<&str> = errors.iter().filter_map(|e| e.path.as_deref()).collect();
+                if !paths.is_empty() {
+                    write!(f, " (at {})", paths.join(", "))?;
+                }
+                Ok(())
+            }
+            LinearError::RateLimited { message, .. } => write!(f, "Rate limited: {}", message),
+            LinearError::Network { message, .. } => write!(f, "Network error: {}", message),
+            LinearError::Server {
+                status: Some(status),
+                message,
+            } => write!(f, "Server error (HTTP {}): {}", status, message),
+            LinearError::Server { message, .. } => write!(f, "Server error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for LinearError {}
+
+/// Whether `err` means Linear couldn't be reached (rather than that it refused
+/// the request), so the request can be retried later as is
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        matches!(
+            e.downcast_ref::<LinearError>(),
+            Some(LinearError::Network { .. })
+        )
+    })
 }
 
 pub struct LinearClient {
     client: Client,
-    api_key: String,
+    credential: tokio::sync::Mutex<Credential>,
+    api_url: String,
+    retry: RetryPolicy,
+    /// Where retry notices go; `None` prints them to stderr
+    on_retry: Option<RetryNotify>,
 }
 
+/// Receives a one-line notice each time a request is about to be retried
+pub type RetryNotify = Box<dyn Fn(String) + Send + Sync>;
+
 impl LinearClient {
     pub fn new() -> Result<Self> {
-        let api_key = config::get_api_key()?;
+        let credential = config::get_credential()?;
+        let api_url = config::get_api_url()?.unwrap_or_else(|| LINEAR_API_URL.to_string());
+        let retry = RetryPolicy::with_max_attempts(config::get_max_attempts()?);
         Ok(Self {
             client: Client::new(),
-            api_key,
+            credential: tokio::sync::Mutex::new(credential),
+            api_url,
+            retry,
+            on_retry: None,
         })
     }
 
+    /// Send retry notices to `notify` instead of stderr, e.g. for a full-screen
+    /// UI that stderr would draw over
+    pub fn on_retry(mut self, notify: impl Fn(String) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(Box::new(notify));
+        self
+    }
+
+    /// Short hash of the endpoint and the key (or OAuth login) requests are
+    /// made with, so cached data is never reused after the key changes
+    pub async fn fingerprint(&self) -> String {
+        let identity = match &*self.credential.lock().await {
+            Credential::ApiKey(key) => format!("key:{}", key),
+            Credential::OAuth {
+                workspace, login, ..
+            } => format!("oauth:{}:{}", workspace, login.client_id),
+        };
+        Sha256::digest(format!("{}\n{}", self.api_url, identity).as_bytes())[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// The `Authorization` header, refreshing an OAuth token that has expired
+    /// (or, with `force`, one the server just rejected)
+    async fn authorization(&self, force: bool) -> std::result::Result<String, LinearError> {
+        let mut credential = self.credential.lock().await;
+        if let Credential::OAuth {
+            workspace,
+            login,
+            tokens,
+        } = &mut *credential
+        {
+            if force || login.expires_soon() {
+                let refreshed = self.refresh(workspace, login, tokens).await;
+                refreshed.map_err(|e| LinearError::Auth {
+                    message: format!("{:#}", e),
+                })?;
+            }
+        }
+        Ok(credential.header())
+    }
+
+    async fn refresh(
+        &self,
+        workspace: &str,
+        login: &mut oauth::OAuthLogin,
+        tokens: &mut oauth::Tokens,
+    ) -> Result<()> {
+        let refresh_token = tokens
+            .refresh_token
+            .clone()
+            .context("The OAuth token has expired; run: linear auth login")?;
+        let response = oauth::refresh(
+            &self.client,
+            &oauth::Endpoints::get(),
+            &login.client_id,
+            &refresh_token,
+        )
+        .await?;
+        *tokens = response.update(login, Some(tokens));
+        config::save_oauth(workspace, login.clone(), tokens)
+    }
+
+    /// Whether an auth failure might be fixed by refreshing the OAuth token
+    async fn can_refresh(&self) -> bool {
+        matches!(
+            &*self.credential.lock().await,
+            Credential::OAuth { tokens, .. } if tokens.refresh_token.is_some()
+        )
+    }
+
+    /// Run a GraphQL query, retrying transient failures.
     pub async fn query(&self, query: &str, variables: Option<Value>) -> Result<Value> {
+        self.send(query, variables, true).await
+    }
+
+    /// Send a request with retries. Non-idempotent requests are only resent when
+    /// the server can't have processed them (rate limited or never connected).
+    async fn send(&self, query: &str, variables: Option<Value>, idempotent: bool) -> Result<Value> {
         let body = match variables {
             Some(vars) => json!({ "query": query, "variables": vars }),
             None => json!({ "query": query }),
         };
 
+        let mut attempt = 1;
+        let mut refreshed = false;
+        loop {
+            let error = match self.send_once(&body, false).await {
+                Ok(result) => return Ok(result),
+                // A revoked or early-expired OAuth token: refresh once and resend
+                Err(LinearError::Auth { .. }) if !refreshed && self.can_refresh().await => {
+                    refreshed = true;
+                    match self.send_once(&body, true).await {
+                        Ok(result) => return Ok(result),
+                        Err(error) => error,
+                    }
+                }
+                Err(error) => error,
+            };
+
+            let kind = error.failure_kind();
+            let delay = if idempotent || kind.is_safe_to_resend() {
+                self.retry.next_delay(attempt, kind, error.retry_after())
+            } else {
+                None
+            };
+
+            let Some(delay) = delay else {
+                return Err(error.into());
+            };
+
+            let notice = format!(
+                "{}; retrying in {:.1}s (attempt {}/{})",
+                error,
+                delay.as_secs_f64(),
+                attempt + 1,
+                self.retry.max_attempts
+            );
+            match &self.on_retry {
+                Some(notify) => notify(notice),
+                None => eprintln!("{} {}", "!".yellow(), notice),
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once(
+        &self,
+        body: &Value,
+        refresh: bool,
+    ) -> std::result::Result<Value, LinearError> {
+        let authorization = self.authorization(refresh).await?;
         let response = self
             .client
-            .post(LINEAR_API_URL)
+            .post(&self.api_url)
             .header("Content-Type", "application/json")
-            .header("Authorization", &self.api_key)
-            .json(&body)
+            .header("Authorization", authorization)
+            .json(body)
             .send()
-            .await?;
+            .await
+            .map_err(LinearError::from_reqwest)?;
 
-        let result: Value = response.json().await?;
+        let status = response.status();
+        let retry_after = retry::retry_after(response.headers());
+        let text = response.text().await.map_err(LinearError::from_reqwest)?;
+        let result: Option<Value> = serde_json::from_str(&text).ok();
+
+        // GraphQL errors can arrive with a 200 or a 4xx status
+        if let Some(errors) = result.as_ref().and_then(|r| r.get("errors")) {
+            let status = (!status.is_success()).then(|| status.as_u16());
+            return Err(LinearError::from_graphql(errors, status, retry_after));
+        }
 
-        if let Some(errors) = result.get("errors") {
-            anyhow::bail!("GraphQL error: {}", errors);
+        if !status.is_success() {
+            return Err(LinearError::from_status(status.as_u16(), retry_after));
         }
 
-        Ok(result)
+        result.ok_or_else(|| LinearError::Server {
+            status: Some(status.as_u16()),
+            message: "Invalid JSON response from Linear API".to_string(),
+        })
+    }
+
+    /// Walks a connection page by page, following `pageInfo { hasNextPage endCursor }`.
+    ///
+    /// The query must accept `$first: Int` and `$after: String` and select `nodes` and
+    /// `pageInfo` on the connection found at `path` below `data`. `on_page` is called
+    /// with each page's nodes as soon as it arrives. Returns the total number of nodes.
+    pub async fn paginate<F>(
+        &self,
+        query: &str,
+        variables: Option<Value>,
+        path: &[&str],
+        options: PageOptions,
+        mut on_page: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(Vec<Value>) -> Result<()>,
+    {
+        let base = variables.unwrap_or_else(|| json!({}));
+        let mut fetched = 0;
+        let mut after: Option<String> = None;
+
+        loop {
+            let remaining = options.limit.map(|l| l.saturating_sub(fetched));
+            if remaining == Some(0) {
+                break;
+            }
+            let first = remaining.map_or(options.page_size, |r| r.min(options.page_size));
+
+            let mut vars = base.clone();
+            vars["first"] = json!(first);
+            vars["after"] = json!(after);
+
+            let result = self.query(query, Some(vars)).await?;
+            let connection = path.iter().fold(&result["data"], |value, key| &value[*key]);
+
+            if connection.is_null() {
+                anyhow::bail!("Unexpected response: missing data.{}", path.join("."));
+            }
+
+            let mut nodes = connection["nodes"].as_array().cloned().unwrap_or_default();
+            if let Some(remaining) = remaining {
+                nodes.truncate(remaining);
+            }
+            fetched += nodes.len();
+
+            let has_next = connection["pageInfo"]["hasNextPage"]
+                .as_bool()
+                .unwrap_or(false);
+            after = connection["pageInfo"]["endCursor"]
+                .as_str()
+                .map(|c| c.to_string());
+
+            let empty_page = nodes.is_empty();
+            on_page(nodes)?;
+
+            if !has_next || after.is_none() || empty_page {
+                break;
+            }
+        }
+
+        Ok(fetched)
+    }
+
+    /// Like [`paginate`](Self::paginate), but collects every node into a single vector.
+    pub async fn fetch_all(
+        &self,
+        query: &str,
+        variables: Option<Value>,
+        path: &[&str],
+        options: PageOptions,
+    ) -> Result<Vec<Value>> {
+        let mut all = Vec::new();
+        self.paginate(query, variables, path, options, |nodes| {
+            all.extend(nodes);
+            Ok(())
+        })
+        .await?;
+        Ok(all)
     }
 
+    /// Run a GraphQL mutation. Only resent when it can't have been applied.
     pub async fn mutate(&self, mutation: &str, variables: Option<Value>) -> Result<Value> {
-        self.query(mutation, variables).await
+        self.send(mutation, variables, false).await
     }
 
     /// Fetch raw bytes from a URL with authorization header (for Linear uploads)
     pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let authorization = self.authorization(false).await?;
         let response = self
             .client
             .get(url)
-            .header("Authorization", &self.api_key)
+            .header("Authorization", authorization)
             .send()
             .await
-            .context("Failed to connect to Linear uploads")?;
+            .map_err(LinearError::from_reqwest)?;
 
         let status = response.status();
         if !status.is_success() {
-            let error_msg = match status.as_u16() {
-                401 => "Authentication failed - check your API key".to_string(),
-                403 => "Access denied to this upload".to_string(),
-                404 => "Upload not found".to_string(),
-                _ => format!(
-                    "HTTP {} {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown error")
-                ),
+            let error = match status.as_u16() {
+                403 => LinearError::Auth {
+                    message: "Access denied to this upload".to_string(),
+                },
+                404 => LinearError::NotFound {
+                    message: "Upload not found".to_string(),
+                },
+                other => LinearError::from_status(other, retry::retry_after(response.headers())),
             };
-            anyhow::bail!("{}", error_msg);
+            return Err(error.into());
         }
 
         let bytes: Vec<u8> = response
@@ -134,3 +673,69 @@ impl LinearClient {
         Ok(bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_error_classification() {
+        let errors = json!([{
+            "message": "Authentication required, not authenticated",
+            "extensions": { "code": "AUTHENTICATION_ERROR" }
+        }]);
+        assert_eq!(
+            LinearError::from_graphql(&errors, None, None).kind(),
+            "auth"
+        );
+
+        let errors = json!([{ "message": "Entity not found: Issue" }]);
+        assert_eq!(
+            LinearError::from_graphql(&errors, None, None).kind(),
+            "not_found"
+        );
+
+        let errors = json!([{
+            "message": "ratelimited",
+            "extensions": { "code": "RATELIMITED" }
+        }]);
+        let err = LinearError::from_graphql(&errors, None, Some(Duration::from_secs(5)));
+        assert_eq!(err.failure_kind(), FailureKind::RateLimited);
+        assert_eq!(err.to_json()["error"]["retryAfterSeconds"], 5.0);
+
+        let errors = json!([{ "message": "Something broke" }]);
+        assert_eq!(
+            LinearError::from_graphql(&errors, Some(502), None).exit_code(),
+            exit_code::SERVER
+        );
+    }
+
+    #[test]
+    fn test_validation_errors_carry_field_paths() {
+        let errors = json!([{
+            "message": "Argument Validation Error",
+            "path": ["issueCreate"],
+            "extensions": {
+                "code": "INVALID_INPUT",
+                "argumentName": "title",
+                "userPresentableMessage": "Title can't be empty"
+            }
+        }]);
+        let err = LinearError::from_graphql(&errors, Some(400), None);
+        assert_eq!(
+            err,
+            LinearError::Validation {
+                message: "Title can't be empty".to_string(),
+                errors: vec![FieldError {
+                    path: Some("issueCreate.title".to_string()),
+                    message: "Argument Validation Error".to_string(),
+                }],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid request: Title can't be empty (at issueCreate.title)"
+        );
+        assert_eq!(err.failure_kind(), FailureKind::Permanent);
+    }
+}
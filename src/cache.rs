@@ -1,16 +1,48 @@
+//! On-disk cache of teams, users, states and labels.
+//!
+//! Each organization gets its own directory under `cache/`, named by its ID, so
+//! switching workspaces never serves another organization's data. Which
+//! organization a key belongs to is looked up once and remembered in
+//! `cache/organizations.json`. Entries are stamped with the format version and
+//! a fingerprint of the key that wrote them, and anything else is ignored.
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::api::LinearClient;
+
 /// Default cache TTL in seconds (1 hour)
 const DEFAULT_TTL_SECONDS: u64 = 3600;
 
+/// Version of the entry format; entries written by another version are ignored
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Directory for data that is the same in every organization, like the schema
+const SHARED_DIR: &str = "shared";
+
+/// Which organization each key fingerprint belongs to
+const INDEX_FILE: &str = "organizations.json";
+
+const ORGANIZATION_QUERY: &str = r#"
+    query CacheOrganization {
+        organization { id name }
+    }
+"#;
+
 /// Cache entry with timestamp and data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheEntry {
+    /// Format the entry was written in (0 for entries older than versioning)
+    #[serde(default)]
+    pub version: u32,
+    /// Fingerprint of the key that wrote the entry
+    #[serde(default)]
+    pub key: String,
     /// Unix timestamp when the cache was created
     pub timestamp: u64,
     /// TTL in seconds for this cache entry
@@ -20,13 +52,13 @@ pub struct CacheEntry {
 }
 
 impl CacheEntry {
-    /// Check if the cache entry is still valid
+    /// Check if the cache entry is still valid: fresh and in the current format
     pub fn is_valid(&self) -> bool {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or(Duration::ZERO)
             .as_secs();
-        now < self.timestamp + self.ttl_seconds
+        self.version == FORMAT_VERSION && now < self.timestamp + self.ttl_seconds
     }
 
     /// Get the age of the cache entry in seconds
@@ -46,6 +78,10 @@ pub enum CacheType {
     Users,
     Statuses,
     Labels,
+    /// The authenticated user, for resolving "me"
+    Viewer,
+    /// The API's introspection result, from `linear schema fetch`
+    Schema,
 }
 
 impl CacheType {
@@ -56,6 +92,8 @@ impl CacheType {
             CacheType::Users => "users.json",
             CacheType::Statuses => "statuses.json",
             CacheType::Labels => "labels.json",
+            CacheType::Viewer => "viewer.json",
+            CacheType::Schema => "schema.json",
         }
     }
 
@@ -66,6 +104,8 @@ impl CacheType {
             CacheType::Users => "Users",
             CacheType::Statuses => "Statuses",
             CacheType::Labels => "Labels",
+            CacheType::Viewer => "Viewer",
+            CacheType::Schema => "Schema",
         }
     }
 
@@ -76,39 +116,180 @@ impl CacheType {
             CacheType::Users,
             CacheType::Statuses,
             CacheType::Labels,
+            CacheType::Viewer,
+            CacheType::Schema,
         ]
     }
 }
 
+/// An organization as recorded in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    id: String,
+    name: String,
+    /// Workspace the key was used from when the organization was looked up
+    workspace: String,
+}
+
+/// A cache directory and who it belongs to, for `cache status` and `cache clear`
+pub struct Namespace {
+    /// Organization ID, or "shared"
+    pub id: String,
+    pub name: Option<String>,
+    pub workspaces: Vec<String>,
+    pub cache: Cache,
+}
+
+impl Namespace {
+    /// Whether this is the cache shared by every organization
+    pub fn is_shared(&self) -> bool {
+        self.id == SHARED_DIR
+    }
+}
+
 /// Cache manager for Linear CLI
 pub struct Cache {
     cache_dir: PathBuf,
     ttl_seconds: u64,
+    /// Fingerprint of the current key; entries from other keys are misses
+    key: String,
 }
 
 impl Cache {
-    /// Create a new cache instance with default TTL
-    pub fn new() -> Result<Self> {
-        Self::with_ttl(DEFAULT_TTL_SECONDS)
+    /// The cache of the organization `client` signs in to. The organization
+    /// is asked for the first time a key is used, then remembered.
+    pub async fn open(client: &LinearClient) -> Result<Self> {
+        let key = client.fingerprint().await;
+        let mut index = read_index()?;
+        let organization = match index.get(&key) {
+            Some(entry) => entry.id.clone(),
+            None => {
+                let result = client.query(ORGANIZATION_QUERY, None).await?;
+                let organization = &result["data"]["organization"];
+                let entry = IndexEntry {
+                    id: organization["id"]
+                        .as_str()
+                        .context("Unexpected response: missing organization id")?
+                        .to_string(),
+                    name: organization["name"].as_str().unwrap_or("").to_string(),
+                    workspace: crate::config::current_workspace_name()?,
+                };
+                let id = entry.id.clone();
+                index.insert(key.clone(), entry);
+                write_index(&index)?;
+                id
+            }
+        };
+        Self::in_dir(
+            Self::root()?.join(dir_name(&organization)),
+            DEFAULT_TTL_SECONDS,
+            key,
+        )
     }
 
-    /// Create a new cache instance with custom TTL in seconds
-    pub fn with_ttl(ttl_seconds: u64) -> Result<Self> {
-        let cache_dir = Self::cache_dir()?;
+    /// The cache of the organization `client` signs in to, if the key has been
+    /// used before. Never touches the network.
+    pub async fn known(client: &LinearClient) -> Result<Option<Self>> {
+        let key = client.fingerprint().await;
+        match read_index()?.get(&key) {
+            Some(entry) => Ok(Some(Self::in_dir(
+                Self::root()?.join(dir_name(&entry.id)),
+                DEFAULT_TTL_SECONDS,
+                key,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The cache of the organization last looked up from `workspace`, for
+    /// reading offline without a key
+    pub fn of_workspace(workspace: &str) -> Result<Option<Self>> {
+        let index = read_index()?;
+        let Some((key, entry)) = index.iter().find(|(_, e)| e.workspace == workspace) else {
+            return Ok(None);
+        };
+        Ok(Some(Self::in_dir(
+            Self::root()?.join(dir_name(&entry.id)),
+            DEFAULT_TTL_SECONDS,
+            key.clone(),
+        )?))
+    }
+
+    /// The cache for data every organization shares, with a custom TTL in seconds
+    pub fn shared_with_ttl(ttl_seconds: u64) -> Result<Self> {
+        Self::in_dir(Self::root()?.join(SHARED_DIR), ttl_seconds, String::new())
+    }
+
+    /// The cache for data every organization shares
+    pub fn shared() -> Result<Self> {
+        Self::shared_with_ttl(DEFAULT_TTL_SECONDS)
+    }
+
+    fn in_dir(cache_dir: PathBuf, ttl_seconds: u64, key: String) -> Result<Self> {
         fs::create_dir_all(&cache_dir)?;
         Ok(Self {
             cache_dir,
             ttl_seconds,
+            key,
         })
     }
 
-    /// Get the cache directory path
-    fn cache_dir() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
+    /// The directory this cache's files live in
+    pub fn dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Every cache directory on disk, organizations first
+    pub fn namespaces() -> Result<Vec<Namespace>> {
+        let root = Self::root()?;
+        let index = read_index()?;
+        let mut namespaces = Vec::new();
+        let mut shared = None;
+        for dir in fs::read_dir(&root)? {
+            let dir = dir?;
+            if !dir.file_type()?.is_dir() {
+                continue;
+            }
+            let id = dir.file_name().to_string_lossy().to_string();
+            let known: Vec<&IndexEntry> =
+                index.values().filter(|e| dir_name(&e.id) == id).collect();
+            let mut workspaces: Vec<String> = known.iter().map(|e| e.workspace.clone()).collect();
+            workspaces.sort();
+            workspaces.dedup();
+            let namespace = Namespace {
+                name: known.first().map(|e| e.name.clone()),
+                workspaces,
+                cache: Self::in_dir(dir.path(), DEFAULT_TTL_SECONDS, String::new())?,
+                id,
+            };
+            if namespace.id == SHARED_DIR {
+                shared = Some(namespace);
+            } else {
+                namespaces.push(namespace);
+            }
+        }
+        namespaces.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        namespaces.extend(shared);
+        Ok(namespaces)
+    }
+
+    /// Forget which organization a key belongs to, e.g. after signing in again
+    pub fn forget(key: &str) -> Result<()> {
+        let mut index = read_index()?;
+        if index.remove(key).is_some() {
+            write_index(&index)?;
+        }
+        Ok(())
+    }
+
+    /// The directory every namespace lives in
+    fn root() -> Result<PathBuf> {
+        let root = dirs::config_dir()
             .context("Could not find config directory")?
             .join("linear-cli")
             .join("cache");
-        Ok(config_dir)
+        fs::create_dir_all(&root)?;
+        Ok(root)
     }
 
     /// Get the path for a specific cache type
@@ -126,7 +307,7 @@ impl Cache {
         let content = fs::read_to_string(&path).ok()?;
         let entry: CacheEntry = serde_json::from_str(&content).ok()?;
 
-        if entry.is_valid() {
+        if entry.is_valid() && entry.key == self.key {
             Some(entry.data)
         } else {
             // Cache expired, remove it
@@ -155,6 +336,8 @@ impl Cache {
             .as_secs();
 
         let entry = CacheEntry {
+            version: FORMAT_VERSION,
+            key: self.key.clone(),
             timestamp,
             ttl_seconds: self.ttl_seconds,
             data,
@@ -220,6 +403,10 @@ impl Cache {
                                     .and_then(|n| n.as_array())
                                     .map(|a| a.len())
                             })
+                            .or_else(|| {
+                                // Introspection results count their types
+                                entry.data["__schema"]["types"].as_array().map(|a| a.len())
+                            })
                             .unwrap_or(1);
                         (
                             entry.is_valid(),
@@ -278,6 +465,38 @@ impl CacheStatus {
     }
 }
 
+fn read_index() -> Result<BTreeMap<String, IndexEntry>> {
+    let path = Cache::root()?.join(INDEX_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(serde_json::from_str(&content).unwrap_or_default()),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+fn write_index(index: &BTreeMap<String, IndexEntry>) -> Result<()> {
+    let path = Cache::root()?.join(INDEX_FILE);
+    fs::write(path, serde_json::to_string_pretty(index)?)?;
+    Ok(())
+}
+
+/// An ID made safe to use as a single directory name
+fn dir_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match name.as_str() {
+        "" => "_".to_string(),
+        _ => name,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,6 +504,8 @@ mod tests {
     #[test]
     fn test_cache_entry_validity() {
         let entry = CacheEntry {
+            version: FORMAT_VERSION,
+            key: String::new(),
             timestamp: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -298,10 +519,35 @@ mod tests {
     #[test]
     fn test_cache_entry_expired() {
         let entry = CacheEntry {
+            version: FORMAT_VERSION,
+            key: String::new(),
             timestamp: 0, // Very old timestamp
             ttl_seconds: 3600,
             data: serde_json::json!({"test": "data"}),
         };
         assert!(!entry.is_valid());
     }
+
+    #[test]
+    fn test_cache_entry_from_another_version() {
+        let entry: CacheEntry = serde_json::from_value(serde_json::json!({
+            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            "ttl_seconds": 3600,
+            "data": []
+        }))
+        .unwrap();
+        assert_eq!(entry.version, 0);
+        assert!(!entry.is_valid());
+    }
+
+    #[test]
+    fn test_dir_name() {
+        assert_eq!(dir_name("work"), "work");
+        assert_eq!(dir_name("../acme corp"), "___acme_corp");
+        assert_eq!(
+            dir_name("a1b2c3d4-0000-4000-8000-000000000000"),
+            "a1b2c3d4-0000-4000-8000-000000000000"
+        );
+        assert_eq!(dir_name(""), "_");
+    }
 }
@@ -0,0 +1,265 @@
+//! `linear auth`: sign in with OAuth instead of a personal API key, see who
+//! the CLI is acting as, and sign out.
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::api::LinearClient;
+use crate::cache::Cache;
+use crate::config::{self, Credential};
+use crate::credentials::CredentialStore;
+use crate::oauth::{self, Endpoints, OAuthLogin, Pkce};
+use crate::output::print_value;
+use crate::OutputFormat;
+
+#[derive(Subcommand)]
+pub enum AuthCommands {
+    /// Sign in through the browser with OAuth
+    #[command(after_help = r#"EXAMPLES:
+    linear auth login --client-id abc123              # Opens the browser to approve access
+    linear auth login --client-id abc123 --no-browser # Print the URL instead
+    linear auth login --scope read                    # Read-only access
+
+Register an OAuth application in Linear (Settings > API > OAuth applications)
+with the redirect URI http://127.0.0.1:8976/callback. The client ID can also
+come from LINEAR_OAUTH_CLIENT_ID, and is remembered for later logins."#)]
+    Login {
+        /// Client ID of your Linear OAuth application
+        #[arg(long)]
+        client_id: Option<String>,
+        /// Scopes to ask for, comma-separated
+        #[arg(long, value_delimiter = ',', default_values_t = oauth::DEFAULT_SCOPES.iter().map(|s| s.to_string()))]
+        scope: Vec<String>,
+        /// Local port for the redirect (0 picks a free one)
+        #[arg(long, default_value_t = oauth::DEFAULT_PORT)]
+        port: u16,
+        /// Print the authorization URL instead of opening a browser
+        #[arg(long)]
+        no_browser: bool,
+    },
+    /// Show who requests are made as, and how
+    Status,
+    /// Revoke the OAuth token and forget it
+    Logout,
+}
+
+pub async fn handle(cmd: AuthCommands, output: OutputFormat) -> Result<()> {
+    match cmd {
+        AuthCommands::Login {
+            client_id,
+            scope,
+            port,
+            no_browser,
+        } => login(client_id, scope, port, no_browser).await,
+        AuthCommands::Status => status(output).await,
+        AuthCommands::Logout => logout().await,
+    }
+}
+
+const VIEWER_QUERY: &str = r#"
+    query {
+        viewer { id name email }
+        organization { id name urlKey }
+    }
+"#;
+
+async fn login(
+    client_id: Option<String>,
+    scopes: Vec<String>,
+    port: u16,
+    no_browser: bool,
+) -> Result<()> {
+    let workspace = config::current_workspace_name()?;
+    let config = config::load_config()?;
+    let client_id = client_id
+        .or_else(|| std::env::var("LINEAR_OAUTH_CLIENT_ID").ok())
+        .filter(|id| !id.is_empty())
+        .or_else(|| {
+            config
+                .workspaces
+                .get(&workspace)
+                .and_then(|w| w.oauth.as_ref())
+                .map(|o| o.client_id.clone())
+        })
+        .context(
+            "No OAuth client ID. Register an application in Linear with the redirect URI \
+             http://127.0.0.1:8976/callback, then pass --client-id",
+        )?;
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Could not listen on 127.0.0.1:{}", port))?;
+    let redirect_uri = format!(
+        "http://127.0.0.1:{}/callback",
+        listener.local_addr()?.port()
+    );
+
+    let endpoints = Endpoints::get();
+    let pkce = Pkce::new();
+    let state = oauth::random_token(16);
+    let url = oauth::authorize_url(
+        &endpoints,
+        &client_id,
+        &redirect_uri,
+        &scopes,
+        &state,
+        &pkce,
+    )?;
+
+    if no_browser || !oauth::open_browser(&url) {
+        println!("Open this URL to sign in:\n\n  {}\n", url);
+    } else {
+        println!(
+            "Opened the browser to sign in. If it didn't open, visit:\n\n  {}\n",
+            url
+        );
+    }
+    println!("Waiting for the browser...");
+
+    let code = oauth::wait_for_code(listener, &state).await?;
+    let http = reqwest::Client::new();
+    let response =
+        oauth::exchange_code(&http, &endpoints, &client_id, &code, &redirect_uri, &pkce).await?;
+
+    let mut login = OAuthLogin {
+        client_id,
+        scopes,
+        expires_at: None,
+        token_store: CredentialStore::Plaintext,
+        tokens: None,
+    };
+    let tokens = response.update(&mut login, None);
+    config::save_oauth(&workspace, login, &tokens)?;
+    // The login may be to another organization than last time; look it up again
+    if let Ok(client) = LinearClient::new() {
+        let _ = Cache::forget(&client.fingerprint().await);
+    }
+
+    match viewer().await {
+        Ok(viewer) => println!(
+            "{} Signed in to {} as {} ({})",
+            "+".green(),
+            viewer["organization"]["name"].as_str().unwrap_or("Linear"),
+            viewer["viewer"]["name"].as_str().unwrap_or("-"),
+            viewer["viewer"]["email"].as_str().unwrap_or("-"),
+        ),
+        Err(_) => println!("{} Signed in to workspace '{}'", "+".green(), workspace),
+    }
+    if std::env::var("LINEAR_API_KEY").is_ok_and(|k| !k.is_empty()) {
+        println!(
+            "{} LINEAR_API_KEY is set and takes precedence over the OAuth login",
+            "!".yellow()
+        );
+    }
+    Ok(())
+}
+
+async fn viewer() -> Result<Value> {
+    let client = LinearClient::new()?;
+    let result = client.query(VIEWER_QUERY, None).await?;
+    Ok(result["data"].clone())
+}
+
+async fn status(output: OutputFormat) -> Result<()> {
+    // Ask first: this refreshes an expired token, so the details below are current
+    let viewer = viewer().await?;
+    let workspace = config::current_workspace_name()?;
+    let credential = config::get_credential()?;
+    let config = config::load_config()?;
+
+    let mut info = json!({ "workspace": workspace });
+    match &credential {
+        Credential::OAuth { login, .. } => {
+            info["method"] = json!("oauth");
+            info["clientId"] = json!(login.client_id);
+            info["scopes"] = json!(login.scopes);
+            info["expiresAt"] = json!(login.expires_at);
+            info["tokenStore"] = json!(login.token_store.name());
+        }
+        Credential::ApiKey(_) if std::env::var("LINEAR_API_KEY").is_ok_and(|k| !k.is_empty()) => {
+            info["method"] = json!("api_key");
+            info["source"] = json!("LINEAR_API_KEY");
+        }
+        Credential::ApiKey(_) => {
+            info["method"] = json!("api_key");
+            info["source"] = json!(config
+                .workspaces
+                .get(&workspace)
+                .map(|w| w.describe_key())
+                .unwrap_or_default());
+        }
+    }
+
+    info["user"] = viewer["viewer"].clone();
+    info["organization"] = viewer["organization"].clone();
+
+    if !output.is_table() {
+        return print_value(&info, output);
+    }
+
+    println!(
+        "Signed in to {} as {} ({})",
+        viewer["organization"]["name"]
+            .as_str()
+            .unwrap_or("-")
+            .bold(),
+        viewer["viewer"]["name"].as_str().unwrap_or("-").bold(),
+        viewer["viewer"]["email"].as_str().unwrap_or("-")
+    );
+    println!("Workspace: {}", workspace);
+    match &credential {
+        Credential::OAuth { login, .. } => {
+            println!("Method: OAuth (client {})", login.client_id);
+            if !login.scopes.is_empty() {
+                println!("Scopes: {}", login.scopes.join(", "));
+            }
+            match login.expires_at {
+                Some(at) => println!(
+                    "Token expires: {} (refreshed automatically)",
+                    at.format("%Y-%m-%d %H:%M UTC")
+                ),
+                None => println!("Token expires: never"),
+            }
+            println!("Tokens stored: {}", login.token_store.name());
+        }
+        Credential::ApiKey(_) => {
+            println!("Method: API key {}", info["source"].as_str().unwrap_or("-"));
+        }
+    }
+    Ok(())
+}
+
+async fn logout() -> Result<()> {
+    let workspace = config::current_workspace_name()?;
+    let Some((_, tokens)) = config::remove_oauth(&workspace)? else {
+        println!("Workspace '{}' has no OAuth login.", workspace);
+        return Ok(());
+    };
+
+    if let Some(tokens) = tokens {
+        let revoked = oauth::revoke(
+            &reqwest::Client::new(),
+            &Endpoints::get(),
+            &tokens.access_token,
+        )
+        .await;
+        if let Err(err) = revoked {
+            eprintln!(
+                "{} {:#}; the token was forgotten locally",
+                "!".yellow(),
+                err
+            );
+        }
+    }
+
+    println!("{} Signed out of workspace '{}'", "+".green(), workspace);
+    let config = config::load_config()?;
+    if config.workspaces.get(&workspace).is_some_and(|w| {
+        w.credential_command.is_some() || w.credential_store.is_some() || !w.api_key.is_empty()
+    }) {
+        println!("Requests will use the workspace's API key again.");
+    }
+    Ok(())
+}
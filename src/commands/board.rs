@@ -0,0 +1,801 @@
+//! `linear board`: a team's issues laid out in columns by workflow state.
+//!
+//! Without `--interactive` the board is printed once, sized to the terminal.
+//! With it, [`BoardApp`] runs full-screen and moving a card to another column
+//! updates the issue's state.
+
+use anyhow::Result;
+use colored::Colorize;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use ratatui::Frame;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::api::{LinearClient, PageOptions};
+use crate::models::{self, Issue, WorkflowState};
+use crate::output::{print_value, terminal_width, truncate};
+use crate::resolver::Resolver;
+use crate::tui::{self, Picker, PickerOutcome};
+use crate::OutputFormat;
+
+/// How often the interactive board is reloaded in the background
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Space between columns of the printed board
+const GUTTER: &str = " | ";
+
+/// Narrowest a column gets, however many states the team has. Columns that
+/// don't fit go to another band when printed, and scroll into view on the
+/// interactive board.
+const MIN_COLUMN_WIDTH: usize = 8;
+
+const BOARD_ISSUES_QUERY: &str = r#"
+    query($teamId: ID!, $cycle: NullableCycleFilter, $states: [String!], $first: Int, $after: String) {
+        issues(
+            first: $first,
+            after: $after,
+            filter: {
+                team: { id: { eq: $teamId } },
+                cycle: $cycle,
+                state: { type: { nin: $states } }
+            }
+        ) {
+            nodes {
+                id
+                identifier
+                title
+                priority
+                state { id name type }
+                assignee { id name }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+/// What to put on the board: the team, and optionally a single cycle
+#[derive(Debug, Clone)]
+pub struct BoardQuery {
+    team_id: String,
+    cycle: Option<Value>,
+    hide_done: bool,
+    limit: usize,
+}
+
+/// One workflow state and the issues in it
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Column {
+    pub state: WorkflowState,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub team_name: String,
+    pub columns: Vec<Column>,
+}
+
+pub async fn run(
+    team: &str,
+    cycle: Option<&str>,
+    hide_done: bool,
+    limit: usize,
+    interactive: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let (tx, messages) = mpsc::unbounded_channel();
+    let mut client = LinearClient::new()?;
+    if interactive {
+        // Retries would print over the board, so show them in its footer
+        let retries = tx.clone();
+        client = client.on_retry(move |notice| {
+            let _ = retries.send(Message::Retrying(notice));
+        });
+    }
+    let client = Arc::new(client);
+    let query = BoardQuery {
+        team_id: Resolver::new(&client).team_id(team).await?,
+        cycle: cycle.map(cycle_filter).transpose()?,
+        hide_done,
+        limit,
+    };
+
+    let board = load_board(&client, &query).await?;
+
+    if interactive {
+        let mut app = BoardApp::new(board);
+        let mut terminal = tui::enter()?;
+        let result = event_loop(&mut terminal, &mut app, client, query, tx, messages).await;
+        tui::leave(&mut terminal)?;
+        return result;
+    }
+
+    if !output.is_table() {
+        return print_value(&board.columns, output);
+    }
+
+    if board.columns.is_empty() {
+        println!("No workflow states found for team '{}'.", board.team_name);
+        return Ok(());
+    }
+
+    let total: usize = board.columns.iter().map(|c| c.issues.len()).sum();
+    for line in render_columns(&board.columns, terminal_width()) {
+        println!("{}", line);
+    }
+    println!("\n{} issues in {}", total, board.team_name);
+
+    Ok(())
+}
+
+/// Issue filter for `--cycle`: "current", "next", "previous" or a cycle number
+pub fn cycle_filter(cycle: &str) -> Result<Value> {
+    let filter = match cycle.to_lowercase().as_str() {
+        "current" | "active" => json!({ "isActive": { "eq": true } }),
+        "next" => json!({ "isNext": { "eq": true } }),
+        "previous" | "prev" => json!({ "isPrevious": { "eq": true } }),
+        other => match other.parse::<i64>() {
+            Ok(number) => json!({ "number": { "eq": number } }),
+            Err(_) => anyhow::bail!(
+                "Invalid cycle '{}'. Use current, next, previous or a cycle number.",
+                cycle
+            ),
+        },
+    };
+    Ok(filter)
+}
+
+async fn load_board(client: &LinearClient, query: &BoardQuery) -> Result<Board> {
+    let (team_name, states) = Resolver::new(client).team_states(&query.team_id).await?;
+    let mut states: Vec<WorkflowState> = models::from_nodes(&states)?;
+    if query.hide_done {
+        states.retain(|s| !is_done(s.kind.as_deref()));
+    }
+
+    let hidden: Vec<&str> = if query.hide_done {
+        vec!["completed", "canceled"]
+    } else {
+        Vec::new()
+    };
+    let issues = client
+        .fetch_all(
+            BOARD_ISSUES_QUERY,
+            Some(json!({
+                "teamId": query.team_id,
+                "cycle": query.cycle,
+                "states": hidden,
+            })),
+            &["issues"],
+            PageOptions::limit(query.limit),
+        )
+        .await?;
+
+    Ok(Board {
+        team_name,
+        columns: group(states, models::from_nodes(&issues)?),
+    })
+}
+
+fn is_done(kind: Option<&str>) -> bool {
+    matches!(kind, Some("completed" | "canceled" | "cancelled"))
+}
+
+/// One column per state, ordered by the state's position on the team's board
+fn group(mut states: Vec<WorkflowState>, issues: Vec<Issue>) -> Vec<Column> {
+    states.sort_by(|a, b| {
+        a.position
+            .unwrap_or(0.0)
+            .total_cmp(&b.position.unwrap_or(0.0))
+    });
+    let mut columns: Vec<Column> = states
+        .into_iter()
+        .map(|state| Column {
+            state,
+            issues: Vec::new(),
+        })
+        .collect();
+
+    for issue in issues {
+        let state_id = issue.state.as_ref().map(|s| s.id.clone());
+        if let Some(column) = columns
+            .iter_mut()
+            .find(|c| Some(&c.state.id) == state_id.as_ref())
+        {
+            column.issues.push(issue);
+        }
+    }
+    columns
+}
+
+fn card_text(issue: &Issue) -> String {
+    format!("{} {}", issue.identifier, issue.title)
+}
+
+/// How many columns at least `min_width` wide, `gap` apart, fit in `width`
+fn columns_that_fit(width: usize, min_width: usize, gap: usize) -> usize {
+    ((width + gap) / (min_width + gap)).max(1)
+}
+
+/// Lay the columns out side by side in `width` characters, in bands one
+/// under the other when they don't all fit. Each card is one line, cut to
+/// fit its column.
+fn render_columns(columns: &[Column], width: usize) -> Vec<String> {
+    let per_band = columns_that_fit(width, MIN_COLUMN_WIDTH, GUTTER.len());
+    let mut lines = Vec::new();
+    for (i, band) in columns.chunks(per_band).enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+        lines.extend(render_band(band, width));
+    }
+    lines
+}
+
+fn render_band(columns: &[Column], width: usize) -> Vec<String> {
+    let count = columns.len().max(1);
+    let gutters = GUTTER.len() * (count - 1);
+    let column_width = (width.saturating_sub(gutters) / count).max(MIN_COLUMN_WIDTH);
+
+    let cell = |text: &str| format!("{:<column_width$}", truncate(text, column_width));
+    let row = |cells: Vec<String>| cells.join(GUTTER).trim_end().to_string();
+
+    let mut lines = Vec::new();
+    lines.push(row(columns
+        .iter()
+        .map(|c| {
+            let title = cell(&format!("{} ({})", c.state.name, c.issues.len()));
+            c.state.paint(&title).bold().to_string()
+        })
+        .collect()));
+    lines.push(
+        columns
+            .iter()
+            .map(|_| "-".repeat(column_width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+
+    let depth = columns.iter().map(|c| c.issues.len()).max().unwrap_or(0);
+    for i in 0..depth {
+        lines.push(row(columns
+            .iter()
+            .map(|c| cell(&c.issues.get(i).map(card_text).unwrap_or_default()))
+            .collect()));
+    }
+    lines
+}
+
+/// Results delivered by background tasks
+pub enum Message {
+    Loaded(Result<Board>),
+    /// An issue was moved; `Ok` carries a status line
+    Saved(Result<String>),
+    /// A request failed and is about to be retried
+    Retrying(String),
+}
+
+/// Side effects requested by the board
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Refresh,
+    Move {
+        issue_id: String,
+        identifier: String,
+        state_id: String,
+    },
+}
+
+pub struct BoardApp {
+    board: Board,
+    column: usize,
+    /// Selected card in each column
+    rows: Vec<usize>,
+    picker: Option<Picker<usize>>,
+    status: String,
+    quit: bool,
+}
+
+impl BoardApp {
+    pub fn new(board: Board) -> Self {
+        let rows = vec![0; board.columns.len()];
+        Self {
+            board,
+            column: 0,
+            rows,
+            picker: None,
+            status: String::new(),
+            quit: false,
+        }
+    }
+
+    fn selected_issue(&self) -> Option<&Issue> {
+        let column = self.board.columns.get(self.column)?;
+        column.issues.get(self.rows[self.column])
+    }
+
+    fn move_row(&mut self, delta: isize) {
+        let Some(column) = self.board.columns.get(self.column) else {
+            return;
+        };
+        let last = column.issues.len().saturating_sub(1);
+        let row = &mut self.rows[self.column];
+        *row = row.saturating_add_signed(delta).min(last);
+    }
+
+    fn move_column(&mut self, delta: isize) {
+        let last = self.board.columns.len().saturating_sub(1);
+        self.column = self.column.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
+
+        if let Some(picker) = &mut self.picker {
+            return match picker.on_key(key) {
+                PickerOutcome::Pending => None,
+                PickerOutcome::Cancelled => {
+                    self.picker = None;
+                    None
+                }
+                PickerOutcome::Chosen(target) => {
+                    self.picker = None;
+                    self.move_card(target)
+                }
+            };
+        }
+
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('H') => return self.move_card(self.column.checked_sub(1)?),
+            KeyCode::Char('L') => return self.move_card(self.column + 1),
+            KeyCode::Left if shift => return self.move_card(self.column.checked_sub(1)?),
+            KeyCode::Right if shift => return self.move_card(self.column + 1),
+            KeyCode::Left | KeyCode::Char('h') => self.move_column(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_column(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_row(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_row(1),
+            KeyCode::Char('g') => {
+                if let Some(row) = self.rows.get_mut(self.column) {
+                    *row = 0;
+                }
+            }
+            KeyCode::Char('G') => self.move_row(isize::MAX),
+            KeyCode::Char('m') if self.selected_issue().is_some() => {
+                let options = self
+                    .board
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| (c.state.name.clone(), i))
+                    .collect();
+                self.picker = Some(Picker::new("Move to", options, self.column));
+            }
+            KeyCode::Char('r') => return Some(Action::Refresh),
+            _ => {}
+        }
+        None
+    }
+
+    /// Move the selected card to column `target`, keeping it selected, and
+    /// return the update that persists the move
+    fn move_card(&mut self, target: usize) -> Option<Action> {
+        if target == self.column || target >= self.board.columns.len() {
+            return None;
+        }
+        let row = self.rows[self.column];
+        if row >= self.board.columns[self.column].issues.len() {
+            return None;
+        }
+
+        let mut issue = self.board.columns[self.column].issues.remove(row);
+        let state = self.board.columns[target].state.clone();
+        issue.state = Some(state.clone());
+        let action = Action::Move {
+            issue_id: issue.id.clone(),
+            identifier: issue.identifier.clone(),
+            state_id: state.id,
+        };
+        self.status = format!("Moving {} to {}...", issue.identifier, state.name);
+
+        self.move_row(0);
+        self.board.columns[target].issues.insert(0, issue);
+        self.column = target;
+        self.rows[target] = 0;
+        Some(action)
+    }
+
+    pub fn on_message(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::Loaded(Ok(board)) => {
+                let selected_id = self.selected_issue().map(|i| i.id.clone());
+                self.rows = vec![0; board.columns.len()];
+                self.board = board;
+                self.column = self.column.min(self.rows.len().saturating_sub(1));
+                // Follow the selected card if it moved in the meantime
+                for (c, column) in self.board.columns.iter().enumerate() {
+                    if let Some(r) = column
+                        .issues
+                        .iter()
+                        .position(|i| Some(&i.id) == selected_id.as_ref())
+                    {
+                        self.column = c;
+                        self.rows[c] = r;
+                    }
+                }
+                None
+            }
+            Message::Loaded(Err(e)) => {
+                self.status = format!("Refresh failed: {:#}", e);
+                None
+            }
+            Message::Saved(Ok(status)) => {
+                self.status = status;
+                None
+            }
+            Message::Saved(Err(e)) => {
+                self.status = format!("Move failed: {:#}", e);
+                // Reload to put the card back where the server has it
+                Some(Action::Refresh)
+            }
+            Message::Retrying(notice) => {
+                self.status = notice;
+                None
+            }
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        // Show as many columns as fit, scrolled so the active one is in view
+        let columns = &self.board.columns;
+        let visible = columns_that_fit(body.width as usize, MIN_COLUMN_WIDTH + 2, 0)
+            .min(columns.len().max(1));
+        let first = (self.column + 1).saturating_sub(visible);
+        let shown = &columns[first..(first + visible).min(columns.len())];
+
+        let mut title = format!(" {} board", self.board.team_name);
+        if shown.len() < columns.len() {
+            title.push_str(&format!(
+                "  (columns {}-{} of {})",
+                first + 1,
+                first + shown.len(),
+                columns.len()
+            ));
+        }
+        frame.render_widget(Line::styled(title, Style::new().fg(Color::Cyan)), header);
+
+        let count = shown.len().max(1) as u32;
+        let areas =
+            Layout::horizontal(vec![Constraint::Ratio(1, count); count as usize]).split(body);
+        for (i, column) in shown.iter().enumerate() {
+            self.draw_column(frame, areas[i], first + i, column);
+        }
+
+        let footer_line = if self.status.is_empty() {
+            Line::styled(
+                " h/l column  j/k card  H/L move card  m move to...  r refresh  q quit",
+                Style::new().fg(Color::DarkGray),
+            )
+        } else {
+            Line::from(self.status.clone())
+        };
+        frame.render_widget(footer_line, footer);
+
+        if let Some(picker) = &self.picker {
+            picker.draw(frame, body);
+        }
+    }
+
+    fn draw_column(&self, frame: &mut Frame, area: Rect, index: usize, column: &Column) {
+        let active = index == self.column;
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = column
+            .issues
+            .iter()
+            .map(|issue| ListItem::new(truncate(&card_text(issue), inner_width)))
+            .collect();
+
+        let mut block = Block::default().borders(Borders::ALL).title(Line::styled(
+            truncate(
+                &format!(" {} ({}) ", column.state.name, column.issues.len()),
+                inner_width,
+            ),
+            tui::state_style(column.state.kind.as_deref()),
+        ));
+        if active {
+            block = block.border_style(Style::new().fg(Color::Cyan));
+        }
+
+        let mut list = List::new(items).block(block);
+        if active {
+            list = list.highlight_style(tui::highlight_style());
+        }
+        let selected = (!column.issues.is_empty()).then_some(self.rows[index]);
+        let mut state = ListState::default().with_selected(selected);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+async fn event_loop(
+    terminal: &mut tui::Tui,
+    app: &mut BoardApp,
+    client: Arc<LinearClient>,
+    query: BoardQuery,
+    tx: UnboundedSender<Message>,
+    mut messages: UnboundedReceiver<Message>,
+) -> Result<()> {
+    let mut events = tui::terminal_events();
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+    // The board was just loaded, so skip the immediate first tick
+    refresh.tick().await;
+
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let action = tokio::select! {
+            Some(event) = events.recv() => match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.on_key(key),
+                _ => None,
+            },
+            Some(message) = messages.recv() => app.on_message(message),
+            _ = refresh.tick() => Some(Action::Refresh),
+        };
+
+        if let Some(action) = action {
+            perform(action, &client, &query, &tx);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run an action in the background, reporting back through `tx`
+fn perform(
+    action: Action,
+    client: &Arc<LinearClient>,
+    query: &BoardQuery,
+    tx: &UnboundedSender<Message>,
+) {
+    let client = Arc::clone(client);
+    let query = query.clone();
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let message = match action {
+            Action::Refresh => Message::Loaded(load_board(&client, &query).await),
+            Action::Move {
+                issue_id,
+                identifier,
+                state_id,
+            } => Message::Saved(
+                move_issue(&client, &issue_id, &state_id)
+                    .await
+                    .map(|_| format!("Moved {}", identifier)),
+            ),
+        };
+        let _ = tx.send(message);
+    });
+}
+
+async fn move_issue(client: &LinearClient, issue_id: &str, state_id: &str) -> Result<()> {
+    let mutation = r#"
+        mutation($id: String!, $input: IssueUpdateInput!) {
+            issueUpdate(id: $id, input: $input) {
+                success
+            }
+        }
+    "#;
+
+    let result = client
+        .mutate(
+            mutation,
+            Some(json!({ "id": issue_id, "input": { "stateId": state_id } })),
+        )
+        .await?;
+
+    if result["data"]["issueUpdate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Failed to update issue");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn sample_board() -> Board {
+        let states = models::from_value(&json!([
+            { "id": "s-done", "name": "Done", "type": "completed", "position": 3 },
+            { "id": "s-todo", "name": "Todo", "type": "unstarted", "position": 1 },
+            { "id": "s-doing", "name": "In Progress", "type": "started", "position": 2 }
+        ]))
+        .unwrap();
+        let issues = models::from_value(&json!([
+            { "id": "i-1", "identifier": "ENG-1", "title": "Fix the login page redirect loop",
+              "state": { "id": "s-todo", "name": "Todo" } },
+            { "id": "i-2", "identifier": "ENG-2", "title": "Dark mode",
+              "state": { "id": "s-todo", "name": "Todo" } },
+            { "id": "i-3", "identifier": "ENG-3", "title": "Ship it",
+              "state": { "id": "s-done", "name": "Done" } },
+            { "id": "i-4", "identifier": "ENG-4", "title": "Unknown state",
+              "state": { "id": "s-gone", "name": "Archived" } }
+        ]))
+        .unwrap();
+        Board {
+            team_name: "Engineering".to_string(),
+            columns: group(states, issues),
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::from(code)
+    }
+
+    #[test]
+    fn test_columns_follow_state_position() {
+        let board = sample_board();
+        let names: Vec<&str> = board
+            .columns
+            .iter()
+            .map(|c| c.state.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Todo", "In Progress", "Done"]);
+        assert_eq!(board.columns[0].issues.len(), 2);
+        assert_eq!(board.columns[2].issues[0].identifier, "ENG-3");
+    }
+
+    #[test]
+    fn test_render_columns_fits_width() {
+        colored::control::set_override(false);
+        let board = sample_board();
+        let lines = render_columns(&board.columns, 60);
+        for line in &lines {
+            assert!(line.chars().count() <= 60, "too wide: {:?}", line);
+        }
+        assert!(lines[0].starts_with("Todo (2)"));
+        assert!(lines[0].contains("| In Progress (0)"));
+        assert_eq!(
+            lines[2],
+            "ENG-1 Fix the l... |                    | ENG-3 Ship it"
+        );
+        assert_eq!(lines[3], "ENG-2 Dark mode    |                    |");
+
+        // Columns never get narrower than the minimum; those that don't fit
+        // go to the next band
+        let lines = render_columns(&board.columns, 10);
+        assert_eq!(lines[2], "ENG-1 F...");
+        for line in &lines {
+            assert!(line.chars().count() <= 10, "too wide: {:?}", line);
+        }
+        assert!(
+            lines.iter().any(|l| l.starts_with("In Prog...")),
+            "{:?}",
+            lines
+        );
+        assert!(lines.iter().any(|l| l.starts_with("Done (1)")));
+    }
+
+    #[test]
+    fn test_cycle_filter() {
+        assert_eq!(
+            cycle_filter("current").unwrap(),
+            json!({ "isActive": { "eq": true } })
+        );
+        assert_eq!(
+            cycle_filter("12").unwrap(),
+            json!({ "number": { "eq": 12 } })
+        );
+        assert!(cycle_filter("soon").is_err());
+    }
+
+    #[test]
+    fn test_moving_a_card_updates_its_state() {
+        let mut app = BoardApp::new(sample_board());
+        app.on_key(key(KeyCode::Char('j')));
+
+        let action = app.on_key(key(KeyCode::Char('L')));
+        assert_eq!(
+            action,
+            Some(Action::Move {
+                issue_id: "i-2".to_string(),
+                identifier: "ENG-2".to_string(),
+                state_id: "s-doing".to_string(),
+            })
+        );
+        // The card moved with the selection
+        assert_eq!(app.column, 1);
+        assert_eq!(app.selected_issue().unwrap().state_name(), "In Progress");
+        assert_eq!(app.board.columns[0].issues.len(), 1);
+
+        // There's no column left of the first
+        app.on_key(key(KeyCode::Char('h')));
+        assert_eq!(app.on_key(key(KeyCode::Char('H'))), None);
+
+        // "m" picks any column
+        app.on_key(key(KeyCode::Char('m')));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Down));
+        let action = app.on_key(key(KeyCode::Enter));
+        assert!(matches!(action, Some(Action::Move { state_id, .. }) if state_id == "s-done"));
+        assert_eq!(app.board.columns[2].issues[0].identifier, "ENG-1");
+    }
+
+    #[test]
+    fn test_failed_move_reloads() {
+        let mut app = BoardApp::new(sample_board());
+        let action = app.on_message(Message::Saved(Err(anyhow::anyhow!("denied"))));
+        assert_eq!(action, Some(Action::Refresh));
+        assert_eq!(app.status, "Move failed: denied");
+    }
+
+    #[test]
+    fn test_scrolls_through_more_columns_than_fit() {
+        let states: Vec<Value> = (0..9)
+            .map(|i| json!({ "id": format!("s-{}", i), "name": format!("S{}", i), "position": i }))
+            .collect();
+        let board = Board {
+            team_name: "Engineering".to_string(),
+            columns: group(models::from_value(&json!(states)).unwrap(), Vec::new()),
+        };
+        let mut app = BoardApp::new(board);
+        for _ in 0..8 {
+            app.on_key(key(KeyCode::Char('l')));
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("columns 6-9 of 9"), "{}", screen);
+        assert!(screen.contains("S8 (0)"), "{}", screen);
+        assert!(!screen.contains("S0 (0)"), "{}", screen);
+    }
+
+    #[test]
+    fn test_empty_board_ignores_keys() {
+        let mut app = BoardApp::new(Board {
+            team_name: "Engineering".to_string(),
+            columns: Vec::new(),
+        });
+        for code in ['g', 'G', 'j', 'l', 'm', 'L'] {
+            assert_eq!(app.on_key(key(KeyCode::Char(code))), None);
+        }
+        let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+    }
+
+    #[test]
+    fn test_draws_columns() {
+        let app = BoardApp::new(sample_board());
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|c| c.symbol()).collect::<String>() + "\n")
+            .collect();
+
+        assert!(screen.contains("Todo (2)"), "{}", screen);
+        assert!(screen.contains("In Progress (0)"));
+        // Cards are cut to the 20-column width
+        assert!(screen.contains("ENG-1 Fix the l..."), "{}", screen);
+    }
+}
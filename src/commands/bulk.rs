@@ -2,9 +2,18 @@ use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
 use futures::future::join_all;
-use serde_json::json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tabled::{Table, Tabled};
 
-use crate::api::LinearClient;
+use crate::api::{self, LinearClient, PageOptions};
+use crate::filter;
+use crate::models::{self, Issue};
+use crate::output::{print_value, truncate};
+use crate::resolver::Resolver;
+use crate::OutputFormat;
+
+use super::queue::{self, Operation};
 
 #[derive(Subcommand)]
 pub enum BulkCommands {
@@ -12,203 +21,163 @@ pub enum BulkCommands {
     #[command(alias = "state")]
     #[command(after_help = r#"EXAMPLES:
     linear bulk update-state Done -i LIN-1,LIN-2,LIN-3
-    linear b state "In Progress" -i LIN-1,LIN-2"#)]
+    linear b state "In Progress" -i LIN-1,LIN-2
+    linear b state Done -i LIN-1,LIN-2 --queue  # Queue them if Linear can't be reached
+    linear b state Done --where 'team:ENG state:"In Review" updated<-14d'
+    linear b state Done -w 'label:stale' --dry-run  # Preview only
+
+With --where, the matching issues are listed with the planned change and you
+are asked to confirm (skip with --force)."#)]
     UpdateState {
         /// The new state name or ID
         state: String,
         /// Comma-separated list of issue IDs (e.g., "LIN-1,LIN-2,LIN-3")
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', conflicts_with = "filter")]
         issues: Vec<String>,
+        /// Select the issues with a filter expression instead (see: linear issues list --help)
+        #[arg(short = 'w', long = "where", value_name = "FILTER")]
+        filter: Option<String>,
+        /// Show the planned changes without making them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation for issues selected with --where
+        #[arg(long)]
+        force: bool,
+        /// Queue the changes to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
     /// Assign multiple issues to a user
     #[command(after_help = r#"EXAMPLES:
     linear bulk assign me -i LIN-1,LIN-2,LIN-3
-    linear b assign john@example.com -i LIN-1,LIN-2"#)]
+    linear b assign john@example.com -i LIN-1,LIN-2
+    linear b assign me --where 'team:ENG assignee:none priority<=2'"#)]
     Assign {
         /// The user to assign (user ID, name, email, or "me")
         user: String,
         /// Comma-separated list of issue IDs (e.g., "LIN-1,LIN-2,LIN-3")
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', conflicts_with = "filter")]
         issues: Vec<String>,
+        /// Select the issues with a filter expression instead (see: linear issues list --help)
+        #[arg(short = 'w', long = "where", value_name = "FILTER")]
+        filter: Option<String>,
+        /// Show the planned changes without making them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation for issues selected with --where
+        #[arg(long)]
+        force: bool,
+        /// Queue the changes to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
     /// Add a label to multiple issues
     #[command(after_help = r#"EXAMPLES:
     linear bulk label "Bug" -i LIN-1,LIN-2,LIN-3
-    linear b label LABEL_ID -i LIN-1,LIN-2"#)]
+    linear b label LABEL_ID -i LIN-1,LIN-2
+    linear b label stale --where 'state:Backlog updated<-90d' --dry-run"#)]
     Label {
         /// The label name or ID to add
         label: String,
         /// Comma-separated list of issue IDs (e.g., "LIN-1,LIN-2,LIN-3")
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', conflicts_with = "filter")]
         issues: Vec<String>,
+        /// Select the issues with a filter expression instead (see: linear issues list --help)
+        #[arg(short = 'w', long = "where", value_name = "FILTER")]
+        filter: Option<String>,
+        /// Show the planned changes without making them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation for issues selected with --where
+        #[arg(long)]
+        force: bool,
+        /// Queue the changes to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
     /// Unassign multiple issues
     #[command(after_help = r#"EXAMPLES:
-    linear bulk unassign -i LIN-1,LIN-2,LIN-3"#)]
+    linear bulk unassign -i LIN-1,LIN-2,LIN-3
+    linear b unassign --where 'assignee:alice state:Todo'"#)]
     Unassign {
         /// Comma-separated list of issue IDs (e.g., "LIN-1,LIN-2,LIN-3")
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', conflicts_with = "filter")]
         issues: Vec<String>,
+        /// Select the issues with a filter expression instead (see: linear issues list --help)
+        #[arg(short = 'w', long = "where", value_name = "FILTER")]
+        filter: Option<String>,
+        /// Show the planned changes without making them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation for issues selected with --where
+        #[arg(long)]
+        force: bool,
+        /// Queue the changes to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
 }
 
 /// Result of a single bulk operation
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct BulkResult {
     issue_id: String,
     success: bool,
     identifier: Option<String>,
     error: Option<String>,
+    /// The queue entry id, when Linear couldn't be reached and the change
+    /// went into the queue instead
+    queued: Option<String>,
 }
 
-/// Check if a string looks like a UUID (contains dashes and is 36 characters)
-fn is_uuid(s: &str) -> bool {
-    s.len() == 36 && s.chars().filter(|c| *c == '-').count() == 4
-}
-
-/// Resolve a user identifier to a UUID.
-/// Handles "me", UUIDs, names, and emails.
-async fn resolve_user_id(client: &LinearClient, user: &str) -> Result<String> {
-    // Handle "me" - get the current viewer's ID
-    if user.eq_ignore_ascii_case("me") {
-        let query = r#"
-            query {
-                viewer {
-                    id
-                }
-            }
-        "#;
-        let result = client.query(query, None).await?;
-        let user_id = result["data"]["viewer"]["id"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Could not fetch current user ID"))?;
-        return Ok(user_id.to_string());
-    }
-
-    // If already a UUID, return as-is
-    if is_uuid(user) {
-        return Ok(user.to_string());
-    }
-
-    // Try to find user by name or email
-    let query = r#"
-        query {
-            users(first: 100) {
-                nodes {
-                    id
-                    name
-                    email
+impl BulkResult {
+    /// The result of changing `issue_id`; with `queue`, a change Linear
+    /// couldn't be reached for is queued instead of failing
+    async fn new(issue_id: &str, outcome: Result<Value>, queue: Option<Operation>) -> Self {
+        match outcome {
+            Ok(issue) => BulkResult {
+                issue_id: issue_id.to_string(),
+                success: true,
+                identifier: issue["identifier"].as_str().map(str::to_string),
+                error: None,
+                queued: None,
+            },
+            Err(err) => match queue {
+                Some(operation) if api::is_unreachable(&err) => {
+                    Self::queue(issue_id, operation).await
                 }
-            }
-        }
-    "#;
-
-    let result = client.query(query, None).await?;
-    let empty = vec![];
-    let users = result["data"]["users"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
-    // Try to match by name (case-insensitive) or email
-    for u in users {
-        let name = u["name"].as_str().unwrap_or("");
-        let email = u["email"].as_str().unwrap_or("");
-
-        if name.eq_ignore_ascii_case(user) || email.eq_ignore_ascii_case(user) {
-            if let Some(id) = u["id"].as_str() {
-                return Ok(id.to_string());
-            }
+                _ => Self::failed(issue_id, err),
+            },
         }
     }
 
-    anyhow::bail!("User not found: {}", user)
-}
-
-/// Resolve a state name to a UUID for a given team.
-async fn resolve_state_id(client: &LinearClient, team_id: &str, state: &str) -> Result<String> {
-    // If already a UUID, return as-is
-    if is_uuid(state) {
-        return Ok(state.to_string());
-    }
-
-    // Fetch team states
-    let query = r#"
-        query($teamId: String!) {
-            team(id: $teamId) {
-                states {
-                    nodes {
-                        id
-                        name
-                    }
-                }
-            }
-        }
-    "#;
-
-    let result = client
-        .query(query, Some(json!({ "teamId": team_id })))
-        .await?;
-    let empty = vec![];
-    let states = result["data"]["team"]["states"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
-    // Try to match by name (case-insensitive)
-    for s in states {
-        let name = s["name"].as_str().unwrap_or("");
-        if name.eq_ignore_ascii_case(state) {
-            if let Some(id) = s["id"].as_str() {
-                return Ok(id.to_string());
-            }
+    async fn queue(issue_id: &str, operation: Operation) -> Self {
+        match queue::enqueue_quietly(operation).await {
+            Ok(entry) => BulkResult {
+                issue_id: issue_id.to_string(),
+                success: false,
+                identifier: None,
+                error: None,
+                queued: Some(entry.id),
+            },
+            Err(err) => Self::failed(issue_id, err),
         }
     }
 
-    anyhow::bail!("State '{}' not found for team", state)
-}
-
-/// Resolve a label name to a UUID.
-async fn resolve_label_id(client: &LinearClient, label: &str) -> Result<String> {
-    // If already a UUID, return as-is
-    if is_uuid(label) {
-        return Ok(label.to_string());
-    }
-
-    // Fetch all labels
-    let query = r#"
-        query {
-            issueLabels(first: 250) {
-                nodes {
-                    id
-                    name
-                }
-            }
-        }
-    "#;
-
-    let result = client.query(query, None).await?;
-    let empty = vec![];
-    let labels = result["data"]["issueLabels"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
-    // Try to match by name (case-insensitive)
-    for l in labels {
-        let name = l["name"].as_str().unwrap_or("");
-        if name.eq_ignore_ascii_case(label) {
-            if let Some(id) = l["id"].as_str() {
-                return Ok(id.to_string());
-            }
+    fn failed(issue_id: &str, err: anyhow::Error) -> Self {
+        BulkResult {
+            issue_id: issue_id.to_string(),
+            success: false,
+            identifier: None,
+            error: Some(err.to_string()),
+            queued: None,
         }
     }
-
-    anyhow::bail!("Label not found: {}", label)
 }
 
 /// Get issue details including UUID and team ID from identifier (e.g., "LIN-123")
-async fn get_issue_info(
-    client: &LinearClient,
-    issue_id: &str,
-) -> Result<(String, String, Option<String>)> {
+async fn get_issue_info(client: &LinearClient, issue_id: &str) -> Result<(String, String)> {
     let query = r#"
         query($id: String!) {
             issue(id: $id) {
@@ -238,249 +207,534 @@ async fn get_issue_info(
         .ok_or_else(|| anyhow::anyhow!("Failed to get team ID"))?
         .to_string();
 
-    let identifier = issue["identifier"].as_str().map(|s| s.to_string());
+    Ok((uuid, team_id))
+}
+
+/// An issue to change: as given with `-i`, or matched by `--where` with its
+/// UUID and team already fetched
+struct Target {
+    /// What the results show: the `-i` argument or the issue's identifier
+    issue_id: String,
+    /// The issue's UUID and team ID, when the selection fetched them
+    found: Option<(String, String)>,
+}
+
+impl Target {
+    fn given(issue_id: String) -> Self {
+        Target {
+            issue_id,
+            found: None,
+        }
+    }
+
+    /// The issue's UUID and team ID, looked up unless already known
+    async fn locate(&self, client: &LinearClient) -> Result<(String, String)> {
+        match &self.found {
+            Some(found) => Ok(found.clone()),
+            None => get_issue_info(client, &self.issue_id).await,
+        }
+    }
 
-    Ok((uuid, team_id, identifier))
+    /// The ID to send the change to
+    fn id(&self) -> &str {
+        self.found.as_ref().map_or(&self.issue_id, |(uuid, _)| uuid)
+    }
 }
 
-pub async fn handle(cmd: BulkCommands) -> Result<()> {
+pub async fn handle(cmd: BulkCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        BulkCommands::UpdateState { state, issues } => bulk_update_state(&state, issues).await,
-        BulkCommands::Assign { user, issues } => bulk_assign(&user, issues).await,
-        BulkCommands::Label { label, issues } => bulk_label(&label, issues).await,
-        BulkCommands::Unassign { issues } => bulk_unassign(issues).await,
+        BulkCommands::UpdateState {
+            state,
+            issues,
+            filter,
+            dry_run,
+            force,
+            queue,
+        } => {
+            let change = Change::State(&state);
+            let selected = select(issues, filter, &change, dry_run, force, output).await?;
+            if let Some(issues) = selected {
+                bulk_update_state(&state, issues, queue, output).await?;
+            }
+            Ok(())
+        }
+        BulkCommands::Assign {
+            user,
+            issues,
+            filter,
+            dry_run,
+            force,
+            queue,
+        } => {
+            let change = Change::Assign(Some(&user));
+            let selected = select(issues, filter, &change, dry_run, force, output).await?;
+            if let Some(issues) = selected {
+                bulk_assign(Some(&user), issues, queue, output).await?;
+            }
+            Ok(())
+        }
+        BulkCommands::Label {
+            label,
+            issues,
+            filter,
+            dry_run,
+            force,
+            queue,
+        } => {
+            let change = Change::Label(&label);
+            let selected = select(issues, filter, &change, dry_run, force, output).await?;
+            if let Some(issues) = selected {
+                bulk_label(&label, issues, queue, output).await?;
+            }
+            Ok(())
+        }
+        BulkCommands::Unassign {
+            issues,
+            filter,
+            dry_run,
+            force,
+            queue,
+        } => {
+            let change = Change::Assign(None);
+            let selected = select(issues, filter, &change, dry_run, force, output).await?;
+            if let Some(issues) = selected {
+                bulk_assign(None, issues, queue, output).await?;
+            }
+            Ok(())
+        }
     }
 }
 
-async fn bulk_update_state(state: &str, issues: Vec<String>) -> Result<()> {
+/// What a bulk command does to each issue, for the preview
+enum Change<'a> {
+    State(&'a str),
+    Assign(Option<&'a str>),
+    Label(&'a str),
+}
+
+impl Change<'_> {
+    /// Whether the issue already has the value, so changing it would do nothing.
+    /// Users are only known by name once resolved, so assigning is never a no-op.
+    fn unchanged(&self, issue: &Issue) -> bool {
+        let same =
+            |id: &str, name: &str, wanted: &str| id == wanted || name.eq_ignore_ascii_case(wanted);
+        match self {
+            Change::State(state) => issue
+                .state
+                .as_ref()
+                .is_some_and(|s| same(&s.id, &s.name, state)),
+            Change::Label(label) => issue
+                .labels
+                .as_ref()
+                .is_some_and(|l| l.nodes.iter().any(|l| same(&l.id, &l.name, label))),
+            Change::Assign(None) => issue.assignee.is_none(),
+            Change::Assign(Some(_)) => false,
+        }
+    }
+
+    /// E.g. "state: In Review -> Done", from the issue's current value if known
+    fn planned(&self, issue: Option<&Issue>) -> String {
+        let (field, from, to) = match self {
+            Change::Label(label) => return format!("+ label {}", label),
+            Change::State(state) => ("state", issue.map(Issue::state_name), *state),
+            Change::Assign(user) => (
+                "assignee",
+                issue.map(Issue::assignee_name),
+                user.unwrap_or("-"),
+            ),
+        };
+        match from {
+            Some(from) => format!("{}: {} -> {}", field, from, to),
+            None => format!("{}: {}", field, to),
+        }
+    }
+}
+
+#[derive(Tabled, Serialize)]
+struct PreviewRow {
+    #[tabled(rename = "ID")]
+    identifier: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Change")]
+    change: String,
+}
+
+/// The issues to change: the `-i` list, or those matching `--where` once the
+/// preview is confirmed. `None` when there is nothing to do (a dry run, or
+/// the user declined). With a data `output`, a dry run prints the planned
+/// changes as data and the confirmation preview goes to stderr.
+async fn select(
+    issues: Vec<String>,
+    filter: Option<String>,
+    change: &Change<'_>,
+    dry_run: bool,
+    force: bool,
+    output: OutputFormat,
+) -> Result<Option<Vec<Target>>> {
+    let Some(expression) = filter else {
+        if dry_run && !output.is_table() {
+            let rows: Vec<PreviewRow> = issues
+                .iter()
+                .map(|issue| PreviewRow {
+                    identifier: issue.clone(),
+                    title: String::new(),
+                    change: change.planned(None),
+                })
+                .collect();
+            print_value(&rows, output)?;
+            return Ok(None);
+        }
+        if dry_run {
+            for issue in &issues {
+                println!("  {} {}", issue.cyan(), change.planned(None));
+            }
+            println!("\nDry run: {} issues would change.", issues.len());
+            return Ok(None);
+        }
+        return Ok(Some(issues.into_iter().map(Target::given).collect()));
+    };
+
+    // Compile the filter first so a typo fails before any request is made
+    let filter = filter::compile(&expression)?;
+    let matched = matching_issues(&filter).await?;
+    if matched.is_empty() {
+        if !output.is_table() {
+            print_value(&Vec::<PreviewRow>::new(), output)?;
+        } else {
+            println!("No issues match: {}", expression);
+        }
+        return Ok(None);
+    }
+
+    let rows: Vec<PreviewRow> = matched
+        .iter()
+        .map(|issue| PreviewRow {
+            identifier: issue.identifier.clone(),
+            title: truncate(&issue.title, 50),
+            change: if change.unchanged(issue) {
+                "(no change)".to_string()
+            } else {
+                change.planned(Some(issue))
+            },
+        })
+        .collect();
+    // Issues that already have the value are shown but left alone
+    let matched: Vec<Issue> = matched
+        .into_iter()
+        .filter(|issue| !change.unchanged(issue))
+        .collect();
+    if !output.is_table() {
+        if dry_run {
+            print_value(&rows, output)?;
+            return Ok(None);
+        }
+        // Keep stdout for the results
+        eprintln!("{}\n", Table::new(rows));
+    } else {
+        println!("{}", Table::new(rows));
+        println!();
+    }
+
+    if matched.is_empty() {
+        if !output.is_table() {
+            print_value(&Vec::<PreviewRow>::new(), output)?;
+        } else {
+            println!("Nothing to change: every matching issue already has it.");
+        }
+        return Ok(None);
+    }
+
+    if dry_run {
+        println!("Dry run: {} issues would change.", matched.len());
+        return Ok(None);
+    }
+    if !force {
+        let confirm = dialoguer::Confirm::new()
+            .with_prompt(format!("Change {} issues?", matched.len()))
+            .default(false)
+            .interact()?;
+
+        if !confirm {
+            eprintln!("Cancelled.");
+            return Ok(None);
+        }
+    }
+
+    let targets = matched
+        .into_iter()
+        .map(|issue| Target {
+            found: issue.team.map(|team| (issue.id, team.id)),
+            issue_id: issue.identifier,
+        })
+        .collect();
+    Ok(Some(targets))
+}
+
+/// Every issue matching a compiled filter
+async fn matching_issues(filter: &Value) -> Result<Vec<Issue>> {
+    let client = LinearClient::new()?;
+
+    let query = r#"
+        query($filter: IssueFilter, $first: Int, $after: String) {
+            issues(filter: $filter, first: $first, after: $after) {
+                nodes {
+                    id
+                    identifier
+                    title
+                    state { id name }
+                    assignee { id name }
+                    labels { nodes { id name } }
+                    team { id key name }
+                }
+                pageInfo { hasNextPage endCursor }
+            }
+        }
+    "#;
+
+    let mut issues = Vec::new();
+    client
+        .paginate(
+            query,
+            Some(json!({ "filter": filter })),
+            &["issues"],
+            PageOptions::all(),
+            |nodes| {
+                issues.extend(models::from_nodes::<Issue>(&nodes)?);
+                Ok(())
+            },
+        )
+        .await?;
+    Ok(issues)
+}
+
+async fn bulk_update_state(
+    state: &str,
+    issues: Vec<Target>,
+    queue: bool,
+    output: OutputFormat,
+) -> Result<()> {
     if issues.is_empty() {
-        println!("No issues specified.");
-        return Ok(());
+        return report(&[], "", output);
     }
 
-    println!(
-        "{} Updating state to '{}' for {} issues...",
-        ">>".cyan(),
-        state,
-        issues.len()
-    );
+    if output.is_table() {
+        println!(
+            "{} Updating state to '{}' for {} issues...",
+            ">>".cyan(),
+            state,
+            issues.len()
+        );
+    }
 
     let client = LinearClient::new()?;
-    let state_owned = state.to_string();
 
     let futures: Vec<_> = issues
         .iter()
-        .map(|issue_id| {
+        .map(|target| {
             let client = &client;
-            let state = &state_owned;
-            let id = issue_id.clone();
-            async move { update_issue_state(client, &id, state).await }
+            async move {
+                let operation = Operation::SetState {
+                    issue: target.issue_id.clone(),
+                    state: state.to_string(),
+                };
+                let outcome = async {
+                    let (uuid, team_id) = target.locate(client).await?;
+                    set_state(client, &uuid, &team_id, state).await
+                }
+                .await;
+                BulkResult::new(&target.issue_id, outcome, queue.then_some(operation)).await
+            }
         })
         .collect();
 
     let results = join_all(futures).await;
-    print_summary(&results, "state updated");
-
-    Ok(())
+    report(&results, "state updated", output)
 }
 
-async fn bulk_assign(user: &str, issues: Vec<String>) -> Result<()> {
+/// Assign the issues to `user`, or unassign them
+async fn bulk_assign(
+    user: Option<&str>,
+    issues: Vec<Target>,
+    queue: bool,
+    output: OutputFormat,
+) -> Result<()> {
     if issues.is_empty() {
-        println!("No issues specified.");
-        return Ok(());
+        return report(&[], "", output);
     }
 
-    println!(
-        "{} Assigning {} issues to '{}'...",
-        ">>".cyan(),
-        issues.len(),
-        user
-    );
+    match user {
+        _ if !output.is_table() => {}
+        Some(user) => println!(
+            "{} Assigning {} issues to '{}'...",
+            ">>".cyan(),
+            issues.len(),
+            user
+        ),
+        None => println!("{} Unassigning {} issues...", ">>".cyan(), issues.len()),
+    }
 
     let client = LinearClient::new()?;
+    let operation = |issue_id: &str| Operation::Assign {
+        issue: issue_id.to_string(),
+        user: user.map(str::to_string),
+    };
 
     // Resolve the user ID once upfront
-    let user_id = match resolve_user_id(&client, user).await {
-        Ok(id) => id,
-        Err(e) => {
-            println!("{} Failed to resolve user '{}': {}", "x".red(), user, e);
-            return Ok(());
-        }
+    let user_id = match user {
+        Some(user) => match Resolver::new(&client).user_id(user).await {
+            Ok(id) => Some(id),
+            Err(e) if queue && api::is_unreachable(&e) => {
+                return queue_all(&issues, operation, "assignment", output).await;
+            }
+            Err(e) => {
+                let message = format!("Failed to resolve user '{}': {}", user, e);
+                return fail_all(&issues, &message, output);
+            }
+        },
+        None => None,
     };
 
     let futures: Vec<_> = issues
         .iter()
-        .map(|issue_id| {
+        .map(|target| {
             let client = &client;
-            let user_id = &user_id;
-            let id = issue_id.clone();
-            async move { update_issue_assignee(client, &id, Some(user_id)).await }
+            let user_id = user_id.as_deref();
+            let issue_id = &target.issue_id;
+            async move {
+                let outcome = set_assignee(client, target.id(), user_id).await;
+                BulkResult::new(issue_id, outcome, queue.then(|| operation(issue_id))).await
+            }
         })
         .collect();
 
     let results = join_all(futures).await;
-    print_summary(&results, "assigned");
-
-    Ok(())
+    let action = if user.is_some() {
+        "assigned"
+    } else {
+        "unassigned"
+    };
+    report(&results, action, output)
 }
 
-async fn bulk_label(label: &str, issues: Vec<String>) -> Result<()> {
+async fn bulk_label(
+    label: &str,
+    issues: Vec<Target>,
+    queue: bool,
+    output: OutputFormat,
+) -> Result<()> {
     if issues.is_empty() {
-        println!("No issues specified.");
-        return Ok(());
+        return report(&[], "", output);
     }
 
-    println!(
-        "{} Adding label '{}' to {} issues...",
-        ">>".cyan(),
-        label,
-        issues.len()
-    );
+    if output.is_table() {
+        println!(
+            "{} Adding label '{}' to {} issues...",
+            ">>".cyan(),
+            label,
+            issues.len()
+        );
+    }
 
     let client = LinearClient::new()?;
+    let operation = |issue_id: &str| Operation::AddLabel {
+        issue: issue_id.to_string(),
+        label: label.to_string(),
+    };
 
     // Resolve the label ID once upfront
-    let label_id = match resolve_label_id(&client, label).await {
+    let label_id = match Resolver::new(&client).label_id(label, None).await {
         Ok(id) => id,
+        Err(e) if queue && api::is_unreachable(&e) => {
+            return queue_all(&issues, operation, "label", output).await;
+        }
         Err(e) => {
-            println!("{} Failed to resolve label '{}': {}", "x".red(), label, e);
-            return Ok(());
+            let message = format!("Failed to resolve label '{}': {}", label, e);
+            return fail_all(&issues, &message, output);
         }
     };
 
     let futures: Vec<_> = issues
         .iter()
-        .map(|issue_id| {
+        .map(|target| {
             let client = &client;
             let label_id = &label_id;
-            let id = issue_id.clone();
-            async move { add_label_to_issue(client, &id, label_id).await }
+            let issue_id = &target.issue_id;
+            async move {
+                let outcome = add_label(client, target.id(), label_id).await;
+                BulkResult::new(issue_id, outcome, queue.then(|| operation(issue_id))).await
+            }
         })
         .collect();
 
     let results = join_all(futures).await;
-    print_summary(&results, "labeled");
+    report(&results, "labeled", output)
+}
 
-    Ok(())
+/// Queue the change for every issue when Linear couldn't be reached up front
+async fn queue_all(
+    issues: &[Target],
+    operation: impl Fn(&str) -> Operation,
+    what: &str,
+    output: OutputFormat,
+) -> Result<()> {
+    if output.is_table() {
+        println!(
+            "{} Linear can't be reached; queueing the {} instead",
+            "~".yellow(),
+            what
+        );
+    }
+    let mut results = Vec::with_capacity(issues.len());
+    for Target { issue_id, .. } in issues {
+        results.push(BulkResult::queue(issue_id, operation(issue_id)).await);
+    }
+    report(&results, "", output)
 }
 
-async fn bulk_unassign(issues: Vec<String>) -> Result<()> {
-    if issues.is_empty() {
-        println!("No issues specified.");
+/// Report a failure that stops every issue, e.g. a user name that doesn't
+/// resolve: one line for the table, or a failed result per issue as data
+fn fail_all(issues: &[Target], message: &str, output: OutputFormat) -> Result<()> {
+    if output.is_table() {
+        println!("{} {}", "x".red(), message);
         return Ok(());
     }
-
-    println!("{} Unassigning {} issues...", ">>".cyan(), issues.len());
-
-    let client = LinearClient::new()?;
-
-    let futures: Vec<_> = issues
+    let results: Vec<BulkResult> = issues
         .iter()
-        .map(|issue_id| {
-            let client = &client;
-            let id = issue_id.clone();
-            async move { update_issue_assignee(client, &id, None).await }
-        })
+        .map(|target| BulkResult::failed(&target.issue_id, anyhow::anyhow!("{}", message)))
         .collect();
-
-    let results = join_all(futures).await;
-    print_summary(&results, "unassigned");
-
-    Ok(())
+    print_value(&results, output)
 }
 
-async fn update_issue_state(client: &LinearClient, issue_id: &str, state: &str) -> BulkResult {
-    // First, get issue UUID and team ID
-    let (uuid, team_id, identifier) = match get_issue_info(client, issue_id).await {
-        Ok(info) => info,
-        Err(e) => {
-            return BulkResult {
-                issue_id: issue_id.to_string(),
-                success: false,
-                identifier: None,
-                error: Some(e.to_string()),
-            };
-        }
-    };
-
-    // Resolve state name to UUID for this team
-    let state_id = match resolve_state_id(client, &team_id, state).await {
-        Ok(id) => id,
-        Err(e) => {
-            return BulkResult {
-                issue_id: issue_id.to_string(),
-                success: false,
-                identifier,
-                error: Some(e.to_string()),
-            };
-        }
-    };
-
-    let mutation = r#"
-        mutation($id: String!, $input: IssueUpdateInput!) {
-            issueUpdate(id: $id, input: $input) {
-                success
-                issue {
-                    identifier
-                    title
-                }
-            }
-        }
-    "#;
-
-    let input = json!({ "stateId": state_id });
-
-    match client
-        .mutate(mutation, Some(json!({ "id": uuid, "input": input })))
-        .await
-    {
-        Ok(result) => {
-            if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-                let identifier = result["data"]["issueUpdate"]["issue"]["identifier"]
-                    .as_str()
-                    .map(|s| s.to_string());
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: true,
-                    identifier,
-                    error: None,
-                }
-            } else {
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: false,
-                    identifier: None,
-                    error: Some("Update failed".to_string()),
-                }
-            }
-        }
-        Err(e) => BulkResult {
-            issue_id: issue_id.to_string(),
-            success: false,
-            identifier: None,
-            error: Some(e.to_string()),
-        },
-    }
+/// Move an issue to a workflow state, resolved in the issue's team. Returns
+/// the updated issue's identifier and `updatedAt`.
+pub async fn set_state(
+    client: &LinearClient,
+    issue_id: &str,
+    team_id: &str,
+    state: &str,
+) -> Result<Value> {
+    let state_id = Resolver::new(client).state_id(team_id, state).await?;
+    update_issue(client, issue_id, json!({ "stateId": state_id })).await
 }
 
-async fn update_issue_assignee(
+/// Assign an issue to a user UUID, or unassign it with `None`
+pub async fn set_assignee(
     client: &LinearClient,
     issue_id: &str,
     assignee_id: Option<&str>,
-) -> BulkResult {
-    // First, get issue UUID
-    let (uuid, _team_id, identifier) = match get_issue_info(client, issue_id).await {
-        Ok(info) => info,
-        Err(e) => {
-            return BulkResult {
-                issue_id: issue_id.to_string(),
-                success: false,
-                identifier: None,
-                error: Some(e.to_string()),
-            };
-        }
-    };
+) -> Result<Value> {
+    update_issue(client, issue_id, json!({ "assigneeId": assignee_id })).await
+}
 
+/// Add a label UUID to an issue, keeping its other labels
+pub async fn add_label(client: &LinearClient, issue_id: &str, label_id: &str) -> Result<Value> {
+    // `addedLabelIds` keeps the other labels without reading them first
+    update_issue(client, issue_id, json!({ "addedLabelIds": [label_id] })).await
+}
+
+/// Send an `issueUpdate` and return the updated issue's identifier and `updatedAt`
+async fn update_issue(client: &LinearClient, uuid: &str, input: Value) -> Result<Value> {
     let mutation = r#"
         mutation($id: String!, $input: IssueUpdateInput!) {
             issueUpdate(id: $id, input: $input) {
@@ -488,172 +742,49 @@ async fn update_issue_assignee(
                 issue {
                     identifier
                     title
+                    updatedAt
                 }
             }
         }
     "#;
 
-    let input = match assignee_id {
-        Some(id) => json!({ "assigneeId": id }),
-        None => json!({ "assigneeId": null }),
-    };
-
-    match client
+    let result = client
         .mutate(mutation, Some(json!({ "id": uuid, "input": input })))
-        .await
-    {
-        Ok(result) => {
-            if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-                let identifier = result["data"]["issueUpdate"]["issue"]["identifier"]
-                    .as_str()
-                    .map(|s| s.to_string())
-                    .or(identifier);
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: true,
-                    identifier,
-                    error: None,
-                }
-            } else {
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: false,
-                    identifier,
-                    error: Some("Update failed".to_string()),
-                }
-            }
-        }
-        Err(e) => BulkResult {
-            issue_id: issue_id.to_string(),
-            success: false,
-            identifier,
-            error: Some(e.to_string()),
-        },
+        .await?;
+
+    if result["data"]["issueUpdate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Update failed");
     }
+    Ok(result["data"]["issueUpdate"]["issue"].clone())
 }
 
-async fn add_label_to_issue(client: &LinearClient, issue_id: &str, label_id: &str) -> BulkResult {
-    // First, get existing labels for the issue (using the issue identifier/UUID)
-    let query = r#"
-        query($id: String!) {
-            issue(id: $id) {
-                id
-                identifier
-                labels {
-                    nodes {
-                        id
-                    }
-                }
-            }
-        }
-    "#;
-
-    let (uuid, identifier, existing_label_ids) =
-        match client.query(query, Some(json!({ "id": issue_id }))).await {
-            Ok(result) => {
-                if result["data"]["issue"].is_null() {
-                    return BulkResult {
-                        issue_id: issue_id.to_string(),
-                        success: false,
-                        identifier: None,
-                        error: Some("Issue not found".to_string()),
-                    };
-                }
-
-                let uuid = result["data"]["issue"]["id"]
-                    .as_str()
-                    .unwrap_or(issue_id)
-                    .to_string();
-
-                let identifier = result["data"]["issue"]["identifier"]
-                    .as_str()
-                    .map(|s| s.to_string());
-
-                let labels: Vec<String> = result["data"]["issue"]["labels"]["nodes"]
-                    .as_array()
-                    .unwrap_or(&vec![])
-                    .iter()
-                    .filter_map(|l| l["id"].as_str().map(|s| s.to_string()))
-                    .collect();
-
-                (uuid, identifier, labels)
-            }
-            Err(e) => {
-                return BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: false,
-                    identifier: None,
-                    error: Some(e.to_string()),
-                };
-            }
-        };
-
-    let mut label_ids = existing_label_ids;
-
-    // Add the new label if not already present
-    if !label_ids.contains(&label_id.to_string()) {
-        label_ids.push(label_id.to_string());
+/// The per-issue results: a summary for the table, the results themselves as data
+fn report(results: &[BulkResult], action: &str, output: OutputFormat) -> Result<()> {
+    if !output.is_table() {
+        return print_value(results, output);
     }
-
-    // Update the issue with the new label list
-    let mutation = r#"
-        mutation($id: String!, $input: IssueUpdateInput!) {
-            issueUpdate(id: $id, input: $input) {
-                success
-                issue {
-                    identifier
-                }
-            }
-        }
-    "#;
-
-    let input = json!({ "labelIds": label_ids });
-
-    match client
-        .mutate(mutation, Some(json!({ "id": uuid, "input": input })))
-        .await
-    {
-        Ok(result) => {
-            if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-                let identifier = result["data"]["issueUpdate"]["issue"]["identifier"]
-                    .as_str()
-                    .map(|s| s.to_string())
-                    .or(identifier);
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: true,
-                    identifier,
-                    error: None,
-                }
-            } else {
-                BulkResult {
-                    issue_id: issue_id.to_string(),
-                    success: false,
-                    identifier,
-                    error: Some("Update failed".to_string()),
-                }
-            }
-        }
-        Err(e) => BulkResult {
-            issue_id: issue_id.to_string(),
-            success: false,
-            identifier,
-            error: Some(e.to_string()),
-        },
+    if results.is_empty() {
+        println!("No issues specified.");
+    } else {
+        print_summary(results, action);
     }
+    Ok(())
 }
 
 fn print_summary(results: &[BulkResult], action: &str) {
     println!();
 
     let success_count = results.iter().filter(|r| r.success).count();
-    let failure_count = results.len() - success_count;
+    let queued_count = results.iter().filter(|r| r.queued.is_some()).count();
+    let failure_count = results.len() - success_count - queued_count;
 
     // Print individual results
     for result in results {
         if result.success {
             let display_id = result.identifier.as_deref().unwrap_or(&result.issue_id);
             println!("  {} {} {}", "+".green(), display_id.cyan(), action);
+        } else if result.queued.is_some() {
+            println!("  {} {} queued", "~".yellow(), result.issue_id.cyan());
         } else {
             let error_msg = result.error.as_deref().unwrap_or("Unknown error");
             println!(
@@ -667,7 +798,7 @@ fn print_summary(results: &[BulkResult], action: &str) {
 
     // Print summary
     println!();
-    println!(
+    print!(
         "{} Summary: {} succeeded, {} failed",
         ">>".cyan(),
         success_count.to_string().green(),
@@ -677,4 +808,11 @@ fn print_summary(results: &[BulkResult], action: &str) {
             failure_count.to_string()
         }
     );
+    if queued_count > 0 {
+        print!(
+            ", {} queued (send with: linear queue replay)",
+            queued_count.to_string().yellow()
+        );
+    }
+    println!();
 }
@@ -1,15 +1,18 @@
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
+use serde_json::json;
 use tabled::{Table, Tabled};
 
-use crate::cache::{Cache, CacheType};
+use crate::cache::{Cache, CacheStatus, CacheType, Namespace};
+use crate::output::print_value;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum CacheCommands {
     /// Clear all cached data
     Clear {
-        /// Only clear a specific cache type (teams, users, statuses, labels)
+        /// Only clear a specific cache type (teams, users, statuses, labels, viewer, schema)
         #[arg(short, long)]
         r#type: Option<String>,
     },
@@ -31,15 +34,15 @@ struct CacheStatusRow {
     items: String,
 }
 
-pub async fn handle(cmd: CacheCommands) -> Result<()> {
+pub async fn handle(cmd: CacheCommands, output: OutputFormat) -> Result<()> {
     match cmd {
         CacheCommands::Clear { r#type } => clear_cache(r#type).await,
-        CacheCommands::Status => show_status().await,
+        CacheCommands::Status => show_status(output).await,
     }
 }
 
 async fn clear_cache(cache_type: Option<String>) -> Result<()> {
-    let cache = Cache::new()?;
+    let namespaces = Cache::namespaces()?;
 
     if let Some(type_str) = cache_type {
         let cache_type = match type_str.to_lowercase().as_str() {
@@ -47,61 +50,128 @@ async fn clear_cache(cache_type: Option<String>) -> Result<()> {
             "users" => CacheType::Users,
             "statuses" | "states" => CacheType::Statuses,
             "labels" => CacheType::Labels,
+            "viewer" | "me" => CacheType::Viewer,
+            "schema" => CacheType::Schema,
             _ => {
                 anyhow::bail!(
-                    "Unknown cache type: '{}'. Valid types: teams, users, statuses, labels",
+                    "Unknown cache type: '{}'. Valid types: teams, users, statuses, labels, viewer, schema",
                     type_str
                 );
             }
         };
-        cache.clear_type(cache_type)?;
+        for namespace in &namespaces {
+            namespace.cache.clear_type(cache_type)?;
+        }
         println!(
             "{} Cleared {} cache",
             "+".green(),
             cache_type.display_name()
         );
     } else {
-        cache.clear_all()?;
+        for namespace in &namespaces {
+            namespace.cache.clear_all()?;
+        }
         println!("{} Cleared all caches", "+".green());
     }
 
     Ok(())
 }
 
-async fn show_status() -> Result<()> {
-    let cache = Cache::new()?;
-    let statuses = cache.status();
+/// The cache types kept in a namespace: the schema is shared, the rest are per organization
+fn types_in(namespace: &Namespace) -> Vec<CacheStatus> {
+    let shared = namespace.is_shared();
+    namespace
+        .cache
+        .status()
+        .into_iter()
+        .filter(|s| (s.cache_type == CacheType::Schema) == shared)
+        .collect()
+}
+
+/// "Acme (work, personal)" for an organization, "Shared" for the shared cache
+fn namespace_title(namespace: &Namespace) -> String {
+    if namespace.is_shared() {
+        return "Shared".to_string();
+    }
+    let name = namespace.name.as_deref().unwrap_or(&namespace.id);
+    if namespace.workspaces.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} (workspace {})", name, namespace.workspaces.join(", "))
+    }
+}
+
+async fn show_status(output: OutputFormat) -> Result<()> {
+    let namespaces = Cache::namespaces()?;
+
+    if !output.is_table() {
+        let values: Vec<_> = namespaces
+            .iter()
+            .map(|namespace| {
+                let caches: Vec<_> = types_in(namespace)
+                    .iter()
+                    .map(|s| {
+                        json!({
+                            "type": s.cache_type.display_name(),
+                            "valid": s.valid,
+                            "ageSeconds": s.age_seconds,
+                            "sizeBytes": s.size_bytes,
+                            "items": s.item_count,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "organization": { "id": namespace.id, "name": namespace.name },
+                    "workspaces": namespace.workspaces,
+                    "caches": caches,
+                })
+            })
+            .collect();
+        return print_value(&values, output);
+    }
 
     println!("{}", "Cache Status".bold());
     println!("{}", "-".repeat(50));
+    if namespaces.is_empty() {
+        println!("Nothing cached yet.");
+        return Ok(());
+    }
 
-    let rows: Vec<CacheStatusRow> = statuses
-        .iter()
-        .map(|s| CacheStatusRow {
-            cache_type: s.cache_type.display_name().to_string(),
-            valid: if s.valid {
-                "Yes".green().to_string()
-            } else {
-                "No".dimmed().to_string()
-            },
-            age: s.age_display(),
-            size: s.size_display(),
-            items: s
-                .item_count
-                .map(|c| c.to_string())
-                .unwrap_or_else(|| "-".to_string()),
-        })
-        .collect();
-
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
+    let mut valid_count = 0;
+    let mut total_count = 0;
+    let mut total_size: u64 = 0;
+    for namespace in &namespaces {
+        let statuses = types_in(namespace);
+        valid_count += statuses.iter().filter(|s| s.valid).count();
+        total_count += statuses.len();
+        total_size += statuses.iter().filter_map(|s| s.size_bytes).sum::<u64>();
 
-    // Summary
-    let valid_count = statuses.iter().filter(|s| s.valid).count();
-    let total_size: u64 = statuses.iter().filter_map(|s| s.size_bytes).sum();
+        let rows: Vec<CacheStatusRow> = statuses
+            .iter()
+            .map(|s| CacheStatusRow {
+                cache_type: s.cache_type.display_name().to_string(),
+                valid: if s.valid {
+                    "Yes".green().to_string()
+                } else {
+                    "No".dimmed().to_string()
+                },
+                age: s.age_display(),
+                size: s.size_display(),
+                items: s
+                    .item_count
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            })
+            .collect();
 
+        println!();
+        println!("{}", namespace_title(namespace).bold());
+        println!("{}", Table::new(rows));
+    }
+
+    // Summary
     println!();
-    println!("{} of {} caches valid", valid_count, CacheType::all().len());
+    println!("{} of {} caches valid", valid_count, total_count);
     if total_size > 0 {
         let size_display = if total_size < 1024 {
             format!("{} B", total_size)
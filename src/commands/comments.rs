@@ -1,12 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
+use serde_json::{json, Value};
 use tabled::{Table, Tabled};
 
-use crate::api::LinearClient;
+use crate::api::{self, LinearClient};
+use crate::fields;
+use crate::mirror::{self, Entity, Mirror};
+use crate::models::{self, Comment, Issue};
+use crate::output::{print_value, truncate};
 use crate::OutputFormat;
 
+use super::queue::{self, Operation};
+
 #[derive(Subcommand)]
 pub enum CommentCommands {
     /// List comments for an issue
@@ -14,6 +20,9 @@ pub enum CommentCommands {
     List {
         /// Issue ID to list comments for
         issue_id: String,
+        /// Read from the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Create a new comment on an issue
     Create {
@@ -25,6 +34,9 @@ pub enum CommentCommands {
         /// Parent comment ID to reply to (optional)
         #[arg(short, long)]
         parent_id: Option<String>,
+        /// Queue the comment to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
 }
 
@@ -42,16 +54,67 @@ struct CommentRow {
 
 pub async fn handle(cmd: CommentCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        CommentCommands::List { issue_id } => list_comments(&issue_id, output).await,
+        CommentCommands::List { issue_id, offline } => {
+            list_comments(&issue_id, offline, output).await
+        }
         CommentCommands::Create {
             issue_id,
             body,
             parent_id,
-        } => create_comment(&issue_id, &body, parent_id).await,
+            queue,
+        } => create_comment(&issue_id, &body, parent_id, queue, output).await,
     }
 }
 
-async fn list_comments(issue_id: &str, output: OutputFormat) -> Result<()> {
+async fn list_comments(issue_id: &str, offline: bool, output: OutputFormat) -> Result<()> {
+    let issue = if offline {
+        offline_comments(issue_id).await?
+    } else {
+        fetch_comments(issue_id).await?
+    };
+
+    // JSON output - return raw data for LLM consumption
+    if !output.is_table() {
+        return print_value(&issue, output);
+    }
+
+    let header: Issue = models::from_value(&issue)?;
+    println!("{} {}", header.identifier.bold(), header.title);
+    println!("{}", "─".repeat(50));
+
+    let comments: Vec<Comment> = models::from_value(&issue["comments"]["nodes"])?;
+
+    if comments.is_empty() {
+        println!("No comments found for this issue.");
+        return Ok(());
+    }
+
+    let rows: Vec<CommentRow> = comments
+        .iter()
+        .map(|c| CommentRow {
+            author: c
+                .user
+                .as_ref()
+                .map_or("Unknown", |u| u.name.as_str())
+                .to_string(),
+            created_at: c
+                .created_at
+                .map(|d| d.date_naive().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            body: truncate(&c.body, 63).replace('\n', " "),
+            id: c.id.clone(),
+        })
+        .collect();
+
+    let table = Table::new(rows).to_string();
+    println!("{}", table);
+    println!("\n{} comments", comments.len());
+
+    Ok(())
+}
+
+/// An issue with its comments, from the API
+async fn fetch_comments(issue_id: &str) -> Result<Value> {
     let client = LinearClient::new()?;
 
     let query = r#"
@@ -65,7 +128,7 @@ async fn list_comments(issue_id: &str, output: OutputFormat) -> Result<()> {
                         id
                         body
                         createdAt
-                        user { name email }
+                        user { id name email }
                         parent { id }
                     }
                 }
@@ -73,77 +136,116 @@ async fn list_comments(issue_id: &str, output: OutputFormat) -> Result<()> {
         }
     "#;
 
-    let result = client
-        .query(query, Some(json!({ "issueId": issue_id })))
+    let mut result = client
+        .query(
+            &fields::narrow(query, &["issue"]),
+            Some(json!({ "issueId": issue_id })),
+        )
         .await?;
-    let issue = &result["data"]["issue"];
+    let issue = result["data"]["issue"].take();
 
     if issue.is_null() {
         anyhow::bail!("Issue not found: {}", issue_id);
     }
+    Ok(issue)
+}
 
-    // JSON output - return raw data for LLM consumption
-    if matches!(output, OutputFormat::Json) {
-        println!("{}", serde_json::to_string_pretty(&issue)?);
-        return Ok(());
+/// The same shape from the mirror, oldest comment first
+async fn offline_comments(issue_id: &str) -> Result<Value> {
+    let mirror = Mirror::open_offline().await?;
+    let issue = mirror
+        .issue(issue_id)?
+        .with_context(|| format!("Issue not found in the mirror: {}", issue_id))?;
+
+    let filter = json!({
+        "issue": { "id": { "eq": issue["id"] } },
+        "archivedAt": { "null": true },
+    });
+    let mut comments = mirror::select(mirror.all(Entity::Comments)?, &filter, None);
+    comments.sort_by(|a, b| a["createdAt"].as_str().cmp(&b["createdAt"].as_str()));
+    // Nested under their issue, as the API returns them
+    for comment in &mut comments {
+        if let Some(obj) = comment.as_object_mut() {
+            obj.remove("issue");
+        }
     }
 
-    let identifier = issue["identifier"].as_str().unwrap_or("");
-    let title = issue["title"].as_str().unwrap_or("");
+    Ok(json!({
+        "id": issue["id"],
+        "identifier": issue["identifier"],
+        "title": issue["title"],
+        "comments": { "nodes": comments },
+    }))
+}
 
-    println!("{} {}", identifier.bold(), title);
-    println!("{}", "─".repeat(50));
+async fn create_comment(
+    issue_id: &str,
+    body: &str,
+    parent_id: Option<String>,
+    queue: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let client = LinearClient::new()?;
 
-    let empty = vec![];
-    let comments = issue["comments"]["nodes"].as_array().unwrap_or(&empty);
+    // Like queued issues, a queued comment keeps the id of the first attempt
+    let id = queue.then(queue::new_id);
+    let comment =
+        match send_create(&client, id.as_deref(), issue_id, body, parent_id.as_deref()).await {
+            Ok(comment) => comment,
+            Err(err) if queue && api::is_unreachable(&err) => {
+                let operation = Operation::CommentCreate {
+                    id: id.unwrap_or_else(queue::new_id),
+                    issue: issue_id.to_string(),
+                    body: body.to_string(),
+                    parent: parent_id,
+                };
+                return queue::enqueue(operation, &err, output).await;
+            }
+            Err(err) => return Err(err),
+        };
 
-    if comments.is_empty() {
-        println!("No comments found for this issue.");
-        return Ok(());
-    }
+    let comment: Comment = models::from_value(&comment)?;
+    let (issue_identifier, issue_title) = comment
+        .issue
+        .as_ref()
+        .map_or(("", ""), |i| (i.identifier.as_str(), i.title.as_str()));
 
-    let rows: Vec<CommentRow> = comments
-        .iter()
-        .map(|c| {
-            let body = c["body"].as_str().unwrap_or("");
-            let truncated_body = if body.len() > 60 {
-                format!("{}...", body.chars().take(60).collect::<String>())
-            } else {
-                body.to_string()
-            };
-
-            let created_at = c["createdAt"]
-                .as_str()
-                .unwrap_or("")
-                .split('T')
-                .next()
-                .unwrap_or("-")
-                .to_string();
-
-            CommentRow {
-                author: c["user"]["name"].as_str().unwrap_or("Unknown").to_string(),
-                created_at,
-                body: truncated_body.replace('\n', " "),
-                id: c["id"].as_str().unwrap_or("").to_string(),
-            }
-        })
-        .collect();
+    println!(
+        "{} Comment added to {} {}",
+        "✓".green(),
+        issue_identifier,
+        issue_title
+    );
+    println!("  ID: {}", comment.id);
+    println!(
+        "  Author: {}",
+        comment.user.as_ref().map_or("", |u| u.name.as_str())
+    );
 
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} comments", comments.len());
+    let body_preview = comment.body.chars().take(80).collect::<String>();
+    if !body_preview.is_empty() {
+        println!("  Body: {}", body_preview.dimmed());
+    }
 
     Ok(())
 }
 
-async fn create_comment(issue_id: &str, body: &str, parent_id: Option<String>) -> Result<()> {
-    let client = LinearClient::new()?;
-
+/// Post a comment, optionally with a client-chosen UUID. Returns the comment.
+pub async fn send_create(
+    client: &LinearClient,
+    id: Option<&str>,
+    issue_id: &str,
+    body: &str,
+    parent_id: Option<&str>,
+) -> Result<Value> {
     let mut input = json!({
         "issueId": issue_id,
         "body": body
     });
 
+    if let Some(id) = id {
+        input["id"] = json!(id);
+    }
     if let Some(pid) = parent_id {
         input["parentId"] = json!(pid);
     }
@@ -156,8 +258,8 @@ async fn create_comment(issue_id: &str, body: &str, parent_id: Option<String>) -
                     id
                     body
                     createdAt
-                    user { name }
-                    issue { identifier title }
+                    user { id name }
+                    issue { id identifier title updatedAt }
                 }
             }
         }
@@ -167,35 +269,8 @@ async fn create_comment(issue_id: &str, body: &str, parent_id: Option<String>) -
         .mutate(mutation, Some(json!({ "input": input })))
         .await?;
 
-    if result["data"]["commentCreate"]["success"].as_bool() == Some(true) {
-        let comment = &result["data"]["commentCreate"]["comment"];
-        let issue_identifier = comment["issue"]["identifier"].as_str().unwrap_or("");
-        let issue_title = comment["issue"]["title"].as_str().unwrap_or("");
-
-        println!(
-            "{} Comment added to {} {}",
-            "✓".green(),
-            issue_identifier,
-            issue_title
-        );
-        println!("  ID: {}", comment["id"].as_str().unwrap_or(""));
-        println!(
-            "  Author: {}",
-            comment["user"]["name"].as_str().unwrap_or("")
-        );
-
-        let body_preview = comment["body"]
-            .as_str()
-            .unwrap_or("")
-            .chars()
-            .take(80)
-            .collect::<String>();
-        if !body_preview.is_empty() {
-            println!("  Body: {}", body_preview.dimmed());
-        }
-    } else {
+    if result["data"]["commentCreate"]["success"].as_bool() != Some(true) {
         anyhow::bail!("Failed to create comment");
     }
-
-    Ok(())
+    Ok(result["data"]["commentCreate"]["comment"].clone())
 }
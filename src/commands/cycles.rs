@@ -1,28 +1,39 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
+use serde_json::{json, Value};
 use tabled::{Table, Tabled};
 
-use crate::api::{resolve_team_id, LinearClient};
+use crate::api::LinearClient;
+use crate::config;
+use crate::filter;
+use crate::mirror::{self, Entity, Mirror};
+use crate::models::{self, Cycle};
+use crate::output::print_value;
+use crate::resolver::Resolver;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum CycleCommands {
     /// List cycles for a team
     #[command(alias = "ls")]
     List {
-        /// Team ID or name
+        /// Team ID or name [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
         /// Include completed cycles
         #[arg(short, long)]
         all: bool,
+        /// Read from the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Show the current active cycle
     Current {
-        /// Team ID or name
+        /// Team ID or name [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
     },
 }
 
@@ -44,52 +55,38 @@ struct CycleRow {
     id: String,
 }
 
-pub async fn handle(cmd: CycleCommands) -> Result<()> {
+pub async fn handle(cmd: CycleCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        CycleCommands::List { team, all } => list_cycles(&team, all).await,
-        CycleCommands::Current { team } => current_cycle(&team).await,
+        CycleCommands::List { team, all, offline } => {
+            list_cycles(&config::team_or_default(team)?, all, offline, output).await
+        }
+        CycleCommands::Current { team } => {
+            current_cycle(&config::team_or_default(team)?, output).await
+        }
     }
 }
 
-async fn list_cycles(team: &str, include_all: bool) -> Result<()> {
-    let client = LinearClient::new()?;
-
-    // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, team).await?;
-
-    // First, get the team ID if a name was provided
-    let team_query = r#"
-        query($teamId: String!) {
-            team(id: $teamId) {
-                id
-                name
-                cycles(first: 50) {
-                    nodes {
-                        id
-                        name
-                        number
-                        startsAt
-                        endsAt
-                        completedAt
-                        progress
-                    }
-                }
-            }
-        }
-    "#;
-
-    let result = client
-        .query(team_query, Some(json!({ "teamId": team_id })))
-        .await?;
-    let team_data = &result["data"]["team"];
-
-    if team_data.is_null() {
-        anyhow::bail!("Team not found: {}", team);
+async fn list_cycles(
+    team: &str,
+    include_all: bool,
+    offline: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let (team_name, nodes) = if offline {
+        offline_cycles(team).await?
+    } else {
+        fetch_cycles(team).await?
+    };
+
+    if !output.is_table() {
+        let cycles: Vec<&Value> = nodes
+            .iter()
+            .filter(|c| include_all || c["completedAt"].is_null())
+            .collect();
+        return print_value(&cycles, output);
     }
 
-    let team_name = team_data["name"].as_str().unwrap_or("");
-    let empty = vec![];
-    let cycles = team_data["cycles"]["nodes"].as_array().unwrap_or(&empty);
+    let cycles: Vec<Cycle> = models::from_nodes(&nodes)?;
 
     if cycles.is_empty() {
         println!("No cycles found for team '{}'.", team_name);
@@ -98,40 +95,23 @@ async fn list_cycles(team: &str, include_all: bool) -> Result<()> {
 
     let rows: Vec<CycleRow> = cycles
         .iter()
-        .filter(|c| {
-            if include_all {
-                true
-            } else {
-                // Filter out completed cycles unless --all is specified
-                c["completedAt"].is_null()
-            }
-        })
+        // Filter out completed cycles unless --all is specified
+        .filter(|c| include_all || c.completed_at.is_none())
         .map(|c| {
-            let progress = c["progress"].as_f64().unwrap_or(0.0);
-
-            let status = if !c["completedAt"].is_null() {
+            let status = if c.completed_at.is_some() {
                 "Completed".to_string()
             } else {
                 "Active".to_string()
             };
 
             CycleRow {
-                name: c["name"].as_str().unwrap_or("-").to_string(),
-                number: c["number"]
-                    .as_i64()
-                    .map(|n| n.to_string())
-                    .unwrap_or("-".to_string()),
+                name: c.name.clone().unwrap_or_else(|| "-".to_string()),
+                number: c.number.to_string(),
                 status,
-                start_date: c["startsAt"]
-                    .as_str()
-                    .map(|s| s.chars().take(10).collect())
-                    .unwrap_or("-".to_string()),
-                end_date: c["endsAt"]
-                    .as_str()
-                    .map(|s| s.chars().take(10).collect())
-                    .unwrap_or("-".to_string()),
-                progress: format!("{:.0}%", progress * 100.0),
-                id: c["id"].as_str().unwrap_or("").to_string(),
+                start_date: format_date(c.starts_at),
+                end_date: format_date(c.ends_at),
+                progress: format!("{:.0}%", c.progress.unwrap_or(0.0) * 100.0),
+                id: c.id.clone(),
             }
         })
         .collect();
@@ -154,11 +134,73 @@ async fn list_cycles(team: &str, include_all: bool) -> Result<()> {
     Ok(())
 }
 
-async fn current_cycle(team: &str) -> Result<()> {
+/// A team's name and cycles, from the API
+async fn fetch_cycles(team: &str) -> Result<(String, Vec<Value>)> {
+    let client = LinearClient::new()?;
+
+    // Resolve team key/name to UUID
+    let team_id = Resolver::new(&client).team_id(team).await?;
+
+    let team_query = r#"
+        query($teamId: String!) {
+            team(id: $teamId) {
+                id
+                name
+                cycles(first: 50) {
+                    nodes {
+                        id
+                        name
+                        number
+                        startsAt
+                        endsAt
+                        completedAt
+                        progress
+                    }
+                }
+            }
+        }
+    "#;
+
+    let result = client
+        .query(team_query, Some(json!({ "teamId": team_id })))
+        .await?;
+    let team_data = &result["data"]["team"];
+
+    if team_data.is_null() {
+        anyhow::bail!("Team not found: {}", team);
+    }
+
+    let name = team_data["name"].as_str().unwrap_or("").to_string();
+    let nodes = team_data["cycles"]["nodes"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    Ok((name, nodes))
+}
+
+/// A team's name and cycles, from the mirror, in cycle order
+async fn offline_cycles(team: &str) -> Result<(String, Vec<Value>)> {
+    let mirror = Mirror::open_offline().await?;
+    let filter = json!({
+        "team": { "or": [filter::team(team), { "id": { "eq": team } }] },
+        "archivedAt": { "null": true },
+    });
+    let mut nodes = mirror::select(mirror.all(Entity::Cycles)?, &filter, None);
+    nodes.sort_by_key(|c| c["number"].as_i64());
+
+    let name = nodes
+        .first()
+        .and_then(|c| c["team"]["name"].as_str())
+        .unwrap_or(team)
+        .to_string();
+    Ok((name, nodes))
+}
+
+async fn current_cycle(team: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, team).await?;
+    let team_id = Resolver::new(&client).team_id(team).await?;
 
     let query = r#"
         query($teamId: String!) {
@@ -177,7 +219,7 @@ async fn current_cycle(team: &str) -> Result<()> {
                             id
                             identifier
                             title
-                            state { name type }
+                            state { id name type }
                         }
                     }
                 }
@@ -197,54 +239,48 @@ async fn current_cycle(team: &str) -> Result<()> {
     let team_name = team_data["name"].as_str().unwrap_or("");
     let cycle = &team_data["activeCycle"];
 
+    if !output.is_table() {
+        return print_value(cycle, output);
+    }
+
     if cycle.is_null() {
         println!("No active cycle for team '{}'.", team_name);
         return Ok(());
     }
 
-    let progress = cycle["progress"].as_f64().unwrap_or(0.0);
-    let cycle_number = cycle["number"].as_i64().unwrap_or(0);
-    let default_name = format!("Cycle {}", cycle_number);
-    let cycle_name = cycle["name"].as_str().unwrap_or(&default_name);
+    let cycle: Cycle = models::from_value(cycle)?;
 
-    println!("{}", format!("Current Cycle: {}", cycle_name).bold());
+    println!(
+        "{}",
+        format!("Current Cycle: {}", cycle.display_name()).bold()
+    );
     println!("{}", "-".repeat(40));
 
     println!("Team: {}", team_name);
-    println!("Cycle Number: {}", cycle_number);
-    println!(
-        "Start Date: {}",
-        cycle["startsAt"].as_str().map(|s| &s[..10]).unwrap_or("-")
-    );
-    println!(
-        "End Date: {}",
-        cycle["endsAt"].as_str().map(|s| &s[..10]).unwrap_or("-")
-    );
-    println!("Progress: {:.0}%", progress * 100.0);
-    println!("ID: {}", cycle["id"].as_str().unwrap_or("-"));
+    println!("Cycle Number: {}", cycle.number);
+    println!("Start Date: {}", format_date(cycle.starts_at));
+    println!("End Date: {}", format_date(cycle.ends_at));
+    println!("Progress: {:.0}%", cycle.progress.unwrap_or(0.0) * 100.0);
+    println!("ID: {}", cycle.id);
 
     // Show issues in the cycle
-    let issues = cycle["issues"]["nodes"].as_array();
-    if let Some(issues) = issues {
-        if !issues.is_empty() {
-            println!("\n{}", "Issues in this cycle:".bold());
-            for issue in issues {
-                let identifier = issue["identifier"].as_str().unwrap_or("");
-                let title = issue["title"].as_str().unwrap_or("");
-                let state = issue["state"]["name"].as_str().unwrap_or("");
-                let state_type = issue["state"]["type"].as_str().unwrap_or("");
-
-                let state_colored = match state_type {
-                    "completed" => state.green().to_string(),
-                    "started" => state.yellow().to_string(),
-                    "canceled" | "cancelled" => state.red().to_string(),
-                    _ => state.dimmed().to_string(),
-                };
-
-                println!("  {} {} [{}]", identifier.cyan(), title, state_colored);
-            }
+    let issues = cycle.issues.unwrap_or_default().nodes;
+    if !issues.is_empty() {
+        println!("\n{}", "Issues in this cycle:".bold());
+        for issue in &issues {
+            let state = issue
+                .state
+                .as_ref()
+                .map(|s| s.paint(&s.name))
+                .unwrap_or_default();
+            println!("  {} {} [{}]", issue.identifier.cyan(), issue.title, state);
         }
     }
 
     Ok(())
 }
+
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|d| d.date_naive().to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -1,10 +1,17 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
-use tabled::{Table, Tabled};
-
-use crate::api::LinearClient;
+use serde_json::{json, Value};
+use tabled::Tabled;
+
+use crate::api::{LinearClient, PageOptions};
+use crate::config;
+use crate::fields;
+use crate::mirror::{self, Entity, Mirror};
+use crate::models::{self, Document};
+use crate::output::{print_value, TableStream, ValueStream};
+use crate::resolver::Resolver;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum DocumentCommands {
@@ -17,6 +24,15 @@ pub enum DocumentCommands {
         /// Include archived documents
         #[arg(short, long)]
         archived: bool,
+        /// Maximum number of documents to fetch
+        #[arg(short, long, default_value = "100")]
+        limit: usize,
+        /// Fetch all documents, ignoring --limit
+        #[arg(long)]
+        all: bool,
+        /// Read from the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Get document details and content
     Get {
@@ -27,9 +43,9 @@ pub enum DocumentCommands {
     Create {
         /// Document title
         title: String,
-        /// Project name or ID to associate the document with
+        /// Project name or ID to associate the document with [default: default.project]
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
         /// Document content (Markdown)
         #[arg(short, long)]
         content: Option<String>,
@@ -74,17 +90,34 @@ struct DocumentRow {
     id: String,
 }
 
-pub async fn handle(cmd: DocumentCommands) -> Result<()> {
+pub async fn handle(cmd: DocumentCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        DocumentCommands::List { project, archived } => list_documents(project, archived).await,
-        DocumentCommands::Get { id } => get_document(&id).await,
+        DocumentCommands::List {
+            project,
+            archived,
+            limit,
+            all,
+            offline,
+        } => {
+            let pages = PageOptions::new(limit, all);
+            if offline {
+                return list_offline_documents(project, archived, pages, output).await;
+            }
+            list_documents(project, archived, pages, output).await
+        }
+        DocumentCommands::Get { id } => get_document(&id, output).await,
         DocumentCommands::Create {
             title,
             project,
             content,
             icon,
             color,
-        } => create_document(&title, &project, content, icon, color).await,
+        } => {
+            let project = project
+                .or_else(|| config::default_value("project"))
+                .context("No project given: pass --project, or set default.project")?;
+            create_document(&title, &project, content, icon, color, output).await
+        }
         DocumentCommands::Update {
             id,
             title,
@@ -92,81 +125,138 @@ pub async fn handle(cmd: DocumentCommands) -> Result<()> {
             icon,
             color,
             project,
-        } => update_document(&id, title, content, icon, color, project).await,
+        } => update_document(&id, title, content, icon, color, project, output).await,
     }
 }
 
-async fn list_documents(project_id: Option<String>, include_archived: bool) -> Result<()> {
+async fn list_documents(
+    project_id: Option<String>,
+    include_archived: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
-        query($includeArchived: Boolean) {
-            documents(first: 100, includeArchived: $includeArchived) {
+        query($includeArchived: Boolean, $first: Int, $after: String) {
+            documents(first: $first, after: $after, includeArchived: $includeArchived) {
                 nodes {
                     id
                     title
                     updatedAt
                     project { id name }
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
     let variables = json!({ "includeArchived": include_archived });
 
-    let result = client.query(query, Some(variables)).await?;
-
-    let empty = vec![];
-    let documents = result["data"]["documents"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
-    // Filter by project if specified
-    let filtered_docs: Vec<_> = if let Some(ref pid) = project_id {
-        documents
-            .iter()
-            .filter(|d| {
-                d["project"]["id"].as_str() == Some(pid.as_str())
-                    || d["project"]["name"].as_str().map(|n| n.to_lowercase())
-                        == Some(pid.to_lowercase())
-            })
-            .collect()
-    } else {
-        documents.iter().collect()
-    };
+    let mut table = TableStream::new();
+    let mut stream = ValueStream::new(output).columns(query, &["documents"]);
+    client
+        .paginate(query, Some(variables), &["documents"], pages, |nodes| {
+            let documents: Vec<Document> = models::from_nodes(&nodes)?;
+            // Filter by project if specified
+            let (nodes, documents): (Vec<Value>, Vec<Document>) = nodes
+                .into_iter()
+                .zip(documents)
+                .filter(|(_, d)| match (&project_id, &d.project) {
+                    (Some(pid), Some(project)) => {
+                        project.id == *pid || project.name.eq_ignore_ascii_case(pid)
+                    }
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                })
+                .unzip();
+
+            if !output.is_table() {
+                return stream.push(&nodes);
+            }
+
+            table.push(documents.iter().map(document_row).collect());
+            Ok(())
+        })
+        .await?;
+
+    if !output.is_table() {
+        return stream.finish();
+    }
 
-    if filtered_docs.is_empty() {
+    if table.rows() == 0 {
         println!("No documents found.");
         return Ok(());
     }
 
-    let rows: Vec<DocumentRow> = filtered_docs
-        .iter()
-        .map(|d| {
-            let updated = d["updatedAt"]
-                .as_str()
-                .unwrap_or("")
-                .chars()
-                .take(10)
-                .collect::<String>();
-
-            DocumentRow {
-                title: d["title"].as_str().unwrap_or("").to_string(),
-                project: d["project"]["name"].as_str().unwrap_or("-").to_string(),
-                updated,
-                id: d["id"].as_str().unwrap_or("").to_string(),
-            }
+    println!("\n{} documents", table.rows());
+
+    Ok(())
+}
+
+/// `documents list --offline`: the same filter, evaluated against the mirror
+async fn list_offline_documents(
+    project: Option<String>,
+    include_archived: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
+    let mirror = Mirror::open_offline().await?;
+
+    let mut conditions: Vec<Value> = project
+        .map(|p| {
+            json!({ "project": { "or": [
+                { "id": { "eq": p } },
+                { "name": { "eqIgnoreCase": p } },
+            ] } })
         })
+        .into_iter()
         .collect();
+    if !include_archived {
+        conditions.push(json!({ "archivedAt": { "null": true } }));
+    }
+
+    let documents = mirror::select(
+        mirror.all(Entity::Documents)?,
+        &json!({ "and": conditions }),
+        pages.limit,
+    );
 
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} documents", filtered_docs.len());
+    if !output.is_table() {
+        let mut stream = ValueStream::new(output);
+        stream.push(&documents)?;
+        return stream.finish();
+    }
+
+    let documents: Vec<Document> = models::from_nodes(&documents)?;
+    if documents.is_empty() {
+        println!("No documents found.");
+        return Ok(());
+    }
+    let mut table = TableStream::new();
+    table.push(documents.iter().map(document_row).collect());
+    println!("\n{} documents", table.rows());
 
     Ok(())
 }
 
-async fn get_document(id: &str) -> Result<()> {
+fn document_row(document: &Document) -> DocumentRow {
+    DocumentRow {
+        title: document.title.clone(),
+        project: document
+            .project
+            .as_ref()
+            .map_or("-", |p| p.name.as_str())
+            .to_string(),
+        updated: document
+            .updated_at
+            .map(|u| u.date_naive().to_string())
+            .unwrap_or_default(),
+        id: document.id.clone(),
+    }
+}
+
+async fn get_document(id: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
@@ -180,51 +270,61 @@ async fn get_document(id: &str) -> Result<()> {
                 url
                 createdAt
                 updatedAt
-                creator { name email }
+                creator { id name email }
                 project { id name }
             }
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "id": id }))).await?;
+    let result = client
+        .query(
+            &fields::narrow(query, &["document"]),
+            Some(json!({ "id": id })),
+        )
+        .await?;
     let document = &result["data"]["document"];
 
     if document.is_null() {
         anyhow::bail!("Document not found: {}", id);
     }
 
-    println!("{}", document["title"].as_str().unwrap_or("").bold());
+    if !output.is_table() {
+        return print_value(document, output);
+    }
+
+    let document: Document = models::from_value(document)?;
+    println!("{}", document.title.bold());
     println!("{}", "─".repeat(40));
 
-    if let Some(project_name) = document["project"]["name"].as_str() {
-        println!("Project: {}", project_name);
+    if let Some(project) = &document.project {
+        println!("Project: {}", project.name);
     }
 
-    if let Some(creator_name) = document["creator"]["name"].as_str() {
-        println!("Creator: {}", creator_name);
+    if let Some(creator) = &document.creator {
+        println!("Creator: {}", creator.name);
     }
 
-    if let Some(icon) = document["icon"].as_str() {
+    if let Some(icon) = &document.icon {
         println!("Icon: {}", icon);
     }
 
-    if let Some(color) = document["color"].as_str() {
+    if let Some(color) = &document.color {
         println!("Color: {}", color);
     }
 
-    println!("URL: {}", document["url"].as_str().unwrap_or("-"));
-    println!("ID: {}", document["id"].as_str().unwrap_or("-"));
+    println!("URL: {}", document.url.as_deref().unwrap_or("-"));
+    println!("ID: {}", document.id);
 
-    if let Some(created) = document["createdAt"].as_str() {
-        println!("Created: {}", created.chars().take(10).collect::<String>());
+    if let Some(created) = document.created_at {
+        println!("Created: {}", created.date_naive());
     }
 
-    if let Some(updated) = document["updatedAt"].as_str() {
-        println!("Updated: {}", updated.chars().take(10).collect::<String>());
+    if let Some(updated) = document.updated_at {
+        println!("Updated: {}", updated.date_naive());
     }
 
     // Display content
-    if let Some(content) = document["content"].as_str() {
+    if let Some(content) = &document.content {
         println!("\n{}", "Content".bold());
         println!("{}", "─".repeat(40));
         println!("{}", content);
@@ -239,12 +339,14 @@ async fn create_document(
     content: Option<String>,
     icon: Option<String>,
     color: Option<String>,
+    output: OutputFormat,
 ) -> Result<()> {
     let client = LinearClient::new()?;
+    let project_id = Resolver::new(&client).project_id(project).await?;
 
     let mut input = json!({
         "title": title,
-        "projectId": project
+        "projectId": project_id
     });
 
     if let Some(c) = content {
@@ -272,13 +374,14 @@ async fn create_document(
 
     if result["data"]["documentCreate"]["success"].as_bool() == Some(true) {
         let document = &result["data"]["documentCreate"]["document"];
-        println!(
-            "{} Created document: {}",
-            "+".green(),
-            document["title"].as_str().unwrap_or("")
-        );
-        println!("  ID: {}", document["id"].as_str().unwrap_or(""));
-        println!("  URL: {}", document["url"].as_str().unwrap_or(""));
+        if !output.is_table() {
+            return print_value(document, output);
+        }
+
+        let document: Document = models::from_value(document)?;
+        println!("{} Created document: {}", "+".green(), document.title);
+        println!("  ID: {}", document.id);
+        println!("  URL: {}", document.url.as_deref().unwrap_or(""));
     } else {
         anyhow::bail!("Failed to create document");
     }
@@ -293,6 +396,7 @@ async fn update_document(
     icon: Option<String>,
     color: Option<String>,
     project: Option<String>,
+    output: OutputFormat,
 ) -> Result<()> {
     let client = LinearClient::new()?;
 
@@ -332,6 +436,9 @@ async fn update_document(
         .await?;
 
     if result["data"]["documentUpdate"]["success"].as_bool() == Some(true) {
+        if !output.is_table() {
+            return print_value(&result["data"]["documentUpdate"]["document"], output);
+        }
         println!("{} Document updated", "+".green());
     } else {
         anyhow::bail!("Failed to update document");
@@ -6,6 +6,8 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::api::LinearClient;
+use crate::config;
+use crate::models::{self, Issue};
 
 /// Version control system type
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
@@ -186,16 +188,49 @@ async fn get_issue_info(issue_id: &str) -> Result<(String, String, String, Strin
         anyhow::bail!("Issue not found: {}", issue_id);
     }
 
-    let identifier = issue["identifier"].as_str().unwrap_or("").to_string();
-    let title = issue["title"].as_str().unwrap_or("").to_string();
-    let branch_name = issue["branchName"].as_str().unwrap_or("").to_string();
-    let url = issue["url"].as_str().unwrap_or("").to_string();
+    let issue: Issue = models::from_value(issue)?;
+    Ok((
+        issue.identifier,
+        issue.title,
+        issue.branch_name.unwrap_or_default(),
+        issue.url.unwrap_or_default(),
+    ))
+}
 
-    Ok((identifier, title, branch_name, url))
+/// The branch for an issue: `-b` if given, then the `default.branch`
+/// pattern, then the name Linear suggests, then one made from the title
+pub fn issue_branch_name(
+    custom: Option<String>,
+    linear_branch: String,
+    identifier: &str,
+    title: &str,
+) -> String {
+    custom
+        .or_else(|| {
+            config::default_value("branch")
+                .map(|pattern| branch_from_pattern(&pattern, identifier, title))
+        })
+        .or((!linear_branch.is_empty()).then_some(linear_branch))
+        .unwrap_or_else(|| generate_branch_name(identifier, title))
+}
+
+/// Fill in a `default.branch` pattern such as `{team}/{number}-{title}`
+fn branch_from_pattern(pattern: &str, identifier: &str, title: &str) -> String {
+    let identifier = identifier.to_lowercase();
+    let (team, number) = identifier.split_once('-').unwrap_or(("", &identifier));
+    pattern
+        .replace("{identifier}", &identifier)
+        .replace("{team}", team)
+        .replace("{number}", number)
+        .replace("{title}", &title_slug(title))
 }
 
 fn generate_branch_name(identifier: &str, title: &str) -> String {
-    // Convert title to kebab-case for branch name
+    format!("{}/{}", identifier.to_lowercase(), title_slug(title))
+}
+
+/// A title in kebab-case, at most 50 characters
+fn title_slug(title: &str) -> String {
     let slug: String = title
         .to_lowercase()
         .chars()
@@ -207,13 +242,11 @@ fn generate_branch_name(identifier: &str, title: &str) -> String {
         .join("-");
 
     // Truncate if too long
-    let slug = if slug.len() > 50 {
+    if slug.len() > 50 {
         slug[..50].trim_end_matches('-').to_string()
     } else {
         slug
-    };
-
-    format!("{}/{}", identifier.to_lowercase(), slug)
+    }
 }
 
 fn run_git_command(args: &[&str]) -> Result<String> {
@@ -270,13 +303,7 @@ fn generate_jj_description(identifier: &str, title: &str, url: &str) -> String {
 async fn checkout_issue(issue_id: &str, custom_branch: Option<String>, vcs: Vcs) -> Result<()> {
     let (identifier, title, linear_branch, url) = get_issue_info(issue_id).await?;
 
-    let branch_name = custom_branch
-        .or(if linear_branch.is_empty() {
-            None
-        } else {
-            Some(linear_branch)
-        })
-        .unwrap_or_else(|| generate_branch_name(&identifier, &title));
+    let branch_name = issue_branch_name(custom_branch, linear_branch, &identifier, &title);
 
     println!(
         "{} {} {}",
@@ -341,7 +368,10 @@ async fn show_branch(issue_id: &str, vcs: Vcs) -> Result<()> {
         println!("Linear branch: {}", linear_branch.green());
     }
 
-    let generated = generate_branch_name(&identifier, &title);
+    let generated = match config::default_value("branch") {
+        Some(pattern) => branch_from_pattern(&pattern, &identifier, &title),
+        None => generate_branch_name(&identifier, &title),
+    };
     println!("Generated:     {}", generated.yellow());
     println!("Issue URL:     {}", url.blue());
 
@@ -374,13 +404,7 @@ async fn show_branch(issue_id: &str, vcs: Vcs) -> Result<()> {
 async fn create_branch(issue_id: &str, custom_branch: Option<String>, vcs: Vcs) -> Result<()> {
     let (identifier, title, linear_branch, url) = get_issue_info(issue_id).await?;
 
-    let branch_name = custom_branch
-        .or(if linear_branch.is_empty() {
-            None
-        } else {
-            Some(linear_branch)
-        })
-        .unwrap_or_else(|| generate_branch_name(&identifier, &title));
+    let branch_name = issue_branch_name(custom_branch, linear_branch, &identifier, &title);
 
     println!(
         "{} {} {}",
@@ -538,6 +562,18 @@ async fn create_pr(issue_id: &str, base: &str, draft: bool, web: bool) -> Result
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_branch_from_pattern() {
+        assert_eq!(
+            branch_from_pattern("{team}/{number}-{title}", "ENG-42", "Fix login!"),
+            "eng/42-fix-login"
+        );
+        assert_eq!(
+            branch_from_pattern("alice/{identifier}", "ENG-42", "Fix login"),
+            "alice/eng-42"
+        );
+    }
+
     #[test]
     fn test_generate_branch_name_simple() {
         assert_eq!(
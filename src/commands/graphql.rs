@@ -0,0 +1,225 @@
+//! `linear api`: send any GraphQL query or mutation with the configured
+//! credentials, for whatever the other commands don't cover.
+
+use anyhow::{bail, Context, Result};
+use apollo_compiler::ast::{Definition, Document, OperationType};
+use serde_json::{json, Map, Value};
+use std::io::Read;
+
+use crate::api::LinearClient;
+use crate::output::{print_value, ValueStream};
+use crate::OutputFormat;
+
+/// Run a query given on the command line or read from `--input`
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    query: Option<String>,
+    input: Option<String>,
+    fields: Vec<String>,
+    raw_fields: Vec<String>,
+    variables: Option<String>,
+    paginate: bool,
+    raw: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let query = match (query, input) {
+        (Some(query), None) => query,
+        (None, Some(path)) => read_source(&path)?,
+        (Some(_), Some(_)) => bail!("Give the query as an argument or with --input, not both"),
+        (None, None) => bail!("No query given: pass one as an argument or with --input FILE"),
+    };
+    if query.trim().is_empty() {
+        bail!("The query is empty");
+    }
+
+    let mut vars = match variables {
+        Some(source) => {
+            let text = read_source(&source)?;
+            match serde_json::from_str(&text).context("--variables must be a JSON object")? {
+                Value::Object(map) => map,
+                _ => bail!("--variables must be a JSON object"),
+            }
+        }
+        None => Map::new(),
+    };
+    for field in &fields {
+        let (key, value) = split_field(field)?;
+        vars.insert(key.to_string(), typed_value(value)?);
+    }
+    for field in &raw_fields {
+        let (key, value) = split_field(field)?;
+        vars.insert(key.to_string(), Value::String(value.to_string()));
+    }
+
+    let client = LinearClient::new()?;
+    let variables = (!vars.is_empty()).then_some(Value::Object(vars));
+
+    if paginate {
+        return paginate_nodes(&client, &query, variables, raw, output).await;
+    }
+
+    let result = if is_mutation(&query) {
+        client.mutate(&query, variables).await?
+    } else {
+        client.query(&query, variables).await?
+    };
+
+    if raw {
+        println!("{}", result);
+        return Ok(());
+    }
+    print_value(&result, output)
+}
+
+/// Follow the first connection in the response page by page and print its
+/// nodes as one list
+async fn paginate_nodes(
+    client: &LinearClient,
+    query: &str,
+    variables: Option<Value>,
+    raw: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if is_mutation(query) {
+        bail!("--paginate only works with queries");
+    }
+    if !query.contains("$after") {
+        bail!("--paginate needs a query that takes $after: String and passes it to the connection");
+    }
+
+    let mut stream = ValueStream::new(if raw { OutputFormat::Ndjson } else { output });
+    let mut vars = variables.unwrap_or_else(|| json!({}));
+    let mut path: Option<Vec<String>> = None;
+
+    loop {
+        let result = client.query(query, Some(vars.clone())).await?;
+        if path.is_none() {
+            let found = find_connection(&result["data"]).context(
+                "--paginate needs a connection selecting nodes and pageInfo { hasNextPage endCursor }",
+            )?;
+            let keys: Vec<&str> = found.iter().map(String::as_str).collect();
+            stream = stream.columns(query, &keys);
+            path = Some(found);
+        }
+        let connection = path
+            .iter()
+            .flatten()
+            .fold(&result["data"], |value, key| &value[key.as_str()]);
+
+        let nodes = connection["nodes"].as_array().cloned().unwrap_or_default();
+        stream.push(&nodes)?;
+
+        let has_next = connection["pageInfo"]["hasNextPage"]
+            .as_bool()
+            .unwrap_or(false);
+        match connection["pageInfo"]["endCursor"].as_str() {
+            Some(cursor) if has_next && !nodes.is_empty() => vars["after"] = json!(cursor),
+            _ => break,
+        }
+    }
+
+    stream.finish()
+}
+
+/// Path below `data` to the first object with `nodes` and `pageInfo`
+fn find_connection(value: &Value) -> Option<Vec<String>> {
+    let Value::Object(map) = value else {
+        return None;
+    };
+    if map.get("nodes").is_some_and(Value::is_array) && map.contains_key("pageInfo") {
+        return Some(Vec::new());
+    }
+    map.iter().find_map(|(key, child)| {
+        let mut path = find_connection(child)?;
+        path.insert(0, key.clone());
+        Some(path)
+    })
+}
+
+/// Whether the document holds a mutation, wherever it sits among comments,
+/// fragments and other operations. A document that doesn't parse is judged
+/// on what did; the API reports the syntax error.
+fn is_mutation(query: &str) -> bool {
+    let document = Document::parse(query, "query.graphql").unwrap_or_else(|e| e.partial);
+    document.definitions.iter().any(|definition| {
+        matches!(definition, Definition::OperationDefinition(op)
+            if op.operation_type == OperationType::Mutation)
+    })
+}
+
+/// A file's contents, or stdin for `-`
+fn read_source(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read stdin")?;
+        return Ok(text);
+    }
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))
+}
+
+fn split_field(field: &str) -> Result<(&str, &str)> {
+    match field.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key, value)),
+        _ => bail!("Variables are given as key=value, got '{}'", field),
+    }
+}
+
+/// `-F` values: numbers, booleans, null, arrays and objects are parsed as
+/// JSON, `@file` reads a file (`@-` for stdin), and anything else is a string
+fn typed_value(value: &str) -> Result<Value> {
+    if let Some(path) = value.strip_prefix('@') {
+        return Ok(Value::String(read_source(path)?));
+    }
+    Ok(serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_values() {
+        assert_eq!(typed_value("ENG").unwrap(), json!("ENG"));
+        assert_eq!(typed_value("25").unwrap(), json!(25));
+        assert_eq!(typed_value("true").unwrap(), json!(true));
+        assert_eq!(typed_value("null").unwrap(), json!(null));
+        assert_eq!(typed_value(r#"["a","b"]"#).unwrap(), json!(["a", "b"]));
+        assert_eq!(typed_value(r#""25""#).unwrap(), json!("25"));
+        assert!(split_field("=x").is_err());
+        assert_eq!(split_field("a=b=c").unwrap(), ("a", "b=c"));
+    }
+
+    #[test]
+    fn test_find_connection() {
+        let data = json!({
+            "team": {
+                "id": "t",
+                "issues": { "nodes": [], "pageInfo": { "hasNextPage": false } }
+            }
+        });
+        assert_eq!(
+            find_connection(&data),
+            Some(vec!["team".to_string(), "issues".to_string()])
+        );
+        assert_eq!(find_connection(&json!({ "viewer": { "id": "u" } })), None);
+    }
+
+    #[test]
+    fn test_is_mutation() {
+        assert!(is_mutation(
+            "mutation { issueArchive(id: \"x\") { success } }"
+        ));
+        assert!(is_mutation(
+            "# Archive an issue\nmutation Archive { issueArchive(id: \"x\") { success } }"
+        ));
+        assert!(is_mutation(
+            "fragment Payload on IssuePayload { success }\n\
+             mutation { issueArchive(id: \"x\") { ...Payload } }"
+        ));
+        assert!(!is_mutation("# mutation\n{ viewer { id } }"));
+        assert!(!is_mutation("query mutationCount { viewer { id } }"));
+        assert!(!is_mutation("{ mutations: viewer { id } }"));
+    }
+}
@@ -1,668 +1,991 @@
+//! `linear interactive`: a full-screen browser for a team's open issues.
+//!
+//! [`App`] holds all of the screen's state. It is driven by key presses and by
+//! [`Message`]s from background tasks, and asks for side effects by returning an
+//! [`Action`]. [`run`] wires it to the terminal, the API and a refresh timer.
+
 use anyhow::Result;
-use colored::Colorize;
-use console::Term;
-use dialoguer::{Confirm, Input, Select};
-use serde_json::json;
-use tabled::{Table, Tabled};
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::Frame;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
 use crate::api::LinearClient;
-
+use crate::config;
+use crate::models::{self, priority_name, Issue, Team};
+use crate::resolver::Resolver;
+use crate::tui::{self, Picker, PickerOutcome};
+
+/// How often the issue list is reloaded in the background
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Maximum number of open issues loaded per team
+const ISSUE_LIMIT: usize = 100;
+
+const TEAM_ISSUES_QUERY: &str = r#"
+    query($teamId: String!, $first: Int) {
+        team(id: $teamId) {
+            id
+            key
+            name
+            states { nodes { id name type color position } }
+            members { nodes { id name displayName } }
+            issues(
+                first: $first,
+                orderBy: updatedAt,
+                filter: { state: { type: { nin: ["completed", "canceled"] } } }
+            ) {
+                nodes {
+                    id
+                    identifier
+                    title
+                    description
+                    priority
+                    url
+                    updatedAt
+                    state { id name type }
+                    assignee { id name }
+                    labels { nodes { id name } }
+                }
+            }
+        }
+    }
+"#;
+
+const KEY_HELP: &[(&str, &str)] = &[
+    ("j / k, arrows", "Move through issues"),
+    ("g / G", "First / last issue"),
+    ("/", "Filter by typing (Esc clears)"),
+    ("s", "Change state"),
+    ("a", "Change assignee"),
+    ("p", "Change priority"),
+    ("n", "New issue"),
+    ("t", "Switch team"),
+    ("r", "Refresh now"),
+    ("q", "Quit"),
+];
+
+/// A team's open issues, with the states and members needed to edit them
 #[derive(Debug, Clone)]
-struct Team {
-    id: String,
-    name: String,
-    key: String,
+pub struct TeamIssues {
+    pub team: Team,
+    pub issues: Vec<Issue>,
 }
 
-#[derive(Tabled)]
-struct IssueRow {
-    #[tabled(rename = "ID")]
-    identifier: String,
-    #[tabled(rename = "Title")]
-    title: String,
-    #[tabled(rename = "State")]
-    state: String,
-    #[tabled(rename = "Priority")]
-    priority: String,
+/// Results delivered by background tasks
+pub enum Message {
+    Loaded {
+        team_id: String,
+        result: Result<Box<TeamIssues>>,
+    },
+    /// A mutation finished; `Ok` carries a status line such as "Updated ENG-1"
+    Saved(Result<String>),
+    /// A request failed and is about to be retried
+    Retrying(String),
 }
 
-enum MenuAction {
-    CreateIssue,
-    ListIssues,
-    ViewIssue,
-    UpdateIssue,
-    SwitchTeam,
-    Exit,
+/// Side effects requested by the UI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Refresh {
+        team_id: String,
+    },
+    Update {
+        issue_id: String,
+        identifier: String,
+        input: Value,
+    },
+    Create {
+        team_id: String,
+        title: String,
+    },
 }
 
-impl std::fmt::Display for MenuAction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            MenuAction::CreateIssue => write!(f, "Create Issue"),
-            MenuAction::ListIssues => write!(f, "List Issues"),
-            MenuAction::ViewIssue => write!(f, "View Issue"),
-            MenuAction::UpdateIssue => write!(f, "Update Issue"),
-            MenuAction::SwitchTeam => write!(f, "Switch Team"),
-            MenuAction::Exit => write!(f, "Exit"),
-        }
-    }
+/// A choice made in one of the pickers
+#[derive(Debug, Clone, PartialEq)]
+enum Choice {
+    State(String),
+    Assignee(Option<String>),
+    Priority(i64),
+    Team(usize),
 }
 
-pub async fn run() -> Result<()> {
-    let term = Term::stdout();
-    let client = LinearClient::new()?;
+enum Mode {
+    Browse,
+    Filter,
+    Create(String),
+    Pick(Picker<Choice>),
+    Help,
+}
 
-    println!("{}", "Linear CLI - Interactive Mode".cyan().bold());
-    println!("{}", "=".repeat(40));
+pub struct App {
+    teams: Vec<Team>,
+    team: usize,
+    data: Option<TeamIssues>,
+    filter: String,
+    mode: Mode,
+    selected: usize,
+    status: String,
+    loading: bool,
+    quit: bool,
+}
 
-    // Fetch available teams
-    let teams = fetch_teams(&client).await?;
-    if teams.is_empty() {
-        println!("No teams found. Please check your API key.");
-        return Ok(());
+impl App {
+    pub fn new(teams: Vec<Team>) -> Self {
+        Self {
+            teams,
+            team: 0,
+            data: None,
+            filter: String::new(),
+            mode: Mode::Browse,
+            selected: 0,
+            status: String::new(),
+            loading: false,
+            quit: false,
+        }
     }
 
-    // Select initial team
-    let mut current_team = select_team(&teams)?;
-    println!(
-        "\n{} Selected team: {} ({})",
-        "+".green(),
-        current_team.name,
-        current_team.key
-    );
+    /// Start on `team`, matched by key, name or ID, if there is such a team
+    pub fn with_team(mut self, team: &str) -> Self {
+        if let Some(index) = self.teams.iter().position(|t| {
+            t.key.eq_ignore_ascii_case(team) || t.name.eq_ignore_ascii_case(team) || t.id == team
+        }) {
+            self.team = index;
+        }
+        self
+    }
 
-    loop {
-        println!("\n{}", "-".repeat(40));
-        println!(
-            "Current team: {} {}",
-            current_team.name.cyan(),
-            format!("({})", current_team.key).dimmed()
-        );
+    fn team_id(&self) -> String {
+        self.teams[self.team].id.clone()
+    }
 
-        let actions = [
-            MenuAction::CreateIssue,
-            MenuAction::ListIssues,
-            MenuAction::ViewIssue,
-            MenuAction::UpdateIssue,
-            MenuAction::SwitchTeam,
-            MenuAction::Exit,
-        ];
+    /// Start a reload of the current team's issues
+    pub fn refresh(&mut self) -> Action {
+        self.loading = true;
+        Action::Refresh {
+            team_id: self.team_id(),
+        }
+    }
+
+    /// Issues matching the filter. Every word must appear in the identifier,
+    /// title, state, assignee or a label.
+    fn visible(&self) -> Vec<&Issue> {
+        let Some(data) = &self.data else {
+            return Vec::new();
+        };
+        let words: Vec<String> = self
+            .filter
+            .split_whitespace()
+            .map(|w| w.to_lowercase())
+            .collect();
+
+        data.issues
+            .iter()
+            .filter(|issue| {
+                let haystack = format!(
+                    "{} {} {} {} {}",
+                    issue.identifier,
+                    issue.title,
+                    issue.state_name(),
+                    issue.assignee_name(),
+                    issue.label_names().join(" ")
+                )
+                .to_lowercase();
+                words.iter().all(|w| haystack.contains(w))
+            })
+            .collect()
+    }
+
+    fn selected_issue(&self) -> Option<&Issue> {
+        self.visible().get(self.selected).copied()
+    }
 
-        let action_strings: Vec<String> = actions.iter().map(|a| a.to_string()).collect();
+    fn select(&mut self, index: usize) {
+        let count = self.visible().len();
+        self.selected = index.min(count.saturating_sub(1));
+    }
 
-        let selection = Select::new()
-            .with_prompt("Select action")
-            .items(&action_strings)
-            .default(0)
-            .interact_on(&term)?;
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
 
-        match actions.get(selection).unwrap() {
-            MenuAction::CreateIssue => {
-                if let Err(e) = create_issue_interactive(&client, &current_team).await {
-                    println!("{} Error: {}", "!".red(), e);
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.on_browse_key(key),
+            Mode::Filter => {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.filter.push(c);
+                        self.mode = Mode::Filter;
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                        self.mode = Mode::Filter;
+                    }
+                    KeyCode::Esc => self.filter.clear(),
+                    KeyCode::Enter => {}
+                    _ => self.mode = Mode::Filter,
                 }
+                self.select(0);
+                None
             }
-            MenuAction::ListIssues => {
-                if let Err(e) = list_issues_interactive(&client, &current_team).await {
-                    println!("{} Error: {}", "!".red(), e);
+            Mode::Create(mut title) => match key.code {
+                KeyCode::Char(c) => {
+                    title.push(c);
+                    self.mode = Mode::Create(title);
+                    None
                 }
-            }
-            MenuAction::ViewIssue => {
-                if let Err(e) = view_issue_interactive(&client).await {
-                    println!("{} Error: {}", "!".red(), e);
+                KeyCode::Backspace => {
+                    title.pop();
+                    self.mode = Mode::Create(title);
+                    None
                 }
-            }
-            MenuAction::UpdateIssue => {
-                if let Err(e) = update_issue_interactive(&client).await {
-                    println!("{} Error: {}", "!".red(), e);
+                KeyCode::Enter if !title.trim().is_empty() => {
+                    let title = title.trim().to_string();
+                    self.status = format!("Creating \"{}\"...", title);
+                    Some(Action::Create {
+                        team_id: self.team_id(),
+                        title,
+                    })
                 }
-            }
-            MenuAction::SwitchTeam => {
-                current_team = select_team(&teams)?;
-                println!(
-                    "{} Switched to team: {} ({})",
-                    "+".green(),
-                    current_team.name,
-                    current_team.key
-                );
-            }
-            MenuAction::Exit => {
-                println!("Goodbye!");
-                break;
-            }
+                KeyCode::Esc | KeyCode::Enter => None,
+                _ => {
+                    self.mode = Mode::Create(title);
+                    None
+                }
+            },
+            Mode::Pick(mut picker) => match picker.on_key(key) {
+                PickerOutcome::Pending => {
+                    self.mode = Mode::Pick(picker);
+                    None
+                }
+                PickerOutcome::Cancelled => None,
+                PickerOutcome::Chosen(choice) => self.apply(choice),
+            },
+            // Any key closes the help pop-up
+            Mode::Help => None,
         }
     }
 
-    Ok(())
-}
-
-async fn fetch_teams(client: &LinearClient) -> Result<Vec<Team>> {
-    let query = r#"
-        query {
-            teams(first: 100) {
-                nodes {
-                    id
-                    name
-                    key
+    fn on_browse_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.select(0);
+            }
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+            KeyCode::PageDown => self.select(self.selected + 10),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(10)),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('?') => self.mode = Mode::Help,
+            KeyCode::Char('n') => self.mode = Mode::Create(String::new()),
+            KeyCode::Char('r') => return Some(self.refresh()),
+            KeyCode::Char('t') => {
+                let options = self
+                    .teams
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (format!("{} ({})", t.name, t.key), Choice::Team(i)))
+                    .collect();
+                self.mode = Mode::Pick(Picker::new("Team", options, self.team));
+            }
+            KeyCode::Char(c @ ('s' | 'a' | 'p')) => {
+                if let Some(picker) = self.picker_for(c) {
+                    self.mode = Mode::Pick(picker);
                 }
             }
+            _ => {}
         }
-    "#;
-
-    let result = client.query(query, None).await?;
-    let empty = vec![];
-    let teams_json = result["data"]["teams"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
-    let teams: Vec<Team> = teams_json
-        .iter()
-        .map(|t| Team {
-            id: t["id"].as_str().unwrap_or("").to_string(),
-            name: t["name"].as_str().unwrap_or("").to_string(),
-            key: t["key"].as_str().unwrap_or("").to_string(),
-        })
-        .collect();
-
-    Ok(teams)
-}
-
-fn select_team(teams: &[Team]) -> Result<Team> {
-    let team_names: Vec<String> = teams
-        .iter()
-        .map(|t| format!("{} ({})", t.name, t.key))
-        .collect();
-
-    let selection = Select::new()
-        .with_prompt("Select a team")
-        .items(&team_names)
-        .default(0)
-        .interact()?;
-
-    Ok(teams[selection].clone())
-}
-
-async fn create_issue_interactive(client: &LinearClient, team: &Team) -> Result<()> {
-    println!("\n{}", "Create New Issue".cyan().bold());
-
-    let title: String = Input::new().with_prompt("Title").interact_text()?;
-
-    if title.trim().is_empty() {
-        println!("Title cannot be empty.");
-        return Ok(());
-    }
-
-    let description: String = Input::new()
-        .with_prompt("Description (optional)")
-        .allow_empty(true)
-        .interact_text()?;
-
-    let priority_options = vec!["None", "Urgent", "High", "Normal", "Low"];
-    let priority_selection = Select::new()
-        .with_prompt("Priority")
-        .items(&priority_options)
-        .default(3)
-        .interact()?;
-
-    let priority = match priority_selection {
-        0 => 0,
-        1 => 1,
-        2 => 2,
-        3 => 3,
-        4 => 4,
-        _ => 0,
-    };
-
-    let confirm = Confirm::new()
-        .with_prompt("Create this issue?")
-        .default(true)
-        .interact()?;
-
-    if !confirm {
-        println!("Cancelled.");
-        return Ok(());
+        None
     }
 
-    let mut input = json!({
-        "title": title,
-        "teamId": team.id
-    });
-
-    if !description.is_empty() {
-        input["description"] = json!(description);
-    }
-    if priority > 0 {
-        input["priority"] = json!(priority);
+    /// The state, assignee or priority picker for the selected issue
+    fn picker_for(&self, key: char) -> Option<Picker<Choice>> {
+        let issue = self.selected_issue()?;
+        let team = &self.data.as_ref()?.team;
+
+        let picker = match key {
+            's' => {
+                let mut states = team.states.clone().unwrap_or_default().nodes;
+                states.sort_by(|a, b| {
+                    a.position
+                        .unwrap_or(0.0)
+                        .total_cmp(&b.position.unwrap_or(0.0))
+                });
+                let current = issue.state.as_ref().map(|s| s.id.as_str());
+                let selected = states
+                    .iter()
+                    .position(|s| Some(s.id.as_str()) == current)
+                    .unwrap_or(0);
+                let options = states
+                    .into_iter()
+                    .map(|s| (s.name, Choice::State(s.id)))
+                    .collect();
+                Picker::new("State", options, selected)
+            }
+            'a' => {
+                let mut members = team.members.clone().unwrap_or_default().nodes;
+                members.sort_by_key(|m| m.name.to_lowercase());
+                let current = issue.assignee.as_ref().map(|u| u.id.as_str());
+                let selected = members
+                    .iter()
+                    .position(|m| Some(m.id.as_str()) == current)
+                    .map_or(0, |i| i + 1);
+                let options = std::iter::once(("Unassigned".to_string(), Choice::Assignee(None)))
+                    .chain(
+                        members
+                            .into_iter()
+                            .map(|m| (m.name, Choice::Assignee(Some(m.id)))),
+                    )
+                    .collect();
+                Picker::new("Assignee", options, selected)
+            }
+            _ => {
+                let options = (0..=4)
+                    .map(|p| {
+                        let name = if p == 0 {
+                            "No priority"
+                        } else {
+                            priority_name(Some(p))
+                        };
+                        (name.to_string(), Choice::Priority(p))
+                    })
+                    .collect();
+                Picker::new("Priority", options, issue.priority.unwrap_or(0) as usize)
+            }
+        };
+        Some(picker)
     }
 
-    let mutation = r#"
-        mutation($input: IssueCreateInput!) {
-            issueCreate(input: $input) {
-                success
-                issue {
-                    id
-                    identifier
-                    title
-                    url
-                }
+    /// Apply a picker choice locally and return the mutation that persists it
+    fn apply(&mut self, choice: Choice) -> Option<Action> {
+        if let Choice::Team(index) = choice {
+            if index == self.team {
+                return None;
             }
+            self.team = index;
+            self.data = None;
+            self.filter.clear();
+            self.selected = 0;
+            return Some(self.refresh());
         }
-    "#;
-
-    let result = client
-        .mutate(mutation, Some(json!({ "input": input })))
-        .await?;
 
-    if result["data"]["issueCreate"]["success"].as_bool() == Some(true) {
-        let issue = &result["data"]["issueCreate"]["issue"];
-        let identifier = issue["identifier"].as_str().unwrap_or("");
-        let issue_title = issue["title"].as_str().unwrap_or("");
-        println!(
-            "\n{} Created issue: {} {}",
-            "+".green(),
-            identifier.cyan(),
-            issue_title
-        );
-        println!("  URL: {}", issue["url"].as_str().unwrap_or(""));
-    } else {
-        anyhow::bail!("Failed to create issue");
+        let issue_id = self.selected_issue()?.id.clone();
+        let data = self.data.as_mut()?;
+        let team = &data.team;
+        let issue = data.issues.iter_mut().find(|i| i.id == issue_id)?;
+
+        let input = match choice {
+            Choice::State(id) => {
+                issue.state = team
+                    .states
+                    .as_ref()
+                    .and_then(|s| s.nodes.iter().find(|s| s.id == id).cloned());
+                json!({ "stateId": id })
+            }
+            Choice::Assignee(id) => {
+                issue.assignee = id.as_ref().and_then(|id| {
+                    team.members
+                        .as_ref()
+                        .and_then(|m| m.nodes.iter().find(|m| &m.id == id).cloned())
+                });
+                json!({ "assigneeId": id })
+            }
+            Choice::Priority(priority) => {
+                issue.priority = Some(priority);
+                json!({ "priority": priority })
+            }
+            Choice::Team(_) => unreachable!("team changes are handled above"),
+        };
+
+        self.status = format!("Saving {}...", issue.identifier);
+        Some(Action::Update {
+            issue_id,
+            identifier: issue.identifier.clone(),
+            input,
+        })
     }
 
-    Ok(())
-}
-
-async fn list_issues_interactive(client: &LinearClient, team: &Team) -> Result<()> {
-    println!("\n{}", "Issues".cyan().bold());
-
-    let query = r#"
-        query($teamId: String!) {
-            team(id: $teamId) {
-                issues(first: 25) {
-                    nodes {
-                        id
-                        identifier
-                        title
-                        priority
-                        state { name }
+    pub fn on_message(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::Loaded { team_id, result } => {
+                if team_id != self.team_id() {
+                    // A load for a team we've since switched away from
+                    return None;
+                }
+                self.loading = false;
+                match result {
+                    Ok(data) => {
+                        let selected_id = self.selected_issue().map(|i| i.id.clone());
+                        self.data = Some(*data);
+                        let index = selected_id
+                            .and_then(|id| self.visible().iter().position(|i| i.id == id))
+                            .unwrap_or(self.selected);
+                        self.select(index);
                     }
+                    Err(e) => self.status = format!("Refresh failed: {:#}", e),
                 }
+                None
+            }
+            Message::Saved(result) => {
+                self.status = match result {
+                    Ok(status) => status,
+                    Err(e) => format!("Save failed: {:#}", e),
+                };
+                // Reload either way: to pick up new issues, or to undo a local edit
+                Some(self.refresh())
+            }
+            Message::Retrying(notice) => {
+                self.status = notice;
+                None
             }
         }
-    "#;
+    }
 
-    let result = client
-        .query(query, Some(json!({ "teamId": team.id })))
-        .await?;
+    pub fn draw(&self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(body);
+
+        self.draw_header(frame, header);
+        self.draw_list(frame, list);
+        self.draw_detail(frame, detail);
+        self.draw_footer(frame, footer);
+
+        match &self.mode {
+            Mode::Pick(picker) => picker.draw(frame, body),
+            Mode::Help => draw_help(frame, body),
+            _ => {}
+        }
+    }
 
-    let empty = vec![];
-    let issues = result["data"]["team"]["issues"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let team = &self.teams[self.team];
+        let mut spans = vec![
+            Span::styled(
+                format!(" {} ({}) ", team.name, team.key),
+                Style::new().add_modifier(Modifier::BOLD).fg(Color::Cyan),
+            ),
+            Span::raw(match &self.data {
+                Some(data) => format!("{} open issues", data.issues.len()),
+                None => "loading...".to_string(),
+            }),
+        ];
+        if !self.filter.is_empty() {
+            spans.push(Span::raw(format!("  filter: {}", self.filter)));
+        }
+        if self.loading && self.data.is_some() {
+            spans.push(Span::styled(
+                "  refreshing...",
+                Style::new().add_modifier(Modifier::DIM),
+            ));
+        }
+        frame.render_widget(Line::from(spans), area);
+    }
 
-    if issues.is_empty() {
-        println!("No issues found for team {}.", team.name);
-        return Ok(());
+    fn draw_list(&self, frame: &mut Frame, area: Rect) {
+        let issues = self.visible();
+        let rows: Vec<Row> = issues
+            .iter()
+            .map(|issue| {
+                let kind = issue.state.as_ref().and_then(|s| s.kind.as_deref());
+                Row::new([
+                    Cell::from(issue.identifier.clone()),
+                    Cell::from(priority_name(issue.priority)),
+                    Cell::from(issue.state_name().to_string()).style(tui::state_style(kind)),
+                    Cell::from(issue.title.clone()),
+                ])
+            })
+            .collect();
+
+        let id_width = issues.iter().map(|i| i.identifier.len()).max().unwrap_or(6) as u16;
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(id_width),
+                Constraint::Length(6),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+        )
+        .block(Block::default().borders(Borders::ALL).title(" Issues "))
+        .row_highlight_style(tui::highlight_style());
+
+        let selected = (!issues.is_empty()).then_some(self.selected);
+        let mut state = TableState::default().with_selected(selected);
+        frame.render_stateful_widget(table, area, &mut state);
     }
 
-    let rows: Vec<IssueRow> = issues
-        .iter()
-        .map(|issue| IssueRow {
-            identifier: issue["identifier"].as_str().unwrap_or("").to_string(),
-            title: {
-                let t = issue["title"].as_str().unwrap_or("");
-                if t.len() > 50 {
-                    format!("{}...", &t[..47])
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL);
+        let Some(issue) = self.selected_issue() else {
+            let text = match &self.data {
+                Some(_) if !self.filter.is_empty() => "No issues match the filter.",
+                Some(_) => "No open issues.",
+                None => "Loading issues...",
+            };
+            frame.render_widget(Paragraph::new(text).block(block), area);
+            return;
+        };
+
+        let field = |name: &str, value: String| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<10}", name),
+                    Style::new().add_modifier(Modifier::DIM),
+                ),
+                Span::raw(value),
+            ])
+        };
+        let labels = issue.label_names();
+        let mut lines = vec![
+            Line::styled(
+                issue.title.clone(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+            field("State", issue.state_name().to_string()),
+            field("Priority", priority_name(issue.priority).to_string()),
+            field("Assignee", issue.assignee_name().to_string()),
+            field(
+                "Labels",
+                if labels.is_empty() {
+                    "-".to_string()
                 } else {
-                    t.to_string()
-                }
-            },
-            state: issue["state"]["name"].as_str().unwrap_or("-").to_string(),
-            priority: priority_to_string(issue["priority"].as_i64()),
-        })
-        .collect();
-
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} issues (showing up to 25)", issues.len());
+                    labels.join(", ")
+                },
+            ),
+            field("URL", issue.url.clone().unwrap_or_else(|| "-".to_string())),
+        ];
+        if let Some(description) = issue.description.as_deref().filter(|d| !d.is_empty()) {
+            lines.push(Line::default());
+            lines.extend(Text::raw(description.to_string()).lines);
+        }
 
-    Ok(())
-}
+        let paragraph = Paragraph::new(lines)
+            .block(block.title(format!(" {} ", issue.identifier)))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
 
-fn priority_to_string(priority: Option<i64>) -> String {
-    match priority {
-        Some(0) => "-".to_string(),
-        Some(1) => "Urgent".red().to_string(),
-        Some(2) => "High".yellow().to_string(),
-        Some(3) => "Normal".to_string(),
-        Some(4) => "Low".dimmed().to_string(),
-        _ => "-".to_string(),
+    fn draw_footer(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.mode {
+            Mode::Filter => Line::from(format!("/{}_", self.filter)),
+            Mode::Create(title) => Line::from(format!("New issue title: {}_", title)),
+            _ if !self.status.is_empty() => Line::from(self.status.clone()),
+            _ => Line::styled(
+                " j/k move  / filter  s state  a assignee  p priority  n new  t team  ? help  q quit",
+                Style::new().add_modifier(Modifier::DIM),
+            ),
+        };
+        frame.render_widget(line, area);
     }
 }
 
-async fn view_issue_interactive(client: &LinearClient) -> Result<()> {
-    println!("\n{}", "View Issue".cyan().bold());
+fn draw_help(frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = KEY_HELP
+        .iter()
+        .map(|(keys, action)| Line::from(format!(" {:<15} {}", keys, action)))
+        .collect();
+    let popup = tui::centered(area, 48, lines.len() as u16 + 2);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Keys ")),
+        popup,
+    );
+}
 
-    let issue_id: String = Input::new()
-        .with_prompt("Issue ID (e.g., LIN-123)")
-        .interact_text()?;
+pub async fn run() -> Result<()> {
+    let (tx, messages) = mpsc::unbounded_channel();
+    let retries = tx.clone();
+    let client = Arc::new(LinearClient::new()?.on_retry(move |notice| {
+        let _ = retries.send(Message::Retrying(notice));
+    }));
 
-    if issue_id.trim().is_empty() {
-        println!("Issue ID cannot be empty.");
+    let teams: Vec<Team> = models::from_nodes(&Resolver::new(&client).teams().await?)?;
+    if teams.is_empty() {
+        println!("No teams found. Please check your API key.");
         return Ok(());
     }
 
-    let query = r#"
-        query($id: String!) {
-            issue(id: $id) {
-                id
-                identifier
-                title
-                description
-                priority
-                url
-                state { name }
-                team { name }
-                assignee { name email }
-                labels { nodes { name } }
-            }
-        }
-    "#;
-
-    let result = client.query(query, Some(json!({ "id": issue_id }))).await?;
-    let issue = &result["data"]["issue"];
-
-    if issue.is_null() {
-        println!("Issue not found: {}", issue_id);
-        return Ok(());
+    let mut app = App::new(teams);
+    if let Some(team) = config::default_value("team") {
+        app = app.with_team(&team);
     }
+    let mut terminal = tui::enter()?;
+    let result = event_loop(&mut terminal, &mut app, client, tx, messages).await;
+    tui::leave(&mut terminal)?;
+    result
+}
 
-    let identifier = issue["identifier"].as_str().unwrap_or("");
-    let title = issue["title"].as_str().unwrap_or("");
-    println!("\n{} {}", identifier.cyan().bold(), title.bold());
-    println!("{}", "-".repeat(60));
+async fn event_loop(
+    terminal: &mut tui::Tui,
+    app: &mut App,
+    client: Arc<LinearClient>,
+    tx: UnboundedSender<Message>,
+    mut messages: UnboundedReceiver<Message>,
+) -> Result<()> {
+    let mut events = tui::terminal_events();
+    // The first tick fires immediately and loads the initial team
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let action = tokio::select! {
+            Some(event) = events.recv() => match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.on_key(key),
+                _ => None,
+            },
+            Some(message) = messages.recv() => app.on_message(message),
+            _ = refresh.tick() => Some(app.refresh()),
+        };
 
-    if let Some(desc) = issue["description"].as_str() {
-        if !desc.is_empty() {
-            let truncated = if desc.len() > 200 {
-                format!("{}...", &desc[..197])
-            } else {
-                desc.to_string()
-            };
-            println!("\n{}\n", truncated);
+        if let Some(action) = action {
+            perform(action, &client, &tx);
         }
     }
 
-    println!(
-        "State:    {}",
-        issue["state"]["name"].as_str().unwrap_or("-")
-    );
-    println!(
-        "Priority: {}",
-        priority_to_string(issue["priority"].as_i64())
-    );
-    println!(
-        "Team:     {}",
-        issue["team"]["name"].as_str().unwrap_or("-")
-    );
+    Ok(())
+}
 
-    if let Some(assignee) = issue["assignee"]["name"].as_str() {
-        println!("Assignee: {}", assignee);
-    } else {
-        println!("Assignee: -");
-    }
+/// Run an action in the background, reporting back through `tx`
+fn perform(action: Action, client: &Arc<LinearClient>, tx: &UnboundedSender<Message>) {
+    let client = Arc::clone(client);
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let message = match action {
+            Action::Refresh { team_id } => {
+                let result = load_team(&client, &team_id).await.map(Box::new);
+                Message::Loaded { team_id, result }
+            }
+            Action::Update {
+                issue_id,
+                identifier,
+                input,
+            } => Message::Saved(
+                update_issue(&client, &issue_id, input)
+                    .await
+                    .map(|_| format!("Updated {}", identifier)),
+            ),
+            Action::Create { team_id, title } => {
+                Message::Saved(create_issue(&client, &team_id, &title).await)
+            }
+        };
+        let _ = tx.send(message);
+    });
+}
 
-    let labels = issue["labels"]["nodes"].as_array();
-    if let Some(labels) = labels {
-        if !labels.is_empty() {
-            let label_names: Vec<&str> = labels.iter().filter_map(|l| l["name"].as_str()).collect();
-            println!("Labels:   {}", label_names.join(", "));
-        }
-    }
+async fn load_team(client: &LinearClient, team_id: &str) -> Result<TeamIssues> {
+    let result = client
+        .query(
+            TEAM_ISSUES_QUERY,
+            Some(json!({ "teamId": team_id, "first": ISSUE_LIMIT })),
+        )
+        .await?;
+    let team = &result["data"]["team"];
 
-    println!("\nURL: {}", issue["url"].as_str().unwrap_or("-"));
+    if team.is_null() {
+        anyhow::bail!("Team not found: {}", team_id);
+    }
 
-    Ok(())
+    Ok(TeamIssues {
+        team: models::from_value(team)?,
+        issues: models::from_value(&team["issues"]["nodes"])?,
+    })
 }
 
-async fn update_issue_interactive(client: &LinearClient) -> Result<()> {
-    println!("\n{}", "Update Issue".cyan().bold());
+async fn update_issue(client: &LinearClient, issue_id: &str, input: Value) -> Result<()> {
+    let mutation = r#"
+        mutation($id: String!, $input: IssueUpdateInput!) {
+            issueUpdate(id: $id, input: $input) {
+                success
+            }
+        }
+    "#;
 
-    let issue_id: String = Input::new()
-        .with_prompt("Issue ID (e.g., LIN-123)")
-        .interact_text()?;
+    let result = client
+        .mutate(mutation, Some(json!({ "id": issue_id, "input": input })))
+        .await?;
 
-    if issue_id.trim().is_empty() {
-        println!("Issue ID cannot be empty.");
-        return Ok(());
+    if result["data"]["issueUpdate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Failed to update issue");
     }
+    Ok(())
+}
 
-    // First fetch the issue to show current state and get the UUID
-    let query = r#"
-        query($id: String!) {
-            issue(id: $id) {
-                id
-                identifier
-                title
-                description
-                priority
-                state { id name }
-                assignee { id name }
-                team {
+async fn create_issue(client: &LinearClient, team_id: &str, title: &str) -> Result<String> {
+    let mutation = r#"
+        mutation($input: IssueCreateInput!) {
+            issueCreate(input: $input) {
+                success
+                issue {
                     id
-                    states { nodes { id name type } }
-                    members { nodes { id name } }
+                    identifier
+                    title
                 }
             }
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "id": issue_id }))).await?;
-    let issue = &result["data"]["issue"];
+    let input = json!({ "teamId": team_id, "title": title });
+    let result = client
+        .mutate(mutation, Some(json!({ "input": input })))
+        .await?;
 
-    if issue.is_null() {
-        println!("Issue not found: {}", issue_id);
-        return Ok(());
+    if result["data"]["issueCreate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Failed to create issue");
     }
+    let issue: Issue = models::from_value(&result["data"]["issueCreate"]["issue"])?;
+    Ok(format!("Created {} {}", issue.identifier, issue.title))
+}
 
-    // Get the UUID for the update mutation
-    let issue_uuid = issue["id"].as_str().unwrap_or(&issue_id);
-    let current_title = issue["title"].as_str().unwrap_or("");
-    let current_description = issue["description"].as_str().unwrap_or("");
-    let identifier = issue["identifier"].as_str().unwrap_or("");
-    let current_state = issue["state"]["name"].as_str().unwrap_or("Unknown");
-    let current_assignee = issue["assignee"]["name"].as_str().unwrap_or("Unassigned");
-
-    println!("\nCurrent: {} {}", identifier.cyan(), current_title);
-    println!(
-        "Status: {} | Assignee: {}",
-        current_state.yellow(),
-        current_assignee.dimmed()
-    );
-
-    let update_options = vec![
-        "Title",
-        "Priority",
-        "Status",
-        "Assignee",
-        "Description",
-        "Cancel",
-    ];
-    let selection = Select::new()
-        .with_prompt("What to update?")
-        .items(&update_options)
-        .default(0)
-        .interact()?;
-
-    let mut input = json!({});
-
-    match selection {
-        0 => {
-            // Update title
-            let new_title: String = Input::new()
-                .with_prompt("New title")
-                .with_initial_text(current_title)
-                .interact_text()?;
-
-            if !new_title.is_empty() && new_title != current_title {
-                input["title"] = json!(new_title);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn sample_app() -> App {
+        let team: Team = models::from_value(&json!({
+            "id": "team-1",
+            "key": "ENG",
+            "name": "Engineering",
+            "states": { "nodes": [
+                { "id": "s-done", "name": "Done", "type": "completed", "position": 3 },
+                { "id": "s-todo", "name": "Todo", "type": "unstarted", "position": 1 },
+                { "id": "s-doing", "name": "In Progress", "type": "started", "position": 2 }
+            ] },
+            "members": { "nodes": [
+                { "id": "u-bob", "name": "Bob" },
+                { "id": "u-alice", "name": "Alice" }
+            ] }
+        }))
+        .unwrap();
+        let issues = models::from_value(&json!([
+            {
+                "id": "i-1", "identifier": "ENG-1", "title": "Fix login", "priority": 2,
+                "description": "Users get logged out.",
+                "state": { "id": "s-todo", "name": "Todo", "type": "unstarted" },
+                "assignee": { "id": "u-alice", "name": "Alice" },
+                "labels": { "nodes": [{ "id": "l-bug", "name": "Bug" }] }
+            },
+            {
+                "id": "i-2", "identifier": "ENG-2", "title": "Add dark mode", "priority": 0,
+                "state": { "id": "s-doing", "name": "In Progress", "type": "started" }
             }
-        }
-        1 => {
-            // Update priority
-            let priority_options = vec!["None", "Urgent", "High", "Normal", "Low"];
-            let current_priority = issue["priority"].as_i64().unwrap_or(0) as usize;
-            let priority_selection = Select::new()
-                .with_prompt("New priority")
-                .items(&priority_options)
-                .default(current_priority)
-                .interact()?;
-
-            input["priority"] = json!(priority_selection);
-        }
-        2 => {
-            // Update status
-            let states = issue["team"]["states"]["nodes"].as_array();
-            if let Some(states) = states {
-                let state_names: Vec<&str> =
-                    states.iter().filter_map(|s| s["name"].as_str()).collect();
-
-                if state_names.is_empty() {
-                    println!("No states available for this team.");
-                    return Ok(());
-                }
+        ]))
+        .unwrap();
 
-                // Find current state index
-                let current_state_id = issue["state"]["id"].as_str().unwrap_or("");
-                let current_idx = states
-                    .iter()
-                    .position(|s| s["id"].as_str() == Some(current_state_id))
-                    .unwrap_or(0);
-
-                let state_selection = Select::new()
-                    .with_prompt("New status")
-                    .items(&state_names)
-                    .default(current_idx)
-                    .interact()?;
+        let mut app = App::new(vec![team.clone()]);
+        app.data = Some(TeamIssues { team, issues });
+        app
+    }
 
-                if let Some(state_id) = states[state_selection]["id"].as_str() {
-                    input["stateId"] = json!(state_id);
-                }
-            } else {
-                println!("Could not fetch team states.");
-                return Ok(());
-            }
+    /// Feed keys to the app: `\n` is Enter, `\x1b` is Esc and `v` is Down
+    fn press(app: &mut App, keys: &str) -> Option<Action> {
+        let mut action = None;
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\x1b' => KeyCode::Esc,
+                'v' => KeyCode::Down,
+                c => KeyCode::Char(c),
+            };
+            action = app.on_key(KeyEvent::from(code)).or(action);
         }
-        3 => {
-            // Update assignee
-            let members = issue["team"]["members"]["nodes"].as_array();
-            if let Some(members) = members {
-                let mut assignee_names: Vec<&str> = vec!["(Unassign)"];
-                assignee_names.extend(members.iter().filter_map(|m| m["name"].as_str()));
-
-                // Find current assignee index
-                let current_assignee_id = issue["assignee"]["id"].as_str().unwrap_or("");
-                let current_idx = if current_assignee_id.is_empty() {
-                    0 // Unassigned
-                } else {
-                    members
-                        .iter()
-                        .position(|m| m["id"].as_str() == Some(current_assignee_id))
-                        .map(|i| i + 1) // +1 because of "(Unassign)" at index 0
-                        .unwrap_or(0)
-                };
-
-                let assignee_selection = Select::new()
-                    .with_prompt("Assignee")
-                    .items(&assignee_names)
-                    .default(current_idx)
-                    .interact()?;
+        action
+    }
 
-                if assignee_selection == 0 {
-                    input["assigneeId"] = json!(null);
-                } else if let Some(member_id) = members[assignee_selection - 1]["id"].as_str() {
-                    input["assigneeId"] = json!(member_id);
-                }
-            } else {
-                println!("Could not fetch team members.");
-                return Ok(());
-            }
-        }
-        4 => {
-            // Update description
-            println!("Current description:");
-            if current_description.is_empty() {
-                println!("  (empty)");
-            } else {
-                for line in current_description.lines().take(5) {
-                    println!("  {}", line.dimmed());
-                }
-                if current_description.lines().count() > 5 {
-                    println!(
-                        "  {} more lines...",
-                        current_description.lines().count() - 5
-                    );
-                }
-            }
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-            let new_description: String = Input::new()
-                .with_prompt("New description (markdown, single line)")
-                .with_initial_text(current_description)
-                .allow_empty(true)
-                .interact_text()?;
+    #[test]
+    fn test_renders_list_and_detail_pane() {
+        let app = sample_app();
+        let screen = render(&app);
+        assert!(
+            screen.contains("Engineering (ENG) 2 open issues"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("ENG-2"));
+        assert!(screen.contains("Add dark mode"));
+        // Detail pane for the selected (first) issue
+        assert!(screen.contains("Assignee  Alice"), "{}", screen);
+        assert!(screen.contains("Labels    Bug"));
+        assert!(screen.contains("Users get logged out."));
+    }
 
-            if new_description != current_description {
-                input["description"] = json!(new_description);
-            }
-        }
-        _ => {
-            println!("Cancelled.");
-            return Ok(());
-        }
+    #[test]
+    fn test_typing_filters_the_list() {
+        let mut app = sample_app();
+        press(&mut app, "/dark");
+        assert!(matches!(app.mode, Mode::Filter));
+        assert_eq!(app.visible().len(), 1);
+        assert_eq!(app.selected_issue().unwrap().identifier, "ENG-2");
+        assert!(render(&app).contains("/dark_"));
+
+        // Enter keeps the filter, Esc in browse mode clears it
+        press(&mut app, "\n");
+        assert!(matches!(app.mode, Mode::Browse));
+        assert_eq!(app.visible().len(), 1);
+        press(&mut app, "\x1b");
+        assert_eq!(app.visible().len(), 2);
+        assert!(!app.quit);
+
+        press(&mut app, "/nothing matches");
+        assert!(render(&app).contains("No issues match the filter."));
     }
 
-    if input.as_object().map(|o| o.is_empty()).unwrap_or(true) {
-        println!("No changes made.");
-        return Ok(());
+    #[test]
+    fn test_state_hotkey_updates_issue() {
+        let mut app = sample_app();
+        // States are listed by position with the current one selected
+        press(&mut app, "s");
+        let Mode::Pick(picker) = &app.mode else {
+            panic!("expected the state picker")
+        };
+        let names: Vec<&str> = picker.options.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["Todo", "In Progress", "Done"]);
+        assert_eq!(picker.selected, 0);
+        assert!(render(&app).contains(" State "));
+
+        let action = press(&mut app, "v\n");
+        assert_eq!(
+            action,
+            Some(Action::Update {
+                issue_id: "i-1".to_string(),
+                identifier: "ENG-1".to_string(),
+                input: json!({ "stateId": "s-doing" }),
+            })
+        );
+        // Applied locally straight away
+        assert_eq!(app.selected_issue().unwrap().state_name(), "In Progress");
+        assert!(matches!(app.mode, Mode::Browse));
     }
 
-    let confirm = Confirm::new()
-        .with_prompt("Apply changes?")
-        .default(true)
-        .interact()?;
+    #[test]
+    fn test_assignee_and_priority_hotkeys() {
+        let mut app = sample_app();
+        press(&mut app, "j");
+
+        // "Unassigned" comes first, then members sorted by name
+        let action = press(&mut app, "av\n").unwrap();
+        let Action::Update { input, .. } = action else {
+            panic!("expected an update")
+        };
+        assert_eq!(input, json!({ "assigneeId": "u-alice" }));
+        assert_eq!(app.selected_issue().unwrap().assignee_name(), "Alice");
+
+        let action = press(&mut app, "pv\n").unwrap();
+        let Action::Update { input, .. } = action else {
+            panic!("expected an update")
+        };
+        assert_eq!(input, json!({ "priority": 1 }));
+
+        // Esc closes a picker without an action
+        assert_eq!(press(&mut app, "s\x1b"), None);
+    }
 
-    if !confirm {
-        println!("Cancelled.");
-        return Ok(());
+    #[test]
+    fn test_starts_on_the_default_team() {
+        let teams: Vec<Team> = models::from_value(&json!([
+            { "id": "team-1", "key": "ENG", "name": "Engineering" },
+            { "id": "team-2", "key": "DES", "name": "Design" }
+        ]))
+        .unwrap();
+        assert_eq!(App::new(teams.clone()).team_id(), "team-1");
+        assert_eq!(App::new(teams.clone()).with_team("des").team_id(), "team-2");
+        assert_eq!(
+            App::new(teams.clone()).with_team("Design").team_id(),
+            "team-2"
+        );
+        assert_eq!(
+            App::new(teams.clone()).with_team("team-2").team_id(),
+            "team-2"
+        );
+        assert_eq!(App::new(teams).with_team("OPS").team_id(), "team-1");
     }
 
-    let mutation = r#"
-        mutation($id: String!, $input: IssueUpdateInput!) {
-            issueUpdate(id: $id, input: $input) {
-                success
-                issue {
-                    identifier
-                    title
-                }
-            }
-        }
-    "#;
+    #[test]
+    fn test_background_messages() {
+        let mut app = sample_app();
+        press(&mut app, "j");
+
+        // A refresh keeps the selection on the same issue
+        let mut data = app.data.clone().unwrap();
+        data.issues.reverse();
+        app.on_message(Message::Loaded {
+            team_id: "team-1".to_string(),
+            result: Ok(Box::new(data)),
+        });
+        assert_eq!(app.selected_issue().unwrap().identifier, "ENG-2");
+
+        // Loads for another team are ignored
+        app.on_message(Message::Loaded {
+            team_id: "team-2".to_string(),
+            result: Ok(Box::new(TeamIssues {
+                team: app.teams[0].clone(),
+                issues: Vec::new(),
+            })),
+        });
+        assert_eq!(app.visible().len(), 2);
+
+        // A failed save is reported and reloads to undo the local edit
+        let action = app.on_message(Message::Saved(Err(anyhow::anyhow!("boom"))));
+        assert_eq!(
+            action,
+            Some(Action::Refresh {
+                team_id: "team-1".to_string()
+            })
+        );
+        assert!(render(&app).contains("Save failed: boom"));
 
-    // Use the UUID for the update mutation
-    let result = client
-        .mutate(mutation, Some(json!({ "id": issue_uuid, "input": input })))
-        .await?;
+        // Retries show in the status line rather than on stderr
+        let notice = "Network error; retrying in 1.0s (attempt 2/3)".to_string();
+        assert_eq!(app.on_message(Message::Retrying(notice)), None);
+        assert!(render(&app).contains("retrying in 1.0s"));
+    }
 
-    if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-        let updated = &result["data"]["issueUpdate"]["issue"];
-        println!(
-            "\n{} Updated issue: {} {}",
-            "+".green(),
-            updated["identifier"].as_str().unwrap_or(""),
-            updated["title"].as_str().unwrap_or("")
+    #[test]
+    fn test_create_issue_prompt() {
+        let mut app = sample_app();
+        let action = press(&mut app, "nShip it\n");
+        assert_eq!(
+            action,
+            Some(Action::Create {
+                team_id: "team-1".to_string(),
+                title: "Ship it".to_string(),
+            })
         );
-    } else {
-        anyhow::bail!("Failed to update issue");
+        assert_eq!(press(&mut app, "n\n"), None);
     }
-
-    Ok(())
 }
@@ -1,30 +1,58 @@
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::process::Command;
-use tabled::{Table, Tabled};
-
-use crate::api::{resolve_team_id, LinearClient};
+use tabled::Tabled;
+
+use crate::api::{self, LinearClient, PageOptions};
+use crate::config;
+use crate::fields;
+use crate::filter;
+use crate::mirror::{self, Mirror};
+use crate::models::{self, priority_label, Issue};
+use crate::output::{print_value, truncate, TableStream, ValueStream};
+use crate::resolver::Resolver;
 use crate::OutputFormat;
 
-use super::templates;
+use super::queue::{self, Operation};
+use super::{board, git, templates, time};
 
 #[derive(Subcommand)]
 pub enum IssueCommands {
     /// List issues
     #[command(alias = "ls")]
     #[command(after_help = r#"EXAMPLES:
-    linear issues list                         # List all issues
+    linear issues list                         # List issues (in the default team, if set)
     linear i list -t ENG                       # Filter by team
+    linear i list --all-teams                  # Every team, ignoring the default team
     linear i list -t ENG -s "In Progress"      # Filter by team and status
     linear i list --assignee me                # Show my assigned issues
     linear i list --project "My Project"       # Filter by project name
-    linear i list --output json                # Output as JSON"#)]
+    linear i list -t ENG --all                 # Fetch every page
+    linear i list --output json                # Output as JSON
+    linear i list --filter 'priority<=2 and label:bug and updated>-7d and not assignee:none'
+    linear i list --offline -t ENG             # Read the copy from `linear sync pull`
+
+FILTER EXPRESSIONS:
+    Conditions are `field op value`, combined with and, or, not and parentheses
+    (adjacent conditions are and-ed). Quote values with spaces: state:"In Progress".
+    Operators:  :  =  !=  <  <=  >  >=  ~ (contains)
+    Fields:     team, state, assignee, creator, project, cycle, label, priority,
+                estimate, title, parent, created, updated, started, completed,
+                canceled, due
+    Values:     none (unset), me (assignee/creator), current/next/previous (cycle),
+                urgent/high/normal/low (priority), ENG-12 (parent),
+                dates as 2024-06-01 or relative to now: -7d, -2w, 3m
+    priority<=high means urgent or high; issues without a priority never match <, <=."#)]
     List {
-        /// Filter by team name or ID
+        /// Filter by team key or name (default: the default team)
         #[arg(short, long)]
         team: Option<String>,
+        /// List issues in every team, ignoring the default team
+        #[arg(long, conflicts_with = "team")]
+        all_teams: bool,
         /// Filter by state name or ID
         #[arg(short, long)]
         state: Option<String>,
@@ -34,12 +62,21 @@ pub enum IssueCommands {
         /// Filter by project name
         #[arg(long)]
         project: Option<String>,
+        /// Filter expression, e.g. 'priority<=2 and label:bug' (see below)
+        #[arg(short, long)]
+        filter: Option<String>,
         /// Include archived issues
         #[arg(long)]
         archived: bool,
         /// Maximum number of issues to return
         #[arg(short, long, default_value = "50")]
-        limit: u32,
+        limit: usize,
+        /// Fetch all matching issues, ignoring --limit
+        #[arg(long)]
+        all: bool,
+        /// Read from the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Get issue details
     #[command(after_help = r#"EXAMPLES:
@@ -55,11 +92,17 @@ pub enum IssueCommands {
     linear issues create "Fix bug" -t ENG      # Create with title and team
     linear i create "Feature" -t ENG -p 2      # Create with high priority
     linear i create "Task" -t ENG -a me        # Assign to yourself
-    linear i create "Bug" -t ENG -s "Backlog"  # Set initial status"#)]
+    linear i create "Bug" -t ENG -s "Backlog"  # Set initial status
+    linear i create "Bug" -t ENG -l bug        # Add a label by name
+    linear i create "Task" -t ENG --cycle current --project "Q1 Roadmap"
+    linear i create "Bug" -t ENG --queue       # Queue it if Linear can't be reached
+
+Team, project, assignee and cycle fall back to the workspace defaults
+(see: linear config set --help)."#)]
     Create {
         /// Issue title
         title: String,
-        /// Team name or ID (can be provided via template)
+        /// Team name or ID [default: the template's team, or default.team]
         #[arg(short, long)]
         team: Option<String>,
         /// Issue description (markdown)
@@ -71,22 +114,33 @@ pub enum IssueCommands {
         /// State name or ID
         #[arg(short, long)]
         state: Option<String>,
-        /// Assignee (user ID, name, email, or "me")
+        /// Assignee (user ID, name, email, or "me") [default: default.assignee]
         #[arg(short, long)]
         assignee: Option<String>,
-        /// Labels to add (can be specified multiple times)
+        /// Labels to add by name or ID (can be specified multiple times) [default: default.labels]
         #[arg(short, long)]
         labels: Vec<String>,
+        /// Project name or ID [default: default.project]
+        #[arg(long)]
+        project: Option<String>,
+        /// Cycle: current, next, previous, a cycle number, or none [default: default.cycle]
+        #[arg(long)]
+        cycle: Option<String>,
         /// Template name to use for default values
         #[arg(long)]
         template: Option<String>,
+        /// Queue the issue to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
     /// Update an existing issue
     #[command(after_help = r#"EXAMPLES:
     linear issues update LIN-123 -s Done       # Mark as done
     linear i update LIN-123 -T "New title"     # Change title
     linear i update LIN-123 -p 1               # Set to urgent priority
-    linear i update LIN-123 -a me              # Assign to yourself"#)]
+    linear i update LIN-123 -a me              # Assign to yourself
+    linear i update LIN-123 -l bug -l urgent   # Add labels
+    linear i update LIN-123 -s Done --queue    # Queue it if Linear can't be reached"#)]
     Update {
         /// Issue ID
         id: String,
@@ -105,6 +159,12 @@ pub enum IssueCommands {
         /// New assignee (user ID, name, email, or "me")
         #[arg(short, long)]
         assignee: Option<String>,
+        /// Labels to add by name or ID (can be specified multiple times)
+        #[arg(short, long)]
+        labels: Vec<String>,
+        /// Queue the update to send later if Linear can't be reached (see: linear queue)
+        #[arg(long)]
+        queue: bool,
     },
     /// Delete an issue
     #[command(after_help = r#"EXAMPLES:
@@ -121,7 +181,8 @@ pub enum IssueCommands {
     #[command(after_help = r#"EXAMPLES:
     linear issues start LIN-123                # Start working on issue
     linear i start LIN-123 --checkout          # Start and checkout git branch
-    linear i start LIN-123 -c -b feature/fix   # Start with custom branch"#)]
+    linear i start LIN-123 -c -b feature/fix   # Start with custom branch
+    linear i start LIN-123 --timer             # Start and begin tracking time"#)]
     Start {
         /// Issue ID or identifier (e.g., "LIN-123")
         id: String,
@@ -131,17 +192,24 @@ pub enum IssueCommands {
         /// Custom branch name (optional, uses issue's branch name by default)
         #[arg(short, long)]
         branch: Option<String>,
+        /// Start a time tracking timer on the issue
+        #[arg(long)]
+        timer: bool,
     },
     /// Stop working on an issue (return to backlog state)
     #[command(after_help = r#"EXAMPLES:
     linear issues stop LIN-123                 # Stop working on issue
-    linear i stop LIN-123 --unassign           # Stop and unassign"#)]
+    linear i stop LIN-123 --unassign           # Stop and unassign
+    linear i stop LIN-123 --timer              # Stop and log the running timer"#)]
     Stop {
         /// Issue ID or identifier (e.g., "LIN-123")
         id: String,
         /// Unassign the issue
         #[arg(short, long)]
         unassign: bool,
+        /// Stop the issue's running timer and log the elapsed time
+        #[arg(long)]
+        timer: bool,
     },
 }
 
@@ -163,12 +231,29 @@ pub async fn handle(cmd: IssueCommands, output: OutputFormat) -> Result<()> {
     match cmd {
         IssueCommands::List {
             team,
+            all_teams,
             state,
             assignee,
             project,
+            filter,
             archived,
             limit,
-        } => list_issues(team, state, assignee, project, archived, limit, output).await,
+            all,
+            offline,
+        } => {
+            let team = config::list_team(team, all_teams);
+            let pages = PageOptions::new(limit, all);
+            if offline {
+                return list_offline_issues(
+                    team, state, assignee, project, filter, archived, pages, output,
+                )
+                .await;
+            }
+            list_issues(
+                team, state, assignee, project, filter, archived, pages, output,
+            )
+            .await
+        }
         IssueCommands::Get { id } => get_issue(&id, output).await,
         IssueCommands::Create {
             title,
@@ -178,7 +263,10 @@ pub async fn handle(cmd: IssueCommands, output: OutputFormat) -> Result<()> {
             state,
             assignee,
             labels,
+            project,
+            cycle,
             template,
+            queue,
         } => {
             // Load template if specified
             let tpl = if let Some(ref tpl_name) = template {
@@ -195,10 +283,8 @@ pub async fn handle(cmd: IssueCommands, output: OutputFormat) -> Result<()> {
                 }
             };
 
-            // Team from CLI arg takes precedence, then template, then error
-            let final_team = team.or(tpl.team.clone()).ok_or_else(|| {
-                anyhow::anyhow!("--team is required (or use a template with a default team)")
-            })?;
+            // Team from CLI arg takes precedence, then template, then the workspace default
+            let final_team = config::team_or_default(team.or(tpl.team.clone()))?;
 
             // Build title with optional prefix from template
             let final_title = if let Some(ref prefix) = tpl.title_prefix {
@@ -211,21 +297,35 @@ pub async fn handle(cmd: IssueCommands, output: OutputFormat) -> Result<()> {
             let final_description = description.or(tpl.description.clone());
             let final_priority = priority.or(tpl.default_priority);
 
-            // Merge labels: template labels + CLI labels
+            // Merge labels: template labels + CLI labels, or the default labels
+            // when none were given
             let mut final_labels = tpl.default_labels.clone();
-            final_labels.extend(labels);
+            if labels.is_empty() {
+                final_labels.extend(
+                    config::default_value("labels")
+                        .map(|l| config::split_labels(&l))
+                        .unwrap_or_default(),
+                );
+            } else {
+                final_labels.extend(labels);
+            }
 
-            create_issue(
-                &final_title,
-                &final_team,
-                final_description,
-                final_priority,
+            let cycle = cycle
+                .or_else(|| config::default_value("cycle"))
+                .filter(|c| !c.eq_ignore_ascii_case("none"));
+
+            let issue = NewIssue {
+                title: final_title,
+                team: final_team,
+                description: final_description,
+                priority: final_priority,
                 state,
-                assignee,
-                final_labels,
-                output,
-            )
-            .await
+                assignee: assignee.or_else(|| config::default_value("assignee")),
+                labels: final_labels,
+                project: project.or_else(|| config::default_value("project")),
+                cycle,
+            };
+            create_issue(issue, queue, output).await
         }
         IssueCommands::Update {
             id,
@@ -234,49 +334,61 @@ pub async fn handle(cmd: IssueCommands, output: OutputFormat) -> Result<()> {
             priority,
             state,
             assignee,
-        } => update_issue(&id, title, description, priority, state, assignee, output).await,
+            labels,
+            queue,
+        } => {
+            let changes = IssueChanges {
+                title,
+                description,
+                priority,
+                state,
+                assignee,
+                labels,
+            };
+            update_issue(&id, changes, queue, output).await
+        }
         IssueCommands::Delete { id, force } => delete_issue(&id, force).await,
         IssueCommands::Start {
             id,
             checkout,
             branch,
-        } => start_issue(&id, checkout, branch).await,
-        IssueCommands::Stop { id, unassign } => stop_issue(&id, unassign).await,
-    }
-}
-
-fn priority_to_string(priority: Option<i64>) -> String {
-    match priority {
-        Some(0) => "-".to_string(),
-        Some(1) => "Urgent".red().to_string(),
-        Some(2) => "High".yellow().to_string(),
-        Some(3) => "Normal".to_string(),
-        Some(4) => "Low".dimmed().to_string(),
-        _ => "-".to_string(),
+            timer,
+        } => start_issue(&id, checkout, branch, timer).await,
+        IssueCommands::Stop {
+            id,
+            unassign,
+            timer,
+        } => stop_issue(&id, unassign, timer).await,
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn list_issues(
     team: Option<String>,
     state: Option<String>,
     assignee: Option<String>,
     project: Option<String>,
+    filter: Option<String>,
     include_archived: bool,
-    limit: u32,
+    pages: PageOptions,
     output: OutputFormat,
 ) -> Result<()> {
+    // Compile the filter first so a typo fails before any request is made
+    let filter = filter.as_deref().map(filter::compile).transpose()?;
+
     let client = LinearClient::new()?;
 
     let query = r#"
-        query($team: String, $state: String, $assignee: String, $project: String, $includeArchived: Boolean, $limit: Int) {
+        query($state: String, $assignee: String, $project: String, $filter: [IssueFilter!], $includeArchived: Boolean, $first: Int, $after: String) {
             issues(
-                first: $limit,
+                first: $first,
+                after: $after,
                 includeArchived: $includeArchived,
                 filter: {
-                    team: { name: { eqIgnoreCase: $team } },
                     state: { name: { eqIgnoreCase: $state } },
                     assignee: { name: { eqIgnoreCase: $assignee } },
-                    project: { name: { eqIgnoreCase: $project } }
+                    project: { name: { eqIgnoreCase: $project } },
+                    and: $filter
                 }
             ) {
                 nodes {
@@ -284,21 +396,18 @@ async fn list_issues(
                     identifier
                     title
                     priority
-                    state { name }
-                    assignee { name }
+                    state { id name }
+                    assignee { id name }
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
     let mut variables = json!({
-        "includeArchived": include_archived,
-        "limit": limit
+        "includeArchived": include_archived
     });
 
-    if let Some(t) = team {
-        variables["team"] = json!(t);
-    }
     if let Some(s) = state {
         variables["state"] = json!(s);
     }
@@ -308,56 +417,110 @@ async fn list_issues(
     if let Some(p) = project {
         variables["project"] = json!(p);
     }
-
-    let result = client.query(query, Some(variables)).await?;
+    // The team matches by key or name, so a default team like "ENG" works
+    let filters: Vec<Value> = team
+        .map(|t| json!({ "team": filter::team(&t) }))
+        .into_iter()
+        .chain(filter)
+        .collect();
+    if !filters.is_empty() {
+        variables["filter"] = json!(filters);
+    }
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result["data"]["issues"]["nodes"])?
-        );
-        return Ok(());
+    if !output.is_table() {
+        let query = fields::narrow(query, &["issues"]);
+        let mut stream = ValueStream::new(output).columns(&query, &["issues"]);
+        client
+            .paginate(&query, Some(variables), &["issues"], pages, |nodes| {
+                stream.push(&nodes)
+            })
+            .await?;
+        return stream.finish();
     }
 
-    let empty = vec![];
-    let issues = result["data"]["issues"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let mut table = TableStream::new();
+    client
+        .paginate(query, Some(variables), &["issues"], pages, |nodes| {
+            let issues: Vec<Issue> = models::from_nodes(&nodes)?;
+            table.push(issues.iter().map(issue_row).collect());
+            Ok(())
+        })
+        .await?;
 
-    if issues.is_empty() {
+    if table.rows() == 0 {
         println!("No issues found.");
         return Ok(());
     }
 
-    let rows: Vec<IssueRow> = issues
-        .iter()
-        .map(|issue| IssueRow {
-            identifier: issue["identifier"].as_str().unwrap_or("").to_string(),
-            title: {
-                let t = issue["title"].as_str().unwrap_or("");
-                if t.len() > 50 {
-                    format!("{}...", &t[..47])
-                } else {
-                    t.to_string()
-                }
-            },
-            state: issue["state"]["name"].as_str().unwrap_or("-").to_string(),
-            priority: priority_to_string(issue["priority"].as_i64()),
-            assignee: issue["assignee"]["name"]
-                .as_str()
-                .unwrap_or("-")
-                .to_string(),
-        })
+    println!("\n{} issues", table.rows());
+
+    Ok(())
+}
+
+/// `issues list --offline`: the same filter, evaluated against the mirror
+#[allow(clippy::too_many_arguments)]
+async fn list_offline_issues(
+    team: Option<String>,
+    state: Option<String>,
+    assignee: Option<String>,
+    project: Option<String>,
+    filter: Option<String>,
+    include_archived: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
+    let filter = filter.as_deref().map(filter::compile).transpose()?;
+    let mirror = Mirror::open_offline().await?;
+
+    let mut conditions: Vec<Value> = team
+        .map(|t| json!({ "team": filter::team(&t) }))
+        .into_iter()
         .collect();
+    for (field, value) in [
+        ("state", state),
+        ("assignee", assignee),
+        ("project", project),
+    ] {
+        if let Some(value) = value {
+            conditions.push(json!({ field: { "name": { "eqIgnoreCase": value } } }));
+        }
+    }
+    if !include_archived {
+        conditions.push(json!({ "archivedAt": { "null": true } }));
+    }
+    conditions.extend(filter);
 
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} issues", issues.len());
+    let issues = mirror::select(mirror.issues()?, &json!({ "and": conditions }), pages.limit);
+
+    if !output.is_table() {
+        let mut stream = ValueStream::new(output);
+        stream.push(&issues)?;
+        return stream.finish();
+    }
+
+    let issues: Vec<Issue> = models::from_nodes(&issues)?;
+    if issues.is_empty() {
+        println!("No issues found.");
+        return Ok(());
+    }
+    let mut table = TableStream::new();
+    table.push(issues.iter().map(issue_row).collect());
+    println!("\n{} issues", table.rows());
 
     Ok(())
 }
 
+fn issue_row(issue: &Issue) -> IssueRow {
+    IssueRow {
+        identifier: issue.identifier.clone(),
+        title: truncate(&issue.title, 50),
+        state: issue.state_name().to_string(),
+        priority: priority_label(issue.priority),
+        assignee: issue.assignee_name().to_string(),
+    }
+}
+
 async fn get_issue(id: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
@@ -372,17 +535,22 @@ async fn get_issue(id: &str, output: OutputFormat) -> Result<()> {
                 url
                 createdAt
                 updatedAt
-                state { name }
-                team { name }
-                assignee { name email }
-                labels { nodes { name color } }
-                project { name }
-                parent { identifier title }
+                state { id name }
+                team { id key name }
+                assignee { id name email }
+                labels { nodes { id name color } }
+                project { id name }
+                parent { id identifier title }
             }
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "id": id }))).await?;
+    let result = client
+        .query(
+            &fields::narrow(query, &["issue"]),
+            Some(json!({ "id": id })),
+        )
+        .await?;
     let issue = &result["data"]["issue"];
 
     if issue.is_null() {
@@ -390,123 +558,163 @@ async fn get_issue(id: &str, output: OutputFormat) -> Result<()> {
     }
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!("{}", serde_json::to_string_pretty(issue)?);
-        return Ok(());
+    if !output.is_table() {
+        return print_value(issue, output);
     }
 
-    let identifier = issue["identifier"].as_str().unwrap_or("");
-    let title = issue["title"].as_str().unwrap_or("");
-    println!("{} {}", identifier.cyan().bold(), title.bold());
+    let issue: Issue = models::from_value(issue)?;
+    println!("{} {}", issue.identifier.cyan().bold(), issue.title.bold());
     println!("{}", "-".repeat(60));
 
-    if let Some(desc) = issue["description"].as_str() {
-        if !desc.is_empty() {
-            println!("\n{}", desc);
-            println!();
-        }
+    if let Some(desc) = issue.description.as_deref().filter(|d| !d.is_empty()) {
+        println!("\n{}", desc);
+        println!();
     }
 
-    println!(
-        "State:    {}",
-        issue["state"]["name"].as_str().unwrap_or("-")
-    );
-    println!(
-        "Priority: {}",
-        priority_to_string(issue["priority"].as_i64())
-    );
+    println!("State:    {}", issue.state_name());
+    println!("Priority: {}", priority_label(issue.priority));
     println!(
         "Team:     {}",
-        issue["team"]["name"].as_str().unwrap_or("-")
+        issue.team.as_ref().map_or("-", |t| t.name.as_str())
     );
 
-    if let Some(assignee) = issue["assignee"]["name"].as_str() {
-        let email = issue["assignee"]["email"].as_str().unwrap_or("");
-        if !email.is_empty() {
-            println!("Assignee: {} ({})", assignee, email.dimmed());
-        } else {
-            println!("Assignee: {}", assignee);
-        }
-    } else {
-        println!("Assignee: -");
+    match &issue.assignee {
+        Some(assignee) => match assignee.email.as_deref().filter(|e| !e.is_empty()) {
+            Some(email) => println!("Assignee: {} ({})", assignee.name, email.dimmed()),
+            None => println!("Assignee: {}", assignee.name),
+        },
+        None => println!("Assignee: -"),
     }
 
-    if let Some(project) = issue["project"]["name"].as_str() {
-        println!("Project:  {}", project);
+    if let Some(project) = &issue.project {
+        println!("Project:  {}", project.name);
     }
 
-    if let Some(parent) = issue["parent"]["identifier"].as_str() {
-        let parent_title = issue["parent"]["title"].as_str().unwrap_or("");
-        println!("Parent:   {} {}", parent, parent_title.dimmed());
+    if let Some(parent) = &issue.parent {
+        println!("Parent:   {} {}", parent.identifier, parent.title.dimmed());
     }
 
-    let labels = issue["labels"]["nodes"].as_array();
-    if let Some(labels) = labels {
-        if !labels.is_empty() {
-            let label_names: Vec<&str> = labels.iter().filter_map(|l| l["name"].as_str()).collect();
-            println!("Labels:   {}", label_names.join(", "));
-        }
+    let labels = issue.label_names();
+    if !labels.is_empty() {
+        println!("Labels:   {}", labels.join(", "));
     }
 
-    println!("\nURL: {}", issue["url"].as_str().unwrap_or("-"));
-    println!("ID:  {}", issue["id"].as_str().unwrap_or("-"));
+    println!("\nURL: {}", issue.url.as_deref().unwrap_or("-"));
+    println!("ID:  {}", issue.id);
 
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn create_issue(
-    title: &str,
-    team: &str,
-    description: Option<String>,
-    priority: Option<i32>,
-    state: Option<String>,
-    assignee: Option<String>,
-    labels: Vec<String>,
-    output: OutputFormat,
-) -> Result<()> {
+/// Everything `issues create` sends, with names still unresolved so a queued
+/// issue can be resolved when it is replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewIssue {
+    pub title: String,
+    pub team: String,
+    pub description: Option<String>,
+    pub priority: Option<i32>,
+    pub state: Option<String>,
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+    pub project: Option<String>,
+    pub cycle: Option<String>,
+}
+
+/// The fields `issues update` changes, unresolved like [`NewIssue`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueChanges {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub priority: Option<i32>,
+    pub state: Option<String>,
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+}
+
+impl IssueChanges {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.priority.is_none()
+            && self.state.is_none()
+            && self.assignee.is_none()
+            && self.labels.is_empty()
+    }
+}
+
+async fn create_issue(issue: NewIssue, queue: bool, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
-    // Determine the final team (CLI arg takes precedence, then template, then error)
-    let final_team = team;
+    // A queued issue keeps the id it was first sent with, so a replay can tell
+    // whether an attempt that timed out went through after all
+    let id = queue.then(queue::new_id);
+    let created = match send_create(&client, id.as_deref(), &issue).await {
+        Ok(created) => created,
+        Err(err) if queue && api::is_unreachable(&err) => {
+            let id = id.unwrap_or_else(queue::new_id);
+            return queue::enqueue(Operation::IssueCreate { id, issue }, &err, output).await;
+        }
+        Err(err) => return Err(err),
+    };
+
+    // Handle JSON output
+    if !output.is_table() {
+        return print_value(&created, output);
+    }
 
-    // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, final_team).await?;
+    let issue: Issue = models::from_value(&created)?;
+    println!(
+        "{} Created issue: {} {}",
+        "+".green(),
+        issue.identifier.cyan(),
+        issue.title
+    );
+    println!("  ID:  {}", issue.id);
+    println!("  URL: {}", issue.url.as_deref().unwrap_or(""));
 
-    // Build the title with optional prefix from template
-    let final_title = title.to_string();
+    Ok(())
+}
+
+/// Resolve an issue's names and create it, optionally with a client-chosen
+/// UUID. Returns the created issue.
+pub async fn send_create(
+    client: &LinearClient,
+    id: Option<&str>,
+    issue: &NewIssue,
+) -> Result<Value> {
+    let resolver = Resolver::new(client);
+
+    // Resolve team key/name to UUID
+    let team_id = resolver.team_id(&issue.team).await?;
 
     let mut input = json!({
-        "title": final_title,
+        "title": issue.title,
         "teamId": team_id
     });
 
-    // CLI args override template values
-    if let Some(desc) = description {
+    if let Some(id) = id {
+        input["id"] = json!(id);
+    }
+    if let Some(desc) = &issue.description {
         input["description"] = json!(desc);
     }
-    if let Some(p) = priority {
+    if let Some(p) = issue.priority {
         input["priority"] = json!(p);
     }
-    if let Some(s) = state {
-        input["stateId"] = json!(s);
+    if let Some(s) = &issue.state {
+        input["stateId"] = json!(resolver.state_id(&team_id, s).await?);
     }
-    if let Some(a) = assignee {
-        input["assigneeId"] = json!(a);
+    if let Some(a) = &issue.assignee {
+        input["assigneeId"] = json!(resolver.user_id(a).await?);
     }
-    if !labels.is_empty() {
-        // Merge with template labels if present
-        let existing: Vec<String> = input["labelIds"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_default();
-        let mut all_labels = existing;
-        all_labels.extend(labels);
-        input["labelIds"] = json!(all_labels);
+    if !issue.labels.is_empty() {
+        input["labelIds"] = json!(resolve_labels(&resolver, &issue.labels, &team_id).await?);
+    }
+    if let Some(p) = &issue.project {
+        input["projectId"] = json!(resolver.project_id(p).await?);
+    }
+    if let Some(c) = &issue.cycle {
+        input["cycleId"] = json!(cycle_id(client, &team_id, c).await?);
     }
 
     let mutation = r#"
@@ -527,64 +735,149 @@ async fn create_issue(
         .mutate(mutation, Some(json!({ "input": input })))
         .await?;
 
-    if result["data"]["issueCreate"]["success"].as_bool() == Some(true) {
-        let issue = &result["data"]["issueCreate"]["issue"];
+    if result["data"]["issueCreate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Failed to create issue");
+    }
+    Ok(result["data"]["issueCreate"]["issue"].clone())
+}
 
-        // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(issue)?);
-            return Ok(());
+/// The UUID of a team's cycle: current, next, previous or a cycle number
+async fn cycle_id(client: &LinearClient, team_id: &str, cycle: &str) -> Result<String> {
+    let query = r#"
+        query($filter: CycleFilter) {
+            cycles(filter: $filter, first: 1) {
+                nodes {
+                    id
+                    number
+                }
+            }
         }
+    "#;
 
-        let identifier = issue["identifier"].as_str().unwrap_or("");
-        let issue_title = issue["title"].as_str().unwrap_or("");
-        println!(
-            "{} Created issue: {} {}",
-            "+".green(),
-            identifier.cyan(),
-            issue_title
-        );
-        println!("  ID:  {}", issue["id"].as_str().unwrap_or(""));
-        println!("  URL: {}", issue["url"].as_str().unwrap_or(""));
-    } else {
-        anyhow::bail!("Failed to create issue");
+    let mut filter = board::cycle_filter(cycle)?;
+    filter["team"] = json!({ "id": { "eq": team_id } });
+
+    let result = client
+        .query(query, Some(json!({ "filter": filter })))
+        .await?;
+    result["data"]["cycles"]["nodes"][0]["id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .ok_or_else(|| anyhow::anyhow!("No cycle '{}' found for the team", cycle))
+}
+
+/// Resolve label names (or IDs) to label UUIDs usable on the team's issues
+async fn resolve_labels(
+    resolver: &Resolver<'_>,
+    labels: &[String],
+    team_id: &str,
+) -> Result<Vec<String>> {
+    let mut ids = Vec::with_capacity(labels.len());
+    for label in labels {
+        let id = resolver.label_id(label, Some(team_id)).await?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
     }
+    Ok(ids)
+}
 
-    Ok(())
+/// The UUID of the team an issue belongs to
+async fn issue_team_id(client: &LinearClient, id: &str) -> Result<String> {
+    let query = r#"
+        query($id: String!) {
+            issue(id: $id) {
+                id
+                identifier
+                title
+                team { id key name }
+            }
+        }
+    "#;
+
+    let result = client.query(query, Some(json!({ "id": id }))).await?;
+    let issue = &result["data"]["issue"];
+
+    if issue.is_null() {
+        anyhow::bail!("Issue not found: {}", id);
+    }
+
+    let issue: Issue = models::from_value(issue)?;
+    issue
+        .team
+        .map(|t| t.id)
+        .ok_or_else(|| anyhow::anyhow!("Issue {} has no team", id))
 }
 
 async fn update_issue(
     id: &str,
-    title: Option<String>,
-    description: Option<String>,
-    priority: Option<i32>,
-    state: Option<String>,
-    assignee: Option<String>,
+    changes: IssueChanges,
+    queue: bool,
     output: OutputFormat,
 ) -> Result<()> {
+    if changes.is_empty() {
+        println!("No updates specified.");
+        return Ok(());
+    }
+
     let client = LinearClient::new()?;
+    let updated = match send_update(&client, id, &changes).await {
+        Ok(updated) => updated,
+        Err(err) if queue && api::is_unreachable(&err) => {
+            let operation = Operation::IssueUpdate {
+                issue: id.to_string(),
+                changes,
+            };
+            return queue::enqueue(operation, &err, output).await;
+        }
+        Err(err) => return Err(err),
+    };
+
+    // Handle JSON output
+    if !output.is_table() {
+        return print_value(&updated, output);
+    }
+
+    let issue: Issue = models::from_value(&updated)?;
+    println!(
+        "{} Updated issue: {} {}",
+        "+".green(),
+        issue.identifier,
+        issue.title
+    );
+
+    Ok(())
+}
+
+/// Resolve the changed names and update the issue. Returns the updated issue.
+pub async fn send_update(client: &LinearClient, id: &str, changes: &IssueChanges) -> Result<Value> {
+    let resolver = Resolver::new(client);
 
     let mut input = json!({});
 
-    if let Some(t) = title {
+    if let Some(t) = &changes.title {
         input["title"] = json!(t);
     }
-    if let Some(d) = description {
+    if let Some(d) = &changes.description {
         input["description"] = json!(d);
     }
-    if let Some(p) = priority {
+    if let Some(p) = changes.priority {
         input["priority"] = json!(p);
     }
-    if let Some(s) = state {
-        input["stateId"] = json!(s);
-    }
-    if let Some(a) = assignee {
-        input["assigneeId"] = json!(a);
+    if let Some(a) = &changes.assignee {
+        input["assigneeId"] = json!(resolver.user_id(a).await?);
     }
 
-    if input.as_object().map(|o| o.is_empty()).unwrap_or(true) {
-        println!("No updates specified.");
-        return Ok(());
+    // States and labels belong to a team, so look up the issue's team first
+    if changes.state.is_some() || !changes.labels.is_empty() {
+        let team_id = issue_team_id(client, id).await?;
+        if let Some(s) = &changes.state {
+            input["stateId"] = json!(resolver.state_id(&team_id, s).await?);
+        }
+        if !changes.labels.is_empty() {
+            input["addedLabelIds"] =
+                json!(resolve_labels(&resolver, &changes.labels, &team_id).await?);
+        }
     }
 
     let mutation = r#"
@@ -592,8 +885,10 @@ async fn update_issue(
             issueUpdate(id: $id, input: $input) {
                 success
                 issue {
+                    id
                     identifier
                     title
+                    updatedAt
                 }
             }
         }
@@ -603,26 +898,10 @@ async fn update_issue(
         .mutate(mutation, Some(json!({ "id": id, "input": input })))
         .await?;
 
-    if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-        let issue = &result["data"]["issueUpdate"]["issue"];
-
-        // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(issue)?);
-            return Ok(());
-        }
-
-        println!(
-            "{} Updated issue: {} {}",
-            "+".green(),
-            issue["identifier"].as_str().unwrap_or(""),
-            issue["title"].as_str().unwrap_or("")
-        );
-    } else {
+    if result["data"]["issueUpdate"]["success"].as_bool() != Some(true) {
         anyhow::bail!("Failed to update issue");
     }
-
-    Ok(())
+    Ok(result["data"]["issueUpdate"]["issue"].clone())
 }
 
 async fn delete_issue(id: &str, force: bool) -> Result<()> {
@@ -659,6 +938,15 @@ async fn delete_issue(id: &str, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Workflow states of the issue's team, as selected by the start/stop queries
+fn team_states(issue: &Issue) -> &[models::WorkflowState] {
+    issue
+        .team
+        .as_ref()
+        .and_then(|t| t.states.as_ref())
+        .map_or(&[], |s| s.nodes.as_slice())
+}
+
 // Git helper functions for start command
 fn run_git_command(args: &[&str]) -> Result<String> {
     let output = Command::new("git").args(args).output()?;
@@ -675,29 +963,22 @@ fn branch_exists(branch: &str) -> bool {
     run_git_command(&["rev-parse", "--verify", branch]).is_ok()
 }
 
-fn generate_branch_name(identifier: &str, title: &str) -> String {
-    // Convert title to kebab-case for branch name
-    let slug: String = title
-        .to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-");
-
-    // Truncate if too long
-    let slug = if slug.len() > 50 {
-        slug[..50].trim_end_matches('-').to_string()
-    } else {
-        slug
-    };
-
-    format!("{}/{}", identifier.to_lowercase(), slug)
-}
+async fn start_issue(
+    id: &str,
+    checkout: bool,
+    custom_branch: Option<String>,
+    timer: bool,
+) -> Result<()> {
+    // Refuse before touching the issue if another timer is already running
+    if timer {
+        if let Some(running) = time::load_timer()? {
+            anyhow::bail!(
+                "A timer is already running on {}. Stop it first with: linear time stop",
+                running.issue.identifier
+            );
+        }
+    }
 
-async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) -> Result<()> {
     let client = LinearClient::new()?;
 
     // First, get the issue details including team info to find the "started" state
@@ -710,6 +991,8 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
                 branchName
                 team {
                     id
+                    key
+                    name
                     states {
                         nodes {
                             id
@@ -732,9 +1015,10 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
         anyhow::bail!("Issue not found: {}", id);
     }
 
-    let identifier = issue["identifier"].as_str().unwrap_or("");
-    let title = issue["title"].as_str().unwrap_or("");
-    let linear_branch = issue["branchName"].as_str().unwrap_or("").to_string();
+    let issue: Issue = models::from_value(issue)?;
+    let identifier = issue.identifier.as_str();
+    let title = issue.title.as_str();
+    let linear_branch = issue.branch_name.clone().unwrap_or_default();
 
     // Get current user ID
     let viewer_id = result["data"]["viewer"]["id"]
@@ -742,23 +1026,14 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
         .ok_or_else(|| anyhow::anyhow!("Could not fetch current user ID"))?;
 
     // Find a "started" type state (In Progress)
-    let empty = vec![];
-    let states = issue["team"]["states"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
-
+    let states = team_states(&issue);
     let started_state = states
         .iter()
-        .find(|s| s["type"].as_str() == Some("started"));
-
-    let state_id = match started_state {
-        Some(s) => s["id"].as_str().unwrap_or(""),
-        None => anyhow::bail!("No 'started' state found for this team"),
-    };
+        .find(|s| s.kind.as_deref() == Some("started"))
+        .ok_or_else(|| anyhow::anyhow!("No 'started' state found for this team"))?;
 
-    let state_name = started_state
-        .and_then(|s| s["name"].as_str())
-        .unwrap_or("In Progress");
+    let state_id = started_state.id.as_str();
+    let state_name = started_state.name.as_str();
 
     // Update the issue: set state to "In Progress" and assign to current user
     let input = json!({
@@ -771,10 +1046,11 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
             issueUpdate(id: $id, input: $input) {
                 success
                 issue {
+                    id
                     identifier
                     title
-                    state { name }
-                    assignee { name }
+                    state { id name }
+                    assignee { id name }
                 }
             }
         }
@@ -785,34 +1061,35 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
         .await?;
 
     if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-        let updated = &result["data"]["issueUpdate"]["issue"];
+        let updated: Issue = models::from_value(&result["data"]["issueUpdate"]["issue"])?;
         println!(
             "{} Started issue: {} {}",
             "+".green(),
-            updated["identifier"].as_str().unwrap_or("").cyan(),
-            updated["title"].as_str().unwrap_or("")
+            updated.identifier.cyan(),
+            updated.title
         );
         println!(
             "  State:    {}",
-            updated["state"]["name"].as_str().unwrap_or(state_name)
+            updated
+                .state
+                .as_ref()
+                .map_or(state_name, |s| s.name.as_str())
         );
         println!(
             "  Assignee: {}",
-            updated["assignee"]["name"].as_str().unwrap_or("me")
+            updated.assignee.as_ref().map_or("me", |u| u.name.as_str())
         );
     } else {
         anyhow::bail!("Failed to start issue");
     }
 
+    if timer {
+        time::start_timer(&client, id, None, OutputFormat::Table).await?;
+    }
+
     // Optionally checkout a git branch
     if checkout {
-        let branch_name = custom_branch
-            .or(if linear_branch.is_empty() {
-                None
-            } else {
-                Some(linear_branch)
-            })
-            .unwrap_or_else(|| generate_branch_name(identifier, title));
+        let branch_name = git::issue_branch_name(custom_branch, linear_branch, identifier, title);
 
         println!();
         if branch_exists(&branch_name) {
@@ -830,7 +1107,7 @@ async fn start_issue(id: &str, checkout: bool, custom_branch: Option<String>) ->
     Ok(())
 }
 
-async fn stop_issue(id: &str, unassign: bool) -> Result<()> {
+async fn stop_issue(id: &str, unassign: bool, timer: bool) -> Result<()> {
     let client = LinearClient::new()?;
 
     // First, get the issue details including team info to find the "backlog" or "unstarted" state
@@ -842,6 +1119,8 @@ async fn stop_issue(id: &str, unassign: bool) -> Result<()> {
                 title
                 team {
                     id
+                    key
+                    name
                     states {
                         nodes {
                             id
@@ -861,30 +1140,17 @@ async fn stop_issue(id: &str, unassign: bool) -> Result<()> {
         anyhow::bail!("Issue not found: {}", id);
     }
 
-    // Find a "backlog" or "unstarted" type state
-    let empty = vec![];
-    let states = issue["team"]["states"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let issue: Issue = models::from_value(issue)?;
 
-    // Prefer backlog, fall back to unstarted
-    let stop_state = states
+    // Find a "backlog" or "unstarted" type state, preferring backlog
+    let states = team_states(&issue);
+    let stop_state = ["backlog", "unstarted"]
         .iter()
-        .find(|s| s["type"].as_str() == Some("backlog"))
-        .or_else(|| {
-            states
-                .iter()
-                .find(|s| s["type"].as_str() == Some("unstarted"))
-        });
-
-    let state_id = match stop_state {
-        Some(s) => s["id"].as_str().unwrap_or(""),
-        None => anyhow::bail!("No 'backlog' or 'unstarted' state found for this team"),
-    };
+        .find_map(|kind| states.iter().find(|s| s.kind.as_deref() == Some(*kind)))
+        .ok_or_else(|| anyhow::anyhow!("No 'backlog' or 'unstarted' state found for this team"))?;
 
-    let state_name = stop_state
-        .and_then(|s| s["name"].as_str())
-        .unwrap_or("Backlog");
+    let state_id = stop_state.id.as_str();
+    let state_name = stop_state.name.as_str();
 
     // Build the update input
     let mut input = json!({
@@ -901,10 +1167,11 @@ async fn stop_issue(id: &str, unassign: bool) -> Result<()> {
             issueUpdate(id: $id, input: $input) {
                 success
                 issue {
+                    id
                     identifier
                     title
-                    state { name }
-                    assignee { name }
+                    state { id name }
+                    assignee { id name }
                 }
             }
         }
@@ -915,25 +1182,35 @@ async fn stop_issue(id: &str, unassign: bool) -> Result<()> {
         .await?;
 
     if result["data"]["issueUpdate"]["success"].as_bool() == Some(true) {
-        let updated = &result["data"]["issueUpdate"]["issue"];
+        let updated: Issue = models::from_value(&result["data"]["issueUpdate"]["issue"])?;
         println!(
             "{} Stopped issue: {} {}",
             "+".green(),
-            updated["identifier"].as_str().unwrap_or("").cyan(),
-            updated["title"].as_str().unwrap_or("")
+            updated.identifier.cyan(),
+            updated.title
         );
         println!(
             "  State:    {}",
-            updated["state"]["name"].as_str().unwrap_or(state_name)
+            updated
+                .state
+                .as_ref()
+                .map_or(state_name, |s| s.name.as_str())
         );
         if unassign {
             println!("  Assignee: (unassigned)");
-        } else if let Some(assignee) = updated["assignee"]["name"].as_str() {
-            println!("  Assignee: {}", assignee);
+        } else if let Some(assignee) = &updated.assignee {
+            println!("  Assignee: {}", assignee.name);
         }
     } else {
         anyhow::bail!("Failed to stop issue");
     }
 
+    if timer {
+        let identifier = issue.identifier.as_str();
+        if !time::stop_timer(None, Some(identifier), OutputFormat::Table).await? {
+            println!("No timer running on {}", identifier);
+        }
+    }
+
     Ok(())
 }
@@ -4,7 +4,9 @@ use colored::Colorize;
 use serde_json::json;
 use tabled::{Table, Tabled};
 
-use crate::api::LinearClient;
+use crate::api::{LinearClient, PageOptions};
+use crate::models::{self, Label};
+use crate::output::print_value;
 use crate::OutputFormat;
 
 #[derive(Subcommand)]
@@ -86,64 +88,66 @@ async fn list_labels(label_type: &str, output: OutputFormat) -> Result<()> {
 
     let query = if label_type == "project" {
         r#"
-            query {
-                projectLabels(first: 100) {
+            query($first: Int, $after: String) {
+                projectLabels(first: $first, after: $after) {
                     nodes {
                         id
                         name
                         color
-                        parent { name }
+                        parent { id name }
                     }
+                    pageInfo { hasNextPage endCursor }
                 }
             }
         "#
     } else {
         r#"
-            query {
-                issueLabels(first: 100) {
+            query($first: Int, $after: String) {
+                issueLabels(first: $first, after: $after) {
                     nodes {
                         id
                         name
                         color
-                        parent { name }
+                        parent { id name }
                     }
+                    pageInfo { hasNextPage endCursor }
                 }
             }
         "#
     };
 
-    let result = client.query(query, None).await?;
-
     let key = if label_type == "project" {
         "projectLabels"
     } else {
         "issueLabels"
     };
 
+    let labels = client
+        .fetch_all(query, None, &[key], PageOptions::all())
+        .await?;
+
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result["data"][key]["nodes"])?
-        );
-        return Ok(());
+    if !output.is_table() {
+        return print_value(&labels, output);
     }
 
-    let empty = vec![];
-    let labels = result["data"][key]["nodes"].as_array().unwrap_or(&empty);
-
     if labels.is_empty() {
         println!("No {} labels found.", label_type);
         return Ok(());
     }
 
+    let labels: Vec<Label> = models::from_nodes(&labels)?;
     let rows: Vec<LabelRow> = labels
         .iter()
         .map(|l| LabelRow {
-            name: l["name"].as_str().unwrap_or("").to_string(),
-            group: l["parent"]["name"].as_str().unwrap_or("-").to_string(),
-            color: l["color"].as_str().unwrap_or("").to_string(),
-            id: l["id"].as_str().unwrap_or("").to_string(),
+            name: l.name.clone(),
+            group: l
+                .parent
+                .as_ref()
+                .map_or("-", |p| p.name.as_str())
+                .to_string(),
+            color: l.color.clone().unwrap_or_default(),
+            id: l.id.clone(),
         })
         .collect();
 
@@ -211,18 +215,18 @@ async fn create_label(
         let label = &result["data"][key][label_key];
 
         // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(label)?);
-            return Ok(());
+        if !output.is_table() {
+            return print_value(label, output);
         }
 
+        let label: Label = models::from_value(label)?;
         println!(
             "{} Created {} label: {}",
             "+".green(),
             label_type,
-            label["name"].as_str().unwrap_or("")
+            label.name
         );
-        println!("  ID: {}", label["id"].as_str().unwrap_or(""));
+        println!("  ID: {}", label.id);
     } else {
         anyhow::bail!("Failed to create label");
     }
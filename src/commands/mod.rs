@@ -1,14 +1,19 @@
+pub mod auth;
+pub mod board;
 pub mod bulk;
 pub mod cache;
 pub mod comments;
 pub mod cycles;
 pub mod documents;
 pub mod git;
+pub mod graphql;
 pub mod interactive;
 pub mod issues;
 pub mod labels;
 pub mod notifications;
 pub mod projects;
+pub mod queue;
+pub mod schema;
 pub mod search;
 pub mod statuses;
 pub mod sync;
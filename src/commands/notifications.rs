@@ -4,7 +4,9 @@ use colored::Colorize;
 use serde_json::json;
 use tabled::{Table, Tabled};
 
-use crate::api::LinearClient;
+use crate::api::{LinearClient, PageOptions};
+use crate::output::print_value;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum NotificationCommands {
@@ -14,9 +16,12 @@ pub enum NotificationCommands {
         /// Include read notifications
         #[arg(short, long)]
         all: bool,
-        /// Maximum number of notifications to show
+        /// Maximum number of notifications to fetch
         #[arg(short, long, default_value = "50")]
-        limit: u32,
+        limit: usize,
+        /// Fetch every page of notifications, ignoring --limit
+        #[arg(long = "all-pages")]
+        all_pages: bool,
     },
     /// Mark a notification as read
     Read {
@@ -44,12 +49,16 @@ struct NotificationRow {
     id: String,
 }
 
-pub async fn handle(cmd: NotificationCommands) -> Result<()> {
+pub async fn handle(cmd: NotificationCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        NotificationCommands::List { all, limit } => list_notifications(all, limit).await,
-        NotificationCommands::Read { id } => mark_as_read(&id).await,
-        NotificationCommands::ReadAll => mark_all_as_read().await,
-        NotificationCommands::Count => show_count().await,
+        NotificationCommands::List {
+            all,
+            limit,
+            all_pages,
+        } => list_notifications(all, PageOptions::new(limit, all_pages), output).await,
+        NotificationCommands::Read { id } => mark_as_read(&id, output).await,
+        NotificationCommands::ReadAll => mark_all_as_read(output).await,
+        NotificationCommands::Count => show_count(output).await,
     }
 }
 
@@ -68,12 +77,16 @@ fn format_notification_type(notification_type: &str) -> String {
     }
 }
 
-async fn list_notifications(include_all: bool, limit: u32) -> Result<()> {
+async fn list_notifications(
+    include_all: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
-        query($first: Int!) {
-            notifications(first: $first) {
+        query($first: Int, $after: String) {
+            notifications(first: $first, after: $after) {
                 nodes {
                     id
                     type
@@ -97,16 +110,14 @@ async fn list_notifications(include_all: bool, limit: u32) -> Result<()> {
                         }
                     }
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "first": limit }))).await?;
-
-    let empty = vec![];
-    let notifications = result["data"]["notifications"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let notifications = client
+        .fetch_all(query, None, &["notifications"], pages)
+        .await?;
 
     let filtered: Vec<_> = if include_all {
         notifications.iter().collect()
@@ -117,6 +128,10 @@ async fn list_notifications(include_all: bool, limit: u32) -> Result<()> {
             .collect()
     };
 
+    if !output.is_table() {
+        return print_value(&filtered, output);
+    }
+
     if filtered.is_empty() {
         if include_all {
             println!("No notifications found.");
@@ -184,7 +199,7 @@ async fn list_notifications(include_all: bool, limit: u32) -> Result<()> {
     Ok(())
 }
 
-async fn mark_as_read(id: &str) -> Result<()> {
+async fn mark_as_read(id: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     let mutation = r#"
@@ -209,6 +224,10 @@ async fn mark_as_read(id: &str) -> Result<()> {
 
     if result["data"]["notificationUpdate"]["success"].as_bool() == Some(true) {
         let notification = &result["data"]["notificationUpdate"]["notification"];
+        if !output.is_table() {
+            return print_value(notification, output);
+        }
+
         let issue_identifier = notification["issue"]["identifier"].as_str().unwrap_or("");
         let issue_title = notification["issue"]["title"].as_str().unwrap_or("");
 
@@ -223,7 +242,7 @@ async fn mark_as_read(id: &str) -> Result<()> {
     Ok(())
 }
 
-async fn mark_all_as_read() -> Result<()> {
+async fn mark_all_as_read(output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
@@ -248,7 +267,7 @@ async fn mark_all_as_read() -> Result<()> {
         .filter(|n| n["readAt"].is_null())
         .collect();
 
-    if unread.is_empty() {
+    if unread.is_empty() && output.is_table() {
         println!("{} No unread notifications to mark.", "+".green());
         return Ok(());
     }
@@ -273,6 +292,13 @@ async fn mark_all_as_read() -> Result<()> {
         }
     }
 
+    if !output.is_table() {
+        return print_value(
+            &json!({ "marked": success_count, "failed": count - success_count }),
+            output,
+        );
+    }
+
     println!(
         "{} Marked {} notification{} as read",
         "+".green(),
@@ -292,7 +318,7 @@ async fn mark_all_as_read() -> Result<()> {
     Ok(())
 }
 
-async fn show_count() -> Result<()> {
+async fn show_count(output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
@@ -317,6 +343,10 @@ async fn show_count() -> Result<()> {
         .filter(|n| n["readAt"].is_null())
         .count();
 
+    if !output.is_table() {
+        return print_value(&json!({ "unread": unread_count }), output);
+    }
+
     if unread_count == 0 {
         println!("{} No unread notifications", "+".green());
     } else {
@@ -1,10 +1,17 @@
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
-use tabled::{Table, Tabled};
-
-use crate::api::{resolve_team_id, LinearClient};
+use serde_json::{json, Value};
+use tabled::Tabled;
+
+use crate::api::{LinearClient, PageOptions};
+use crate::config;
+use crate::fields;
+use crate::filter;
+use crate::mirror::{self, Entity, Mirror};
+use crate::models::{self, Project};
+use crate::output::{print_value, truncate, TableStream, ValueStream};
+use crate::resolver::Resolver;
 use crate::OutputFormat;
 
 #[derive(Subcommand)]
@@ -12,13 +19,32 @@ pub enum ProjectCommands {
     /// List all projects
     #[command(alias = "ls")]
     #[command(after_help = r#"EXAMPLES:
-    linear projects list                       # List all projects
+    linear projects list                       # List projects (in the default team, if set)
+    linear p list -t ENG                       # Projects the ENG team is part of
+    linear p list --all-teams                  # Every team, ignoring the default team
     linear p list --archived                   # Include archived projects
+    linear p list --all                        # Fetch every page
+    linear p list --offline                    # Read the copy from `linear sync pull`
     linear p list --output json                # Output as JSON"#)]
     List {
+        /// Only list projects in this team (default: the default team)
+        #[arg(short, long)]
+        team: Option<String>,
+        /// List projects in every team, ignoring the default team
+        #[arg(long, conflicts_with = "team")]
+        all_teams: bool,
         /// Show archived projects
         #[arg(short, long)]
         archived: bool,
+        /// Maximum number of projects to return
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+        /// Fetch all projects, ignoring --limit
+        #[arg(long)]
+        all: bool,
+        /// Read from the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Get project details
     #[command(after_help = r#"EXAMPLES:
@@ -37,9 +63,9 @@ pub enum ProjectCommands {
     Create {
         /// Project name
         name: String,
-        /// Team name or ID
+        /// Team name or ID [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
         /// Project description
         #[arg(short, long)]
         description: Option<String>,
@@ -92,7 +118,7 @@ pub enum ProjectCommands {
 }
 
 #[derive(Tabled)]
-struct ProjectRow {
+pub struct ProjectRow {
     #[tabled(rename = "Name")]
     name: String,
     #[tabled(rename = "Status")]
@@ -105,14 +131,31 @@ struct ProjectRow {
 
 pub async fn handle(cmd: ProjectCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        ProjectCommands::List { archived } => list_projects(archived, output).await,
+        ProjectCommands::List {
+            team,
+            all_teams,
+            archived,
+            limit,
+            all,
+            offline,
+        } => {
+            let team = config::list_team(team, all_teams);
+            let pages = PageOptions::new(limit, all);
+            if offline {
+                return list_offline_projects(team, archived, pages, output).await;
+            }
+            list_projects(team, archived, pages, output).await
+        }
         ProjectCommands::Get { id } => get_project(&id, output).await,
         ProjectCommands::Create {
             name,
             team,
             description,
             color,
-        } => create_project(&name, &team, description, color, output).await,
+        } => {
+            let team = config::team_or_default(team)?;
+            create_project(&name, &team, description, color, output).await
+        }
         ProjectCommands::Update {
             id,
             name,
@@ -125,13 +168,18 @@ pub async fn handle(cmd: ProjectCommands, output: OutputFormat) -> Result<()> {
     }
 }
 
-async fn list_projects(include_archived: bool, output: OutputFormat) -> Result<()> {
+async fn list_projects(
+    team: Option<String>,
+    include_archived: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
     let client = LinearClient::new()?;
 
     // Simplified query to reduce GraphQL complexity (was exceeding 10000 limit)
     let query = r#"
-        query($includeArchived: Boolean) {
-            projects(first: 50, includeArchived: $includeArchived) {
+        query($includeArchived: Boolean, $filter: ProjectFilter, $first: Int, $after: String) {
+            projects(first: $first, after: $after, includeArchived: $includeArchived, filter: $filter) {
                 nodes {
                     id
                     name
@@ -140,50 +188,105 @@ async fn list_projects(include_archived: bool, output: OutputFormat) -> Result<(
                     startDate
                     targetDate
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
-    let result = client
-        .query(query, Some(json!({ "includeArchived": include_archived })))
-        .await?;
+    let mut variables = json!({ "includeArchived": include_archived });
+    if let Some(team) = team {
+        variables["filter"] = json!({ "accessibleTeams": { "some": filter::team(&team) } });
+    }
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&result["data"]["projects"]["nodes"])?
-        );
-        return Ok(());
+    if !output.is_table() {
+        let query = fields::narrow(query, &["projects"]);
+        let mut stream = ValueStream::new(output).columns(&query, &["projects"]);
+        client
+            .paginate(&query, Some(variables), &["projects"], pages, |nodes| {
+                stream.push(&nodes)
+            })
+            .await?;
+        return stream.finish();
     }
 
-    let empty = vec![];
-    let projects = result["data"]["projects"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let mut table = TableStream::new();
+    client
+        .paginate(query, Some(variables), &["projects"], pages, |nodes| {
+            let projects: Vec<Project> = models::from_nodes(&nodes)?;
+            table.push(projects.iter().map(project_row).collect());
+            Ok(())
+        })
+        .await?;
 
-    if projects.is_empty() {
+    if table.rows() == 0 {
         println!("No projects found.");
         return Ok(());
     }
 
-    let rows: Vec<ProjectRow> = projects
-        .iter()
-        .map(|p| ProjectRow {
-            name: p["name"].as_str().unwrap_or("").to_string(),
-            status: p["state"].as_str().unwrap_or("-").to_string(),
-            labels: "-".to_string(),
-            id: p["id"].as_str().unwrap_or("").to_string(),
-        })
+    println!("\n{} projects", table.rows());
+
+    Ok(())
+}
+
+/// `projects list --offline`: the same filter, evaluated against the mirror
+async fn list_offline_projects(
+    team: Option<String>,
+    include_archived: bool,
+    pages: PageOptions,
+    output: OutputFormat,
+) -> Result<()> {
+    let mirror = Mirror::open_offline().await?;
+
+    // The mirror keeps a project's teams as `teams`
+    let mut conditions: Vec<Value> = team
+        .map(|t| json!({ "teams": { "some": filter::team(&t) } }))
+        .into_iter()
         .collect();
+    if !include_archived {
+        conditions.push(json!({ "archivedAt": { "null": true } }));
+    }
+
+    let projects = mirror::select(
+        mirror.all(Entity::Projects)?,
+        &json!({ "and": conditions }),
+        pages.limit,
+    );
 
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} projects", projects.len());
+    if !output.is_table() {
+        let mut stream = ValueStream::new(output);
+        stream.push(&projects)?;
+        return stream.finish();
+    }
+
+    let projects: Vec<Project> = models::from_nodes(&projects)?;
+    if projects.is_empty() {
+        println!("No projects found.");
+        return Ok(());
+    }
+    let mut table = TableStream::new();
+    table.push(projects.iter().map(project_row).collect());
+    println!("\n{} projects", table.rows());
 
     Ok(())
 }
 
+/// Table row for a project; shared with `search projects`
+pub fn project_row(project: &Project) -> ProjectRow {
+    let labels = project.label_names();
+
+    ProjectRow {
+        name: project.name.clone(),
+        status: project.status_name().to_string(),
+        labels: if labels.is_empty() {
+            "-".to_string()
+        } else {
+            labels.join(", ")
+        },
+        id: project.id.clone(),
+    }
+}
+
 async fn get_project(id: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
@@ -196,13 +299,18 @@ async fn get_project(id: &str, output: OutputFormat) -> Result<()> {
                 icon
                 color
                 url
-                status { name }
-                labels { nodes { id name color parent { name } } }
+                status { id name }
+                labels { nodes { id name color parent { id name } } }
             }
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "id": id }))).await?;
+    let result = client
+        .query(
+            &fields::narrow(query, &["project"]),
+            Some(json!({ "id": id })),
+        )
+        .await?;
     let project = &result["data"]["project"];
 
     if project.is_null() {
@@ -210,42 +318,31 @@ async fn get_project(id: &str, output: OutputFormat) -> Result<()> {
     }
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!("{}", serde_json::to_string_pretty(project)?);
-        return Ok(());
+    if !output.is_table() {
+        return print_value(project, output);
     }
 
-    println!("{}", project["name"].as_str().unwrap_or("").bold());
+    let project: Project = models::from_value(project)?;
+    println!("{}", project.name.bold());
     println!("{}", "-".repeat(40));
 
-    if let Some(desc) = project["description"].as_str() {
-        println!(
-            "Description: {}",
-            desc.chars().take(100).collect::<String>()
-        );
+    if let Some(desc) = &project.description {
+        println!("Description: {}", truncate(desc, 100));
     }
 
-    println!(
-        "Status: {}",
-        project["status"]["name"].as_str().unwrap_or("-")
-    );
-    println!("Color: {}", project["color"].as_str().unwrap_or("-"));
-    println!("Icon: {}", project["icon"].as_str().unwrap_or("-"));
-    println!("URL: {}", project["url"].as_str().unwrap_or("-"));
-    println!("ID: {}", project["id"].as_str().unwrap_or("-"));
-
-    let labels = project["labels"]["nodes"].as_array();
-    if let Some(labels) = labels {
-        if !labels.is_empty() {
-            println!("\nLabels:");
-            for label in labels {
-                let parent = label["parent"]["name"].as_str().unwrap_or("");
-                let name = label["name"].as_str().unwrap_or("");
-                if parent.is_empty() {
-                    println!("  - {}", name);
-                } else {
-                    println!("  - {} > {}", parent.dimmed(), name);
-                }
+    println!("Status: {}", project.status_name());
+    println!("Color: {}", project.color.as_deref().unwrap_or("-"));
+    println!("Icon: {}", project.icon.as_deref().unwrap_or("-"));
+    println!("URL: {}", project.url.as_deref().unwrap_or("-"));
+    println!("ID: {}", project.id);
+
+    let labels = project.labels.unwrap_or_default().nodes;
+    if !labels.is_empty() {
+        println!("\nLabels:");
+        for label in labels {
+            match label.parent {
+                Some(parent) => println!("  - {} > {}", parent.name.dimmed(), label.name),
+                None => println!("  - {}", label.name),
             }
         }
     }
@@ -263,7 +360,7 @@ async fn create_project(
     let client = LinearClient::new()?;
 
     // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, team).await?;
+    let team_id = Resolver::new(&client).team_id(team).await?;
 
     let mut input = json!({
         "name": name,
@@ -294,18 +391,14 @@ async fn create_project(
         let project = &result["data"]["projectCreate"]["project"];
 
         // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(project)?);
-            return Ok(());
+        if !output.is_table() {
+            return print_value(project, output);
         }
 
-        println!(
-            "{} Created project: {}",
-            "+".green(),
-            project["name"].as_str().unwrap_or("")
-        );
-        println!("  ID: {}", project["id"].as_str().unwrap_or(""));
-        println!("  URL: {}", project["url"].as_str().unwrap_or(""));
+        let project: Project = models::from_value(project)?;
+        println!("{} Created project: {}", "+".green(), project.name);
+        println!("  ID: {}", project.id);
+        println!("  URL: {}", project.url.as_deref().unwrap_or(""));
     } else {
         anyhow::bail!("Failed to create project");
     }
@@ -354,9 +447,8 @@ async fn update_project(
         let project = &result["data"]["projectUpdate"]["project"];
 
         // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(project)?);
-            return Ok(());
+        if !output.is_table() {
+            return print_value(project, output);
         }
 
         println!("{} Project updated", "+".green());
@@ -403,8 +495,9 @@ async fn add_labels(id: &str, label_ids: Vec<String>, output: OutputFormat) -> R
             projectUpdate(id: $id, input: $input) {
                 success
                 project {
+                    id
                     name
-                    labels { nodes { name } }
+                    labels { nodes { id name } }
                 }
             }
         }
@@ -419,18 +512,12 @@ async fn add_labels(id: &str, label_ids: Vec<String>, output: OutputFormat) -> R
         let project = &result["data"]["projectUpdate"]["project"];
 
         // Handle JSON output
-        if matches!(output, OutputFormat::Json) {
-            println!("{}", serde_json::to_string_pretty(project)?);
-            return Ok(());
+        if !output.is_table() {
+            return print_value(project, output);
         }
 
-        let empty = vec![];
-        let labels: Vec<&str> = project["labels"]["nodes"]
-            .as_array()
-            .unwrap_or(&empty)
-            .iter()
-            .filter_map(|l| l["name"].as_str())
-            .collect();
+        let project: Project = models::from_value(project)?;
+        let labels = project.label_names();
         println!("{} Labels updated: {}", "+".green(), labels.join(", "));
     } else {
         anyhow::bail!("Failed to add labels");
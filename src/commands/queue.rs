@@ -0,0 +1,780 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tabled::{Table, Tabled};
+
+use crate::api::{self, LinearClient, LinearError};
+use crate::config;
+use crate::mirror::{self, Mirror};
+use crate::output::{print_value, truncate};
+use crate::resolver::Resolver;
+use crate::OutputFormat;
+
+use super::issues::{IssueChanges, NewIssue};
+use super::{bulk, comments};
+
+#[derive(Subcommand)]
+pub enum QueueCommands {
+    /// List the changes waiting to be sent
+    #[command(alias = "ls")]
+    Status,
+    /// Send the queued changes, oldest first
+    #[command(after_help = r#"EXAMPLES:
+    linear queue replay                        # Send what's queued
+    linear queue replay --force                # Also apply changes that conflict
+
+A change to an issue conflicts when the issue was updated on Linear after the
+copy it was based on: the last `linear sync pull`, or else the moment it was
+queued. Conflicting changes stay queued until replayed with --force or dropped."#)]
+    Replay {
+        /// Apply changes even if the issue changed on Linear since they were queued
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove queued changes without sending them
+    #[command(after_help = r#"EXAMPLES:
+    linear queue drop 3f2a9c1e                 # Drop one change (ID prefix from `queue status`)
+    linear queue drop --all                    # Drop everything queued"#)]
+    Drop {
+        /// IDs (or ID prefixes) of the changes to drop
+        ids: Vec<String>,
+        /// Drop every queued change of the workspace
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+}
+
+/// A change recorded by `--queue`. Names are kept unresolved, so they are
+/// looked up when the change is finally sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Operation {
+    /// `issues create`, with the UUID the issue will get
+    IssueCreate { id: String, issue: NewIssue },
+    /// `issues update`
+    IssueUpdate {
+        issue: String,
+        changes: IssueChanges,
+    },
+    /// `comments create`, with the UUID the comment will get
+    CommentCreate {
+        id: String,
+        issue: String,
+        body: String,
+        parent: Option<String>,
+    },
+    /// `bulk update-state`, one per issue
+    SetState { issue: String, state: String },
+    /// `bulk assign` and `bulk unassign`, one per issue
+    Assign { issue: String, user: Option<String> },
+    /// `bulk label`, one per issue
+    AddLabel { issue: String, label: String },
+}
+
+impl Operation {
+    /// The existing issue this changes, checked for conflicts on replay
+    fn changed_issue(&self) -> Option<&str> {
+        match self {
+            Operation::IssueCreate { .. } | Operation::CommentCreate { .. } => None,
+            Operation::IssueUpdate { issue, .. }
+            | Operation::SetState { issue, .. }
+            | Operation::Assign { issue, .. }
+            | Operation::AddLabel { issue, .. } => Some(issue),
+        }
+    }
+
+    /// One-line summary, e.g. "move ENG-1 to Done"
+    fn describe(&self) -> String {
+        match self {
+            Operation::IssueCreate { issue, .. } => {
+                format!(
+                    "create \"{}\" in {}",
+                    truncate(&issue.title, 40),
+                    issue.team
+                )
+            }
+            Operation::IssueUpdate { issue, .. } => format!("update {}", issue),
+            Operation::CommentCreate { issue, .. } => format!("comment on {}", issue),
+            Operation::SetState { issue, state } => format!("move {} to {}", issue, state),
+            Operation::Assign {
+                issue,
+                user: Some(user),
+            } => format!("assign {} to {}", issue, user),
+            Operation::Assign { issue, user: None } => format!("unassign {}", issue),
+            Operation::AddLabel { issue, label } => format!("label {} {}", issue, label),
+        }
+    }
+}
+
+/// One line of the queue journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    pub id: String,
+    /// Changes are replayed with the workspace they were made in
+    pub workspace: String,
+    pub queued_at: String,
+    /// The issue's `updatedAt` the change was based on; a later one on replay
+    /// is a conflict
+    pub based_on: String,
+    pub operation: Operation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Tabled)]
+struct EntryRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Queued")]
+    queued: String,
+    #[tabled(rename = "Change")]
+    change: String,
+    #[tabled(rename = "Last error")]
+    last_error: String,
+}
+
+/// A random UUID v4, for the issues and comments created from the queue
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn queue_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("linear-cli");
+
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir.join("queue.jsonl"))
+}
+
+/// Every queued change, of every workspace, oldest first
+fn load_all() -> Result<Vec<Entry>> {
+    let path = queue_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(n, line)| {
+            serde_json::from_str(line).with_context(|| {
+                format!(
+                    "Failed to parse line {} of the queue at {}",
+                    n + 1,
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Take the journal's advisory lock, held until the returned file is dropped.
+/// Every read-modify-write of the journal and every append happens under it.
+fn lock() -> Result<fs::File> {
+    let path = queue_path()?.with_extension("lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", path.display()))?;
+    Ok(file)
+}
+
+/// Replace the journal, atomically so a crash never leaves half of it. The
+/// caller holds the lock.
+fn save_all(entries: &[Entry]) -> Result<()> {
+    let path = queue_path()?;
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    file.sync_all()?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Append a change to the journal and return its entry. The change is on disk
+/// when this returns.
+pub async fn enqueue_quietly(operation: Operation) -> Result<Entry> {
+    let queued_at = Utc::now().to_rfc3339();
+    let based_on = match operation.changed_issue() {
+        Some(issue) => mirrored_updated_at(issue).await,
+        None => None,
+    };
+    let entry = Entry {
+        id: new_id(),
+        workspace: config::current_workspace_name()?,
+        based_on: based_on.unwrap_or_else(|| queued_at.clone()),
+        queued_at,
+        operation,
+        last_error: None,
+    };
+
+    let _lock = lock()?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(queue_path()?)?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    file.sync_all()?;
+    Ok(entry)
+}
+
+/// Queue a change that couldn't be sent because of `err`, and say so
+pub async fn enqueue(
+    operation: Operation,
+    err: &anyhow::Error,
+    output: OutputFormat,
+) -> Result<()> {
+    let entry = enqueue_quietly(operation).await?;
+
+    if !output.is_table() {
+        return print_value(&json!({ "queued": entry }), output);
+    }
+
+    println!(
+        "{} Linear can't be reached ({}).",
+        "~".yellow(),
+        format!("{:#}", err).dimmed()
+    );
+    println!(
+        "  Queued: {} [{}]",
+        entry.operation.describe(),
+        short_id(&entry.id)
+    );
+    println!("  Send it later with: linear queue replay");
+    Ok(())
+}
+
+/// The issue's `updatedAt` in the offline mirror, if it has a copy
+async fn mirrored_updated_at(issue: &str) -> Option<String> {
+    let mirror = Mirror::open_offline().await.ok()?;
+    let node = mirror.issue(issue).ok()??;
+    node["updatedAt"].as_str().map(str::to_string)
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+pub async fn handle(cmd: QueueCommands, output: OutputFormat) -> Result<()> {
+    match cmd {
+        QueueCommands::Status => show_status(output),
+        QueueCommands::Replay { force } => replay(force, output).await,
+        QueueCommands::Drop { ids, all } => drop_entries(&ids, all),
+    }
+}
+
+/// The current workspace's changes, and everyone else's
+fn partition() -> Result<(Vec<Entry>, Vec<Entry>)> {
+    let workspace = config::current_workspace_name()?;
+    Ok(load_all()?
+        .into_iter()
+        .partition(|e| e.workspace == workspace))
+}
+
+fn show_status(output: OutputFormat) -> Result<()> {
+    let (entries, _) = partition()?;
+
+    if !output.is_table() {
+        return print_value(&entries, output);
+    }
+
+    if entries.is_empty() {
+        println!("Nothing queued.");
+        return Ok(());
+    }
+
+    let rows: Vec<EntryRow> = entries
+        .iter()
+        .map(|e| EntryRow {
+            id: short_id(&e.id).to_string(),
+            queued: DateTime::parse_from_rfc3339(&e.queued_at)
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|_| e.queued_at.clone()),
+            change: e.operation.describe(),
+            last_error: e
+                .last_error
+                .as_deref()
+                .map(|err| truncate(err, 50))
+                .unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    println!(
+        "\n{} queued changes. Send them with: linear queue replay",
+        entries.len()
+    );
+    Ok(())
+}
+
+fn drop_entries(ids: &[String], all: bool) -> Result<()> {
+    if ids.is_empty() && !all {
+        anyhow::bail!("Name the changes to drop (see: linear queue status), or pass --all");
+    }
+    let _lock = lock()?;
+    let (entries, others) = partition()?;
+
+    let (dropped, kept): (Vec<Entry>, Vec<Entry>) = entries.into_iter().partition(|e| {
+        all || ids
+            .iter()
+            .any(|id| !id.is_empty() && e.id.starts_with(id.as_str()))
+    });
+    if !all {
+        for id in ids {
+            if !dropped.iter().any(|e| e.id.starts_with(id.as_str())) {
+                anyhow::bail!("No queued change with ID {}", id);
+            }
+        }
+    }
+
+    save_all(&[others, kept].concat())?;
+    println!("{} Dropped {} queued changes", "-".red(), dropped.len());
+    Ok(())
+}
+
+/// What replaying one change did
+enum Outcome {
+    /// Sent; the issue it touched, as Linear returned it
+    Applied(Value),
+    /// An earlier attempt had gone through after all
+    AlreadyApplied(Value),
+    /// The issue changed on Linear after the change was based on it
+    Conflict(String),
+}
+
+/// Per-change result of a replay
+struct Replayed {
+    entry: Entry,
+    status: &'static str,
+    identifier: Option<String>,
+    error: Option<String>,
+}
+
+impl Replayed {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.entry.id,
+            "change": self.entry.operation.describe(),
+            "status": self.status,
+            "identifier": self.identifier,
+            "error": self.error,
+        })
+    }
+
+    /// Whether the change leaves the queue
+    fn done(&self) -> bool {
+        matches!(self.status, "applied" | "already-applied")
+    }
+}
+
+async fn replay(force: bool, output: OutputFormat) -> Result<()> {
+    let (mut entries, _) = partition()?;
+    if entries.is_empty() {
+        if output.is_table() {
+            println!("Nothing queued.");
+            return Ok(());
+        }
+        return print_value(&json!([]), output);
+    }
+
+    let client = LinearClient::new()?;
+    let mut results: Vec<Replayed> = Vec::with_capacity(entries.len());
+    let mut unreachable = false;
+
+    for i in 0..entries.len() {
+        let mut entry = entries[i].clone();
+        let (status, identifier, error) = if unreachable {
+            ("not-sent", None, None)
+        } else {
+            match replay_one(&client, &entry, force).await {
+                Ok(Outcome::Applied(issue)) => {
+                    advance(&mut entries[i + 1..], &entry.operation, &issue);
+                    ("applied", identifier(&issue), None)
+                }
+                Ok(Outcome::AlreadyApplied(issue)) => {
+                    advance(&mut entries[i + 1..], &entry.operation, &issue);
+                    ("already-applied", identifier(&issue), None)
+                }
+                Ok(Outcome::Conflict(message)) => ("conflict", None, Some(message)),
+                Err(err) => {
+                    // Without a connection the rest can't go either; keep
+                    // them in order for the next replay
+                    unreachable = api::is_unreachable(&err);
+                    let status = if unreachable { "not-sent" } else { "failed" };
+                    (status, None, Some(format!("{:#}", err)))
+                }
+            }
+        };
+        if let Some(error) = &error {
+            entry.last_error = Some(error.clone());
+        }
+        results.push(Replayed {
+            entry,
+            status,
+            identifier,
+            error,
+        });
+
+        // Record progress after every change, so an interrupted replay never
+        // sends a change twice
+        let kept = results
+            .iter()
+            .filter(|r| !r.done())
+            .map(|r| r.entry.clone());
+        let pending: Vec<Entry> = kept.chain(entries[i + 1..].iter().cloned()).collect();
+        record_progress(&entries, pending)?;
+    }
+
+    if !output.is_table() {
+        let values: Vec<Value> = results.iter().map(Replayed::to_json).collect();
+        return print_value(&values, output);
+    }
+
+    print_summary(&results);
+    Ok(())
+}
+
+/// Rewrite the journal with `pending` in place of the replayed `entries`.
+/// The journal is read again under the lock, so changes queued by other
+/// processes since the replay started are kept (after this workspace's), and
+/// changes dropped meanwhile stay dropped.
+fn record_progress(entries: &[Entry], pending: Vec<Entry>) -> Result<()> {
+    let _lock = lock()?;
+    let current = load_all()?;
+    let on_disk = |id: &str| current.iter().any(|e| e.id == id);
+    let replayed = |id: &str| entries.iter().any(|e| e.id == id);
+
+    let journal: Vec<Entry> = pending
+        .into_iter()
+        .filter(|e| on_disk(&e.id))
+        .chain(current.iter().filter(|e| !replayed(&e.id)).cloned())
+        .collect();
+    save_all(&journal)
+}
+
+/// Rebase the later changes to the issue `operation` touched on its new
+/// `updatedAt`, so the replay's own writes don't count as conflicts
+fn advance(later: &mut [Entry], operation: &Operation, issue: &Value) {
+    let Some(updated) = issue["updatedAt"].as_str() else {
+        return;
+    };
+    let touched = match operation {
+        Operation::CommentCreate { issue, .. } => Some(issue.as_str()),
+        operation => operation.changed_issue(),
+    };
+    let names = [touched, issue["id"].as_str(), issue["identifier"].as_str()];
+    let same_issue = |other: &str| {
+        names
+            .iter()
+            .flatten()
+            .any(|name| name.eq_ignore_ascii_case(other))
+    };
+
+    for entry in later {
+        let Some(other) = entry.operation.changed_issue() else {
+            continue;
+        };
+        if same_issue(other) && mirror::is_later(updated, &entry.based_on) {
+            entry.based_on = updated.to_string();
+        }
+    }
+}
+
+/// Send one queued change, unless it conflicts or was already applied
+async fn replay_one(client: &LinearClient, entry: &Entry, force: bool) -> Result<Outcome> {
+    // The issue the change applies to, as it is on Linear now
+    let current = match entry.operation.changed_issue() {
+        Some(issue) => Some(
+            fetch_issue(client, issue)
+                .await?
+                .with_context(|| format!("Issue not found: {}", issue))?,
+        ),
+        None => None,
+    };
+    if let (Some(issue), Some(current)) = (entry.operation.changed_issue(), &current) {
+        let updated = current["updatedAt"].as_str().unwrap_or_default();
+        if !force && mirror::is_later(updated, &entry.based_on) {
+            // The later update may be our own: an earlier attempt that went
+            // through although its response never arrived
+            if already_applied(client, &entry.operation, current).await? {
+                return Ok(Outcome::AlreadyApplied(current.clone()));
+            }
+            return Ok(Outcome::Conflict(format!(
+                "{} was updated on Linear at {}, after this change was based on it",
+                issue, updated
+            )));
+        }
+    }
+
+    match &entry.operation {
+        Operation::IssueCreate { id, issue } => {
+            if let Some(existing) = fetch_issue(client, id).await? {
+                return Ok(Outcome::AlreadyApplied(existing));
+            }
+            let created = super::issues::send_create(client, Some(id), issue).await?;
+            Ok(Outcome::Applied(created))
+        }
+        Operation::IssueUpdate { issue, changes } => {
+            let updated = super::issues::send_update(client, issue, changes).await?;
+            Ok(Outcome::Applied(updated))
+        }
+        Operation::CommentCreate {
+            id,
+            issue,
+            body,
+            parent,
+        } => {
+            if comment_exists(client, id).await? {
+                return Ok(Outcome::AlreadyApplied(json!({ "identifier": issue })));
+            }
+            let comment =
+                comments::send_create(client, Some(id), issue, body, parent.as_deref()).await?;
+            Ok(Outcome::Applied(comment["issue"].clone()))
+        }
+        Operation::SetState { state, .. } => {
+            // States belong to a team, so go by the issue fetched above
+            let current = current.as_ref().context("The issue wasn't fetched")?;
+            let uuid = current["id"].as_str().unwrap_or_default();
+            let team_id = current["team"]["id"].as_str().unwrap_or_default();
+            Ok(Outcome::Applied(
+                bulk::set_state(client, uuid, team_id, state).await?,
+            ))
+        }
+        Operation::Assign { issue, user } => {
+            let user_id = match user {
+                Some(user) => Some(Resolver::new(client).user_id(user).await?),
+                None => None,
+            };
+            Ok(Outcome::Applied(
+                bulk::set_assignee(client, issue, user_id.as_deref()).await?,
+            ))
+        }
+        Operation::AddLabel { issue, label } => {
+            // Labels are resolved in the issue's team, as when adding one directly
+            let team_id = current.as_ref().and_then(|c| c["team"]["id"].as_str());
+            let label_id = Resolver::new(client).label_id(label, team_id).await?;
+            Ok(Outcome::Applied(
+                bulk::add_label(client, issue, &label_id).await?,
+            ))
+        }
+    }
+}
+
+/// Whether the issue already has every value the change sets
+async fn already_applied(
+    client: &LinearClient,
+    operation: &Operation,
+    current: &Value,
+) -> Result<bool> {
+    let resolver = Resolver::new(client);
+    let team_id = current["team"]["id"].as_str().unwrap_or_default();
+    let is = |field: &str, id: &str| current[field]["id"].as_str() == Some(id);
+    let has_label = |id: &str| {
+        current["labels"]["nodes"]
+            .as_array()
+            .is_some_and(|labels| labels.iter().any(|l| l["id"] == id))
+    };
+
+    match operation {
+        Operation::IssueUpdate { changes, .. } => {
+            if changes
+                .title
+                .as_ref()
+                .is_some_and(|t| current["title"] != *t)
+                || changes
+                    .description
+                    .as_ref()
+                    .is_some_and(|d| current["description"] != *d)
+                || changes
+                    .priority
+                    .is_some_and(|p| current["priority"].as_f64() != Some(p.into()))
+            {
+                return Ok(false);
+            }
+            if let Some(state) = &changes.state {
+                if !is("state", &resolver.state_id(team_id, state).await?) {
+                    return Ok(false);
+                }
+            }
+            if let Some(user) = &changes.assignee {
+                if !is("assignee", &resolver.user_id(user).await?) {
+                    return Ok(false);
+                }
+            }
+            for label in &changes.labels {
+                if !has_label(&resolver.label_id(label, Some(team_id)).await?) {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        Operation::SetState { state, .. } => {
+            Ok(is("state", &resolver.state_id(team_id, state).await?))
+        }
+        Operation::Assign { user: None, .. } => Ok(current["assignee"].is_null()),
+        Operation::Assign {
+            user: Some(user), ..
+        } => Ok(is("assignee", &resolver.user_id(user).await?)),
+        Operation::AddLabel { label, .. } => {
+            Ok(has_label(&resolver.label_id(label, Some(team_id)).await?))
+        }
+        Operation::IssueCreate { .. } | Operation::CommentCreate { .. } => Ok(false),
+    }
+}
+
+fn identifier(node: &Value) -> Option<String> {
+    node["identifier"].as_str().map(str::to_string)
+}
+
+/// Whether a lookup failed because the entity doesn't exist
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        matches!(
+            e.downcast_ref::<LinearError>(),
+            Some(LinearError::NotFound { .. })
+        )
+    })
+}
+
+/// An issue's `updatedAt`, team and the fields a queued change can set, or
+/// `None` if there's no such issue
+async fn fetch_issue(client: &LinearClient, id: &str) -> Result<Option<Value>> {
+    let query = r#"
+        query($id: String!) {
+            issue(id: $id) {
+                id
+                identifier
+                updatedAt
+                title
+                description
+                priority
+                state { id }
+                assignee { id }
+                labels { nodes { id } }
+                team { id }
+            }
+        }
+    "#;
+
+    match client.query(query, Some(json!({ "id": id }))).await {
+        Ok(result) => {
+            let issue = &result["data"]["issue"];
+            Ok((!issue.is_null()).then(|| issue.clone()))
+        }
+        Err(err) if is_not_found(&err) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn comment_exists(client: &LinearClient, id: &str) -> Result<bool> {
+    let query = r#"
+        query($id: String) {
+            comment(id: $id) {
+                id
+            }
+        }
+    "#;
+
+    match client.query(query, Some(json!({ "id": id }))).await {
+        Ok(result) => Ok(!result["data"]["comment"].is_null()),
+        Err(err) if is_not_found(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+fn print_summary(results: &[Replayed]) {
+    for result in results {
+        let change = result.entry.operation.describe();
+        let detail = result.error.as_deref().unwrap_or_default();
+        match result.status {
+            "applied" => println!(
+                "  {} {}{}",
+                "+".green(),
+                change,
+                result
+                    .identifier
+                    .as_deref()
+                    .map(|id| format!(" ({})", id.cyan()))
+                    .unwrap_or_default()
+            ),
+            "already-applied" => println!("  {} {} (already applied)", "=".green(), change),
+            "conflict" => println!("  {} {}: {}", "!".yellow(), change, detail.dimmed()),
+            "not-sent" => println!("  {} {} not sent", "~".yellow(), change),
+            _ => println!("  {} {} failed: {}", "x".red(), change, detail.dimmed()),
+        }
+    }
+
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    let still_queued = results.iter().filter(|r| !r.done()).count();
+    println!();
+    println!(
+        "{} Summary: {} applied, {} conflicts, {} failed, {} still queued",
+        ">>".cyan(),
+        (count("applied") + count("already-applied"))
+            .to_string()
+            .green(),
+        count("conflict"),
+        count("failed"),
+        still_queued
+    );
+    if results.iter().any(|r| r.status == "not-sent") {
+        println!("Linear can't be reached; replay again once it can.");
+    }
+    if count("conflict") > 0 {
+        println!(
+            "Review the conflicts, then: linear queue replay --force, or linear queue drop <ID>"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::is_uuid;
+
+    #[test]
+    fn test_new_id_is_a_uuid_v4() {
+        let id = new_id();
+        assert!(is_uuid(&id), "{}", id);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(new_id(), id);
+    }
+
+    #[test]
+    fn test_entries_round_trip_through_the_journal_format() {
+        let entry = Entry {
+            id: new_id(),
+            workspace: "work".to_string(),
+            queued_at: "2026-10-01T09:00:00+00:00".to_string(),
+            based_on: "2026-09-30T12:00:00.000Z".to_string(),
+            operation: Operation::Assign {
+                issue: "ENG-1".to_string(),
+                user: None,
+            },
+            last_error: None,
+        };
+        let line = serde_json::to_string(&entry).unwrap();
+        assert!(line.contains(r#""kind":"assign""#), "{}", line);
+        assert!(!line.contains('\n'));
+
+        let parsed: Entry = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.operation.describe(), "unassign ENG-1");
+        assert_eq!(parsed.operation.changed_issue(), Some("ENG-1"));
+        assert_eq!(parsed.based_on, entry.based_on);
+    }
+}
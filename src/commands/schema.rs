@@ -0,0 +1,491 @@
+//! `linear schema`: fetch the API's schema by introspection, keep it in the
+//! cache, and print it as SDL.
+//!
+//! `schema/linear.graphql` in the repository is a snapshot printed by
+//! `linear schema show`; `tests/schema_validation.rs` checks every embedded
+//! query against it.
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::api::LinearClient;
+use crate::cache::{Cache, CacheType};
+use crate::output::print_value;
+use crate::OutputFormat;
+
+/// How long a fetched schema counts as fresh in `cache status`
+const SCHEMA_TTL_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Built-in scalars and directives, left out of printed SDL
+const BUILT_IN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+const BUILT_IN_DIRECTIVES: &[&str] = &["include", "skip", "deprecated", "specifiedBy", "oneOf"];
+
+const INTROSPECTION_QUERY: &str = r#"
+    query IntrospectionQuery {
+        __schema {
+            queryType { name }
+            mutationType { name }
+            subscriptionType { name }
+            types { ...FullType }
+            directives {
+                name
+                description
+                locations
+                args { ...InputValue }
+            }
+        }
+    }
+
+    fragment FullType on __Type {
+        kind
+        name
+        description
+        fields(includeDeprecated: true) {
+            name
+            description
+            args { ...InputValue }
+            type { ...TypeRef }
+            isDeprecated
+            deprecationReason
+        }
+        inputFields { ...InputValue }
+        interfaces { ...TypeRef }
+        enumValues(includeDeprecated: true) {
+            name
+            description
+            isDeprecated
+            deprecationReason
+        }
+        possibleTypes { ...TypeRef }
+    }
+
+    fragment InputValue on __InputValue {
+        name
+        description
+        type { ...TypeRef }
+        defaultValue
+    }
+
+    fragment TypeRef on __Type {
+        kind
+        name
+        ofType {
+            kind
+            name
+            ofType {
+                kind
+                name
+                ofType {
+                    kind
+                    name
+                    ofType {
+                        kind
+                        name
+                        ofType { kind name ofType { kind name ofType { kind name } } }
+                    }
+                }
+            }
+        }
+    }
+"#;
+
+#[derive(Subcommand)]
+pub enum SchemaCommands {
+    /// Download the API schema by introspection and cache it
+    Fetch,
+    /// Print the cached schema as SDL, or one type from it
+    Show {
+        /// Only this type, e.g. Issue or IssueFilter
+        r#type: Option<String>,
+    },
+}
+
+pub async fn handle(cmd: SchemaCommands, output: OutputFormat) -> Result<()> {
+    match cmd {
+        SchemaCommands::Fetch => fetch_schema(output).await,
+        SchemaCommands::Show { r#type } => show_schema(r#type.as_deref(), output),
+    }
+}
+
+async fn fetch_schema(output: OutputFormat) -> Result<()> {
+    let client = LinearClient::new()?;
+    let result = client.query(INTROSPECTION_QUERY, None).await?;
+    let data = &result["data"];
+    let schema = &data["__schema"];
+    if !schema["types"].is_array() {
+        anyhow::bail!("Unexpected response: missing data.__schema.types");
+    }
+
+    Cache::shared_with_ttl(SCHEMA_TTL_SECONDS)?.set(CacheType::Schema, data.clone())?;
+
+    let summary = json!({
+        "types": schema["types"].as_array().map_or(0, |t| t.len()),
+        "queries": root_field_count(schema, "queryType"),
+        "mutations": root_field_count(schema, "mutationType"),
+    });
+    if !output.is_table() {
+        return print_value(&summary, output);
+    }
+
+    println!(
+        "{} Fetched schema: {} types, {} queries, {} mutations",
+        "+".green(),
+        summary["types"],
+        summary["queries"],
+        summary["mutations"]
+    );
+    println!("  Print it with: linear schema show [TYPE]");
+    Ok(())
+}
+
+fn show_schema(type_name: Option<&str>, output: OutputFormat) -> Result<()> {
+    let entry = Cache::shared()?
+        .get_entry(CacheType::Schema)
+        .context("No schema cached yet. Run: linear schema fetch")?;
+    let schema = &entry.data["__schema"];
+
+    let Some(name) = type_name else {
+        if !output.is_table() {
+            return print_value(&entry.data, output);
+        }
+        print!("{}", render_sdl(schema));
+        return Ok(());
+    };
+
+    let found = schema["types"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|t| {
+            t["name"]
+                .as_str()
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })
+        .with_context(|| format!("Type not found in the cached schema: {}", name))?;
+
+    if !output.is_table() {
+        return print_value(found, output);
+    }
+    println!("{}", render_type(found));
+    Ok(())
+}
+
+fn root_field_count(schema: &Value, root: &str) -> usize {
+    let Some(name) = schema[root]["name"].as_str() else {
+        return 0;
+    };
+    schema["types"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|t| t["name"] == name)
+        .and_then(|t| t["fields"].as_array())
+        .map_or(0, |f| f.len())
+}
+
+/// The whole schema as SDL: custom directives, then every type in the order
+/// the API lists them
+pub fn render_sdl(schema: &Value) -> String {
+    let mut blocks = Vec::new();
+
+    let roots: Vec<(&str, &str)> = [
+        ("query", "queryType", "Query"),
+        ("mutation", "mutationType", "Mutation"),
+        ("subscription", "subscriptionType", "Subscription"),
+    ]
+    .iter()
+    .filter_map(|(op, key, default)| {
+        let name = schema[*key]["name"].as_str()?;
+        Some((*op, name)).filter(|_| name != *default)
+    })
+    .collect();
+    if !roots.is_empty() {
+        let lines: Vec<String> = roots
+            .iter()
+            .map(|(op, name)| format!("  {}: {}", op, name))
+            .collect();
+        blocks.push(format!("schema {{\n{}\n}}", lines.join("\n")));
+    }
+
+    for directive in schema["directives"].as_array().into_iter().flatten() {
+        let name = directive["name"].as_str().unwrap_or_default();
+        if BUILT_IN_DIRECTIVES.contains(&name) {
+            continue;
+        }
+        let locations: Vec<&str> = directive["locations"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        blocks.push(format!(
+            "{}directive @{}{} on {}",
+            description(&directive["description"], ""),
+            name,
+            arguments(&directive["args"], ""),
+            locations.join(" | ")
+        ));
+    }
+
+    for ty in schema["types"].as_array().into_iter().flatten() {
+        let name = ty["name"].as_str().unwrap_or_default();
+        if name.starts_with("__") || BUILT_IN_SCALARS.contains(&name) {
+            continue;
+        }
+        blocks.push(render_type(ty));
+    }
+
+    let mut sdl = blocks.join("\n\n");
+    sdl.push('\n');
+    sdl
+}
+
+/// One type from an introspection result as SDL
+pub fn render_type(ty: &Value) -> String {
+    let name = ty["name"].as_str().unwrap_or_default();
+    let head = description(&ty["description"], "");
+
+    match ty["kind"].as_str().unwrap_or_default() {
+        "SCALAR" => format!("{}scalar {}", head, name),
+        "UNION" => {
+            let members: Vec<String> = ty["possibleTypes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(type_ref)
+                .collect();
+            format!("{}union {} = {}", head, name, members.join(" | "))
+        }
+        "ENUM" => {
+            let values: Vec<String> = ty["enumValues"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|v| {
+                    format!(
+                        "{}  {}{}",
+                        description(&v["description"], "  "),
+                        v["name"].as_str().unwrap_or_default(),
+                        deprecated(v)
+                    )
+                })
+                .collect();
+            format!("{}enum {} {{\n{}\n}}", head, name, values.join("\n"))
+        }
+        "INPUT_OBJECT" => {
+            let fields: Vec<String> = ty["inputFields"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|f| {
+                    format!(
+                        "{}  {}",
+                        description(&f["description"], "  "),
+                        input_value(f)
+                    )
+                })
+                .collect();
+            format!("{}input {} {{\n{}\n}}", head, name, fields.join("\n"))
+        }
+        kind => {
+            let keyword = if kind == "INTERFACE" {
+                "interface"
+            } else {
+                "type"
+            };
+            let interfaces: Vec<String> = ty["interfaces"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(type_ref)
+                .collect();
+            let implements = if interfaces.is_empty() {
+                String::new()
+            } else {
+                format!(" implements {}", interfaces.join(" & "))
+            };
+            let fields: Vec<String> = ty["fields"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|f| {
+                    format!(
+                        "{}  {}{}: {}{}",
+                        description(&f["description"], "  "),
+                        f["name"].as_str().unwrap_or_default(),
+                        arguments(&f["args"], "  "),
+                        type_ref(&f["type"]),
+                        deprecated(f)
+                    )
+                })
+                .collect();
+            format!(
+                "{}{} {}{} {{\n{}\n}}",
+                head,
+                keyword,
+                name,
+                implements,
+                fields.join("\n")
+            )
+        }
+    }
+}
+
+/// `(a: Int, b: String = "x")`, one argument per line when any has a description
+fn arguments(args: &Value, indent: &str) -> String {
+    let args: Vec<&Value> = args.as_array().into_iter().flatten().collect();
+    if args.is_empty() {
+        return String::new();
+    }
+    if args.iter().all(|a| a["description"].is_null()) {
+        let inline: Vec<String> = args.iter().map(|a| input_value(a)).collect();
+        return format!("({})", inline.join(", "));
+    }
+    let inner = format!("{}  ", indent);
+    let lines: Vec<String> = args
+        .iter()
+        .map(|a| {
+            format!(
+                "{}{}{}",
+                description(&a["description"], &inner),
+                inner,
+                input_value(a)
+            )
+        })
+        .collect();
+    format!("(\n{}\n{})", lines.join("\n"), indent)
+}
+
+fn input_value(value: &Value) -> String {
+    let mut out = format!(
+        "{}: {}",
+        value["name"].as_str().unwrap_or_default(),
+        type_ref(&value["type"])
+    );
+    if let Some(default) = value["defaultValue"].as_str() {
+        out.push_str(" = ");
+        out.push_str(default);
+    }
+    out
+}
+
+/// `[Issue!]!` from nested `{ kind, name, ofType }` references
+fn type_ref(ty: &Value) -> String {
+    match ty["kind"].as_str() {
+        Some("NON_NULL") => format!("{}!", type_ref(&ty["ofType"])),
+        Some("LIST") => format!("[{}]", type_ref(&ty["ofType"])),
+        _ => ty["name"].as_str().unwrap_or_default().to_string(),
+    }
+}
+
+fn deprecated(item: &Value) -> String {
+    if item["isDeprecated"] != true {
+        return String::new();
+    }
+    match item["deprecationReason"].as_str() {
+        Some(reason) if reason != "No longer supported" => {
+            format!(
+                " @deprecated(reason: {})",
+                Value::String(reason.to_string())
+            )
+        }
+        _ => " @deprecated".to_string(),
+    }
+}
+
+/// A block string description, followed by a newline and the indent
+fn description(text: &Value, indent: &str) -> String {
+    let Some(text) = text.as_str().filter(|t| !t.is_empty()) else {
+        return String::new();
+    };
+    let text = text.replace("\"\"\"", "\\\"\"\"");
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect();
+    format!("{}\"\"\"\n{}\n{}\"\"\"\n", indent, lines.join("\n"), indent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({ "kind": kind, "name": name, "ofType": null })
+    }
+
+    fn non_null(inner: Value) -> Value {
+        json!({ "kind": "NON_NULL", "name": null, "ofType": inner })
+    }
+
+    #[test]
+    fn test_render_types() {
+        let issue = json!({
+            "kind": "OBJECT",
+            "name": "Issue",
+            "description": "An issue.",
+            "interfaces": [named("INTERFACE", "Node")],
+            "fields": [
+                { "name": "id", "description": null, "args": [],
+                  "type": non_null(named("SCALAR", "ID")), "isDeprecated": false },
+                { "name": "labels", "description": "Labels on the issue.",
+                  "args": [{ "name": "first", "description": null,
+                             "type": named("SCALAR", "Int"), "defaultValue": "50" }],
+                  "type": non_null(json!({ "kind": "LIST", "name": null,
+                                           "ofType": non_null(named("OBJECT", "IssueLabel")) })),
+                  "isDeprecated": false },
+                { "name": "old", "description": null, "args": [],
+                  "type": named("SCALAR", "String"), "isDeprecated": true,
+                  "deprecationReason": "Use new" }
+            ]
+        });
+        assert_eq!(
+            render_type(&issue),
+            "\"\"\"\nAn issue.\n\"\"\"\n\
+             type Issue implements Node {\n  \
+               id: ID!\n  \
+               \"\"\"\n  Labels on the issue.\n  \"\"\"\n  \
+               labels(first: Int = 50): [IssueLabel!]!\n  \
+               old: String @deprecated(reason: \"Use new\")\n\
+             }"
+        );
+
+        let state = json!({
+            "kind": "ENUM", "name": "Kind", "description": null,
+            "enumValues": [
+                { "name": "a", "description": null, "isDeprecated": false },
+                { "name": "b", "description": null, "isDeprecated": true,
+                  "deprecationReason": "No longer supported" }
+            ]
+        });
+        assert_eq!(render_type(&state), "enum Kind {\n  a\n  b @deprecated\n}");
+    }
+
+    #[test]
+    fn test_render_sdl_skips_built_ins() {
+        let schema = json!({
+            "queryType": { "name": "Query" },
+            "mutationType": null,
+            "directives": [{ "name": "skip", "locations": ["FIELD"], "args": [] }],
+            "types": [
+                named("SCALAR", "String"),
+                named("SCALAR", "DateTime"),
+                { "kind": "OBJECT", "name": "__Type", "fields": [] },
+                { "kind": "UNION", "name": "Result",
+                  "possibleTypes": [named("OBJECT", "A"), named("OBJECT", "B")] }
+            ]
+        });
+        assert_eq!(
+            render_sdl(&schema),
+            "scalar DateTime\n\nunion Result = A | B\n"
+        );
+    }
+}
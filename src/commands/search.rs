@@ -1,9 +1,18 @@
 use anyhow::Result;
 use clap::Subcommand;
-use serde_json::json;
-use tabled::{Table, Tabled};
+use serde_json::{json, Value};
+use tabled::Tabled;
 
-use crate::api::LinearClient;
+use crate::api::{LinearClient, PageOptions};
+use crate::config;
+use crate::fields;
+use crate::filter;
+use crate::mirror::{self, Entity, Mirror};
+use crate::models::{self, priority_label, Issue, Project};
+use crate::output::{truncate, TableStream, ValueStream};
+use crate::OutputFormat;
+
+use super::projects::project_row;
 
 #[derive(Subcommand)]
 pub enum SearchCommands {
@@ -13,10 +22,22 @@ pub enum SearchCommands {
         query: String,
         /// Maximum number of results
         #[arg(short, long, default_value = "50")]
-        limit: u32,
+        limit: usize,
         /// Include archived issues
         #[arg(short, long)]
         archived: bool,
+        /// Fetch all matches, ignoring --limit
+        #[arg(long)]
+        all: bool,
+        /// Only search issues in this team (default: the default team)
+        #[arg(short, long)]
+        team: Option<String>,
+        /// Search every team, ignoring the default team
+        #[arg(long, conflicts_with = "team")]
+        all_teams: bool,
+        /// Search the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
     /// Search projects by query string
     Projects {
@@ -24,10 +45,22 @@ pub enum SearchCommands {
         query: String,
         /// Maximum number of results
         #[arg(short, long, default_value = "50")]
-        limit: u32,
+        limit: usize,
         /// Include archived projects
         #[arg(short, long)]
         archived: bool,
+        /// Fetch all matches, ignoring --limit
+        #[arg(long)]
+        all: bool,
+        /// Only search projects in this team (default: the default team)
+        #[arg(short, long)]
+        team: Option<String>,
+        /// Search every team, ignoring the default team
+        #[arg(long, conflicts_with = "team")]
+        all_teams: bool,
+        /// Search the local mirror instead of the API (see: linear sync pull)
+        #[arg(long)]
+        offline: bool,
     },
 }
 
@@ -45,172 +78,254 @@ struct IssueRow {
     id: String,
 }
 
-#[derive(Tabled)]
-struct ProjectRow {
-    #[tabled(rename = "Name")]
-    name: String,
-    #[tabled(rename = "Status")]
-    status: String,
-    #[tabled(rename = "Labels")]
-    labels: String,
-    #[tabled(rename = "ID")]
-    id: String,
-}
-
-pub async fn handle(cmd: SearchCommands) -> Result<()> {
+pub async fn handle(cmd: SearchCommands, output: OutputFormat) -> Result<()> {
     match cmd {
         SearchCommands::Issues {
             query,
             limit,
             archived,
-        } => search_issues(&query, limit, archived).await,
+            all,
+            team,
+            all_teams,
+            offline,
+        } => {
+            let pages = PageOptions::new(limit, all);
+            let team = config::list_team(team, all_teams);
+            search_issues(&query, team, pages, archived, offline, output).await
+        }
         SearchCommands::Projects {
             query,
             limit,
             archived,
-        } => search_projects(&query, limit, archived).await,
+            all,
+            team,
+            all_teams,
+            offline,
+        } => {
+            let pages = PageOptions::new(limit, all);
+            let team = config::list_team(team, all_teams);
+            search_projects(&query, team, pages, archived, offline, output).await
+        }
     }
 }
 
-async fn search_issues(query: &str, limit: u32, include_archived: bool) -> Result<()> {
-    let client = LinearClient::new()?;
+fn issue_row(issue: &Issue) -> IssueRow {
+    IssueRow {
+        identifier: issue.identifier.clone(),
+        title: truncate(&issue.title, 50),
+        state: issue.state_name().to_string(),
+        priority: priority_label(issue.priority),
+        id: issue.id.clone(),
+    }
+}
+
+/// The online filter plus what `includeArchived` does on the server
+fn offline_filter(filter: Value, include_archived: bool) -> Value {
+    if include_archived {
+        return filter;
+    }
+    json!({ "and": [filter, { "archivedAt": { "null": true } }] })
+}
 
+async fn search_issues(
+    query: &str,
+    team: Option<String>,
+    pages: PageOptions,
+    include_archived: bool,
+    offline: bool,
+    output: OutputFormat,
+) -> Result<()> {
     let graphql_query = r#"
-        query($first: Int!, $includeArchived: Boolean, $filter: IssueFilter) {
-            issues(first: $first, includeArchived: $includeArchived, filter: $filter) {
+        query($first: Int, $after: String, $includeArchived: Boolean, $filter: IssueFilter) {
+            issues(first: $first, after: $after, includeArchived: $includeArchived, filter: $filter) {
                 nodes {
                     id
                     identifier
                     title
                     priority
-                    state { name }
+                    state { id name }
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
+    let mut filter = json!({
+        "or": [
+            { "title": { "containsIgnoreCase": query } },
+            { "description": { "containsIgnoreCase": query } }
+        ]
+    });
+    if let Some(team) = team {
+        filter["team"] = filter::team(&team);
+    }
+
+    if offline {
+        let mirror = Mirror::open_offline().await?;
+        let issues = mirror::select(
+            mirror.issues()?,
+            &offline_filter(filter, include_archived),
+            pages.limit,
+        );
+        if !output.is_table() {
+            let mut stream = ValueStream::new(output);
+            stream.push(&issues)?;
+            return stream.finish();
+        }
+        let issues: Vec<Issue> = models::from_nodes(&issues)?;
+        if issues.is_empty() {
+            println!("No issues found matching: {}", query);
+            return Ok(());
+        }
+        let mut table = TableStream::new();
+        table.push(issues.iter().map(issue_row).collect());
+        println!("\n{} issues found", table.rows());
+        return Ok(());
+    }
+
+    let client = LinearClient::new()?;
     let variables = json!({
-        "first": limit,
         "includeArchived": include_archived,
-        "filter": {
-            "or": [
-                { "title": { "containsIgnoreCase": query } },
-                { "description": { "containsIgnoreCase": query } }
-            ]
-        }
+        "filter": filter
     });
 
-    let result = client.query(graphql_query, Some(variables)).await?;
+    if !output.is_table() {
+        let graphql_query = fields::narrow(graphql_query, &["issues"]);
+        let mut stream = ValueStream::new(output).columns(&graphql_query, &["issues"]);
+        client
+            .paginate(
+                &graphql_query,
+                Some(variables),
+                &["issues"],
+                pages,
+                |nodes| stream.push(&nodes),
+            )
+            .await?;
+        return stream.finish();
+    }
 
-    let empty = vec![];
-    let issues = result["data"]["issues"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let mut table = TableStream::new();
+    client
+        .paginate(
+            graphql_query,
+            Some(variables),
+            &["issues"],
+            pages,
+            |issues| {
+                let issues: Vec<Issue> = models::from_nodes(&issues)?;
+                table.push(issues.iter().map(issue_row).collect());
+                Ok(())
+            },
+        )
+        .await?;
 
-    if issues.is_empty() {
+    if table.rows() == 0 {
         println!("No issues found matching: {}", query);
         return Ok(());
     }
 
-    let rows: Vec<IssueRow> = issues
-        .iter()
-        .map(|issue| {
-            let priority = match issue["priority"].as_i64() {
-                Some(0) => "-".to_string(),
-                Some(1) => "Urgent".to_string(),
-                Some(2) => "High".to_string(),
-                Some(3) => "Normal".to_string(),
-                Some(4) => "Low".to_string(),
-                _ => "-".to_string(),
-            };
-
-            IssueRow {
-                identifier: issue["identifier"].as_str().unwrap_or("").to_string(),
-                title: truncate_string(issue["title"].as_str().unwrap_or(""), 50),
-                state: issue["state"]["name"].as_str().unwrap_or("-").to_string(),
-                priority,
-                id: issue["id"].as_str().unwrap_or("").to_string(),
-            }
-        })
-        .collect();
-
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} issues found", issues.len());
+    println!("\n{} issues found", table.rows());
 
     Ok(())
 }
 
-async fn search_projects(query: &str, limit: u32, include_archived: bool) -> Result<()> {
-    let client = LinearClient::new()?;
-
+async fn search_projects(
+    query: &str,
+    team: Option<String>,
+    pages: PageOptions,
+    include_archived: bool,
+    offline: bool,
+    output: OutputFormat,
+) -> Result<()> {
     let graphql_query = r#"
-        query($first: Int!, $includeArchived: Boolean, $filter: ProjectFilter) {
-            projects(first: $first, includeArchived: $includeArchived, filter: $filter) {
+        query($first: Int, $after: String, $includeArchived: Boolean, $filter: ProjectFilter) {
+            projects(first: $first, after: $after, includeArchived: $includeArchived, filter: $filter) {
                 nodes {
                     id
                     name
-                    status { name }
-                    labels { nodes { name } }
+                    status { id name }
+                    labels { nodes { id name } }
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
+    let mut filter = json!({ "name": { "containsIgnoreCase": query } });
+    let team = team.map(|team| json!({ "some": filter::team(&team) }));
+
+    if offline {
+        // The mirror keeps a project's teams as `teams`
+        if let Some(team) = team {
+            filter["teams"] = team;
+        }
+        let mirror = Mirror::open_offline().await?;
+        let projects = mirror::select(
+            mirror.all(Entity::Projects)?,
+            &offline_filter(filter, include_archived),
+            pages.limit,
+        );
+        if !output.is_table() {
+            let mut stream = ValueStream::new(output);
+            stream.push(&projects)?;
+            return stream.finish();
+        }
+        let projects: Vec<Project> = models::from_nodes(&projects)?;
+        if projects.is_empty() {
+            println!("No projects found matching: {}", query);
+            return Ok(());
+        }
+        let mut table = TableStream::new();
+        table.push(projects.iter().map(project_row).collect());
+        println!("\n{} projects found", table.rows());
+        return Ok(());
+    }
+
+    if let Some(team) = team {
+        filter["accessibleTeams"] = team;
+    }
+    let client = LinearClient::new()?;
     let variables = json!({
-        "first": limit,
         "includeArchived": include_archived,
-        "filter": {
-            "name": { "containsIgnoreCase": query }
-        }
+        "filter": filter
     });
 
-    let result = client.query(graphql_query, Some(variables)).await?;
+    if !output.is_table() {
+        let graphql_query = fields::narrow(graphql_query, &["projects"]);
+        let mut stream = ValueStream::new(output).columns(&graphql_query, &["projects"]);
+        client
+            .paginate(
+                &graphql_query,
+                Some(variables),
+                &["projects"],
+                pages,
+                |nodes| stream.push(&nodes),
+            )
+            .await?;
+        return stream.finish();
+    }
 
-    let empty = vec![];
-    let projects = result["data"]["projects"]["nodes"]
-        .as_array()
-        .unwrap_or(&empty);
+    let mut table = TableStream::new();
+    client
+        .paginate(
+            graphql_query,
+            Some(variables),
+            &["projects"],
+            pages,
+            |projects| {
+                let projects: Vec<Project> = models::from_nodes(&projects)?;
+                table.push(projects.iter().map(project_row).collect());
+                Ok(())
+            },
+        )
+        .await?;
 
-    if projects.is_empty() {
+    if table.rows() == 0 {
         println!("No projects found matching: {}", query);
         return Ok(());
     }
 
-    let rows: Vec<ProjectRow> = projects
-        .iter()
-        .map(|p| {
-            let labels: Vec<String> = p["labels"]["nodes"]
-                .as_array()
-                .unwrap_or(&vec![])
-                .iter()
-                .map(|l| l["name"].as_str().unwrap_or("").to_string())
-                .collect();
-
-            ProjectRow {
-                name: p["name"].as_str().unwrap_or("").to_string(),
-                status: p["status"]["name"].as_str().unwrap_or("-").to_string(),
-                labels: if labels.is_empty() {
-                    "-".to_string()
-                } else {
-                    labels.join(", ")
-                },
-                id: p["id"].as_str().unwrap_or("").to_string(),
-            }
-        })
-        .collect();
-
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-    println!("\n{} projects found", projects.len());
+    println!("\n{} projects found", table.rows());
 
     Ok(())
 }
-
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len - 3])
-    }
-}
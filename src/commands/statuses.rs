@@ -1,28 +1,32 @@
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::{json, Value};
+use serde_json::json;
 use tabled::{Table, Tabled};
 
-use crate::api::{resolve_team_id, LinearClient};
-use crate::cache::{Cache, CacheType};
+use crate::api::LinearClient;
+use crate::config;
+use crate::models::{self, WorkflowState};
+use crate::output::print_value;
+use crate::resolver::Resolver;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum StatusCommands {
     /// List all issue statuses for a team
     #[command(alias = "ls")]
     List {
-        /// Team name or ID
+        /// Team name or ID [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
     },
     /// Get details of a specific status
     Get {
         /// Status name or ID
         id: String,
-        /// Team name or ID
+        /// Team name or ID [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
     },
 }
 
@@ -40,71 +44,30 @@ struct StatusRow {
     id: String,
 }
 
-pub async fn handle(cmd: StatusCommands) -> Result<()> {
+pub async fn handle(cmd: StatusCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        StatusCommands::List { team } => list_statuses(&team).await,
-        StatusCommands::Get { id, team } => get_status(&id, &team).await,
+        StatusCommands::List { team } => {
+            list_statuses(&config::team_or_default(team)?, output).await
+        }
+        StatusCommands::Get { id, team } => {
+            get_status(&id, &config::team_or_default(team)?, output).await
+        }
     }
 }
 
-async fn list_statuses(team: &str) -> Result<()> {
+async fn list_statuses(team: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
-    let cache = Cache::new()?;
+    let resolver = Resolver::new(&client);
 
-    // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, team).await?;
-
-    // Try to get statuses from cache first
-    let (team_name, states): (String, Vec<Value>) =
-        if let Some(cached) = cache.get_keyed(CacheType::Statuses, &team_id) {
-            let name = cached["team_name"].as_str().unwrap_or("").to_string();
-            let states_data = cached["states"].as_array().cloned().unwrap_or_default();
-            (name, states_data)
-        } else {
-            // Fetch from API
-            let query = r#"
-                query($teamId: String!) {
-                    team(id: $teamId) {
-                        id
-                        name
-                        states {
-                            nodes {
-                                id
-                                name
-                                type
-                                color
-                                position
-                                description
-                            }
-                        }
-                    }
-                }
-            "#;
+    // Resolve team key/name to UUID, then its states (cached per team)
+    let team_id = resolver.team_id(team).await?;
+    let (team_name, states) = resolver.team_states(&team_id).await?;
 
-            let result = client
-                .query(query, Some(json!({ "teamId": team_id })))
-                .await?;
-            let team_data = &result["data"]["team"];
-
-            if team_data.is_null() {
-                anyhow::bail!("Team not found: {}", team);
-            }
-
-            let name = team_data["name"].as_str().unwrap_or("").to_string();
-            let states_data = team_data["states"]["nodes"]
-                .as_array()
-                .cloned()
-                .unwrap_or_default();
-
-            // Cache the result
-            let cache_data = json!({
-                "team_name": name,
-                "states": states_data
-            });
-            let _ = cache.set_keyed(CacheType::Statuses, &team_id, cache_data);
+    if !output.is_table() {
+        return print_value(&states, output);
+    }
 
-            (name, states_data)
-        };
+    let states: Vec<WorkflowState> = models::from_nodes(&states)?;
 
     if states.is_empty() {
         println!("No statuses found for team '{}'.", team_name);
@@ -119,27 +82,12 @@ async fn list_statuses(team: &str) -> Result<()> {
 
     let rows: Vec<StatusRow> = states
         .iter()
-        .map(|s| {
-            let status_type = s["type"].as_str().unwrap_or("");
-            let type_colored = match status_type {
-                "completed" => status_type.green().to_string(),
-                "started" => status_type.yellow().to_string(),
-                "canceled" | "cancelled" => status_type.red().to_string(),
-                "backlog" => status_type.dimmed().to_string(),
-                "unstarted" => status_type.cyan().to_string(),
-                _ => status_type.to_string(),
-            };
-
-            StatusRow {
-                name: s["name"].as_str().unwrap_or("").to_string(),
-                status_type: type_colored,
-                color: s["color"].as_str().unwrap_or("").to_string(),
-                position: s["position"]
-                    .as_f64()
-                    .map(|p| format!("{:.0}", p))
-                    .unwrap_or("-".to_string()),
-                id: s["id"].as_str().unwrap_or("").to_string(),
-            }
+        .map(|s| StatusRow {
+            name: s.name.clone(),
+            status_type: s.paint(s.kind.as_deref().unwrap_or("")),
+            color: s.color.clone().unwrap_or_default(),
+            position: format_position(s.position),
+            id: s.id.clone(),
         })
         .collect();
 
@@ -150,11 +98,11 @@ async fn list_statuses(team: &str) -> Result<()> {
     Ok(())
 }
 
-async fn get_status(id: &str, team: &str) -> Result<()> {
+async fn get_status(id: &str, team: &str, output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, team).await?;
+    let team_id = Resolver::new(&client).team_id(team).await?;
 
     // First get all states for the team and find the matching one
     let query = r#"
@@ -185,34 +133,25 @@ async fn get_status(id: &str, team: &str) -> Result<()> {
         anyhow::bail!("Team not found: {}", team);
     }
 
-    let empty = vec![];
-    let states = team_data["states"]["nodes"].as_array().unwrap_or(&empty);
+    let states: Vec<WorkflowState> = models::from_value(&team_data["states"]["nodes"])?;
 
     // Find matching status by ID or name
-    let status = states.iter().find(|s| {
-        s["id"].as_str() == Some(id)
-            || s["name"].as_str().map(|n| n.to_lowercase()) == Some(id.to_lowercase())
-    });
+    let status = states
+        .iter()
+        .find(|s| s.id == id || s.name.eq_ignore_ascii_case(id));
 
     match status {
+        Some(s) if !output.is_table() => print_value(s, output),
         Some(s) => {
-            println!("{}", s["name"].as_str().unwrap_or("").bold());
+            println!("{}", s.name.bold());
             println!("{}", "-".repeat(40));
-            println!("Type: {}", s["type"].as_str().unwrap_or("-"));
-            println!("Color: {}", s["color"].as_str().unwrap_or("-"));
-            println!(
-                "Position: {}",
-                s["position"]
-                    .as_f64()
-                    .map(|p| format!("{:.0}", p))
-                    .unwrap_or("-".to_string())
-            );
-            if let Some(desc) = s["description"].as_str() {
-                if !desc.is_empty() {
-                    println!("Description: {}", desc);
-                }
+            println!("Type: {}", s.kind.as_deref().unwrap_or("-"));
+            println!("Color: {}", s.color.as_deref().unwrap_or("-"));
+            println!("Position: {}", format_position(s.position));
+            if let Some(desc) = s.description.as_deref().filter(|d| !d.is_empty()) {
+                println!("Description: {}", desc);
             }
-            println!("ID: {}", s["id"].as_str().unwrap_or("-"));
+            println!("ID: {}", s.id);
             Ok(())
         }
         None => {
@@ -220,3 +159,9 @@ async fn get_status(id: &str, team: &str) -> Result<()> {
         }
     }
 }
+
+fn format_position(position: Option<f64>) -> String {
+    position
+        .map(|p| format!("{:.0}", p))
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -1,12 +1,17 @@
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
-use crate::api::{resolve_team_id, LinearClient};
+use crate::api::{LinearClient, PageOptions};
+use crate::config;
+use crate::mirror::{Entity, Mirror};
+use crate::output::print_value;
+use crate::resolver::Resolver;
+use crate::OutputFormat;
 
 /// Get default directory to scan for local projects (cross-platform)
 fn get_default_code_dir() -> String {
@@ -39,9 +44,9 @@ pub enum SyncCommands {
         /// Directory to scan for local projects (default: ~/code)
         #[arg(short, long)]
         directory: Option<String>,
-        /// Team name or ID to create projects in
+        /// Team name or ID to create projects in [default: default.team]
         #[arg(short, long)]
-        team: String,
+        team: Option<String>,
         /// Only push specific folders (comma-separated)
         #[arg(short, long)]
         only: Option<String>,
@@ -49,6 +54,21 @@ pub enum SyncCommands {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Copy issues, projects, cycles, comments and documents into a local
+    /// database for --offline commands
+    #[command(after_help = r#"EXAMPLES:
+    linear sync pull                           # Fetch what changed since the last pull
+    linear sy pull --full                      # Fetch everything again
+    linear sy pull --only issues,cycles        # Only some entities
+    linear issues list --offline               # Then read without the API"#)]
+    Pull {
+        /// Ignore the stored watermarks and fetch everything
+        #[arg(long)]
+        full: bool,
+        /// Only pull these entities (comma-separated: issues, projects, cycles, comments, documents)
+        #[arg(long)]
+        only: Option<String>,
+    },
 }
 
 /// Represents a local folder that could be a project
@@ -65,7 +85,6 @@ struct LinearProject {
     #[allow(dead_code)]
     id: String,
     name: String,
-    #[allow(dead_code)]
     url: Option<String>,
 }
 
@@ -83,21 +102,116 @@ enum SyncStatus {
     RemoteOnly(LinearProject),
 }
 
-pub async fn handle(cmd: SyncCommands) -> Result<()> {
+impl SyncStatus {
+    /// One record per project for the data output formats
+    fn to_json(&self) -> Value {
+        match self {
+            SyncStatus::Synced { local, remote } => json!({
+                "name": local.name,
+                "status": "synced",
+                "path": local.path,
+                "git": local.has_git,
+                "url": remote.url,
+            }),
+            SyncStatus::LocalOnly(local) => json!({
+                "name": local.name,
+                "status": "local_only",
+                "path": local.path,
+                "git": local.has_git,
+                "url": null,
+            }),
+            SyncStatus::RemoteOnly(remote) => json!({
+                "name": remote.name,
+                "status": "remote_only",
+                "path": null,
+                "git": false,
+                "url": remote.url,
+            }),
+        }
+    }
+}
+
+pub async fn handle(cmd: SyncCommands, output: OutputFormat) -> Result<()> {
     match cmd {
         SyncCommands::Status {
             directory,
             missing_only,
-        } => status_command(directory, missing_only).await,
+        } => status_command(directory, missing_only, output).await,
         SyncCommands::Push {
             directory,
             team,
             only,
             dry_run,
-        } => push_command(directory, team, only, dry_run).await,
+        } => {
+            let team = config::team_or_default(team)?;
+            push_command(directory, team, only, dry_run, output).await
+        }
+        SyncCommands::Pull { full, only } => pull_command(full, only, output).await,
     }
 }
 
+/// Entities named by `--only`, or all of them
+fn parse_entities(only: Option<&str>) -> Result<Vec<Entity>> {
+    let Some(only) = only else {
+        return Ok(Entity::all().to_vec());
+    };
+    only.split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            Entity::parse(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown entity: '{}'. Valid entities: issues, projects, cycles, comments, documents",
+                    name.trim()
+                )
+            })
+        })
+        .collect()
+}
+
+async fn pull_command(full: bool, only: Option<String>, output: OutputFormat) -> Result<()> {
+    let entities = parse_entities(only.as_deref())?;
+    let client = LinearClient::new()?;
+    let mut mirror = Mirror::open(&client).await?;
+    mirror.pull_viewer(&client).await?;
+
+    if output.is_table() {
+        println!("{}", "Pulling into the offline mirror".bold());
+        println!("{}", "─".repeat(60));
+    }
+
+    let mut records = Vec::new();
+    for &entity in &entities {
+        let pulled = mirror.pull(&client, entity, full).await?;
+        if output.is_table() {
+            println!(
+                "{} {:<10} {:>6} fetched, {:>6} stored{}",
+                "+".green(),
+                entity.name(),
+                pulled.fetched,
+                pulled.total,
+                pulled
+                    .watermark
+                    .as_deref()
+                    .map(|w| format!(", up to {}", w).dimmed().to_string())
+                    .unwrap_or_default()
+            );
+        }
+        records.push(json!({
+            "entity": entity.name(),
+            "fetched": pulled.fetched,
+            "stored": pulled.total,
+            "watermark": pulled.watermark,
+        }));
+    }
+
+    if !output.is_table() {
+        return print_value(&records, output);
+    }
+    println!();
+    println!("Mirror: {}", mirror.path().display().to_string().dimmed());
+    Ok(())
+}
+
 /// Scan a directory for local project folders
 fn scan_local_projects(dir: &str) -> Result<Vec<LocalProject>> {
     let path = Path::new(dir);
@@ -139,7 +253,7 @@ fn scan_local_projects(dir: &str) -> Result<Vec<LocalProject>> {
     }
 
     // Sort alphabetically
-    projects.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    projects.sort_by_key(|a| a.name.to_lowercase());
 
     Ok(projects)
 }
@@ -147,22 +261,21 @@ fn scan_local_projects(dir: &str) -> Result<Vec<LocalProject>> {
 /// Fetch all Linear projects
 async fn fetch_linear_projects(client: &LinearClient) -> Result<Vec<LinearProject>> {
     let query = r#"
-        query {
-            projects(first: 250) {
+        query($first: Int, $after: String) {
+            projects(first: $first, after: $after) {
                 nodes {
                     id
                     name
                     url
                 }
+                pageInfo { hasNextPage endCursor }
             }
         }
     "#;
 
-    let result = client.query(query, None).await?;
-
-    let projects = result["data"]["projects"]["nodes"]
-        .as_array()
-        .unwrap_or(&vec![])
+    let projects = client
+        .fetch_all(query, None, &["projects"], PageOptions::all())
+        .await?
         .iter()
         .map(|p| LinearProject {
             id: p["id"].as_str().unwrap_or("").to_string(),
@@ -210,27 +323,40 @@ fn compare_projects(local: Vec<LocalProject>, remote: Vec<LinearProject>) -> Vec
 }
 
 /// Display sync status
-async fn status_command(directory: Option<String>, missing_only: bool) -> Result<()> {
+async fn status_command(
+    directory: Option<String>,
+    missing_only: bool,
+    output: OutputFormat,
+) -> Result<()> {
     let dir = directory.unwrap_or_else(get_default_code_dir);
 
-    println!("{}", "Sync Status".bold());
-    println!("{}", "─".repeat(60));
-    println!("Scanning: {}", dir.cyan());
-    println!();
-
-    // Scan local projects
+    // Scan local projects and fetch Linear projects
     let local_projects = scan_local_projects(&dir)?;
-    println!("Found {} local folders", local_projects.len());
-
-    // Fetch Linear projects
     let client = LinearClient::new()?;
     let linear_projects = fetch_linear_projects(&client).await?;
-    println!("Found {} Linear projects", linear_projects.len());
-    println!();
+
+    if output.is_table() {
+        println!("{}", "Sync Status".bold());
+        println!("{}", "─".repeat(60));
+        println!("Scanning: {}", dir.cyan());
+        println!();
+        println!("Found {} local folders", local_projects.len());
+        println!("Found {} Linear projects", linear_projects.len());
+        println!();
+    }
 
     // Compare
     let statuses = compare_projects(local_projects, linear_projects);
 
+    if !output.is_table() {
+        let records: Vec<Value> = statuses
+            .iter()
+            .filter(|s| !missing_only || matches!(s, SyncStatus::LocalOnly(_)))
+            .map(SyncStatus::to_json)
+            .collect();
+        return print_value(&records, output);
+    }
+
     // Count stats
     let synced_count = statuses
         .iter()
@@ -351,6 +477,7 @@ async fn push_command(
     team: String,
     only: Option<String>,
     dry_run: bool,
+    output: OutputFormat,
 ) -> Result<()> {
     let dir = directory.unwrap_or_else(get_default_code_dir);
 
@@ -358,22 +485,24 @@ async fn push_command(
     let client = LinearClient::new()?;
 
     // Resolve team key/name to UUID
-    let team_id = resolve_team_id(&client, &team).await?;
+    let team_id = Resolver::new(&client).team_id(&team).await?;
 
-    if dry_run {
-        println!(
-            "{}",
-            "[DRY RUN] No projects will be created".yellow().bold()
-        );
+    if output.is_table() {
+        if dry_run {
+            println!(
+                "{}",
+                "[DRY RUN] No projects will be created".yellow().bold()
+            );
+            println!();
+        }
+
+        println!("{}", "Push to Linear".bold());
+        println!("{}", "─".repeat(60));
+        println!("Source: {}", dir.cyan());
+        println!("Team: {}", team.cyan());
         println!();
     }
 
-    println!("{}", "Push to Linear".bold());
-    println!("{}", "─".repeat(60));
-    println!("Source: {}", dir.cyan());
-    println!("Team: {}", team.cyan());
-    println!();
-
     // Scan local projects
     let local_projects = scan_local_projects(&dir)?;
 
@@ -402,6 +531,29 @@ async fn push_command(
         to_create.retain(|p| only_names.contains(&p.name.to_lowercase()));
     }
 
+    if !output.is_table() {
+        let mut records = Vec::new();
+        for project in to_create {
+            let mut record = json!({ "name": project.name, "path": project.path });
+            if dry_run {
+                record["status"] = json!("would_create");
+            } else {
+                match create_linear_project(&client, &project.name, &team_id, &project.path).await {
+                    Ok(url) => {
+                        record["status"] = json!("created");
+                        record["url"] = json!(url);
+                    }
+                    Err(e) => {
+                        record["status"] = json!("failed");
+                        record["error"] = json!(format!("{:#}", e));
+                    }
+                }
+            }
+            records.push(record);
+        }
+        return print_value(&records, output);
+    }
+
     if to_create.is_empty() {
         println!(
             "{} All local projects already exist in Linear",
@@ -1,11 +1,15 @@
 use anyhow::Result;
+use chrono::SecondsFormat;
 use clap::Subcommand;
 use colored::Colorize;
 use serde_json::{json, Value};
 use tabled::{Table, Tabled};
 
-use crate::api::LinearClient;
+use crate::api::{LinearClient, PageOptions};
 use crate::cache::{Cache, CacheType};
+use crate::fields;
+use crate::models::{self, Team};
+use crate::output::print_value;
 use crate::OutputFormat;
 
 #[derive(Subcommand)]
@@ -38,43 +42,48 @@ pub async fn handle(cmd: TeamCommands, output: OutputFormat) -> Result<()> {
 }
 
 async fn list_teams(output: OutputFormat) -> Result<()> {
-    let cache = Cache::new()?;
+    let client = LinearClient::new()?;
+    let cache = Cache::open(&client).await.ok();
 
     // Try to get teams from cache first
-    let teams_data: Value = if let Some(cached) = cache.get(CacheType::Teams) {
-        cached
-    } else {
-        // Fetch from API
-        let client = LinearClient::new()?;
-
-        let query = r#"
-            query {
-                teams(first: 100) {
+    let teams_data: Value =
+        if let Some(cached) = cache.as_ref().and_then(|c| c.get(CacheType::Teams)) {
+            cached
+        } else {
+            // Fetch from API
+
+            let query = r#"
+            query($first: Int, $after: String) {
+                teams(first: $first, after: $after) {
                     nodes {
                         id
                         name
                         key
                     }
+                    pageInfo { hasNextPage endCursor }
                 }
             }
         "#;
 
-        let result = client.query(query, None).await?;
-        let data = result["data"]["teams"]["nodes"].clone();
+            let data = json!(
+                client
+                    .fetch_all(query, None, &["teams"], PageOptions::all())
+                    .await?
+            );
 
-        // Cache the result
-        let _ = cache.set(CacheType::Teams, data.clone());
-        data
-    };
+            // Cache the result
+            if let Some(cache) = &cache {
+                let _ = cache.set(CacheType::Teams, data.clone());
+            }
+            data
+        };
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!("{}", serde_json::to_string_pretty(&teams_data)?);
-        return Ok(());
+    if !output.is_table() {
+        return print_value(&teams_data, output);
     }
 
-    let empty = vec![];
-    let teams = teams_data.as_array().unwrap_or(&empty);
+    let teams: Vec<Team> = models::from_value(&teams_data)?;
 
     if teams.is_empty() {
         println!("No teams found.");
@@ -84,9 +93,9 @@ async fn list_teams(output: OutputFormat) -> Result<()> {
     let rows: Vec<TeamRow> = teams
         .iter()
         .map(|t| TeamRow {
-            name: t["name"].as_str().unwrap_or("").to_string(),
-            key: t["key"].as_str().unwrap_or("").to_string(),
-            id: t["id"].as_str().unwrap_or("").to_string(),
+            name: t.name.clone(),
+            key: t.key.clone(),
+            id: t.id.clone(),
         })
         .collect();
 
@@ -118,7 +127,9 @@ async fn get_team(id: &str, output: OutputFormat) -> Result<()> {
         }
     "#;
 
-    let result = client.query(query, Some(json!({ "id": id }))).await?;
+    let result = client
+        .query(&fields::narrow(query, &["team"]), Some(json!({ "id": id })))
+        .await?;
     let team = &result["data"]["team"];
 
     if team.is_null() {
@@ -126,48 +137,52 @@ async fn get_team(id: &str, output: OutputFormat) -> Result<()> {
     }
 
     // Handle JSON output
-    if matches!(output, OutputFormat::Json) {
-        println!("{}", serde_json::to_string_pretty(team)?);
-        return Ok(());
+    if !output.is_table() {
+        return print_value(team, output);
     }
 
-    println!("{}", team["name"].as_str().unwrap_or("").bold());
+    let team: Team = models::from_value(team)?;
+    println!("{}", team.name.bold());
     println!("{}", "-".repeat(40));
 
-    println!("Key: {}", team["key"].as_str().unwrap_or("-"));
+    println!("Key: {}", team.key);
 
-    if let Some(desc) = team["description"].as_str() {
-        if !desc.is_empty() {
-            println!("Description: {}", desc);
-        }
+    if let Some(desc) = team.description.as_deref().filter(|d| !d.is_empty()) {
+        println!("Description: {}", desc);
     }
 
-    println!("Private: {}", team["private"].as_bool().unwrap_or(false));
+    println!("Private: {}", team.private.unwrap_or(false));
 
-    if let Some(timezone) = team["timezone"].as_str() {
+    if let Some(timezone) = &team.timezone {
         println!("Timezone: {}", timezone);
     }
 
-    if let Some(issue_count) = team["issueCount"].as_i64() {
+    if let Some(issue_count) = team.issue_count {
         println!("Issue Count: {}", issue_count);
     }
 
-    if let Some(color) = team["color"].as_str() {
+    if let Some(color) = &team.color {
         println!("Color: {}", color);
     }
 
-    if let Some(icon) = team["icon"].as_str() {
+    if let Some(icon) = &team.icon {
         println!("Icon: {}", icon);
     }
 
-    println!("ID: {}", team["id"].as_str().unwrap_or("-"));
+    println!("ID: {}", team.id);
 
-    if let Some(created_at) = team["createdAt"].as_str() {
-        println!("Created: {}", created_at);
+    if let Some(created_at) = team.created_at {
+        println!(
+            "Created: {}",
+            created_at.to_rfc3339_opts(SecondsFormat::Millis, true)
+        );
     }
 
-    if let Some(updated_at) = team["updatedAt"].as_str() {
-        println!("Updated: {}", updated_at);
+    if let Some(updated_at) = team.updated_at {
+        println!(
+            "Updated: {}",
+            updated_at.to_rfc3339_opts(SecondsFormat::Millis, true)
+        );
     }
 
     Ok(())
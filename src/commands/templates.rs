@@ -8,6 +8,9 @@ use std::fs;
 use std::path::PathBuf;
 use tabled::{Table, Tabled};
 
+use crate::output::print_value;
+use crate::OutputFormat;
+
 /// Issue template structure for creating issues with predefined values
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IssueTemplate {
@@ -109,11 +112,11 @@ pub fn get_template(name: &str) -> Result<Option<IssueTemplate>> {
     Ok(store.templates.get(name).cloned())
 }
 
-pub async fn handle(cmd: TemplateCommands) -> Result<()> {
+pub async fn handle(cmd: TemplateCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        TemplateCommands::List => list_templates(),
+        TemplateCommands::List => list_templates(output),
         TemplateCommands::Create { name } => create_template(&name),
-        TemplateCommands::Show { name } => show_template(&name),
+        TemplateCommands::Show { name } => show_template(&name, output),
         TemplateCommands::Delete { name, force } => delete_template(&name, force),
     }
 }
@@ -130,9 +133,15 @@ fn priority_to_string(priority: Option<i32>) -> String {
     }
 }
 
-fn list_templates() -> Result<()> {
+fn list_templates(output: OutputFormat) -> Result<()> {
     let store = load_templates()?;
 
+    if !output.is_table() {
+        let mut templates: Vec<&IssueTemplate> = store.templates.values().collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        return print_value(&templates, output);
+    }
+
     if store.templates.is_empty() {
         println!("No templates found.");
         println!("\nCreate one with: linear-cli templates create <name>");
@@ -251,7 +260,7 @@ fn create_template(name: &str) -> Result<()> {
     Ok(())
 }
 
-fn show_template(name: &str) -> Result<()> {
+fn show_template(name: &str, output: OutputFormat) -> Result<()> {
     let store = load_templates()?;
 
     let template = store
@@ -259,6 +268,10 @@ fn show_template(name: &str) -> Result<()> {
         .get(name)
         .ok_or_else(|| anyhow::anyhow!("Template not found"))?;
 
+    if !output.is_table() {
+        return print_value(template, output);
+    }
+
     println!("{} {}", "Template:".bold(), template.name.cyan().bold());
     println!("{}", "-".repeat(40));
 
@@ -1,38 +1,1043 @@
-use anyhow::Result;
-use clap::Subcommand;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use tabled::{Table, Tabled};
+
+use crate::api::LinearClient;
+use crate::config;
+use crate::models::{self, Issue};
+use crate::output::print_value;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum TimeCommands {
     /// Log time spent on an issue
+    #[command(after_help = r#"EXAMPLES:
+    linear time log LIN-123 2h30m              # Log 2.5 hours today
+    linear tm log LIN-123 45m -m "Code review" # With a note
+    linear tm log LIN-123 1.5h --date 2024-03-01
+    linear tm log LIN-123 2h --comment         # Also post a comment on the issue"#)]
     Log {
         /// Issue ID
         issue: String,
-        /// Time spent (e.g., "2h", "30m")
+        /// Time spent (e.g., "2h", "30m", "2h30m", "1.5h", "1:45")
         duration: String,
+        /// Day the work was done (YYYY-MM-DD, defaults to today)
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        /// Note describing the work
+        #[arg(short = 'm', long)]
+        note: Option<String>,
+        /// Mirror the entry as a comment on the issue
+        #[arg(short, long)]
+        comment: bool,
     },
     /// List time entries
+    #[command(alias = "ls")]
+    #[command(after_help = r#"EXAMPLES:
+    linear time list                           # Totals per issue
+    linear tm list --by day                    # Totals per day
+    linear tm list --by cycle                  # Totals per cycle
+    linear tm list --issue LIN-123 --entries   # Individual entries for an issue"#)]
     List {
         /// Filter by issue ID
         #[arg(short, long)]
         issue: Option<String>,
+        /// Group totals by issue, day or cycle
+        #[arg(long, value_enum, default_value = "issue")]
+        by: TimeGrouping,
+        /// Only include entries on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Show individual entries instead of totals
+        #[arg(short, long)]
+        entries: bool,
+    },
+    /// Start a timer on an issue
+    #[command(after_help = r#"EXAMPLES:
+    linear time start LIN-123                  # Start timing work on an issue
+    linear tm start LIN-123 -m "Pairing"       # With a note for the entry"#)]
+    Start {
+        /// Issue ID
+        issue: String,
+        /// Note to attach to the logged entry
+        #[arg(short = 'm', long)]
+        note: Option<String>,
+    },
+    /// Stop the running timer and log the elapsed time
+    #[command(after_help = r#"EXAMPLES:
+    linear time stop                           # Log elapsed time to the ledger
+    linear tm stop --comment                   # Also post a comment on the issue
+    linear tm stop --discard                   # Throw the timer away"#)]
+    Stop {
+        /// Mirror the entry as a comment on the issue
+        #[arg(short, long)]
+        comment: bool,
+        /// Discard the timer without logging anything
+        #[arg(long, conflicts_with = "comment")]
+        discard: bool,
+    },
+    /// Show the running timer
+    Status,
+}
+
+/// How `time list` groups its totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimeGrouping {
+    Issue,
+    Day,
+    Cycle,
+}
+
+/// Cycle an issue belonged to when time was logged
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CycleRef {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl CycleRef {
+    fn label(&self) -> String {
+        match (&self.name, self.number) {
+            (Some(name), Some(n)) if !name.is_empty() => format!("Cycle {} ({})", n, name),
+            (_, Some(n)) => format!("Cycle {}", n),
+            (Some(name), None) => name.clone(),
+            (None, None) => self.id.clone(),
+        }
+    }
+}
+
+/// A single logged block of time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    /// Local entry ID
+    pub id: String,
+    /// Issue UUID
+    pub issue_id: String,
+    /// Issue title at the time of logging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Minutes spent
+    pub minutes: u64,
+    /// Day the work was done
+    pub date: NaiveDate,
+    /// When the entry was recorded
+    pub logged_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<CycleRef>,
+    /// ID of the mirrored comment, if one was posted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<String>,
+}
+
+/// Time ledger keyed by workspace name, then issue identifier
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimeLedger {
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, BTreeMap<String, Vec<TimeEntry>>>,
+}
+
+impl TimeLedger {
+    /// Record an entry under a workspace and issue identifier
+    pub fn add(&mut self, workspace: &str, identifier: &str, entry: TimeEntry) {
+        self.workspaces
+            .entry(workspace.to_string())
+            .or_default()
+            .entry(identifier.to_string())
+            .or_default()
+            .push(entry);
+    }
+
+    /// The entry `id` recorded under a workspace and issue identifier
+    fn entry_mut(&mut self, workspace: &str, identifier: &str, id: &str) -> Option<&mut TimeEntry> {
+        self.workspaces
+            .get_mut(workspace)?
+            .get_mut(identifier)?
+            .iter_mut()
+            .find(|e| e.id == id)
+    }
+
+    /// Take back an entry recorded under a workspace and issue identifier
+    fn remove(&mut self, workspace: &str, identifier: &str, id: &str) {
+        let Some(issues) = self.workspaces.get_mut(workspace) else {
+            return;
+        };
+        if let Some(entries) = issues.get_mut(identifier) {
+            entries.retain(|e| e.id != id);
+            if entries.is_empty() {
+                issues.remove(identifier);
+            }
+        }
+    }
+
+    /// All entries for a workspace as (identifier, entry) pairs
+    pub fn entries(&self, workspace: &str) -> Vec<(&str, &TimeEntry)> {
+        self.workspaces
+            .get(workspace)
+            .map(|issues| {
+                issues
+                    .iter()
+                    .flat_map(|(identifier, entries)| {
+                        entries.iter().map(move |e| (identifier.as_str(), e))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Tabled)]
+struct TotalRow {
+    #[tabled(rename = "Group")]
+    group: String,
+    #[tabled(rename = "Entries")]
+    entries: usize,
+    #[tabled(rename = "Total")]
+    total: String,
+}
+
+#[derive(Tabled)]
+struct EntryRow {
+    #[tabled(rename = "Date")]
+    date: String,
+    #[tabled(rename = "Issue")]
+    issue: String,
+    #[tabled(rename = "Duration")]
+    duration: String,
+    #[tabled(rename = "Note")]
+    note: String,
+    #[tabled(rename = "ID")]
+    id: String,
+}
+
+/// A running timer, persisted so it survives process exits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTimer {
+    /// Workspace the time will be filed under
+    pub workspace: String,
+    pub issue: IssueRef,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl ActiveTimer {
+    /// Whole minutes elapsed, rounded to the nearest minute
+    pub fn elapsed_minutes(&self, now: DateTime<Utc>) -> u64 {
+        let seconds = (now - self.started_at).num_seconds().max(0) as u64;
+        (seconds + 30) / 60
+    }
+}
+
+fn ledger_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("linear-cli");
+
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir.join("time_ledger.json"))
+}
+
+pub fn load_ledger() -> Result<TimeLedger> {
+    let path = ledger_path()?;
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        let ledger: TimeLedger = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse time ledger at {}", path.display()))?;
+        Ok(ledger)
+    } else {
+        Ok(TimeLedger::default())
+    }
+}
+
+pub fn save_ledger(ledger: &TimeLedger) -> Result<()> {
+    let path = ledger_path()?;
+    let content = serde_json::to_string_pretty(ledger)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn timer_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("linear-cli");
+
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir.join("timer.json"))
+}
+
+pub fn load_timer() -> Result<Option<ActiveTimer>> {
+    let path = timer_path()?;
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        let timer: ActiveTimer = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse timer at {}", path.display()))?;
+        Ok(Some(timer))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Write a new timer. Creating the file is what claims the single timer slot,
+/// so of two concurrent starts only one succeeds.
+fn save_timer(timer: &ActiveTimer) -> Result<()> {
+    let path = timer_path()?;
+    let content = serde_json::to_string_pretty(timer)?;
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            return Err(match load_timer()? {
+                Some(running) => already_running(&running),
+                None => anyhow::anyhow!("A timer is already running"),
+            });
+        }
+        Err(e) => return Err(e.into()),
+    };
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn already_running(running: &ActiveTimer) -> anyhow::Error {
+    anyhow::anyhow!(
+        "A timer is already running on {} (started {} ago). Stop it first with: linear time stop",
+        running.issue.identifier,
+        format_duration(running.elapsed_minutes(Utc::now()))
+    )
+}
+
+fn clear_timer() -> Result<()> {
+    let path = timer_path()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Parse a duration such as "2h30m", "1.5h", "45m", "1h 15m", "1:45" or "90" into minutes.
+pub fn parse_duration(input: &str) -> Result<u64> {
+    let s: String = input
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    if s.is_empty() {
+        anyhow::bail!("Duration cannot be empty");
+    }
+
+    let minutes = if let Some((hours, mins)) = s.split_once(':') {
+        // "H:MM" clock notation
+        let hours: u64 = hours
+            .parse()
+            .with_context(|| format!("Invalid duration: '{}'", input))?;
+        let mins: u64 = mins
+            .parse()
+            .with_context(|| format!("Invalid duration: '{}'", input))?;
+        if mins >= 60 {
+            anyhow::bail!("Invalid duration: '{}' (minutes must be below 60)", input);
+        }
+        hours * 60 + mins
+    } else if s.chars().all(|c| c.is_ascii_digit()) {
+        // Bare number means minutes
+        s.parse()?
+    } else {
+        let mut total = 0.0;
+        let mut chars = s.chars().peekable();
+        let mut saw_hours = false;
+
+        while chars.peek().is_some() {
+            let number: String =
+                std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit() || *c == '.')).collect();
+            let unit: String =
+                std::iter::from_fn(|| chars.next_if(|c| c.is_alphabetic())).collect();
+
+            if number.is_empty() {
+                anyhow::bail!("Invalid duration: '{}'", input);
+            }
+            let value: f64 = number
+                .parse()
+                .with_context(|| format!("Invalid duration: '{}'", input))?;
+
+            let factor = match unit.as_str() {
+                "h" | "hr" | "hrs" | "hour" | "hours" => {
+                    saw_hours = true;
+                    60.0
+                }
+                "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+                // "2h30" - a trailing bare number after hours is minutes
+                "" if saw_hours => 1.0,
+                _ => anyhow::bail!(
+                    "Invalid duration: '{}'. Use hours and minutes, e.g. 2h30m, 1.5h, 45m",
+                    input
+                ),
+            };
+            total += value * factor;
+        }
+
+        total.round() as u64
+    };
+
+    if minutes == 0 {
+        anyhow::bail!("Duration must be greater than zero");
+    }
+
+    Ok(minutes)
+}
+
+/// Format minutes as "2h 30m"
+pub fn format_duration(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Generate a reasonably unique local ID for ledger entries
+pub fn new_entry_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("{:x}", nanos)
+}
+
+/// What `group_totals` groups by, in the order the groups are listed
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum GroupKey {
+    Text(String),
+    /// Cycles by team, then number; the ID keeps apart cycles that share both
+    Cycle {
+        team: String,
+        number: Option<i64>,
+        id: String,
     },
+    NoCycle,
 }
 
-pub async fn handle(cmd: TimeCommands) -> Result<()> {
+/// Totals grouped by issue, day or cycle, sorted by group. Cycles are told
+/// apart by ID and labelled with the team key, e.g. "ENG Cycle 4".
+fn group_totals(entries: &[(&str, &TimeEntry)], by: TimeGrouping) -> Vec<(String, usize, u64)> {
+    let mut totals: BTreeMap<GroupKey, (String, usize, u64)> = BTreeMap::new();
+
+    for (identifier, entry) in entries {
+        let (key, label) = match (by, &entry.cycle) {
+            (TimeGrouping::Issue, _) => (
+                GroupKey::Text(identifier.to_string()),
+                identifier.to_string(),
+            ),
+            (TimeGrouping::Day, _) => (
+                GroupKey::Text(entry.date.to_string()),
+                entry.date.to_string(),
+            ),
+            (TimeGrouping::Cycle, Some(cycle)) => {
+                // The issue's identifier starts with its team's key
+                let team = identifier.rsplit_once('-').map_or("", |(key, _)| key);
+                let label = if team.is_empty() {
+                    cycle.label()
+                } else {
+                    format!("{} {}", team, cycle.label())
+                };
+                let key = GroupKey::Cycle {
+                    team: team.to_string(),
+                    number: cycle.number,
+                    id: cycle.id.clone(),
+                };
+                (key, label)
+            }
+            (TimeGrouping::Cycle, None) => (GroupKey::NoCycle, "No cycle".to_string()),
+        };
+        let total = totals.entry(key).or_insert_with(|| (label, 0, 0));
+        total.1 += 1;
+        total.2 += entry.minutes;
+    }
+
+    totals.into_values().collect()
+}
+
+pub async fn handle(cmd: TimeCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        TimeCommands::Log { issue, duration } => {
-            println!("Time tracking not yet implemented.");
-            println!("Would log {} on issue {}", duration, issue);
-            Ok(())
-        }
-        TimeCommands::List { issue } => {
-            println!("Time tracking not yet implemented.");
-            if let Some(id) = issue {
-                println!("Would list time entries for issue {}", id);
+        TimeCommands::Log {
+            issue,
+            duration,
+            date,
+            note,
+            comment,
+        } => log_time(&issue, &duration, date, note, comment, output).await,
+        TimeCommands::List {
+            issue,
+            by,
+            since,
+            entries,
+        } => list_time(issue, by, since, entries, output),
+        TimeCommands::Start { issue, note } => {
+            let client = LinearClient::new()?;
+            start_timer(&client, &issue, note, output).await
+        }
+        TimeCommands::Stop { comment, discard } => {
+            if discard {
+                return discard_timer(output);
+            }
+            let client = if comment {
+                Some(LinearClient::new()?)
             } else {
-                println!("Would list all time entries");
+                None
+            };
+            stop_timer(client.as_ref(), None, output).await.map(|_| ())
+        }
+        TimeCommands::Status => timer_status(output),
+    }
+}
+
+async fn log_time(
+    issue: &str,
+    duration: &str,
+    date: Option<NaiveDate>,
+    note: Option<String>,
+    mirror_comment: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let minutes = parse_duration(duration)?;
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+
+    let client = LinearClient::new()?;
+    let issue = fetch_issue(&client, issue).await?;
+    let workspace = config::current_workspace_name()?;
+    let entry = issue.entry(minutes, date, note);
+    record_entry(
+        mirror_comment.then_some(&client),
+        &workspace,
+        &issue.identifier,
+        entry,
+        output,
+    )
+    .await
+}
+
+/// The parts of an issue a time entry records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueRef {
+    pub id: String,
+    pub identifier: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<CycleRef>,
+}
+
+impl IssueRef {
+    /// Build a ledger entry for this issue
+    pub fn entry(&self, minutes: u64, date: NaiveDate, note: Option<String>) -> TimeEntry {
+        TimeEntry {
+            id: new_entry_id(),
+            issue_id: self.id.clone(),
+            title: self.title.clone(),
+            minutes,
+            date,
+            logged_at: Utc::now(),
+            note,
+            cycle: self.cycle.clone(),
+            comment_id: None,
+        }
+    }
+}
+
+/// Look up an issue's canonical identifier, title and current cycle
+pub async fn fetch_issue(client: &LinearClient, issue: &str) -> Result<IssueRef> {
+    let query = r#"
+        query($id: String!) {
+            issue(id: $id) {
+                id
+                identifier
+                title
+                cycle { id number name }
+            }
+        }
+    "#;
+
+    let result = client.query(query, Some(json!({ "id": issue }))).await?;
+    let data = &result["data"]["issue"];
+
+    if data.is_null() {
+        anyhow::bail!("Issue not found: {}", issue);
+    }
+
+    let issue: Issue = models::from_value(data)?;
+    Ok(IssueRef {
+        id: issue.id,
+        identifier: issue.identifier,
+        title: Some(issue.title),
+        cycle: issue.cycle.map(|c| CycleRef {
+            id: c.id,
+            number: Some(c.number),
+            name: c.name,
+        }),
+    })
+}
+
+/// Persist an entry to the ledger, and mirror it as an issue comment with
+/// `mirror` when given. The entry is saved before the comment is posted, and
+/// taken back out if posting fails, so the two never disagree.
+pub async fn record_entry(
+    mirror: Option<&LinearClient>,
+    workspace: &str,
+    identifier: &str,
+    mut entry: TimeEntry,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut ledger = load_ledger()?;
+    ledger.add(workspace, identifier, entry.clone());
+    save_ledger(&ledger)?;
+
+    if let Some(client) = mirror {
+        match post_time_comment(client, &entry).await {
+            Ok(comment_id) => {
+                entry.comment_id = Some(comment_id.clone());
+                if let Some(saved) = ledger.entry_mut(workspace, identifier, &entry.id) {
+                    saved.comment_id = Some(comment_id);
+                }
+                save_ledger(&ledger)?;
+            }
+            Err(err) => {
+                ledger.remove(workspace, identifier, &entry.id);
+                save_ledger(&ledger)?;
+                return Err(err.context("Time not logged: the comment could not be posted"));
+            }
+        }
+    }
+
+    if !output.is_table() {
+        let mut value = serde_json::to_value(&entry)?;
+        value["identifier"] = json!(identifier);
+        return print_value(&value, output);
+    }
+
+    println!(
+        "{} Logged {} on {} ({})",
+        "+".green(),
+        format_duration(entry.minutes).bold(),
+        identifier.cyan(),
+        entry.date
+    );
+    if entry.comment_id.is_some() {
+        println!("  Mirrored as a comment on the issue");
+    }
+
+    Ok(())
+}
+
+/// Start a timer on an issue. Only one timer may run at a time.
+pub async fn start_timer(
+    client: &LinearClient,
+    issue: &str,
+    note: Option<String>,
+    output: OutputFormat,
+) -> Result<()> {
+    // Fail fast before the lookup; `save_timer` settles a race
+    if let Some(running) = load_timer()? {
+        return Err(already_running(&running));
+    }
+
+    let issue = fetch_issue(client, issue).await?;
+    let timer = ActiveTimer {
+        workspace: config::current_workspace_name()?,
+        issue,
+        started_at: Utc::now(),
+        note,
+    };
+    save_timer(&timer)?;
+
+    if !output.is_table() {
+        return print_value(&timer, output);
+    }
+
+    println!(
+        "{} Timer started on {}",
+        "+".green(),
+        timer.issue.identifier.cyan()
+    );
+
+    Ok(())
+}
+
+/// Stop the running timer and record the elapsed time, mirrored as a comment
+/// with `mirror` when given.
+///
+/// When `issue` is given, the timer is only stopped if it is running on that
+/// issue. Returns whether a timer was stopped.
+pub async fn stop_timer(
+    mirror: Option<&LinearClient>,
+    issue: Option<&str>,
+    output: OutputFormat,
+) -> Result<bool> {
+    let timer = match load_timer()? {
+        Some(timer) => timer,
+        None if issue.is_some() => return Ok(false),
+        None => anyhow::bail!("No timer is running. Start one with: linear time start <issue>"),
+    };
+
+    if let Some(issue) = issue {
+        if !timer.issue.identifier.eq_ignore_ascii_case(issue) && timer.issue.id != issue {
+            return Ok(false);
+        }
+    }
+
+    let minutes = timer.elapsed_minutes(Utc::now());
+    if minutes == 0 {
+        clear_timer()?;
+        if output.is_table() {
+            println!(
+                "Timer on {} ran for less than a minute; nothing logged.",
+                timer.issue.identifier
+            );
+        }
+        return Ok(true);
+    }
+
+    let date = timer.started_at.with_timezone(&Local).date_naive();
+    let entry = timer.issue.entry(minutes, date, timer.note.clone());
+    record_entry(
+        mirror,
+        &timer.workspace,
+        &timer.issue.identifier,
+        entry,
+        output,
+    )
+    .await?;
+    clear_timer()?;
+
+    Ok(true)
+}
+
+fn discard_timer(output: OutputFormat) -> Result<()> {
+    let timer = load_timer()?.context("No timer is running")?;
+    clear_timer()?;
+
+    if !output.is_table() {
+        return print_value(&timer, output);
+    }
+
+    println!(
+        "Discarded timer on {} ({})",
+        timer.issue.identifier,
+        format_duration(timer.elapsed_minutes(Utc::now()))
+    );
+
+    Ok(())
+}
+
+fn timer_status(output: OutputFormat) -> Result<()> {
+    let timer = load_timer()?;
+
+    if !output.is_table() {
+        let value = match &timer {
+            Some(timer) => {
+                let mut value = serde_json::to_value(timer)?;
+                value["elapsedMinutes"] = json!(timer.elapsed_minutes(Utc::now()));
+                value
+            }
+            None => serde_json::Value::Null,
+        };
+        return print_value(&value, output);
+    }
+
+    match timer {
+        Some(timer) => {
+            println!(
+                "Timer running on {} {}",
+                timer.issue.identifier.cyan(),
+                timer.issue.title.as_deref().unwrap_or("")
+            );
+            println!(
+                "  Started: {}",
+                timer
+                    .started_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+            );
+            println!(
+                "  Elapsed: {}",
+                format_duration(timer.elapsed_minutes(Utc::now())).bold()
+            );
+        }
+        None => println!("No timer is running."),
+    }
+
+    Ok(())
+}
+
+/// Post a comment recording a time entry. The trailing HTML comment carries a
+/// machine-readable copy so other tools can pick up the entry.
+async fn post_time_comment(client: &LinearClient, entry: &TimeEntry) -> Result<String> {
+    let mut body = format!(
+        "⏱ Logged **{}** on {}",
+        format_duration(entry.minutes),
+        entry.date
+    );
+    if let Some(note) = &entry.note {
+        body.push_str(&format!(" — {}", note));
+    }
+    let payload = json!({
+        "entry": entry.id,
+        "minutes": entry.minutes,
+        "date": entry.date,
+    });
+    body.push_str(&format!("\n\n<!-- linear-cli:time {} -->", payload));
+
+    let mutation = r#"
+        mutation($input: CommentCreateInput!) {
+            commentCreate(input: $input) {
+                success
+                comment { id }
             }
-            Ok(())
         }
+    "#;
+
+    let input = json!({ "issueId": entry.issue_id, "body": body });
+    let result = client
+        .mutate(mutation, Some(json!({ "input": input })))
+        .await?;
+
+    if result["data"]["commentCreate"]["success"].as_bool() != Some(true) {
+        anyhow::bail!("Failed to post time comment");
+    }
+
+    Ok(result["data"]["commentCreate"]["comment"]["id"]
+        .as_str()
+        .unwrap_or("")
+        .to_string())
+}
+
+fn list_time(
+    issue: Option<String>,
+    by: TimeGrouping,
+    since: Option<NaiveDate>,
+    show_entries: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let workspace = config::current_workspace_name()?;
+    let ledger = load_ledger()?;
+
+    let mut entries: Vec<(&str, &TimeEntry)> = ledger
+        .entries(&workspace)
+        .into_iter()
+        .filter(|(identifier, entry)| match &issue {
+            Some(i) => identifier.eq_ignore_ascii_case(i) || entry.issue_id == *i,
+            None => true,
+        })
+        .filter(|(_, entry)| since.is_none_or(|s| entry.date >= s))
+        .collect();
+    entries.sort_by_key(|(_, e)| (e.date, e.logged_at));
+
+    let total_minutes: u64 = entries.iter().map(|(_, e)| e.minutes).sum();
+
+    if show_entries {
+        if !output.is_table() {
+            let values: Vec<_> = entries
+                .iter()
+                .map(|(identifier, entry)| {
+                    let mut value = serde_json::to_value(entry).unwrap_or_default();
+                    value["identifier"] = json!(identifier);
+                    value
+                })
+                .collect();
+            return print_value(&values, output);
+        }
+
+        if entries.is_empty() {
+            println!("No time entries found.");
+            return Ok(());
+        }
+
+        let rows: Vec<EntryRow> = entries
+            .iter()
+            .map(|(identifier, entry)| EntryRow {
+                date: entry.date.to_string(),
+                issue: identifier.to_string(),
+                duration: format_duration(entry.minutes),
+                note: entry.note.clone().unwrap_or_else(|| "-".to_string()),
+                id: entry.id.clone(),
+            })
+            .collect();
+
+        println!("{}", Table::new(rows));
+        println!(
+            "\n{} entries, {} total",
+            entries.len(),
+            format_duration(total_minutes)
+        );
+        return Ok(());
+    }
+
+    let totals = group_totals(&entries, by);
+
+    if !output.is_table() {
+        let values: Vec<_> = totals
+            .iter()
+            .map(|(group, count, minutes)| {
+                json!({ "group": group, "entries": count, "minutes": minutes })
+            })
+            .collect();
+        return print_value(&values, output);
+    }
+
+    if totals.is_empty() {
+        println!("No time entries found.");
+        return Ok(());
+    }
+
+    let heading = match by {
+        TimeGrouping::Issue => "Time by issue",
+        TimeGrouping::Day => "Time by day",
+        TimeGrouping::Cycle => "Time by cycle",
+    };
+    println!("{}", heading.bold());
+    println!("{}", "-".repeat(40));
+
+    let rows: Vec<TotalRow> = totals
+        .into_iter()
+        .map(|(group, entries, minutes)| TotalRow {
+            group,
+            entries,
+            total: format_duration(minutes),
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    println!("\nTotal: {}", format_duration(total_minutes).bold());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("2h").unwrap(), 120);
+        assert_eq!(parse_duration("30m").unwrap(), 30);
+        assert_eq!(parse_duration("2h30m").unwrap(), 150);
+        assert_eq!(parse_duration("2h 30m").unwrap(), 150);
+        assert_eq!(parse_duration("1.5h").unwrap(), 90);
+        assert_eq!(parse_duration("2 hours 15 mins").unwrap(), 135);
+    }
+
+    #[test]
+    fn test_parse_duration_shorthand() {
+        assert_eq!(parse_duration("90").unwrap(), 90);
+        assert_eq!(parse_duration("1:45").unwrap(), 105);
+        assert_eq!(parse_duration("2h30").unwrap(), 150);
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("1:75").is_err());
+        assert!(parse_duration("h").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(150), "2h 30m");
+    }
+
+    fn entry(minutes: u64, date: &str, cycle: Option<i64>) -> TimeEntry {
+        TimeEntry {
+            id: new_entry_id(),
+            issue_id: "uuid".to_string(),
+            title: None,
+            minutes,
+            date: date.parse().unwrap(),
+            logged_at: Utc::now(),
+            note: None,
+            cycle: cycle.map(|n| CycleRef {
+                id: format!("cycle-{}", n),
+                number: Some(n),
+                name: None,
+            }),
+            comment_id: None,
+        }
+    }
+
+    #[test]
+    fn test_timer_elapsed_minutes_rounds() {
+        let started_at = Utc::now();
+        let timer = ActiveTimer {
+            workspace: "default".to_string(),
+            issue: IssueRef {
+                id: "uuid".to_string(),
+                identifier: "ENG-1".to_string(),
+                title: None,
+                cycle: None,
+            },
+            started_at,
+            note: None,
+        };
+
+        let at = |secs| started_at + chrono::Duration::seconds(secs);
+        assert_eq!(timer.elapsed_minutes(at(20)), 0);
+        assert_eq!(timer.elapsed_minutes(at(90)), 2);
+        assert_eq!(timer.elapsed_minutes(at(3 * 3600 + 10)), 180);
+        assert_eq!(timer.elapsed_minutes(at(-5)), 0);
+    }
+
+    #[test]
+    fn test_group_totals() {
+        let a = entry(60, "2024-03-01", Some(4));
+        let b = entry(30, "2024-03-01", None);
+        let c = entry(45, "2024-03-02", Some(4));
+        let entries = vec![("ENG-1", &a), ("ENG-2", &b), ("ENG-1", &c)];
+
+        // Cycles sort by number, and another team's cycle 4 is its own group
+        let d = entry(15, "2024-03-03", Some(10));
+        let mut e = entry(20, "2024-03-03", Some(4));
+        e.cycle.as_mut().unwrap().id = "design-cycle-4".to_string();
+        let mixed = vec![("ENG-3", &d), ("ENG-1", &a), ("DES-7", &e), ("ENG-2", &b)];
+        assert_eq!(
+            group_totals(&mixed, TimeGrouping::Cycle),
+            vec![
+                ("DES Cycle 4".to_string(), 1, 20),
+                ("ENG Cycle 4".to_string(), 1, 60),
+                ("ENG Cycle 10".to_string(), 1, 15),
+                ("No cycle".to_string(), 1, 30)
+            ]
+        );
+
+        assert_eq!(
+            group_totals(&entries, TimeGrouping::Issue),
+            vec![("ENG-1".to_string(), 2, 105), ("ENG-2".to_string(), 1, 30)]
+        );
+        assert_eq!(
+            group_totals(&entries, TimeGrouping::Day),
+            vec![
+                ("2024-03-01".to_string(), 2, 90),
+                ("2024-03-02".to_string(), 1, 45)
+            ]
+        );
+        assert_eq!(
+            group_totals(&entries, TimeGrouping::Cycle),
+            vec![
+                ("ENG Cycle 4".to_string(), 2, 105),
+                ("No cycle".to_string(), 1, 30)
+            ]
+        );
     }
 }
@@ -1,20 +1,30 @@
 use anyhow::Result;
+use chrono::SecondsFormat;
 use clap::Subcommand;
 use colored::Colorize;
 use serde_json::{json, Value};
 use tabled::{Table, Tabled};
 
-use crate::api::{resolve_team_id, LinearClient};
+use crate::api::{LinearClient, PageOptions};
 use crate::cache::{Cache, CacheType};
+use crate::config;
+use crate::fields;
+use crate::models::{self, User};
+use crate::output::print_value;
+use crate::resolver::Resolver;
+use crate::OutputFormat;
 
 #[derive(Subcommand)]
 pub enum UserCommands {
     /// List all users in the workspace
     #[command(alias = "ls")]
     List {
-        /// Filter users by team name or ID
+        /// Filter users by team name or ID (default: the default team)
         #[arg(short, long)]
         team: Option<String>,
+        /// List users in every team, ignoring the default team
+        #[arg(long, conflicts_with = "team")]
+        all_teams: bool,
     },
     /// Show current user details
     Me,
@@ -30,72 +40,84 @@ struct UserRow {
     id: String,
 }
 
-pub async fn handle(cmd: UserCommands) -> Result<()> {
+pub async fn handle(cmd: UserCommands, output: OutputFormat) -> Result<()> {
     match cmd {
-        UserCommands::List { team } => list_users(team).await,
-        UserCommands::Me => get_me().await,
+        UserCommands::List { team, all_teams } => {
+            list_users(config::list_team(team, all_teams), output).await
+        }
+        UserCommands::Me => get_me(output).await,
     }
 }
 
-async fn list_users(team: Option<String>) -> Result<()> {
-    let cache = Cache::new()?;
+async fn list_users(team: Option<String>, output: OutputFormat) -> Result<()> {
+    let client = LinearClient::new()?;
 
     // Only use cache for full user list (no team filter)
-    let users: Vec<Value> = if team.is_none() {
-        // Try cache first
-        if let Some(cached) = cache.get(CacheType::Users) {
-            cached.as_array().cloned().unwrap_or_default()
-        } else {
-            // Fetch from API
-            let client = LinearClient::new()?;
-            let query = r#"
-                query {
-                    users(first: 100) {
+    let users: Vec<Value> = if let Some(team) = &team {
+        // Team-filtered users - always fetch from API (not cached)
+        let team_id = Resolver::new(&client).team_id(team).await?;
+
+        let query = r#"
+            query($teamId: String!, $first: Int, $after: String) {
+                team(id: $teamId) {
+                    members(first: $first, after: $after) {
                         nodes {
                             id
                             name
                             email
                         }
+                        pageInfo { hasNextPage endCursor }
                     }
                 }
-            "#;
-
-            let result = client.query(query, None).await?;
-            let data = result["data"]["users"]["nodes"].clone();
+            }
+        "#;
 
-            // Cache the result
-            let _ = cache.set(CacheType::Users, data.clone());
-            data.as_array().cloned().unwrap_or_default()
-        }
+        client
+            .fetch_all(
+                query,
+                Some(json!({ "teamId": team_id })),
+                &["team", "members"],
+                PageOptions::all(),
+            )
+            .await?
     } else {
-        // Team-filtered users - always fetch from API (not cached)
-        let client = LinearClient::new()?;
-        let team_id = resolve_team_id(&client, team.as_ref().unwrap()).await?;
-
-        let query = r#"
-            query($teamId: String!) {
-                team(id: $teamId) {
-                    members {
+        // Try cache first
+        let cache = Cache::open(&client).await.ok();
+        if let Some(cached) = cache.as_ref().and_then(|c| c.get(CacheType::Users)) {
+            cached.as_array().cloned().unwrap_or_default()
+        } else {
+            // Fetch from API
+            let query = r#"
+                query($first: Int, $after: String) {
+                    users(first: $first, after: $after) {
                         nodes {
                             id
                             name
                             email
                         }
+                        pageInfo { hasNextPage endCursor }
                     }
                 }
-            }
-        "#;
+            "#;
 
-        let result = client
-            .query(query, Some(json!({ "teamId": team_id })))
-            .await?;
+            let users = client
+                .fetch_all(query, None, &["users"], PageOptions::all())
+                .await?;
 
-        result["data"]["team"]["members"]["nodes"]
-            .as_array()
-            .cloned()
-            .unwrap_or_default()
+            // Cache the result
+            if let Some(cache) = &cache {
+                let _ = cache.set(CacheType::Users, json!(users));
+            }
+            users
+        }
     };
 
+    if !output.is_table() {
+        return print_value(&users, output);
+    }
+
+    let users: Vec<User> = models::from_nodes(&users)?;
+
     if users.is_empty() {
         println!("No users found.");
         return Ok(());
@@ -104,9 +126,9 @@ async fn list_users(team: Option<String>) -> Result<()> {
     let rows: Vec<UserRow> = users
         .iter()
         .map(|u| UserRow {
-            name: u["name"].as_str().unwrap_or("").to_string(),
-            email: u["email"].as_str().unwrap_or("").to_string(),
-            id: u["id"].as_str().unwrap_or("").to_string(),
+            name: u.name.clone(),
+            email: u.email.clone().unwrap_or_default(),
+            id: u.id.clone(),
         })
         .collect();
 
@@ -117,7 +139,7 @@ async fn list_users(team: Option<String>) -> Result<()> {
     Ok(())
 }
 
-async fn get_me() -> Result<()> {
+async fn get_me(output: OutputFormat) -> Result<()> {
     let client = LinearClient::new()?;
 
     let query = r#"
@@ -136,44 +158,42 @@ async fn get_me() -> Result<()> {
         }
     "#;
 
-    let result = client.query(query, None).await?;
+    let result = client
+        .query(&fields::narrow(query, &["viewer"]), None)
+        .await?;
     let user = &result["data"]["viewer"];
 
     if user.is_null() {
         anyhow::bail!("Could not fetch current user");
     }
 
-    println!("{}", user["name"].as_str().unwrap_or("").bold());
+    if !output.is_table() {
+        return print_value(user, output);
+    }
+
+    let user: User = models::from_value(user)?;
+    let yes_no = |b: Option<bool>| b.map(|b| if b { "Yes" } else { "No" }).unwrap_or("-");
+
+    println!("{}", user.name.bold());
     println!("{}", "-".repeat(40));
 
-    if let Some(display_name) = user["displayName"].as_str() {
-        if !display_name.is_empty() {
-            println!("Display Name: {}", display_name);
-        }
+    if let Some(display_name) = user.display_name.as_deref().filter(|d| !d.is_empty()) {
+        println!("Display Name: {}", display_name);
     }
 
-    println!("Email: {}", user["email"].as_str().unwrap_or("-"));
-    println!(
-        "Admin: {}",
-        user["admin"]
-            .as_bool()
-            .map(|b| if b { "Yes" } else { "No" })
-            .unwrap_or("-")
-    );
-    println!(
-        "Active: {}",
-        user["active"]
-            .as_bool()
-            .map(|b| if b { "Yes" } else { "No" })
-            .unwrap_or("-")
-    );
-
-    if let Some(created) = user["createdAt"].as_str() {
-        println!("Created: {}", created);
+    println!("Email: {}", user.email.as_deref().unwrap_or("-"));
+    println!("Admin: {}", yes_no(user.admin));
+    println!("Active: {}", yes_no(user.active));
+
+    if let Some(created) = user.created_at {
+        println!(
+            "Created: {}",
+            created.to_rfc3339_opts(SecondsFormat::Millis, true)
+        );
     }
 
-    println!("URL: {}", user["url"].as_str().unwrap_or("-"));
-    println!("ID: {}", user["id"].as_str().unwrap_or("-"));
+    println!("URL: {}", user.url.as_deref().unwrap_or("-"));
+    println!("ID: {}", user.id);
 
     Ok(())
 }
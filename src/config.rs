@@ -1,12 +1,248 @@
 use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::credentials::{self, CredentialStore};
+use crate::oauth::{OAuthLogin, Tokens};
+use crate::OutputFormat;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Workspace {
+    /// The key itself, only when it's kept in plaintext
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub api_key: String,
+    /// Where the key is kept; unset for keys written before credential stores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<CredentialStore>,
+    /// Command that prints the key, e.g. `pass show linear/work`; wins over the store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_command: Option<String>,
+    /// Override for the GraphQL endpoint (e.g. a local mock or recording proxy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Login from `linear auth login`; used instead of the API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuthLogin>,
+    /// Values used when a command's flag is omitted (`config set default.team ENG`)
+    #[serde(default, skip_serializing_if = "Defaults::is_empty")]
+    pub defaults: Defaults,
+}
+
+/// Per-workspace defaults. Each can be overridden for one shell with its
+/// environment variable; a flag on the command line beats both.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Defaults {
+    /// Team for commands that need one and for new issues
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Project for new issues and documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Assignee for new issues, e.g. "me"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Cycle for new issues and the board: current, next, previous, a number or none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<String>,
+    /// Output format when `--output` is omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Branch name pattern for `git checkout` and `issues start`, e.g.
+    /// `{team}/{number}-{title}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Labels for new issues created without `--label`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+/// Keys accepted by `config set default.<key>`, with the environment
+/// variable that overrides each
+pub const DEFAULT_KEYS: &[(&str, &str)] = &[
+    ("team", "LINEAR_TEAM"),
+    ("project", "LINEAR_PROJECT"),
+    ("assignee", "LINEAR_ASSIGNEE"),
+    ("cycle", "LINEAR_CYCLE"),
+    ("output", "LINEAR_OUTPUT"),
+    ("branch", "LINEAR_BRANCH_FORMAT"),
+    ("labels", "LINEAR_LABELS"),
+];
+
+/// Placeholders a branch pattern can use
+pub const BRANCH_PLACEHOLDERS: &[&str] = &["{identifier}", "{team}", "{number}", "{title}"];
+
+impl Defaults {
+    fn is_empty(&self) -> bool {
+        *self == Defaults::default()
+    }
+
+    fn set(&mut self, key: &str, value: Option<&str>) {
+        let value = value.map(|v| v.to_string());
+        match key {
+            "team" => self.team = value,
+            "project" => self.project = value,
+            "assignee" => self.assignee = value,
+            "cycle" => self.cycle = value,
+            "output" => self.output = value,
+            "branch" => self.branch = value,
+            "labels" => self.labels = value.as_deref().map(split_labels).unwrap_or_default(),
+            _ => {}
+        }
+    }
+
+    /// The value of `key` as it would be typed, labels comma-separated
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "team" => self.team.clone(),
+            "project" => self.project.clone(),
+            "assignee" => self.assignee.clone(),
+            "cycle" => self.cycle.clone(),
+            "output" => self.output.clone(),
+            "branch" => self.branch.clone(),
+            "labels" if !self.labels.is_empty() => Some(self.labels.join(",")),
+            _ => None,
+        }
+    }
+}
+
+/// Split a comma-separated label list, dropping empty entries
+pub fn split_labels(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Name of the repository config, looked for in the working directory and
+/// each of its parents
+pub const REPO_CONFIG_FILE: &str = ".linear.toml";
+
+/// Defaults from the nearest `.linear.toml`, which layer over the workspace's
+/// own defaults
+#[derive(Debug, Clone)]
+pub struct RepoConfig {
+    pub path: PathBuf,
+    pub defaults: Defaults,
+}
+
+static REPO_CONFIG: OnceLock<Option<RepoConfig>> = OnceLock::new();
+
+/// Find and read the nearest `.linear.toml`. Runs once at startup so a broken
+/// file is reported before any command does its work.
+pub fn load_repo_config() -> Result<()> {
+    let found = match std::env::current_dir()
+        .ok()
+        .and_then(|dir| find_repo_config(&dir))
+    {
+        Some(path) => Some(read_repo_config(path)?),
+        None => None,
+    };
+    let _ = REPO_CONFIG.set(found);
+    Ok(())
+}
+
+/// The `.linear.toml` in effect, if one was found
+pub fn repo_config() -> Option<&'static RepoConfig> {
+    REPO_CONFIG.get()?.as_ref()
+}
+
+fn find_repo_config(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(REPO_CONFIG_FILE))
+        .find(|p| p.is_file())
+}
+
+fn read_repo_config(path: PathBuf) -> Result<RepoConfig> {
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let table: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    for key in table.keys() {
+        if !DEFAULT_KEYS.iter().any(|(k, _)| k == key) {
+            let keys: Vec<&str> = DEFAULT_KEYS.iter().map(|(k, _)| *k).collect();
+            anyhow::bail!(
+                "Unknown key '{}' in {} (keys: {})",
+                key,
+                path.display(),
+                keys.join(", ")
+            );
+        }
+    }
+    let defaults: Defaults = table
+        .try_into()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    for (key, _) in DEFAULT_KEYS {
+        if let Some(value) = defaults.get(key) {
+            validate_default(key, &value).with_context(|| format!("In {}", path.display()))?;
+        }
+    }
+    Ok(RepoConfig { path, defaults })
+}
+
+/// What requests are authorized with
+#[derive(Debug, Clone)]
+pub enum Credential {
+    ApiKey(String),
+    OAuth {
+        workspace: String,
+        login: OAuthLogin,
+        tokens: Tokens,
+    },
+}
+
+impl Credential {
+    /// Value for the `Authorization` header
+    pub fn header(&self) -> String {
+        match self {
+            Credential::ApiKey(key) => key.clone(),
+            Credential::OAuth { tokens, .. } => format!("Bearer {}", tokens.access_token),
+        }
+    }
+}
+
+/// Account OAuth tokens are filed under in a credential store
+fn oauth_account(workspace: &str) -> String {
+    format!("{}/oauth", workspace)
+}
+
+impl Workspace {
+    /// The workspace's API key, from its command, store or the config
+    pub fn api_key(&self, name: &str) -> Result<String> {
+        if let Some(command) = &self.credential_command {
+            return credentials::run_command(command);
+        }
+        match self.credential_store {
+            Some(store @ (CredentialStore::Keyring | CredentialStore::EncryptedFile)) => {
+                store.load(name)
+            }
+            _ if self.api_key.is_empty() => anyhow::bail!(
+                "Workspace '{}' has no API key. Run: linear config set-key <key>",
+                name
+            ),
+            _ => Ok(self.api_key.clone()),
+        }
+    }
+
+    /// Where the key comes from, for `config show` and `workspace list`
+    pub fn describe_key(&self) -> String {
+        if self.oauth.is_some() {
+            return "OAuth login".to_string();
+        }
+        if let Some(command) = &self.credential_command {
+            return format!("from `{}`", command);
+        }
+        match self.credential_store {
+            Some(CredentialStore::Keyring) => "in OS keyring".to_string(),
+            Some(CredentialStore::EncryptedFile) => "in credentials.enc".to_string(),
+            _ => format!("{} (plaintext)", credentials::mask_key(&self.api_key)),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -14,11 +250,68 @@ pub struct Config {
     pub current: Option<String>,
     #[serde(default)]
     pub workspaces: HashMap<String, Workspace>,
+    /// Attempts per API request, including the first (retries transient failures)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// Where new API keys are kept (default: the OS keyring)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<CredentialStore>,
+    /// Set once keys from before credential stores existed have had their
+    /// one move into the preferred store; `set-credential-store` moves them later
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keys_migrated: bool,
     // Legacy field for backward compatibility
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
 }
 
+static WORKSPACE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Act on `name` instead of the current workspace for this invocation only
+/// (`--workspace`), leaving `current` in the config file alone
+pub fn set_workspace_override(name: &str) -> Result<()> {
+    let config = load_config()?;
+    if !config.workspaces.contains_key(name) {
+        let mut names: Vec<&str> = config.workspaces.keys().map(|k| k.as_str()).collect();
+        names.sort();
+        anyhow::bail!(
+            "Workspace '{}' not found. Workspaces: {}",
+            name,
+            if names.is_empty() {
+                "none (run: linear workspace add <name>)".to_string()
+            } else {
+                names.join(", ")
+            }
+        );
+    }
+    let _ = WORKSPACE_OVERRIDE.set(name.to_string());
+    Ok(())
+}
+
+/// A non-empty environment variable that stands in for the current
+/// workspace's settings. Ignored under `--workspace`, which names the
+/// workspace to act on explicitly.
+fn env_override(var: &str) -> Option<String> {
+    if WORKSPACE_OVERRIDE.get().is_some() {
+        return None;
+    }
+    std::env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+impl Config {
+    /// The workspace this invocation acts on: `--workspace`, else the current one
+    pub fn active(&self) -> Option<&String> {
+        WORKSPACE_OVERRIDE.get().or(self.current.as_ref())
+    }
+
+    /// Like `active`, falling back to "default" when nothing is configured
+    fn active_name(&self) -> String {
+        self.active()
+            .cloned()
+            .unwrap_or_else(|| "default".to_string())
+    }
+}
+
 fn config_path() -> Result<PathBuf> {
     let config_dir = dirs::config_dir()
         .context("Could not find config directory")?
@@ -41,6 +334,7 @@ pub fn load_config() -> Result<Config> {
                     "default".to_string(),
                     Workspace {
                         api_key: legacy_key,
+                        ..Workspace::default()
                     },
                 );
                 if config.current.is_none() {
@@ -51,31 +345,129 @@ pub fn load_config() -> Result<Config> {
             }
         }
 
+        if migrate_plaintext_keys(&mut config) {
+            save_config(&config)?;
+        } else if has_plaintext_keys(&config) {
+            restrict_permissions(&path)?;
+        }
+
         Ok(config)
     } else {
         Ok(Config::default())
     }
 }
 
+/// Save the config, readable only by the current user since it may hold keys
 pub fn save_config(config: &Config) -> Result<()> {
     let path = config_path()?;
     let content = toml::to_string_pretty(config)?;
-    fs::write(path, content)?;
+    credentials::write_private(&path, content.as_bytes())
+}
+
+fn has_plaintext_keys(config: &Config) -> bool {
+    config
+        .workspaces
+        .values()
+        .any(|w| !w.api_key.is_empty() || w.oauth.as_ref().is_some_and(|o| o.tokens.is_some()))
+}
+
+/// Tighten an existing config file that still holds plaintext keys to 0600
+fn restrict_permissions(path: &std::path::Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Move keys written before credential stores existed into the preferred
+/// store, once: the attempt is recorded in the config whether or not it
+/// worked. Keys stay in plaintext when the keyring isn't available, or the
+/// encrypted file's passphrase can't be had without asking. Returns whether
+/// the config changed.
+fn migrate_plaintext_keys(config: &mut Config) -> bool {
+    let in_plaintext = |w: &Workspace| !w.api_key.is_empty() && w.credential_store.is_none();
+    if config.keys_migrated || !config.workspaces.values().any(in_plaintext) {
+        return false;
+    }
+    let Ok(store) = CredentialStore::preferred(config.credential_store) else {
+        return false;
+    };
+    if store == CredentialStore::Plaintext
+        || (store == CredentialStore::EncryptedFile && credentials::passphrase_from_env().is_none())
+    {
+        return false;
+    }
+
+    config.keys_migrated = true;
+    for (name, workspace) in config.workspaces.iter_mut() {
+        if !in_plaintext(workspace) {
+            continue;
+        }
+        if store.save(name, &workspace.api_key).is_err() {
+            // The keyring is the same for every workspace; no point retrying
+            break;
+        }
+        workspace.api_key.clear();
+        workspace.credential_store = Some(store);
+    }
+    true
+}
+
+/// Put `secret` in the preferred store under `account`. Returns the store
+/// used: plaintext when the keyring isn't available, in which case the
+/// caller keeps the secret in the config.
+fn save_secret(config: &Config, account: &str, secret: &str) -> Result<CredentialStore> {
+    let store = CredentialStore::preferred(config.credential_store)?;
+    match store.save(account, secret) {
+        Ok(()) => Ok(store),
+        Err(err) if store == CredentialStore::Keyring => {
+            eprintln!(
+                "{} {:#}; keeping it in config.toml (readable only by you)",
+                "!".yellow(),
+                err
+            );
+            Ok(CredentialStore::Plaintext)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Put `key` in the preferred store for `name`, falling back to plaintext
+/// when the keyring isn't available
+fn store_key(config: &Config, name: &str, key: &str, workspace: &mut Workspace) -> Result<()> {
+    let previous = workspace.credential_store;
+    let store = save_secret(config, name, key)?;
+
+    // Don't leave the old copy behind in another store
+    if let Some(old) = previous.filter(|old| *old != store) {
+        let _ = old.delete(name);
+    }
+    workspace.api_key = if store == CredentialStore::Plaintext {
+        key.to_string()
+    } else {
+        String::new()
+    };
+    workspace.credential_store = Some(store);
     Ok(())
 }
 
 pub fn set_api_key(key: &str) -> Result<()> {
     let mut config = load_config()?;
-    let workspace_name = config
-        .current
-        .clone()
-        .unwrap_or_else(|| "default".to_string());
-    config.workspaces.insert(
-        workspace_name.clone(),
-        Workspace {
-            api_key: key.to_string(),
-        },
-    );
+    let workspace_name = config.active_name();
+    let mut workspace = config
+        .workspaces
+        .get(&workspace_name)
+        .cloned()
+        .unwrap_or_default();
+    store_key(&config, &workspace_name, key, &mut workspace)?;
+    config.workspaces.insert(workspace_name.clone(), workspace);
     if config.current.is_none() {
         config.current = Some("default".to_string());
     }
@@ -83,55 +475,435 @@ pub fn set_api_key(key: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn get_api_key() -> Result<String> {
-    // Check for LINEAR_API_KEY environment variable first
-    if let Ok(api_key) = std::env::var("LINEAR_API_KEY") {
-        if !api_key.is_empty() {
-            return Ok(api_key);
+/// Choose where new keys go and move every existing key and OAuth token there
+pub fn set_credential_store(store: CredentialStore) -> Result<()> {
+    let mut config = load_config()?;
+    config.credential_store = Some(store);
+
+    let names: Vec<String> = config.workspaces.keys().cloned().collect();
+    for name in names {
+        let mut workspace = config.workspaces[&name].clone();
+        let has_key = workspace.credential_store.is_some() || !workspace.api_key.is_empty();
+        if has_key && workspace.credential_command.is_none() {
+            let key = workspace.api_key(&name)?;
+            let previous = workspace.credential_store;
+            store.save(&name, &key)?;
+            if let Some(old) = previous.filter(|old| *old != store) {
+                old.delete(&name)?;
+            }
+            workspace.api_key = if store == CredentialStore::Plaintext {
+                key
+            } else {
+                String::new()
+            };
+            workspace.credential_store = Some(store);
+        }
+        if let Some(login) = workspace.oauth.as_mut() {
+            let account = oauth_account(&name);
+            let tokens = load_oauth_tokens(&name, login)?;
+            store.save(&account, &serde_json::to_string(&tokens)?)?;
+            if login.token_store != store {
+                login.token_store.delete(&account)?;
+            }
+            login.token_store = store;
+            login.tokens = (store == CredentialStore::Plaintext).then_some(tokens);
         }
+        config.workspaces.insert(name, workspace);
+    }
+
+    save_config(&config)?;
+    Ok(())
+}
+
+/// Set or clear the command that prints the current workspace's key
+pub fn set_credential_command(command: Option<&str>) -> Result<()> {
+    let mut config = load_config()?;
+    let current = config.active_name();
+    let workspace = config.workspaces.entry(current.clone()).or_default();
+    workspace.credential_command = command.map(|c| c.to_string());
+    if config.current.is_none() {
+        config.current = Some(current);
+    }
+    save_config(&config)?;
+    Ok(())
+}
+
+/// How to authorize requests: `LINEAR_API_KEY`, then the current
+/// workspace's OAuth login, then its API key. An explicit `--workspace`
+/// always uses that workspace's credentials.
+pub fn get_credential() -> Result<Credential> {
+    if let Some(api_key) = env_override("LINEAR_API_KEY") {
+        return Ok(Credential::ApiKey(api_key));
     }
 
     // Fall back to config file
     let config = load_config()?;
     let current = config
-        .current
-        .as_ref()
-        .context("No workspace selected. Run: linear workspace add <name>")?;
+        .active()
+        .context("No workspace selected. Run: linear auth login, or linear workspace add <name>")?;
     let workspace = config.workspaces.get(current).context(format!(
         "Workspace '{}' not found. Run: linear workspace add <name>",
         current
     ))?;
-    Ok(workspace.api_key.clone())
+    if let Some(login) = &workspace.oauth {
+        return Ok(Credential::OAuth {
+            workspace: current.clone(),
+            login: login.clone(),
+            tokens: load_oauth_tokens(current, login)?,
+        });
+    }
+    Ok(Credential::ApiKey(workspace.api_key(current)?))
 }
 
-pub fn show_config() -> Result<()> {
-    let config = load_config()?;
-    let path = config_path()?;
+fn load_oauth_tokens(name: &str, login: &OAuthLogin) -> Result<Tokens> {
+    if let Some(tokens) = &login.tokens {
+        return Ok(tokens.clone());
+    }
+    let json = login.token_store.load(&oauth_account(name))?;
+    serde_json::from_str(&json).context("Stored OAuth tokens are corrupt; run: linear auth login")
+}
 
-    println!("Config file: {}", path.display());
-    println!();
+/// Save an OAuth login for workspace `name`, creating the workspace (and
+/// making it current) if there isn't one yet
+pub fn save_oauth(name: &str, mut login: OAuthLogin, tokens: &Tokens) -> Result<()> {
+    let mut config = load_config()?;
+    let previous = config
+        .workspaces
+        .get(name)
+        .and_then(|w| w.oauth.as_ref())
+        .map(|o| o.token_store);
+
+    let store = save_secret(
+        &config,
+        &oauth_account(name),
+        &serde_json::to_string(tokens)?,
+    )?;
+    if let Some(old) = previous.filter(|old| *old != store) {
+        let _ = old.delete(&oauth_account(name));
+    }
+    login.token_store = store;
+    login.tokens = (store == CredentialStore::Plaintext).then(|| tokens.clone());
 
-    if let Some(current) = &config.current {
-        println!("Current workspace: {}", current);
-        if let Some(workspace) = config.workspaces.get(current) {
-            let key = &workspace.api_key;
-            if key.len() > 12 {
-                let masked = format!("{}...{}", &key[..8], &key[key.len() - 4..]);
-                println!("API Key: {}", masked);
-            } else {
-                println!("API Key: {}", key);
+    config.workspaces.entry(name.to_string()).or_default().oauth = Some(login);
+    if config.current.is_none() {
+        config.current = Some(name.to_string());
+    }
+    save_config(&config)
+}
+
+/// Forget workspace `name`'s OAuth login. Returns the removed login and its
+/// tokens, if there was one.
+pub fn remove_oauth(name: &str) -> Result<Option<(OAuthLogin, Option<Tokens>)>> {
+    let mut config = load_config()?;
+    let Some(login) = config.workspaces.get_mut(name).and_then(|w| w.oauth.take()) else {
+        return Ok(None);
+    };
+    let tokens = load_oauth_tokens(name, &login).ok();
+    login.token_store.delete(&oauth_account(name))?;
+    save_config(&config)?;
+    Ok(Some((login, tokens)))
+}
+
+/// The value of `default.<key>`: its environment variable if set, else the
+/// repository's `.linear.toml`, else the current workspace's setting
+pub fn default_value(key: &str) -> Option<String> {
+    default_with_source(key).map(|(value, _)| value)
+}
+
+/// The value of `default.<key>` and where it came from
+fn default_with_source(key: &str) -> Option<(String, String)> {
+    let (_, var) = DEFAULT_KEYS.iter().find(|(k, _)| *k == key)?;
+    if let Ok(value) = std::env::var(var) {
+        if !value.is_empty() {
+            return Some((value, var.to_string()));
+        }
+    }
+    if let Some(repo) = repo_config() {
+        if let Some(value) = repo.defaults.get(key) {
+            return Some((value, repo.path.display().to_string()));
+        }
+    }
+    let config = load_config().ok()?;
+    let current = config.active()?;
+    let value = config.workspaces.get(current)?.defaults.get(key)?;
+    Some((value, format!("workspace '{}'", current)))
+}
+
+/// `--team` if given, else the default team
+pub fn team_or_default(team: Option<String>) -> Result<String> {
+    team.or_else(|| default_value("team")).context(
+        "No team given: pass --team, or set a default with: linear config set default.team ENG",
+    )
+}
+
+/// The team a list or search is narrowed to: `--team` if given, else the
+/// default team, unless `--all-teams` asks for every team
+pub fn list_team(team: Option<String>, all_teams: bool) -> Option<String> {
+    if all_teams {
+        return None;
+    }
+    team.or_else(|| default_value("team"))
+}
+
+/// The output format used when `--output` is omitted
+pub fn default_output() -> Result<Option<OutputFormat>> {
+    default_value("output")
+        .map(|value| parse_output(&value))
+        .transpose()
+}
+
+fn parse_output(value: &str) -> Result<OutputFormat> {
+    match OutputFormat::from_str(value, true) {
+        Ok(OutputFormat::Template) => {
+            anyhow::bail!("The template format can't be a default; it needs --template")
+        }
+        Ok(format) => Ok(format),
+        Err(_) => anyhow::bail!(
+            "Invalid output format '{}' (use table, json, yaml, csv, tsv, markdown or ndjson)",
+            value
+        ),
+    }
+}
+
+fn validate_default(key: &str, value: &str) -> Result<()> {
+    match key {
+        "output" => {
+            parse_output(value)?;
+        }
+        "cycle" => {
+            let known = ["current", "active", "next", "previous", "prev", "none"];
+            if !known.contains(&value.to_lowercase().as_str()) && value.parse::<i64>().is_err() {
+                anyhow::bail!(
+                    "Invalid cycle '{}'. Use current, next, previous, a cycle number or none.",
+                    value
+                );
             }
         }
+        "branch" => {
+            let mut rest = value;
+            while let Some(start) = rest.find('{') {
+                let end = rest[start..]
+                    .find('}')
+                    .map(|e| start + e + 1)
+                    .with_context(|| format!("Unclosed placeholder in '{}'", value))?;
+                let placeholder = &rest[start..end];
+                if !BRANCH_PLACEHOLDERS.contains(&placeholder) {
+                    anyhow::bail!(
+                        "Unknown placeholder {} in the branch pattern (use {})",
+                        placeholder,
+                        BRANCH_PLACEHOLDERS.join(", ")
+                    );
+                }
+                rest = &rest[end..];
+            }
+            if !value.contains("{identifier}") && !value.contains("{number}") {
+                anyhow::bail!("The branch pattern needs {{identifier}} or {{number}}");
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Set (or with `None`, clear) a setting such as `default.team` for the
+/// current workspace
+pub fn set_value(key: &str, value: Option<&str>) -> Result<()> {
+    let name = key
+        .strip_prefix("default.")
+        .filter(|name| DEFAULT_KEYS.iter().any(|(k, _)| k == name))
+        .with_context(|| {
+            let keys: Vec<String> = DEFAULT_KEYS
+                .iter()
+                .map(|(k, _)| format!("default.{}", k))
+                .collect();
+            format!("Unknown setting '{}'. Settings: {}", key, keys.join(", "))
+        })?;
+    if let Some(value) = value {
+        validate_default(name, value)?;
+    }
+
+    let mut config = load_config()?;
+    let current = config.active_name();
+    let workspace = config.workspaces.entry(current.clone()).or_default();
+    workspace.defaults.set(name, value);
+    if config.current.is_none() {
+        config.current = Some(current);
+    }
+    save_config(&config)
+}
+
+/// Name of the workspace local data (time ledger, timers) is filed under.
+/// Falls back to "default" when no workspace is configured.
+pub fn current_workspace_name() -> Result<String> {
+    Ok(load_config()?.active_name())
+}
+
+/// Returns the GraphQL endpoint override, if any.
+/// `LINEAR_API_URL` takes precedence over the current workspace's `api_url`,
+/// but not over one picked with `--workspace`.
+pub fn get_api_url() -> Result<Option<String>> {
+    if let Some(url) = env_override("LINEAR_API_URL") {
+        return Ok(Some(url));
+    }
+
+    let config = load_config()?;
+    Ok(config
+        .active()
+        .and_then(|current| config.workspaces.get(current))
+        .and_then(|workspace| workspace.api_url.clone()))
+}
+
+/// Attempts per API request. `LINEAR_MAX_ATTEMPTS` takes precedence over the config file.
+pub fn get_max_attempts() -> Result<u32> {
+    if let Ok(value) = std::env::var("LINEAR_MAX_ATTEMPTS") {
+        if !value.is_empty() {
+            let attempts: u32 = value
+                .parse()
+                .with_context(|| format!("Invalid LINEAR_MAX_ATTEMPTS: '{}'", value))?;
+            return Ok(attempts.max(1));
+        }
+    }
+
+    let config = load_config()?;
+    Ok(config
+        .max_attempts
+        .unwrap_or(crate::retry::DEFAULT_MAX_ATTEMPTS)
+        .max(1))
+}
+
+pub fn set_max_attempts(attempts: Option<u32>) -> Result<()> {
+    let mut config = load_config()?;
+    config.max_attempts = attempts;
+    save_config(&config)?;
+    Ok(())
+}
+
+pub fn set_api_url(url: Option<&str>) -> Result<()> {
+    let mut config = load_config()?;
+    let current = config
+        .active()
+        .cloned()
+        .context("No workspace selected. Run: linear workspace add <name>")?;
+    let workspace = config.workspaces.get_mut(&current).context(format!(
+        "Workspace '{}' not found. Run: linear workspace add <name>",
+        current
+    ))?;
+    workspace.api_url = url.map(|u| u.to_string());
+    save_config(&config)?;
+    Ok(())
+}
+
+/// Print the effective settings, merged from flags' fallbacks (environment,
+/// `.linear.toml`, config file, built-in) with where each value came from
+pub fn show_config(output: OutputFormat) -> Result<()> {
+    let config = load_config()?;
+    let path = config_path()?;
+    let file = path.display().to_string();
+    let env_set = |var: &str| std::env::var(var).is_ok_and(|v| !v.is_empty());
+
+    // (setting, value, source)
+    let mut settings: Vec<(String, String, String)> = Vec::new();
+    let workspace = config
+        .active()
+        .and_then(|current| config.workspaces.get(current).map(|w| (current, w)));
+    if let Some((name, workspace)) = workspace {
+        let source = if WORKSPACE_OVERRIDE.get().is_some() {
+            "--workspace".to_string()
+        } else {
+            file.clone()
+        };
+        settings.push(("workspace".into(), name.clone(), source));
+        let key_source = if env_override("LINEAR_API_KEY").is_some() {
+            "LINEAR_API_KEY".to_string()
+        } else {
+            format!("workspace '{}'", name)
+        };
+        settings.push(("api_key".into(), workspace.describe_key(), key_source));
+    } else if env_override("LINEAR_API_KEY").is_some() {
+        settings.push(("api_key".into(), "set".into(), "LINEAR_API_KEY".into()));
+    }
+    if let Some(url) = get_api_url()? {
+        let source = if env_override("LINEAR_API_URL").is_some() {
+            "LINEAR_API_URL".to_string()
+        } else {
+            format!("workspace '{}'", config.active_name())
+        };
+        settings.push(("api_url".into(), url, source));
+    }
+    let attempts_source = if env_set("LINEAR_MAX_ATTEMPTS") {
+        "LINEAR_MAX_ATTEMPTS".to_string()
+    } else if config.max_attempts.is_some() {
+        file.clone()
     } else {
+        "built-in".to_string()
+    };
+    settings.push((
+        "max_attempts".into(),
+        get_max_attempts()?.to_string(),
+        attempts_source,
+    ));
+    let store_source = if env_set(credentials::STORE_ENV) {
+        credentials::STORE_ENV.to_string()
+    } else if config.credential_store.is_some() {
+        file.clone()
+    } else {
+        "built-in".to_string()
+    };
+    settings.push((
+        "credential_store".into(),
+        CredentialStore::preferred(config.credential_store)?
+            .name()
+            .to_string(),
+        store_source,
+    ));
+    for (key, _) in DEFAULT_KEYS {
+        if let Some((value, source)) = default_with_source(key) {
+            settings.push((format!("default.{}", key), value, source));
+        }
+    }
+
+    if !output.is_table() {
+        let value: serde_json::Map<String, serde_json::Value> = settings
+            .iter()
+            .map(|(key, value, source)| {
+                (
+                    key.clone(),
+                    serde_json::json!({ "value": value, "source": source }),
+                )
+            })
+            .collect();
+        let value = serde_json::json!({
+            "configFile": file,
+            "repoConfig": repo_config().map(|r| r.path.display().to_string()),
+            "settings": value,
+        });
+        return crate::output::print_value(&value, output);
+    }
+
+    println!("Config file: {}", file);
+    match repo_config() {
+        Some(repo) => println!("Repository config: {}", repo.path.display()),
+        None => println!("Repository config: none ({} not found)", REPO_CONFIG_FILE),
+    }
+    println!();
+    if config.active().is_none() {
         println!("No workspace configured. Run: linear workspace add <name>");
     }
+    for (key, value, source) in &settings {
+        println!(
+            "{} = {} {}",
+            key,
+            value,
+            format!("(from {})", source).dimmed()
+        );
+    }
 
     Ok(())
 }
 
 // Workspace management functions
 
-pub fn workspace_add(name: &str, api_key: &str) -> Result<()> {
+pub fn workspace_add(name: &str, api_key: &str, api_url: Option<&str>) -> Result<()> {
     let mut config = load_config()?;
 
     if config.workspaces.contains_key(name) {
@@ -141,12 +913,12 @@ pub fn workspace_add(name: &str, api_key: &str) -> Result<()> {
         );
     }
 
-    config.workspaces.insert(
-        name.to_string(),
-        Workspace {
-            api_key: api_key.to_string(),
-        },
-    );
+    let mut workspace = Workspace {
+        api_url: api_url.map(|u| u.to_string()),
+        ..Workspace::default()
+    };
+    store_key(&config, name, api_key, &mut workspace)?;
+    config.workspaces.insert(name.to_string(), workspace);
 
     // If this is the first workspace, make it current
     if config.current.is_none() {
@@ -177,13 +949,7 @@ pub fn workspace_list() -> Result<()> {
     for (name, workspace) in &config.workspaces {
         let is_current = config.current.as_ref() == Some(name);
         let marker = if is_current { "*" } else { " " };
-        let key = &workspace.api_key;
-        let masked = if key.len() > 12 {
-            format!("{}...{}", &key[..8], &key[key.len() - 4..])
-        } else {
-            key.clone()
-        };
-        println!("{} {} ({})", marker, name, masked);
+        println!("{} {} ({})", marker, name, workspace.describe_key());
     }
 
     println!();
@@ -212,16 +978,10 @@ pub fn workspace_switch(name: &str) -> Result<()> {
 pub fn workspace_current() -> Result<()> {
     let config = load_config()?;
 
-    if let Some(current) = &config.current {
+    if let Some(current) = config.active() {
         println!("Current workspace: {}", current);
         if let Some(workspace) = config.workspaces.get(current) {
-            let key = &workspace.api_key;
-            if key.len() > 12 {
-                let masked = format!("{}...{}", &key[..8], &key[key.len() - 4..]);
-                println!("API Key: {}", masked);
-            } else {
-                println!("API Key: {}", key);
-            }
+            println!("API Key: {}", workspace.describe_key());
         }
     } else {
         println!("No workspace selected. Run: linear workspace add <name>");
@@ -237,7 +997,14 @@ pub fn workspace_remove(name: &str) -> Result<()> {
         anyhow::bail!("Workspace '{}' not found.", name);
     }
 
-    config.workspaces.remove(name);
+    if let Some(workspace) = config.workspaces.remove(name) {
+        if let Some(store) = workspace.credential_store {
+            store.delete(name)?;
+        }
+        if let Some(login) = workspace.oauth {
+            login.token_store.delete(&oauth_account(name))?;
+        }
+    }
 
     // If we removed the current workspace, clear it or switch to another
     if config.current.as_ref() == Some(&name.to_string()) {
@@ -0,0 +1,356 @@
+//! Where workspace API keys are kept.
+//!
+//! Keys live in the OS keyring (Secret Service on Linux, Keychain on macOS,
+//! Credential Manager on Windows), in a passphrase-encrypted file next to the
+//! config, or as plaintext in `config.toml`. A workspace can instead name a
+//! `credential_command` whose output is the key, e.g. `pass show linear`.
+
+use anyhow::{bail, Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Service name keys are filed under in the OS keyring
+const KEYRING_SERVICE: &str = "linear-cli";
+
+/// Passphrase for the encrypted file, for scripts and CI
+pub const PASSPHRASE_ENV: &str = "LINEAR_CLI_PASSPHRASE";
+
+/// Store for new keys, overriding `credential_store` in the config
+pub const STORE_ENV: &str = "LINEAR_CREDENTIAL_STORE";
+
+/// A place API keys can be kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialStore {
+    /// The OS keyring
+    Keyring,
+    /// credentials.enc, encrypted with a passphrase
+    EncryptedFile,
+    /// Plaintext in config.toml (readable only by you)
+    Plaintext,
+}
+
+impl CredentialStore {
+    /// `LINEAR_CREDENTIAL_STORE` if set, else the configured store, else the keyring
+    pub fn preferred(configured: Option<CredentialStore>) -> Result<Self> {
+        if let Ok(value) = std::env::var(STORE_ENV) {
+            if !value.is_empty() {
+                return CredentialStore::from_str(&value, true).map_err(|_| {
+                    anyhow::anyhow!(
+                        "Invalid {}: '{}' (use keyring, encrypted-file or plaintext)",
+                        STORE_ENV,
+                        value
+                    )
+                });
+            }
+        }
+        Ok(configured.unwrap_or(CredentialStore::Keyring))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CredentialStore::Keyring => "keyring",
+            CredentialStore::EncryptedFile => "encrypted-file",
+            CredentialStore::Plaintext => "plaintext",
+        }
+    }
+
+    /// Save `workspace`'s key. Plaintext keys are written by the config itself.
+    pub fn save(&self, workspace: &str, key: &str) -> Result<()> {
+        match self {
+            CredentialStore::Keyring => keyring_entry(workspace)?
+                .set_password(key)
+                .context("Could not save the key in the OS keyring"),
+            CredentialStore::EncryptedFile => {
+                let passphrase = passphrase(true)?;
+                let mut keys = read_encrypted(&encrypted_path()?, &passphrase)?;
+                keys.insert(workspace.to_string(), key.to_string());
+                write_encrypted(&encrypted_path()?, &keys, &passphrase)
+            }
+            CredentialStore::Plaintext => Ok(()),
+        }
+    }
+
+    /// Read `workspace`'s key
+    pub fn load(&self, workspace: &str) -> Result<String> {
+        match self {
+            CredentialStore::Keyring => {
+                keyring_entry(workspace)?.get_password().with_context(|| {
+                    format!(
+                        "Could not read the key for workspace '{}' from the OS keyring",
+                        workspace
+                    )
+                })
+            }
+            CredentialStore::EncryptedFile => {
+                let keys = read_encrypted(&encrypted_path()?, &passphrase(false)?)?;
+                keys.get(workspace).cloned().with_context(|| {
+                    format!("No key for workspace '{}' in credentials.enc", workspace)
+                })
+            }
+            CredentialStore::Plaintext => {
+                bail!("Plaintext keys are read from config.toml")
+            }
+        }
+    }
+
+    /// Forget `workspace`'s key; a key that isn't there is not an error
+    pub fn delete(&self, workspace: &str) -> Result<()> {
+        match self {
+            CredentialStore::Keyring => match keyring_entry(workspace)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e).context("Could not remove the key from the OS keyring"),
+            },
+            CredentialStore::EncryptedFile => {
+                let path = encrypted_path()?;
+                if !path.exists() {
+                    return Ok(());
+                }
+                let passphrase = passphrase(false)?;
+                let mut keys = read_encrypted(&path, &passphrase)?;
+                if keys.remove(workspace).is_some() {
+                    write_encrypted(&path, &keys, &passphrase)?;
+                }
+                Ok(())
+            }
+            CredentialStore::Plaintext => Ok(()),
+        }
+    }
+}
+
+fn keyring_entry(workspace: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, workspace).context("Could not open the OS keyring")
+}
+
+/// Run a `credential_command` and return what it prints
+pub fn run_command(command: &str) -> Result<String> {
+    let mut shell = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    let output = shell
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run credential_command '{}'", command))?;
+
+    if !output.status.success() {
+        bail!(
+            "credential_command '{}' failed with {}",
+            command,
+            output.status
+        );
+    }
+    let key = String::from_utf8(output.stdout)
+        .context("credential_command printed something that isn't text")?
+        .trim()
+        .to_string();
+    if key.is_empty() {
+        bail!("credential_command '{}' printed nothing", command);
+    }
+    Ok(key)
+}
+
+/// The passphrase, asked for at most once per run
+static PASSPHRASE: OnceLock<String> = OnceLock::new();
+
+/// The passphrase from `LINEAR_CLI_PASSPHRASE`, if set
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty())
+}
+
+fn passphrase(creating: bool) -> Result<String> {
+    if let Some(passphrase) = PASSPHRASE.get() {
+        return Ok(passphrase.clone());
+    }
+    let passphrase = match passphrase_from_env() {
+        Some(passphrase) => passphrase,
+        None => {
+            if !std::io::stdin().is_terminal() {
+                bail!(
+                    "credentials.enc needs a passphrase: set {} or run in a terminal",
+                    PASSPHRASE_ENV
+                );
+            }
+            let mut prompt =
+                dialoguer::Password::new().with_prompt("Passphrase for credentials.enc");
+            if creating && !encrypted_path()?.exists() {
+                prompt = prompt.with_confirmation("Repeat passphrase", "Passphrases don't match");
+            }
+            prompt.interact()?
+        }
+    };
+    Ok(PASSPHRASE.get_or_init(|| passphrase).clone())
+}
+
+fn encrypted_path() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("linear-cli")
+        .join("credentials.enc"))
+}
+
+/// On-disk form of credentials.enc: an Argon2id-derived key and
+/// ChaCha20-Poly1305 over a JSON map of workspace name to API key
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Could not derive a key from the passphrase: {}", e))?;
+    Ok(key)
+}
+
+fn read_encrypted(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: EncryptedFile = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a credentials file", path.display()))?;
+    if file.version != 1 {
+        bail!("Unsupported credentials file version {}", file.version);
+    }
+
+    let salt = BASE64.decode(&file.salt)?;
+    let nonce = BASE64.decode(&file.nonce)?;
+    let ciphertext = BASE64.decode(&file.ciphertext)?;
+    if nonce.len() != 12 {
+        bail!("{} is corrupt", path.display());
+    }
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase for {}", path.display()))?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn write_encrypted(path: &Path, keys: &BTreeMap<String, String>, passphrase: &str) -> Result<()> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&nonce, serde_json::to_vec(keys)?.as_ref())
+        .map_err(|_| anyhow::anyhow!("Could not encrypt credentials"))?;
+
+    let file = EncryptedFile {
+        version: 1,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_private(path, serde_json::to_string_pretty(&file)?.as_bytes())
+}
+
+/// Write a file only the current user can read (0600 on Unix)
+pub fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        // `mode` only applies to new files
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(content)?;
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Show the start and end of a key, e.g. `lin_api_...wxyz`
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() > 12 {
+        let start: String = chars[..8].iter().collect();
+        let end: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", start, end)
+    } else {
+        key.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.enc");
+
+        let mut keys = BTreeMap::new();
+        keys.insert("work".to_string(), "lin_api_secret".to_string());
+        write_encrypted(&path, &keys, "correct horse").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("lin_api_secret"));
+        assert_eq!(read_encrypted(&path, "correct horse").unwrap(), keys);
+
+        let err = read_encrypted(&path, "wrong").unwrap_err();
+        assert!(err.to_string().starts_with("Wrong passphrase"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = read_encrypted(&dir.path().join("none.enc"), "x").unwrap();
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn test_mask_key() {
+        assert_eq!(mask_key("lin_api_abcdefghwxyz"), "lin_api_...wxyz");
+        assert_eq!(mask_key("short"), "short");
+        // A pasted key with stray non-ASCII characters doesn't panic
+        assert_eq!(mask_key("lin_api_ééééééwxyz"), "lin_api_...wxyz");
+        assert_eq!(mask_key("ключ_ключ_ключ"), "ключ_клю...ключ");
+    }
+}
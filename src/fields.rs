@@ -0,0 +1,547 @@
+//! `--fields identifier,title,state.name`: the fields to keep in data output.
+//!
+//! The same list narrows the GraphQL selection set before it is sent, so the
+//! API only returns what will be printed, and then projects each result so
+//! nested paths come back in the order they were asked for.
+
+use anyhow::{bail, Result};
+use apollo_compiler::ast::OperationType;
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::Schema;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::sync::{LazyLock, OnceLock};
+
+/// A parsed `--fields` list: each entry is a field name and the subfields
+/// wanted from it (empty to keep the whole field)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fields(Vec<(String, Fields)>);
+
+impl Fields {
+    /// Parse a comma-separated list of dotted paths
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut fields = Fields::default();
+        for path in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let segments: Vec<&str> = path.split('.').collect();
+            if segments.iter().any(|s| !is_name(s)) {
+                bail!(
+                    "Invalid field '{}': use names like identifier or state.name",
+                    path
+                );
+            }
+            fields.insert(&segments);
+        }
+        if fields.0.is_empty() {
+            bail!("--fields needs at least one field name");
+        }
+        Ok(fields)
+    }
+
+    fn insert(&mut self, segments: &[&str]) {
+        let Some((first, rest)) = segments.split_first() else {
+            return;
+        };
+        let (index, existed) = match self.0.iter().position(|(name, _)| name == first) {
+            Some(index) => (index, true),
+            None => {
+                self.0.push((first.to_string(), Fields::default()));
+                (self.0.len() - 1, false)
+            }
+        };
+        let child = &mut self.0[index].1;
+        // `state` together with `state.name`, in either order, keeps all of state
+        if rest.is_empty() {
+            child.0.clear();
+        } else if !existed || !child.is_whole() {
+            child.insert(rest);
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&Fields> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, f)| f)
+    }
+
+    fn is_whole(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Where a flattened column such as `state.name` comes in this list, for
+    /// putting columns in the order the fields were asked for
+    pub fn rank(&self, column: &str) -> Vec<usize> {
+        let mut rank = Vec::new();
+        let mut fields = self;
+        for segment in column.split('.') {
+            if fields.is_whole() {
+                break;
+            }
+            match fields.0.iter().position(|(name, _)| name == segment) {
+                Some(index) => {
+                    rank.push(index);
+                    fields = &fields.0[index].1;
+                }
+                None => {
+                    rank.push(usize::MAX);
+                    break;
+                }
+            }
+        }
+        rank
+    }
+
+    /// Keep only these fields of `value`. Arrays and connections are projected
+    /// item by item; missing fields come back as null so every record has the
+    /// same shape.
+    pub fn project(&self, value: &Value) -> Value {
+        if self.is_whole() {
+            return value.clone();
+        }
+        match value {
+            Value::Array(items) => Value::Array(items.iter().map(|i| self.project(i)).collect()),
+            Value::Object(map) if is_connection(map) => {
+                let mut out = Map::new();
+                out.insert("nodes".to_string(), self.project(&map["nodes"]));
+                Value::Object(out)
+            }
+            Value::Object(map) => Value::Object(
+                self.0
+                    .iter()
+                    .map(|(name, sub)| {
+                        let field = map.get(name).map_or(Value::Null, |v| sub.project(v));
+                        (name.clone(), field)
+                    })
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_connection(map: &Map<String, Value>) -> bool {
+    map.len() == 1 && map.get("nodes").is_some_and(Value::is_array)
+}
+
+/// The `--fields` list, set once at startup
+static FIELDS: OnceLock<Fields> = OnceLock::new();
+
+/// Set the fields kept by data output
+pub fn set(fields: Fields) {
+    let _ = FIELDS.set(fields);
+}
+
+/// The `--fields` list, if one was given
+pub fn selected() -> Option<&'static Fields> {
+    FIELDS.get()
+}
+
+/// Narrow `query` to the `--fields` list at the object reached by `path`, e.g.
+/// `&["issues"]` for `issues { nodes { ... } }` or `&["issue"]` for a single
+/// issue. Requested fields the query doesn't select are added, so
+/// `--fields dueDate` fetches `dueDate` too, and `--fields team` fetches
+/// `team { id name }`.
+///
+/// Only call this where the result is printed as is: the narrowed query no
+/// longer fetches what a table or a cache would need. Without `--fields`, or
+/// if the query can't be parsed, it is returned unchanged.
+pub fn narrow<'a>(query: &'a str, path: &[&str]) -> Cow<'a, str> {
+    match selected().and_then(|fields| narrow_with(query, path, fields)) {
+        Some(narrowed) => Cow::Owned(narrowed),
+        None => Cow::Borrowed(query),
+    }
+}
+
+fn narrow_with(query: &str, path: &[&str], fields: &Fields) -> Option<String> {
+    let open = query.find('{')?;
+    let mut parser = Parser {
+        src: &query[open + 1..],
+        pos: 0,
+    };
+    let mut selection = parser.selection()?;
+
+    let mut target = &mut selection;
+    let mut at = Vec::new();
+    for segment in path {
+        let item = target.iter_mut().find(|s| s.key == *segment)?;
+        at.push(item.name.clone());
+        target = item.children.as_mut()?;
+    }
+    if let Some(nodes) = target.iter().position(|s| s.key == "nodes") {
+        at.push("nodes".to_string());
+        target = target[nodes].children.as_mut()?;
+    }
+    *target = prune(std::mem::take(target), fields, true, &at);
+
+    Some(format!("{}{}", &query[..open], render(&selection)))
+}
+
+/// One entry of a selection set: `alias: name(args) { children }`, or an
+/// inline fragment `... on Type { children }`. `key` is the alias or name the
+/// field comes back under, `name` the field's own name; both are empty for
+/// fragments.
+#[derive(Debug)]
+struct Selection {
+    head: String,
+    key: String,
+    name: String,
+    children: Option<Vec<Selection>>,
+}
+
+impl Selection {
+    fn field(name: &str, children: Option<Vec<Selection>>) -> Self {
+        Selection {
+            head: name.to_string(),
+            key: name.to_string(),
+            name: name.to_string(),
+            children,
+        }
+    }
+}
+
+/// Keep the selections `fields` asks for. With `add_missing`, requested fields
+/// the query doesn't select are added (not inside fragments, where the type
+/// may not have them). `at` is the path of field names from the query root to
+/// `selection`, for looking up the types of added fields.
+fn prune(
+    selection: Vec<Selection>,
+    fields: &Fields,
+    add_missing: bool,
+    at: &[String],
+) -> Vec<Selection> {
+    let mut kept = Vec::new();
+    for mut item in selection {
+        if item.key.is_empty() {
+            let children = prune(item.children.take().unwrap_or_default(), fields, false, at);
+            if !children.is_empty() {
+                item.children = Some(children);
+                kept.push(item);
+            }
+            continue;
+        }
+        // Connections are transparent: `labels.name` means `labels { nodes { name } }`
+        let sub = match fields.get(&item.key) {
+            Some(sub) => sub,
+            None if item.key == "nodes" => fields,
+            None => continue,
+        };
+        if let Some(children) = item.children.take() {
+            item.children = Some(if sub.is_whole() {
+                children
+            } else {
+                let below = [at, std::slice::from_ref(&item.name)].concat();
+                prune(children, sub, add_missing, &below)
+            });
+        }
+        kept.push(item);
+    }
+
+    if add_missing && !kept.iter().any(|s| s.key == "nodes") {
+        for (name, sub) in &fields.0 {
+            if !kept.iter().any(|s| s.key == *name) {
+                let children = if sub.is_whole() {
+                    object_selection(at, name)
+                } else {
+                    let below = [at, std::slice::from_ref(name)].concat();
+                    Some(prune(Vec::new(), sub, true, &below))
+                };
+                kept.push(Selection::field(name, children));
+            }
+        }
+    }
+    kept
+}
+
+/// The API schema snapshot, parsed the first time `--fields` adds a field
+/// the query doesn't select
+static SCHEMA: LazyLock<Schema> = LazyLock::new(|| {
+    Schema::parse(include_str!("../schema/linear.graphql"), "linear.graphql")
+        .unwrap_or_else(|invalid| invalid.partial)
+});
+
+/// What to fetch of field `name` below the field names `at`, if the schema
+/// says it is an object: its `id` and `name` (a connection's nodes' for a
+/// connection). `None` for scalars, enums and fields the schema doesn't know.
+fn object_selection(at: &[String], name: &str) -> Option<Vec<Selection>> {
+    let mut type_name = SCHEMA.root_operation(OperationType::Query)?.as_str();
+    for field in at.iter().map(String::as_str).chain([name]) {
+        type_name = field_type(type_name, field)?;
+    }
+    subselection(type_name)
+}
+
+/// The named type of `field` on the object or interface `type_name`
+fn field_type(type_name: &str, field: &str) -> Option<&'static str> {
+    let fields = match SCHEMA.types.get(type_name)? {
+        ExtendedType::Object(object) => &object.fields,
+        ExtendedType::Interface(interface) => &interface.fields,
+        _ => return None,
+    };
+    Some(fields.get(field)?.ty.inner_named_type().as_str())
+}
+
+fn subselection(type_name: &str) -> Option<Vec<Selection>> {
+    let fields = match SCHEMA.types.get(type_name)? {
+        ExtendedType::Object(object) => &object.fields,
+        ExtendedType::Interface(interface) => &interface.fields,
+        ExtendedType::Union(_) => return Some(vec![Selection::field("__typename", None)]),
+        _ => return None,
+    };
+    if let Some(nodes) = fields.get("nodes") {
+        let children = subselection(nodes.ty.inner_named_type().as_str())?;
+        return Some(vec![Selection::field("nodes", Some(children))]);
+    }
+    let mut picked: Vec<Selection> = ["id", "name"]
+        .into_iter()
+        .filter(|field| fields.contains_key(*field))
+        .map(|field| Selection::field(field, None))
+        .collect();
+    if picked.is_empty() {
+        picked.push(Selection::field("__typename", None));
+    }
+    Some(picked)
+}
+
+fn render(selection: &[Selection]) -> String {
+    let items: Vec<String> = selection
+        .iter()
+        .map(|s| match &s.children {
+            Some(children) => format!("{} {}", s.head, render(children)),
+            None => s.head.clone(),
+        })
+        .collect();
+    format!("{{ {} }}", items.join(" "))
+}
+
+/// Just enough of a GraphQL parser to take a selection set apart and put it
+/// back together; arguments and directives are kept as written.
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// The selection set after an opening brace, up to its closing brace
+    fn selection(&mut self) -> Option<Vec<Selection>> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_space();
+            match self.peek()? {
+                '}' => {
+                    self.pos += 1;
+                    return Some(items);
+                }
+                _ => items.push(self.item()?),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Option<Selection> {
+        let start = self.pos;
+        if self.src[self.pos..].starts_with("...") {
+            self.pos += 3;
+            while self.peek()? != '{' {
+                self.pos += 1;
+            }
+            let head = self.src[start..self.pos].trim().to_string();
+            self.pos += 1;
+            let children = self.selection()?;
+            return Some(Selection {
+                head,
+                key: String::new(),
+                name: String::new(),
+                children: Some(children),
+            });
+        }
+
+        let key = self.name()?;
+        let mut name = key.clone();
+        self.skip_space();
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            self.skip_space();
+            name = self.name()?;
+        }
+        loop {
+            self.skip_space();
+            match self.peek() {
+                Some('(') => self.skip_group()?,
+                Some('@') => {
+                    self.pos += 1;
+                    self.name()?;
+                }
+                _ => break,
+            }
+        }
+        let head = self.src[start..self.pos].trim().to_string();
+        let children = if self.peek() == Some('{') {
+            self.pos += 1;
+            Some(self.selection()?)
+        } else {
+            None
+        };
+        Some(Selection {
+            head,
+            key,
+            name,
+            children,
+        })
+    }
+
+    fn name(&mut self) -> Option<String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.src[start..self.pos].to_string())
+    }
+
+    /// Skip a parenthesised argument list, including nested input objects
+    /// and strings that contain brackets
+    fn skip_group(&mut self) -> Option<()> {
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut chars = self.src[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\\' if in_string => {
+                    chars.next();
+                }
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += offset + 1;
+                        return Some(());
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() || c == ',' => self.pos += c.len_utf8(),
+                Some('#') => {
+                    let rest = &self.src[self.pos..];
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LIST: &str = r#"
+        query($first: Int, $after: String, $filter: IssueFilter) {
+            issues(first: $first, after: $after, filter: { title: { contains: "a{b)" } }) {
+                nodes {
+                    id
+                    identifier
+                    title
+                    state { id name }
+                    assignee { id name }
+                }
+                pageInfo { hasNextPage endCursor }
+            }
+        }
+    "#;
+
+    fn narrowed(query: &str, path: &[&str], spec: &str) -> String {
+        narrow_with(query, path, &Fields::parse(spec).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_merges_paths() {
+        let fields = Fields::parse("identifier, state.name,state.id,title").unwrap();
+        let names: Vec<&str> = fields.0.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["identifier", "state", "title"]);
+        assert_eq!(fields.get("state").unwrap().0.len(), 2);
+
+        let whole = Fields::parse("state.name,state").unwrap();
+        assert!(whole.get("state").unwrap().is_whole());
+
+        assert!(Fields::parse("state..name").is_err());
+        assert!(Fields::parse(" , ").is_err());
+    }
+
+    #[test]
+    fn test_narrow_keeps_arguments_and_page_info() {
+        let query = narrowed(LIST, &["issues"], "identifier,state.name");
+        assert!(query.contains(
+            r#"issues(first: $first, after: $after, filter: { title: { contains: "a{b)" } })"#
+        ));
+        assert!(query.contains("nodes { identifier state { name } }"));
+        assert!(query.contains("pageInfo { hasNextPage endCursor }"));
+        assert!(!query.contains("assignee"));
+        assert!(query.trim_start().starts_with("query($first: Int"));
+    }
+
+    #[test]
+    fn test_narrow_adds_missing_fields() {
+        let query = narrowed(LIST, &["issues"], "title,dueDate,cycle.number,state");
+        assert!(query.contains("nodes { title state { id name } dueDate cycle { number } }"));
+
+        // Objects the query doesn't select get a selection of their own
+        let query = narrowed(LIST, &["issues"], "identifier,team,labels,parent.assignee");
+        assert!(
+            query.contains(
+                "nodes { identifier team { id name } labels { nodes { id name } } \
+                 parent { assignee { id name } } }"
+            ),
+            "{}",
+            query
+        );
+    }
+
+    #[test]
+    fn test_narrow_single_object_and_aliases() {
+        let query = "query($id: String!) { issue(id: $id) { id name: title labels { nodes { id name } } } }";
+        assert_eq!(
+            narrowed(query, &["issue"], "name,labels.name"),
+            "query($id: String!) { issue(id: $id) { name: title labels { nodes { name } } } }"
+        );
+        assert!(narrow_with(query, &["project"], &Fields::parse("id").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_project() {
+        let fields = Fields::parse("identifier,state.name,labels.name,missing").unwrap();
+        let issue = json!({
+            "id": "1",
+            "identifier": "ENG-1",
+            "state": { "id": "s", "name": "Todo" },
+            "labels": { "nodes": [{ "id": "l", "name": "bug" }] }
+        });
+        assert_eq!(
+            fields.project(&json!([issue])),
+            json!([{
+                "identifier": "ENG-1",
+                "state": { "name": "Todo" },
+                "labels": { "nodes": [{ "name": "bug" }] },
+                "missing": null
+            }])
+        );
+    }
+}
@@ -0,0 +1,962 @@
+//! The `--filter` expression language for `issues list`.
+//!
+//! An expression such as `priority<=2 and label:bug and updated>-7d and not assignee:none`
+//! is parsed into an [`Expr`] and compiled to Linear's `IssueFilter` input object.
+//! `IssueFilter` has no `not`, so negation is pushed down to the comparators
+//! (`eq` becomes `neq`, `some` becomes `every`, and so on). Negated conditions on
+//! optional relations also match issues where the relation is unset.
+//!
+//! [`matches`] evaluates a compiled filter against a node locally, for
+//! `--offline` commands reading the mirror.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// A parsed filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+/// A single `field op value` condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    pub field: Field,
+    pub op: Op,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Team,
+    State,
+    Assignee,
+    Creator,
+    Project,
+    Cycle,
+    Label,
+    Priority,
+    Estimate,
+    Title,
+    Parent,
+    Created,
+    Updated,
+    Started,
+    Completed,
+    Canceled,
+    Due,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "team" => Field::Team,
+            "state" | "status" => Field::State,
+            "assignee" => Field::Assignee,
+            "creator" => Field::Creator,
+            "project" => Field::Project,
+            "cycle" => Field::Cycle,
+            "label" | "labels" => Field::Label,
+            "priority" => Field::Priority,
+            "estimate" => Field::Estimate,
+            "title" => Field::Title,
+            "parent" => Field::Parent,
+            "created" => Field::Created,
+            "updated" => Field::Updated,
+            "started" => Field::Started,
+            "completed" => Field::Completed,
+            "canceled" | "cancelled" => Field::Canceled,
+            "due" => Field::Due,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Team => "team",
+            Field::State => "state",
+            Field::Assignee => "assignee",
+            Field::Creator => "creator",
+            Field::Project => "project",
+            Field::Cycle => "cycle",
+            Field::Label => "label",
+            Field::Priority => "priority",
+            Field::Estimate => "estimate",
+            Field::Title => "title",
+            Field::Parent => "parent",
+            Field::Created => "created",
+            Field::Updated => "updated",
+            Field::Started => "started",
+            Field::Completed => "completed",
+            Field::Canceled => "canceled",
+            Field::Due => "due",
+        }
+    }
+}
+
+const FIELDS: &str = "team, state, assignee, creator, project, cycle, label, priority, estimate, \
+                      title, parent, created, updated, started, completed, canceled, due";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `:` - equality for names, numbers and dates; "contains" for titles
+    Match,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// `~` - case-insensitive substring match
+    Contains,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Match => ":",
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Contains => "~",
+        })
+    }
+}
+
+/// Parse and compile a filter expression to an `IssueFilter` object
+pub fn compile(input: &str) -> Result<Value> {
+    parse(input)
+        .and_then(|expr| expr.to_issue_filter())
+        .with_context(|| format!("Invalid filter expression: {}", input))
+}
+
+/// Matches a team by key or name, like `team:ENG`; wrap it in `team` for an
+/// issue or in `accessibleTeams: { some }` for a project
+pub fn team(team: &str) -> Value {
+    let cmp = json!({ "eqIgnoreCase": team });
+    any_of(vec![obj("key", cmp.clone()), obj("name", cmp)], false)
+}
+
+/// Parse a filter expression
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        anyhow::bail!("Unexpected {}", token);
+    }
+    Ok(expr)
+}
+
+impl Expr {
+    /// Compile to Linear's `IssueFilter` input
+    pub fn to_issue_filter(&self) -> Result<Value> {
+        self.compile(false)
+    }
+
+    fn compile(&self, negated: bool) -> Result<Value> {
+        match self {
+            Expr::And(exprs) | Expr::Or(exprs) => {
+                let compiled = exprs
+                    .iter()
+                    .map(|e| e.compile(negated))
+                    .collect::<Result<Vec<_>>>()?;
+                // De Morgan: a negated conjunction is a disjunction of negations
+                let conjunction = matches!(self, Expr::And(_)) != negated;
+                Ok(obj(if conjunction { "and" } else { "or" }, json!(compiled)))
+            }
+            Expr::Not(expr) => expr.compile(!negated),
+            Expr::Term(term) => term.compile(negated),
+        }
+    }
+}
+
+impl Term {
+    fn compile(&self, negated: bool) -> Result<Value> {
+        // `a != b` is `not a = b`, which lets every field handle `!=` uniformly
+        let (op, negated) = match self.op {
+            Op::Ne => (Op::Eq, !negated),
+            op => (op, negated),
+        };
+        let value = self.value.as_str();
+        let is_none = value.eq_ignore_ascii_case("none");
+
+        match self.field {
+            Field::Team => {
+                let cmp = self.text(op, negated)?;
+                Ok(obj(
+                    "team",
+                    any_of(vec![obj("key", cmp.clone()), obj("name", cmp)], negated),
+                ))
+            }
+            Field::State => Ok(obj("state", obj("name", self.text(op, negated)?))),
+            Field::Assignee | Field::Creator => {
+                let field = self.field.name();
+                if is_none {
+                    self.require_equality(op)?;
+                    return Ok(obj(field, json!({ "null": !negated })));
+                }
+                let inner = if value.eq_ignore_ascii_case("me") {
+                    self.require_equality(op)?;
+                    json!({ "isMe": { "eq": !negated } })
+                } else if value.contains('@') {
+                    obj("email", self.text(op, negated)?)
+                } else {
+                    let cmp = self.text(op, negated)?;
+                    any_of(
+                        vec![obj("name", cmp.clone()), obj("displayName", cmp)],
+                        negated,
+                    )
+                };
+                Ok(relation(field, inner, negated))
+            }
+            Field::Project => {
+                if is_none {
+                    self.require_equality(op)?;
+                    return Ok(obj("project", json!({ "null": !negated })));
+                }
+                Ok(relation(
+                    "project",
+                    obj("name", self.text(op, negated)?),
+                    negated,
+                ))
+            }
+            Field::Cycle => {
+                let inner = match value.to_lowercase().as_str() {
+                    "none" => {
+                        self.require_equality(op)?;
+                        return Ok(obj("cycle", json!({ "null": !negated })));
+                    }
+                    "current" | "active" => self.flag("isActive", op, negated)?,
+                    "next" => self.flag("isNext", op, negated)?,
+                    "previous" | "last" => self.flag("isPrevious", op, negated)?,
+                    _ => match value.parse::<i64>() {
+                        Ok(number) => obj("number", compare(op, json!(number), negated, self)?),
+                        Err(_) => obj("name", self.text(op, negated)?),
+                    },
+                };
+                Ok(relation("cycle", inner, negated))
+            }
+            Field::Label => {
+                if is_none {
+                    self.require_equality(op)?;
+                    let cmp = if negated { "neq" } else { "eq" };
+                    return Ok(json!({ "labels": { "length": { cmp: 0 } } }));
+                }
+                // "has a label named x", negated, is "every label isn't named x"
+                let quantifier = if negated { "every" } else { "some" };
+                Ok(obj(
+                    "labels",
+                    obj(quantifier, obj("name", self.text(op, negated)?)),
+                ))
+            }
+            Field::Priority => {
+                let priority = parse_priority(value).with_context(|| {
+                    format!(
+                        "Invalid priority '{}': use 0-4 or none, urgent, high, normal, low",
+                        value
+                    )
+                })?;
+                let mut cmp = compare(op, json!(priority), negated, self)?;
+                // 0 is "no priority", not more urgent than urgent: orderings
+                // toward urgent leave it out
+                if cmp.get("lt").or(cmp.get("lte")).is_some() {
+                    cmp["neq"] = json!(0);
+                }
+                Ok(obj("priority", cmp))
+            }
+            Field::Estimate => {
+                if is_none {
+                    self.require_equality(op)?;
+                    return Ok(obj("estimate", json!({ "null": !negated })));
+                }
+                let estimate: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid estimate '{}'", value))?;
+                let cmp = compare(op, json!(estimate), negated, self)?;
+                Ok(relation("estimate", cmp, negated))
+            }
+            Field::Title => {
+                // A bare `title:x` means "contains", like a search box
+                let op = if op == Op::Match { Op::Contains } else { op };
+                Ok(obj("title", self.text(op, negated)?))
+            }
+            Field::Parent => {
+                self.require_equality(op)?;
+                if is_none {
+                    return Ok(obj("parent", json!({ "null": !negated })));
+                }
+                let (key, number) = parse_identifier(value).with_context(|| {
+                    format!(
+                        "Invalid parent '{}': expected an identifier like ENG-123",
+                        value
+                    )
+                })?;
+                let inner = if negated {
+                    json!({ "or": [
+                        { "number": { "neq": number } },
+                        { "team": { "key": { "neqIgnoreCase": key } } }
+                    ] })
+                } else {
+                    json!({
+                        "number": { "eq": number },
+                        "team": { "key": { "eqIgnoreCase": key } }
+                    })
+                };
+                Ok(relation("parent", inner, negated))
+            }
+            Field::Created | Field::Updated => {
+                let field = if self.field == Field::Created {
+                    "createdAt"
+                } else {
+                    "updatedAt"
+                };
+                Ok(obj(
+                    field,
+                    compare(op, json!(parse_date(value)?), negated, self)?,
+                ))
+            }
+            Field::Started | Field::Completed | Field::Canceled | Field::Due => {
+                let field = match self.field {
+                    Field::Started => "startedAt",
+                    Field::Completed => "completedAt",
+                    Field::Canceled => "canceledAt",
+                    _ => "dueDate",
+                };
+                if is_none {
+                    self.require_equality(op)?;
+                    return Ok(obj(field, json!({ "null": !negated })));
+                }
+                let cmp = compare(op, json!(parse_date(value)?), negated, self)?;
+                Ok(relation(field, cmp, negated))
+            }
+        }
+    }
+
+    /// A case-insensitive string comparator
+    fn text(&self, op: Op, negated: bool) -> Result<Value> {
+        let key = match (op, negated) {
+            (Op::Match | Op::Eq, false) => "eqIgnoreCase",
+            (Op::Match | Op::Eq, true) => "neqIgnoreCase",
+            (Op::Contains, false) => "containsIgnoreCase",
+            (Op::Contains, true) => "notContainsIgnoreCase",
+            _ => return Err(self.unsupported(op)),
+        };
+        Ok(obj(key, json!(self.value)))
+    }
+
+    /// A boolean flag on the related entity, like `cycle: { isActive: { eq: true } }`
+    fn flag(&self, name: &str, op: Op, negated: bool) -> Result<Value> {
+        self.require_equality(op)?;
+        Ok(obj(name, json!({ "eq": !negated })))
+    }
+
+    fn require_equality(&self, op: Op) -> Result<()> {
+        match op {
+            Op::Match | Op::Eq => Ok(()),
+            _ => Err(self.unsupported(op)),
+        }
+    }
+
+    fn unsupported(&self, op: Op) -> anyhow::Error {
+        anyhow::anyhow!(
+            "'{}' can't be used with {}:{}",
+            op,
+            self.field.name(),
+            self.value
+        )
+    }
+}
+
+/// A number or date comparator
+fn compare(op: Op, value: Value, negated: bool, term: &Term) -> Result<Value> {
+    let key = match (op, negated) {
+        (Op::Match | Op::Eq, false) => "eq",
+        (Op::Match | Op::Eq, true) => "neq",
+        (Op::Lt, false) | (Op::Ge, true) => "lt",
+        (Op::Le, false) | (Op::Gt, true) => "lte",
+        (Op::Gt, false) | (Op::Le, true) => "gt",
+        (Op::Ge, false) | (Op::Lt, true) => "gte",
+        _ => return Err(term.unsupported(op)),
+    };
+    Ok(obj(key, value))
+}
+
+fn obj(key: &str, value: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(key.to_string(), value);
+    Value::Object(map)
+}
+
+/// Alternatives that each (already negated if `negated`) describe a match
+fn any_of(filters: Vec<Value>, negated: bool) -> Value {
+    obj(if negated { "and" } else { "or" }, json!(filters))
+}
+
+/// A condition on an optional relation or field. Negated, it also matches issues
+/// where the value is unset, which `neq`-style comparators alone would not.
+fn relation(field: &str, inner: Value, negated: bool) -> Value {
+    if negated {
+        json!({ "or": [obj(field, json!({ "null": true })), obj(field, inner)] })
+    } else {
+        obj(field, inner)
+    }
+}
+
+fn parse_priority(value: &str) -> Option<i64> {
+    match value.to_lowercase().as_str() {
+        "none" | "no" => Some(0),
+        "urgent" => Some(1),
+        "high" => Some(2),
+        "normal" | "medium" => Some(3),
+        "low" => Some(4),
+        other => other.parse().ok().filter(|p| (0..=4).contains(p)),
+    }
+}
+
+/// Split an identifier like "ENG-123" into its team key and number
+fn parse_identifier(value: &str) -> Option<(&str, i64)> {
+    let (key, number) = value.rsplit_once('-')?;
+    if key.is_empty() {
+        return None;
+    }
+    Some((key, number.parse().ok()?))
+}
+
+/// Dates are either absolute (`2024-06-01`, RFC 3339) or relative to now, like
+/// `-7d` or `2w`, which become ISO 8601 durations (`-P7D`, `P2W`) that Linear
+/// evaluates server-side.
+fn parse_date(value: &str) -> Result<String> {
+    if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+    {
+        return Ok(value.to_string());
+    }
+
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    };
+    let split = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (amount, unit) = rest.split_at(split);
+    let amount: u32 = amount.parse().ok().with_context(|| {
+        format!(
+            "Invalid date '{}': use YYYY-MM-DD or a relative offset like -7d, 2w, -3m",
+            value
+        )
+    })?;
+
+    let duration = match unit.to_lowercase().as_str() {
+        "h" => format!("PT{}H", amount),
+        "d" => format!("P{}D", amount),
+        "w" => format!("P{}W", amount),
+        "m" => format!("P{}M", amount),
+        "y" => format!("P{}Y", amount),
+        _ => anyhow::bail!(
+            "Invalid date unit in '{}': use h, d, w, m or y (e.g. -7d)",
+            value
+        ),
+    };
+    Ok(format!("{}{}", sign, duration))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some(ch) => text.push(ch),
+                        None => anyhow::bail!("Unterminated quote in filter"),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            ':' | '=' | '~' | '<' | '>' | '!' => {
+                chars.next();
+                let followed_by_eq = chars.peek() == Some(&'=');
+                let op = match (c, followed_by_eq) {
+                    (':', _) => Op::Match,
+                    ('=', _) => Op::Eq,
+                    ('~', _) => Op::Contains,
+                    ('<', true) => Op::Le,
+                    ('<', false) => Op::Lt,
+                    ('>', true) => Op::Ge,
+                    ('>', false) => Op::Gt,
+                    ('!', true) => Op::Ne,
+                    ('!', false) => anyhow::bail!("Expected '!=' in filter; use 'not' to negate"),
+                    _ => unreachable!(),
+                };
+                if followed_by_eq && matches!(op, Op::Le | Op::Ge | Op::Ne) {
+                    chars.next();
+                }
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()\"':=~<>!".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.eat_keyword("or") {
+            exprs.push(self.parse_and()?);
+        }
+        Ok(flatten(exprs, Expr::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut exprs = vec![self.parse_unary()?];
+        loop {
+            if self.eat_keyword("and") {
+                exprs.push(self.parse_unary()?);
+                continue;
+            }
+            // Adjacent conditions are implicitly and-ed: `label:bug priority<=2`
+            match self.peek() {
+                Some(Token::Word(w)) if !w.eq_ignore_ascii_case("or") => {
+                    exprs.push(self.parse_unary()?)
+                }
+                Some(Token::LParen) => exprs.push(self.parse_unary()?),
+                _ => break,
+            }
+        }
+        Ok(flatten(exprs, Expr::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            match self.next() {
+                Some(Token::RParen) => return Ok(expr),
+                Some(token) => anyhow::bail!("Expected ')' but found {}", token),
+                None => anyhow::bail!("Missing ')'"),
+            }
+        }
+        self.parse_term()
+    }
+
+    fn parse_term(&mut self) -> Result<Expr> {
+        let name = match self.next() {
+            Some(Token::Word(word)) => word,
+            Some(token) => anyhow::bail!("Expected a field name but found {}", token),
+            None => anyhow::bail!("Expected a condition like label:bug"),
+        };
+        let field = Field::parse(&name)
+            .with_context(|| format!("Unknown field '{}'. Fields: {}", name, FIELDS))?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => anyhow::bail!("Expected an operator (: = != < <= > >= ~) after '{}'", name),
+        };
+        let value = match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => value,
+            _ => anyhow::bail!("Expected a value after '{}{}'", name, op),
+        };
+        Ok(Expr::Term(Term { field, op, value }))
+    }
+}
+
+fn flatten(mut exprs: Vec<Expr>, combine: fn(Vec<Expr>) -> Expr) -> Expr {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        combine(exprs)
+    }
+}
+
+/// Whether `node` passes `filter`, an `IssueFilter`-style object like the
+/// ones [`compile`] builds. Comparisons on an unset value fail, except `null`,
+/// as they do server-side. Durations such as `-P7D` are taken relative to `now`.
+pub fn matches(filter: &Value, node: &Value, now: DateTime<Utc>) -> bool {
+    let Some(conditions) = filter.as_object() else {
+        return true;
+    };
+    conditions.iter().all(|(key, arg)| match key.as_str() {
+        "and" => arg
+            .as_array()
+            .is_none_or(|all| all.iter().all(|f| matches(f, node, now))),
+        "or" => arg
+            .as_array()
+            .is_none_or(|any| any.iter().any(|f| matches(f, node, now))),
+        "null" => node.is_null() == arg.as_bool().unwrap_or(true),
+        "some" => connection(node).iter().any(|n| matches(arg, n, now)),
+        "every" => connection(node).iter().all(|n| matches(arg, n, now)),
+        "length" => matches(arg, &json!(connection(node).len()), now),
+        "eq"
+        | "neq"
+        | "lt"
+        | "lte"
+        | "gt"
+        | "gte"
+        | "eqIgnoreCase"
+        | "neqIgnoreCase"
+        | "containsIgnoreCase"
+        | "notContainsIgnoreCase"
+        | "in"
+        | "nin" => !node.is_null() && compare_value(key, node, arg, now),
+        field => matches(arg, node.get(field).unwrap_or(&Value::Null), now),
+    })
+}
+
+/// The nodes of a connection (`{ nodes: [...] }`) or a plain list
+fn connection(value: &Value) -> &[Value] {
+    value
+        .get("nodes")
+        .unwrap_or(value)
+        .as_array()
+        .map_or(&[], |nodes| nodes.as_slice())
+}
+
+fn compare_value(op: &str, value: &Value, arg: &Value, now: DateTime<Utc>) -> bool {
+    let text = |v: &Value| match v {
+        Value::String(s) => s.to_lowercase(),
+        other => other.to_string().to_lowercase(),
+    };
+    match op {
+        "eqIgnoreCase" => return text(value) == text(arg),
+        "neqIgnoreCase" => return text(value) != text(arg),
+        "containsIgnoreCase" => return text(value).contains(&text(arg)),
+        "notContainsIgnoreCase" => return !text(value).contains(&text(arg)),
+        "in" => {
+            return connection(arg)
+                .iter()
+                .any(|a| ordering(value, a, now).is_some_and(|o| o.is_eq()))
+        }
+        "nin" => {
+            return !connection(arg)
+                .iter()
+                .any(|a| ordering(value, a, now).is_some_and(|o| o.is_eq()))
+        }
+        _ => {}
+    }
+    let Some(ordering) = ordering(value, arg, now) else {
+        return op == "neq";
+    };
+    match op {
+        "eq" => ordering.is_eq(),
+        "neq" => ordering.is_ne(),
+        "lt" => ordering.is_lt(),
+        "lte" => ordering.is_le(),
+        "gt" => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }
+}
+
+/// Compare numbers as numbers, dates as instants and anything else as is
+fn ordering(value: &Value, arg: &Value, now: DateTime<Utc>) -> Option<std::cmp::Ordering> {
+    if let (Some(a), Some(b)) = (value.as_f64(), arg.as_f64()) {
+        return a.partial_cmp(&b);
+    }
+    if let (Some(a), Some(b)) = (value.as_str(), arg.as_str()) {
+        if let (Some(a), Some(b)) = (instant(a, now), instant(b, now)) {
+            return Some(a.cmp(&b));
+        }
+        return Some(a.cmp(b));
+    }
+    (value == arg).then_some(std::cmp::Ordering::Equal)
+}
+
+/// A date, timestamp or ISO 8601 duration (`-P7D`, `P2W`, `-PT4H`) as an instant
+fn instant(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    let (ago, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let (time, rest) = match rest.strip_prefix('T') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let (amount, unit) = rest.split_at(rest.len().checked_sub(1)?);
+    let amount: u32 = amount.parse().ok()?;
+    let months = |n: u32| Months::new(n);
+    match (time, unit) {
+        (true, "H") => {
+            let delta = Duration::hours(amount.into());
+            Some(if ago { now - delta } else { now + delta })
+        }
+        (false, "D" | "W") => {
+            let days = if unit == "W" { amount * 7 } else { amount };
+            let delta = Duration::days(days.into());
+            Some(if ago { now - delta } else { now + delta })
+        }
+        (false, "M" | "Y") => {
+            let n = if unit == "Y" { amount * 12 } else { amount };
+            if ago {
+                now.checked_sub_months(months(n))
+            } else {
+                now.checked_add_months(months(n))
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiles_the_example_expression() {
+        let filter =
+            compile("priority<=2 and label:bug and updated>-7d and not assignee:none").unwrap();
+        assert_eq!(
+            filter,
+            json!({ "and": [
+                { "priority": { "lte": 2, "neq": 0 } },
+                { "labels": { "some": { "name": { "eqIgnoreCase": "bug" } } } },
+                { "updatedAt": { "gt": "-P7D" } },
+                { "assignee": { "null": false } }
+            ] })
+        );
+    }
+
+    #[test]
+    fn test_precedence_parentheses_and_implicit_and() {
+        let expr =
+            parse("label:bug priority:urgent or (state:Todo and not cycle:current)").unwrap();
+        let Expr::Or(branches) = expr else {
+            panic!("expected or, got {:?}", expr)
+        };
+        assert!(matches!(&branches[0], Expr::And(terms) if terms.len() == 2));
+        assert!(matches!(&branches[1], Expr::And(terms) if matches!(terms[1], Expr::Not(_))));
+    }
+
+    #[test]
+    fn test_negation_is_pushed_down() {
+        assert_eq!(
+            compile("not (priority<2 or label:bug)").unwrap(),
+            json!({ "and": [
+                { "priority": { "gte": 2 } },
+                { "labels": { "every": { "name": { "neqIgnoreCase": "bug" } } } }
+            ] })
+        );
+        // Negating a condition on an optional relation keeps issues without one
+        assert_eq!(
+            compile("project!=Roadmap").unwrap(),
+            json!({ "or": [
+                { "project": { "null": true } },
+                { "project": { "name": { "neqIgnoreCase": "Roadmap" } } }
+            ] })
+        );
+        assert_eq!(
+            compile("not not estimate>=3").unwrap(),
+            json!({ "estimate": { "gte": 3.0 } })
+        );
+    }
+
+    #[test]
+    fn test_field_specific_values() {
+        assert_eq!(
+            compile("assignee:me").unwrap(),
+            json!({ "assignee": { "isMe": { "eq": true } } })
+        );
+        assert_eq!(
+            compile("creator:alice@example.com").unwrap(),
+            json!({ "creator": { "email": { "eqIgnoreCase": "alice@example.com" } } })
+        );
+        assert_eq!(
+            compile("cycle:current").unwrap(),
+            json!({ "cycle": { "isActive": { "eq": true } } })
+        );
+        assert_eq!(
+            compile("cycle>=12").unwrap(),
+            json!({ "cycle": { "number": { "gte": 12 } } })
+        );
+        assert_eq!(
+            compile("parent:ENG-12").unwrap(),
+            json!({ "parent": {
+                "number": { "eq": 12 },
+                "team": { "key": { "eqIgnoreCase": "ENG" } }
+            } })
+        );
+        assert_eq!(
+            compile("due<2w and created>=2024-01-01").unwrap(),
+            json!({ "and": [
+                { "dueDate": { "lt": "P2W" } },
+                { "createdAt": { "gte": "2024-01-01" } }
+            ] })
+        );
+        assert_eq!(
+            compile("title:\"sign in\" label:none").unwrap(),
+            json!({ "and": [
+                { "title": { "containsIgnoreCase": "sign in" } },
+                { "labels": { "length": { "eq": 0 } } }
+            ] })
+        );
+        assert_eq!(
+            compile("team:ENG").unwrap(),
+            json!({ "team": { "or": [
+                { "key": { "eqIgnoreCase": "ENG" } },
+                { "name": { "eqIgnoreCase": "ENG" } }
+            ] } })
+        );
+    }
+
+    #[test]
+    fn test_errors_are_descriptive() {
+        let error = |input: &str| format!("{:#}", compile(input).unwrap_err());
+        assert!(error("colour:red").contains("Unknown field 'colour'"));
+        assert!(error("label<bug").contains("'<' can't be used with label:bug"));
+        assert!(error("priority:huge").contains("Invalid priority 'huge'"));
+        assert!(error("updated>-7x").contains("Invalid date unit"));
+        assert!(error("(label:bug").contains("Missing ')'"));
+        assert!(error("label:").contains("Expected a value after 'label:'"));
+        assert!(error("label:bug and").contains("Expected a condition"));
+        assert!(error("title:\"open").contains("Unterminated quote"));
+    }
+
+    #[test]
+    fn test_compiled_filters_match_locally() {
+        let now = DateTime::parse_from_rfc3339("2024-06-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let issue = json!({
+            "title": "Crash on login",
+            "priority": 2,
+            "estimate": null,
+            "updatedAt": "2024-06-12T09:00:00.000Z",
+            "dueDate": "2024-06-20",
+            "team": { "key": "ENG", "name": "Engineering" },
+            "state": { "name": "In Progress" },
+            "assignee": { "name": "Alice", "displayName": "alice", "isMe": true },
+            "project": null,
+            "cycle": { "number": 7, "isActive": true, "isNext": false, "isPrevious": false },
+            "parent": { "number": 12, "team": { "key": "ENG" } },
+            "labels": { "nodes": [{ "name": "Bug" }, { "name": "Backend" }] }
+        });
+        let check = |expression: &str| matches(&compile(expression).unwrap(), &issue, now);
+
+        assert!(check(
+            "priority<=2 and label:bug and updated>-7d and not assignee:none"
+        ));
+        assert!(check(
+            "team:eng state:\"in progress\" assignee:me cycle:current"
+        ));
+        assert!(check("title:login and not label:frontend and project:none"));
+        assert!(check("parent:ENG-12 and due<2024-07-01 and cycle>=7"));
+        assert!(check("not estimate:3"));
+        assert!(!check("updated>-2d"));
+        assert!(!check("label:frontend or priority:urgent"));
+        assert!(!check("label:none"));
+        assert!(!check("estimate<5"));
+        assert!(!check("assignee:bob"));
+
+        // Issues without a priority aren't urgent or high
+        let mut unprioritized = issue.clone();
+        unprioritized["priority"] = json!(0);
+        let filter = compile("priority<=2").unwrap();
+        assert!(!matches(&filter, &unprioritized, now));
+        assert!(!matches(
+            &compile("not priority>=3").unwrap(),
+            &unprioritized,
+            now
+        ));
+        assert!(matches(
+            &compile("priority:none").unwrap(),
+            &unprioritized,
+            now
+        ));
+        assert!(matches(
+            &compile("priority>2").unwrap(),
+            &json!({ "priority": 4 }),
+            now
+        ));
+    }
+}
@@ -0,0 +1,938 @@
+//! The subset of jq understood by `--query`.
+//!
+//! Paths (`.state.name`, `.[0]`, `.[]`, `.labels[]?`), pipes, `,`, comparisons,
+//! `and`/`or`/`not`, `//`, array and object construction, literals, and the
+//! usual helpers: `select`, `map`, `length`, `keys`, `values`, `first`, `last`,
+//! `sort`, `sort_by`, `group_by`, `unique`, `reverse`, `add`, `join`, `has`,
+//! `contains`, `startswith`, `endswith`, `ascii_downcase`, `ascii_upcase`,
+//! `tostring`, `tonumber`, `type`, `min`, `max`, `empty`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+/// A compiled `--query` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(Expr);
+
+impl Filter {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.pipe()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {} in query", token.describe());
+        }
+        Ok(Filter(expr))
+    }
+
+    /// Run the filter. Like jq, a filter can produce any number of results.
+    pub fn run(&self, input: &Value) -> Result<Vec<Value>> {
+        eval(&self.0, input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Identity,
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Iterate(Box<Expr>),
+    Optional(Box<Expr>),
+    Literal(Value),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Expr, Expr)>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Alternative(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Dot => "'.'".to_string(),
+            Token::DotDot => "'..'".to_string(),
+            Token::Field(name) => format!("'.{}'", name),
+            Token::Ident(name) => format!("'{}'", name),
+            Token::Str(s) => format!("\"{}\"", s),
+            Token::Num(n) => format!("'{}'", n),
+            Token::Punct(p) => format!("'{}'", p),
+        }
+    }
+}
+
+const PUNCTUATION: &[&str] = &[
+    "==", "!=", "<=", ">=", "//", "|", ",", "<", ">", "(", ")", "[", "]", "{", "}", ":", ";", "?",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '.' {
+            if chars.get(i + 1) == Some(&'.') {
+                tokens.push(Token::DotDot);
+                i += 2;
+            } else if chars.get(i + 1).is_some_and(|&c| is_name_start(c)) {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && is_name_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Field(chars[start..i].iter().collect()));
+            } else if chars.get(i + 1) == Some(&'"') {
+                i += 1;
+                let (s, next) = string(&chars, i)?;
+                tokens.push(Token::Field(s));
+                i = next;
+            } else {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+        } else if c == '"' {
+            let (s, next) = string(&chars, i)?;
+            tokens.push(Token::Str(s));
+            i = next;
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text
+                .parse()
+                .with_context(|| format!("Invalid number '{}' in query", text))?;
+            tokens.push(Token::Num(n));
+        } else if is_name_start(c) {
+            let start = i;
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let punct = PUNCTUATION
+                .iter()
+                .find(|p| rest.starts_with(**p))
+                .ok_or_else(|| anyhow!("Unexpected '{}' in query", c))?;
+            tokens.push(Token::Punct(punct));
+            i += punct.len();
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// A double-quoted string starting at `chars[start]`; returns it and the
+/// index after the closing quote
+fn string(chars: &[char], start: usize) -> Result<(String, usize)> {
+    let mut out = String::new();
+    let mut i = start + 1;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            '"' => return Ok((out, i + 1)),
+            '\\' => {
+                i += 1;
+                match chars.get(i) {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some(&other) => out.push(other),
+                    None => break,
+                }
+            }
+            other => out.push(other),
+        }
+        i += 1;
+    }
+    bail!("Unterminated string in query")
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<()> {
+        if self.eat(punct) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => bail!(
+                "Expected '{}' but found {} in query",
+                punct,
+                token.describe()
+            ),
+            None => bail!("Expected '{}' at the end of the query", punct),
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Expr> {
+        let mut left = self.comma()?;
+        while self.eat("|") {
+            let right = self.comma()?;
+            left = Expr::Pipe(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Expr> {
+        let mut left = self.alternative()?;
+        while self.eat(",") {
+            let right = self.alternative()?;
+            left = Expr::Comma(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn alternative(&mut self) -> Result<Expr> {
+        let mut left = self.or()?;
+        while self.eat("//") {
+            let right = self.or()?;
+            left = Expr::Alternative(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.eat_keyword("or") {
+            let right = self.and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.comparison()?;
+        while self.eat_keyword("and") {
+            let right = self.comparison()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.postfix()?;
+        let op = match self.peek() {
+            Some(Token::Punct("==")) => CompareOp::Eq,
+            Some(Token::Punct("!=")) => CompareOp::Ne,
+            Some(Token::Punct("<")) => CompareOp::Lt,
+            Some(Token::Punct("<=")) => CompareOp::Le,
+            Some(Token::Punct(">")) => CompareOp::Gt,
+            Some(Token::Punct(">=")) => CompareOp::Ge,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.postfix()?;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(right)))
+    }
+
+    fn postfix(&mut self) -> Result<Expr> {
+        let mut expr = self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = name.clone();
+                    self.pos += 1;
+                    expr = Expr::Field(Box::new(expr), name);
+                }
+                Some(Token::Dot) if self.tokens.get(self.pos + 1) == Some(&Token::Punct("[")) => {
+                    self.pos += 1;
+                }
+                Some(Token::Punct("[")) => {
+                    self.pos += 1;
+                    expr = self.bracket(expr)?;
+                }
+                Some(Token::Punct("?")) => {
+                    self.pos += 1;
+                    expr = Expr::Optional(Box::new(expr));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// The rest of `[]` or `[index]` after the opening bracket
+    fn bracket(&mut self, target: Expr) -> Result<Expr> {
+        if self.eat("]") {
+            return Ok(Expr::Iterate(Box::new(target)));
+        }
+        let index = self.pipe()?;
+        self.expect("]")?;
+        Ok(Expr::Index(Box::new(target), Box::new(index)))
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| anyhow!("Query ended unexpectedly"))?;
+        self.pos += 1;
+        match token {
+            Token::Dot => {
+                if self.eat("[") {
+                    return self.bracket(Expr::Identity);
+                }
+                Ok(Expr::Identity)
+            }
+            Token::DotDot => bail!("Recursive descent '..' isn't supported in --query"),
+            Token::Field(name) => Ok(Expr::Field(Box::new(Expr::Identity), name)),
+            Token::Str(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::Num(n) => Ok(Expr::Literal(number(n))),
+            Token::Punct("(") => {
+                let inner = self.pipe()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Punct("[") => {
+                if self.eat("]") {
+                    return Ok(Expr::Array(None));
+                }
+                let inner = self.pipe()?;
+                self.expect("]")?;
+                Ok(Expr::Array(Some(Box::new(inner))))
+            }
+            Token::Punct("{") => self.object(),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => {
+                    let mut args = Vec::new();
+                    if self.eat("(") {
+                        loop {
+                            args.push(self.pipe()?);
+                            if !self.eat(";") {
+                                break;
+                            }
+                        }
+                        self.expect(")")?;
+                    }
+                    check_call(&name, args.len())?;
+                    Ok(Expr::Call(name, args))
+                }
+            },
+            other => bail!("Unexpected {} in query", other.describe()),
+        }
+    }
+
+    /// `{a, b: .x, "c d": .y, (.k): .v}` after the opening brace
+    fn object(&mut self) -> Result<Expr> {
+        let mut entries = Vec::new();
+        if self.eat("}") {
+            return Ok(Expr::Object(entries));
+        }
+        loop {
+            let key = match self.peek().cloned() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => {
+                    self.pos += 1;
+                    name
+                }
+                Some(Token::Punct("(")) => {
+                    self.pos += 1;
+                    let key = self.pipe()?;
+                    self.expect(")")?;
+                    self.expect(":")?;
+                    entries.push((key, self.alternative()?));
+                    if self.eat("}") {
+                        return Ok(Expr::Object(entries));
+                    }
+                    self.expect(",")?;
+                    continue;
+                }
+                Some(token) => bail!("Unexpected {} in object", token.describe()),
+                None => bail!("Query ended inside an object"),
+            };
+            let value = if self.eat(":") {
+                self.alternative()?
+            } else {
+                Expr::Field(Box::new(Expr::Identity), key.clone())
+            };
+            entries.push((Expr::Literal(Value::String(key)), value));
+            if self.eat("}") {
+                return Ok(Expr::Object(entries));
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+fn check_call(name: &str, args: usize) -> Result<()> {
+    let expected = match name {
+        "length" | "keys" | "values" | "not" | "first" | "last" | "sort" | "unique" | "reverse"
+        | "add" | "tostring" | "tonumber" | "type" | "ascii_downcase" | "ascii_upcase" | "min"
+        | "max" | "empty" => 0,
+        "select" | "map" | "sort_by" | "group_by" | "join" | "has" | "contains" | "startswith"
+        | "endswith" => 1,
+        _ => bail!("Unknown function '{}' in query", name),
+    };
+    if args != expected {
+        bail!("{} takes {} argument(s), not {}", name, expected, args);
+    }
+    Ok(())
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        Value::Number((n as i64).into())
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>> {
+    Ok(match expr {
+        Expr::Identity => vec![input.clone()],
+        Expr::Literal(value) => vec![value.clone()],
+        Expr::Field(target, name) => eval(target, input)?
+            .iter()
+            .map(|v| index(v, &Value::String(name.clone())))
+            .collect::<Result<_>>()?,
+        Expr::Index(target, index_expr) => {
+            let mut out = Vec::new();
+            for value in eval(target, input)? {
+                for key in eval(index_expr, input)? {
+                    out.push(index(&value, &key)?);
+                }
+            }
+            out
+        }
+        Expr::Iterate(target) => {
+            let mut out = Vec::new();
+            for value in eval(target, input)? {
+                match value {
+                    Value::Array(items) => out.extend(items),
+                    Value::Object(map) => out.extend(map.into_iter().map(|(_, v)| v)),
+                    other => bail!("Cannot iterate over {}", type_name(&other)),
+                }
+            }
+            out
+        }
+        Expr::Optional(inner) => eval(inner, input).unwrap_or_default(),
+        Expr::Array(None) => vec![Value::Array(Vec::new())],
+        Expr::Array(Some(inner)) => vec![Value::Array(eval(inner, input)?)],
+        Expr::Object(entries) => {
+            let mut objects = vec![Map::new()];
+            for (key_expr, value_expr) in entries {
+                let keys = eval(key_expr, input)?;
+                let values = eval(value_expr, input)?;
+                let mut next = Vec::new();
+                for object in &objects {
+                    for key in &keys {
+                        let Value::String(key) = key else {
+                            bail!("Object keys must be strings, not {}", type_name(key));
+                        };
+                        for value in &values {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), value.clone());
+                            next.push(object);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            objects.into_iter().map(Value::Object).collect()
+        }
+        Expr::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            out
+        }
+        Expr::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            out
+        }
+        Expr::Alternative(left, right) => {
+            let truthy: Vec<Value> = eval(left, input)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(is_truthy)
+                .collect();
+            if truthy.is_empty() {
+                eval(right, input)?
+            } else {
+                truthy
+            }
+        }
+        Expr::And(left, right) => logic(left, right, input, false)?,
+        Expr::Or(left, right) => logic(left, right, input, true)?,
+        Expr::Compare(left, op, right) => {
+            let mut out = Vec::new();
+            for r in eval(right, input)? {
+                for l in eval(left, input)? {
+                    let ordering = compare(&l, &r);
+                    let result = match op {
+                        CompareOp::Eq => ordering == Ordering::Equal,
+                        CompareOp::Ne => ordering != Ordering::Equal,
+                        CompareOp::Lt => ordering == Ordering::Less,
+                        CompareOp::Le => ordering != Ordering::Greater,
+                        CompareOp::Gt => ordering == Ordering::Greater,
+                        CompareOp::Ge => ordering != Ordering::Less,
+                    };
+                    out.push(Value::Bool(result));
+                }
+            }
+            out
+        }
+        Expr::Call(name, args) => call(name, args, input)?,
+    })
+}
+
+/// `and` / `or`: the right side only runs when the left doesn't decide it
+fn logic(left: &Expr, right: &Expr, input: &Value, is_or: bool) -> Result<Vec<Value>> {
+    let mut out = Vec::new();
+    for l in eval(left, input)? {
+        if is_truthy(&l) == is_or {
+            out.push(Value::Bool(is_or));
+            continue;
+        }
+        for r in eval(right, input)? {
+            out.push(Value::Bool(is_truthy(&r)));
+        }
+    }
+    Ok(out)
+}
+
+fn index(value: &Value, key: &Value) -> Result<Value> {
+    Ok(match (value, key) {
+        (Value::Null, _) => Value::Null,
+        (Value::Object(map), Value::String(k)) => map.get(k).cloned().unwrap_or(Value::Null),
+        (Value::Array(items), Value::Number(n)) => {
+            let i = n.as_f64().unwrap_or(0.0) as i64;
+            let i = if i < 0 { items.len() as i64 + i } else { i };
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Value::Null)
+        }
+        (other, Value::String(k)) => {
+            bail!("Cannot index {} with \"{}\"", type_name(other), k)
+        }
+        (other, k) => bail!("Cannot index {} with {}", type_name(other), type_name(k)),
+    })
+}
+
+fn call(name: &str, args: &[Expr], input: &Value) -> Result<Vec<Value>> {
+    let one = |v: Value| Ok(vec![v]);
+    match name {
+        "empty" => Ok(Vec::new()),
+        "not" => one(Value::Bool(!is_truthy(input))),
+        "select" => {
+            let mut out = Vec::new();
+            for cond in eval(&args[0], input)? {
+                if is_truthy(&cond) {
+                    out.push(input.clone());
+                }
+            }
+            Ok(out)
+        }
+        "map" => {
+            let mut out = Vec::new();
+            for item in items(input, name)? {
+                out.extend(eval(&args[0], item)?);
+            }
+            one(Value::Array(out))
+        }
+        "length" => one(match input {
+            Value::Null => 0.into(),
+            Value::Bool(_) => bail!("boolean has no length"),
+            Value::Number(n) => number(n.as_f64().unwrap_or(0.0).abs()),
+            Value::String(s) => s.chars().count().into(),
+            Value::Array(items) => items.len().into(),
+            Value::Object(map) => map.len().into(),
+        }),
+        "keys" => match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                one(keys.into_iter().map(|k| Value::String(k.clone())).collect())
+            }
+            Value::Array(items) => one((0..items.len()).collect()),
+            other => bail!("{} has no keys", type_name(other)),
+        },
+        "values" => match input {
+            Value::Object(map) => one(map.values().cloned().collect()),
+            other => one(other.clone()),
+        },
+        "first" => one(items(input, name)?.first().cloned().unwrap_or(Value::Null)),
+        "last" => one(items(input, name)?.last().cloned().unwrap_or(Value::Null)),
+        "reverse" => one(match input {
+            Value::String(s) => Value::String(s.chars().rev().collect()),
+            Value::Null => Value::Array(Vec::new()),
+            other => Value::Array(items(other, name)?.iter().rev().cloned().collect()),
+        }),
+        "sort" => {
+            let mut sorted = items(input, name)?.to_vec();
+            sorted.sort_by(compare);
+            one(Value::Array(sorted))
+        }
+        "unique" => {
+            let mut sorted = items(input, name)?.to_vec();
+            sorted.sort_by(compare);
+            sorted.dedup();
+            one(Value::Array(sorted))
+        }
+        "sort_by" | "group_by" => {
+            let mut keyed = Vec::new();
+            for item in items(input, name)? {
+                keyed.push((eval(&args[0], item)?, item.clone()));
+            }
+            keyed.sort_by(|(a, _), (b, _)| compare_lists(a, b));
+            if name == "sort_by" {
+                return one(keyed.into_iter().map(|(_, item)| item).collect());
+            }
+            let mut groups: Vec<(Vec<Value>, Vec<Value>)> = Vec::new();
+            for (key, item) in keyed {
+                match groups.last_mut() {
+                    Some((last, group)) if *last == key => group.push(item),
+                    _ => groups.push((key, vec![item])),
+                }
+            }
+            one(groups
+                .into_iter()
+                .map(|(_, group)| Value::Array(group))
+                .collect())
+        }
+        "min" | "max" => {
+            let list = items(input, name)?;
+            let found = if name == "min" {
+                list.iter().min_by(|a, b| compare(a, b))
+            } else {
+                list.iter().max_by(|a, b| compare(a, b))
+            };
+            one(found.cloned().unwrap_or(Value::Null))
+        }
+        "add" => {
+            let mut total: Option<Value> = None;
+            for item in items(input, name)? {
+                total = Some(match total {
+                    None => item.clone(),
+                    Some(acc) => add(acc, item)?,
+                });
+            }
+            one(total.unwrap_or(Value::Null))
+        }
+        "join" => {
+            let mut out = Vec::new();
+            for sep in eval(&args[0], input)? {
+                let Value::String(sep) = sep else {
+                    bail!("join needs a string separator");
+                };
+                let parts: Vec<String> = items(input, name)?
+                    .iter()
+                    .map(|item| match item {
+                        Value::Null => Ok(String::new()),
+                        Value::String(s) => Ok(s.clone()),
+                        Value::Bool(_) | Value::Number(_) => Ok(item.to_string()),
+                        other => bail!("Cannot join {}", type_name(other)),
+                    })
+                    .collect::<Result<_>>()?;
+                out.push(Value::String(parts.join(&sep)));
+            }
+            Ok(out)
+        }
+        "has" => {
+            let mut out = Vec::new();
+            for key in eval(&args[0], input)? {
+                out.push(Value::Bool(match (input, &key) {
+                    (Value::Object(map), Value::String(k)) => map.contains_key(k),
+                    (Value::Array(items), Value::Number(n)) => {
+                        n.as_u64().is_some_and(|i| (i as usize) < items.len())
+                    }
+                    (other, _) => bail!("Cannot check whether {} has a key", type_name(other)),
+                }));
+            }
+            Ok(out)
+        }
+        "contains" => {
+            let mut out = Vec::new();
+            for needle in eval(&args[0], input)? {
+                out.push(Value::Bool(contains(input, &needle)));
+            }
+            Ok(out)
+        }
+        "startswith" | "endswith" => {
+            let mut out = Vec::new();
+            for affix in eval(&args[0], input)? {
+                let (Value::String(s), Value::String(affix)) = (input, &affix) else {
+                    bail!("{} needs strings", name);
+                };
+                out.push(Value::Bool(if name == "startswith" {
+                    s.starts_with(affix.as_str())
+                } else {
+                    s.ends_with(affix.as_str())
+                }));
+            }
+            Ok(out)
+        }
+        "ascii_downcase" | "ascii_upcase" => match input {
+            Value::String(s) => one(Value::String(if name == "ascii_downcase" {
+                s.to_ascii_lowercase()
+            } else {
+                s.to_ascii_uppercase()
+            })),
+            other => bail!("{} needs a string, not {}", name, type_name(other)),
+        },
+        "tostring" => one(match input {
+            Value::String(_) => input.clone(),
+            other => Value::String(other.to_string()),
+        }),
+        "tonumber" => one(match input {
+            Value::Number(_) => input.clone(),
+            Value::String(s) => number(
+                s.trim()
+                    .parse()
+                    .with_context(|| format!("Cannot parse '{}' as a number", s))?,
+            ),
+            other => bail!("Cannot convert {} to a number", type_name(other)),
+        }),
+        "type" => one(Value::String(type_name(input).to_string())),
+        _ => bail!("Unknown function '{}' in query", name),
+    }
+}
+
+fn items<'a>(value: &'a Value, function: &str) -> Result<&'a [Value]> {
+    match value {
+        Value::Array(items) => Ok(items),
+        Value::Null => Ok(&[]),
+        other => bail!("{} needs an array, not {}", function, type_name(other)),
+    }
+}
+
+fn add(left: Value, right: &Value) -> Result<Value> {
+    Ok(match (left, right) {
+        (Value::Null, r) => r.clone(),
+        (l, Value::Null) => l,
+        (Value::Number(a), Value::Number(b)) => {
+            number(a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0))
+        }
+        (Value::String(a), Value::String(b)) => Value::String(a + b),
+        (Value::Array(mut a), Value::Array(b)) => {
+            a.extend(b.iter().cloned());
+            Value::Array(a)
+        }
+        (Value::Object(mut a), Value::Object(b)) => {
+            a.extend(b.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Value::Object(a)
+        }
+        (l, r) => bail!("Cannot add {} and {}", type_name(&l), type_name(r)),
+    })
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match (haystack, needle) {
+        (Value::String(h), Value::String(n)) => h.contains(n.as_str()),
+        (Value::Array(h), Value::Array(n)) => n.iter().all(|n| h.iter().any(|h| contains(h, n))),
+        (Value::Object(h), Value::Object(n)) => n
+            .iter()
+            .all(|(k, n)| h.get(k).is_some_and(|h| contains(h, n))),
+        (h, n) => h == n,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects
+fn compare(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => compare_lists(x, y),
+        (Value::Object(x), Value::Object(y)) => {
+            let mut xk: Vec<&String> = x.keys().collect();
+            let mut yk: Vec<&String> = y.keys().collect();
+            xk.sort();
+            yk.sort();
+            xk.cmp(&yk).then_with(|| {
+                xk.iter()
+                    .map(|k| compare(&x[*k], &y[*k]))
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn compare_lists(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare(x, y))
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn issues() -> Value {
+        json!([
+            { "identifier": "ENG-1", "priority": 2, "state": { "name": "Todo" },
+              "labels": { "nodes": [{ "name": "bug" }] } },
+            { "identifier": "ENG-2", "priority": 1, "state": { "name": "Done" },
+              "labels": { "nodes": [] } },
+            { "identifier": "ENG-3", "priority": 3, "state": null,
+              "labels": { "nodes": [{ "name": "bug" }, { "name": "ui" }] } }
+        ])
+    }
+
+    /// Every result of the query, as an array
+    fn run(query: &str) -> Value {
+        Value::Array(Filter::parse(query).unwrap().run(&issues()).unwrap())
+    }
+
+    #[test]
+    fn test_paths_and_iteration() {
+        assert_eq!(run(".[0].identifier"), json!(["ENG-1"]));
+        assert_eq!(run(".[-1].state.name"), json!([null]));
+        assert_eq!(run(".[].identifier"), json!(["ENG-1", "ENG-2", "ENG-3"]));
+        assert_eq!(run(".[1].labels.nodes[]"), json!([]));
+        assert_eq!(run("length"), json!([3]));
+        assert_eq!(
+            run(".[0] | keys"),
+            json!([["identifier", "labels", "priority", "state"]])
+        );
+        assert_eq!(run(".[0].identifier[0]?"), json!([]));
+    }
+
+    #[test]
+    fn test_select_map_and_construction() {
+        assert_eq!(
+            run(r#"map(select(.priority <= 2 and .state.name != "Done")) | map(.identifier)"#),
+            json!([["ENG-1"]])
+        );
+        assert_eq!(
+            run(".[] | {id: .identifier, state: (.state.name // \"none\")} | select(.state == \"none\")"),
+            json!([{ "id": "ENG-3", "state": "none" }])
+        );
+        assert_eq!(
+            run("[.[] | .labels.nodes[].name] | unique | join(\", \")"),
+            json!(["bug, ui"])
+        );
+        assert_eq!(
+            run("sort_by(.priority) | map(.identifier) | first"),
+            json!(["ENG-2"])
+        );
+        assert_eq!(run("map(.priority) | add, max"), json!([6, 3]));
+        assert_eq!(
+            run("group_by(.labels.nodes | length) | map(length)"),
+            json!([[1, 1, 1]])
+        );
+        assert_eq!(
+            run(
+                r#"map(select(.identifier | startswith("ENG-") and (ascii_downcase | contains("eng-3")))) | length"#
+            ),
+            json!([1])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Filter::parse(".foo |").is_err());
+        assert!(Filter::parse("frobnicate").is_err());
+        assert!(Filter::parse("map").is_err());
+        assert!(Filter::parse("[.a").is_err());
+        assert!(Filter::parse(".a ..").is_err());
+        let err = Filter::parse(".[0].identifier.x")
+            .unwrap()
+            .run(&issues())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Cannot index string with \"x\"");
+    }
+}
@@ -2,12 +2,26 @@ mod api;
 mod cache;
 mod commands;
 mod config;
+mod credentials;
+mod fields;
+mod filter;
+mod jq;
+mod mirror;
+mod models;
+mod oauth;
+mod output;
+mod resolver;
+mod retry;
+mod tui;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand, ValueEnum};
+use api::LinearError;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use commands::{
-    bulk, comments, cycles, documents, git, interactive, issues, labels, notifications, projects,
-    search, statuses, sync, teams, templates, time, uploads, users,
+    auth, board, bulk, comments, cycles, documents, git, graphql, interactive, issues, labels,
+    notifications, projects, queue, schema, search, statuses, sync, teams, templates, time,
+    uploads, users,
 };
 
 /// Output format for command results
@@ -18,6 +32,26 @@ pub enum OutputFormat {
     Table,
     /// Display results as raw JSON
     Json,
+    /// YAML, one document per result
+    Yaml,
+    /// Comma-separated values with a header row; nested fields become `state.name`
+    Csv,
+    /// Tab-separated values with a header row
+    Tsv,
+    /// A Markdown table
+    #[value(alias = "md")]
+    Markdown,
+    /// One compact JSON object per line
+    Ndjson,
+    /// Each result rendered with --template
+    Template,
+}
+
+impl OutputFormat {
+    /// Whether results are shown as tables and text for people, rather than as data
+    pub fn is_table(self) -> bool {
+        matches!(self, OutputFormat::Table)
+    }
 }
 
 #[derive(Parser)]
@@ -37,9 +71,28 @@ pub enum OutputFormat {
 
 For more info on a command, run: linear <command> --help"#)]
 struct Cli {
-    /// Output format (table or json)
-    #[arg(short, long, global = true, default_value = "table")]
-    output: OutputFormat,
+    /// Output format [default: table, or default.output]
+    #[arg(short, long, global = true)]
+    output: Option<OutputFormat>,
+
+    /// Render each result with a template such as '{{identifier}}\t{{title}}'
+    /// (implies --output template)
+    #[arg(long, global = true, value_name = "TEMPLATE")]
+    template: Option<String>,
+
+    /// Only output these fields, e.g. identifier,title,state.name; the API is
+    /// asked for just these too (implies --output json)
+    #[arg(long, global = true, value_name = "FIELDS", value_parser = parse_fields)]
+    fields: Option<fields::Fields>,
+
+    /// Filter the output with a jq expression such as
+    /// '.[] | select(.priority <= 2) | .identifier' (implies --output json)
+    #[arg(long = "query", global = true, value_name = "JQ", value_parser = parse_query)]
+    jq: Option<jq::Filter>,
+
+    /// Act on this workspace for this command only, without switching to it
+    #[arg(long, global = true, value_name = "NAME")]
+    workspace: Option<String>,
 
     #[command(subcommand)]
     command: Commands,
@@ -70,6 +123,35 @@ enum Commands {
         #[command(subcommand)]
         action: issues::IssueCommands,
     },
+    /// Show a team's issues as a board with a column per workflow state
+    #[command(alias = "kb")]
+    #[command(after_help = r#"EXAMPLES:
+    linear board -t ENG                     # Print the team's board
+    linear kb -t ENG --cycle current        # Only the active cycle
+    linear kb -t ENG --hide-done            # Skip completed and canceled
+    linear kb -t ENG -i                     # Interactive board
+
+In the interactive board, h/l and j/k move around, H/L (or Shift+arrows)
+move the selected card to the neighbouring column and m moves it to any
+state. Moves are saved straight away."#)]
+    Board {
+        /// Team key, name or ID [default: default.team]
+        #[arg(short, long)]
+        team: Option<String>,
+        /// Only issues in this cycle: current, next, previous, a cycle number,
+        /// or none for every issue [default: default.cycle]
+        #[arg(short, long)]
+        cycle: Option<String>,
+        /// Leave out completed and canceled states
+        #[arg(long)]
+        hide_done: bool,
+        /// Maximum number of issues to load
+        #[arg(short, long, default_value = "250")]
+        limit: usize,
+        /// Open the board full-screen and move cards between columns
+        #[arg(short, long)]
+        interactive: bool,
+    },
     /// Manage labels - create and organize project/issue labels
     #[command(alias = "l")]
     #[command(after_help = r##"EXAMPLES:
@@ -93,8 +175,9 @@ enum Commands {
     /// Manage users - list workspace users and view profiles
     #[command(alias = "u")]
     #[command(after_help = r#"EXAMPLES:
-    linear users list                       # List all users
+    linear users list                       # List users (in the default team, if set)
     linear u list --team ENG                # List team members
+    linear u list --all-teams               # Every user, ignoring the default team
     linear u me                             # View your profile"#)]
     Users {
         #[command(subcommand)]
@@ -132,7 +215,9 @@ enum Commands {
     #[command(alias = "s")]
     #[command(after_help = r#"EXAMPLES:
     linear search issues "auth bug"         # Search issues
-    linear s projects "backend"             # Search projects"#)]
+    linear s projects "backend"             # Search projects
+    linear s issues "auth bug" -t ENG       # Search one team (default: the default team)
+    linear s issues "auth bug" --all-teams  # Search every team"#)]
     Search {
         #[command(subcommand)]
         action: search::SearchCommands,
@@ -170,13 +255,57 @@ enum Commands {
     /// Bulk operations - update multiple issues at once
     #[command(alias = "b")]
     #[command(after_help = r#"EXAMPLES:
-    linear bulk update -s Done LIN-1 LIN-2  # Update multiple issues
-    linear b assign --user me LIN-1 LIN-2   # Assign multiple issues
-    linear b label --add bug LIN-1 LIN-2    # Add label to issues"#)]
+    linear bulk update-state Done -i LIN-1,LIN-2        # Update multiple issues
+    linear b assign me -i LIN-1,LIN-2                   # Assign multiple issues
+    linear b label bug -i LIN-1,LIN-2                   # Add label to issues
+    linear b state Done --where 'team:ENG state:"In Review" updated<-14d'"#)]
     Bulk {
         #[command(subcommand)]
         action: bulk::BulkCommands,
     },
+    /// Send a raw GraphQL query or mutation with your credentials
+    #[command(after_help = r#"EXAMPLES:
+    linear api 'query { viewer { id name } }'
+    linear api 'query($key: String!) { teams(filter: { key: { eq: $key } }) { nodes { id name } } }' -F key=ENG
+    linear api --input query.graphql --variables vars.json
+    echo '{"first": 100}' | linear api --input query.graphql --variables -
+    linear api --paginate 'query($after: String) { issues(first: 100, after: $after) { nodes { identifier } pageInfo { hasNextPage endCursor } } }'
+
+-F values are parsed as JSON when they can be (numbers, true, false, null,
+arrays, objects) and @FILE reads a file; -f always sends a string. Mutations
+are only retried when they can't have been applied."#)]
+    Api {
+        /// The GraphQL document; use --input to read it from a file
+        query: Option<String>,
+        /// Read the query from a file (- for stdin)
+        #[arg(long, value_name = "FILE")]
+        input: Option<String>,
+        /// Variable as key=value, parsed as JSON when possible; @FILE reads a file
+        #[arg(short = 'F', long, value_name = "KEY=VALUE")]
+        field: Vec<String>,
+        /// Variable as key=value, always sent as a string
+        #[arg(short = 'f', long, value_name = "KEY=VALUE")]
+        raw_field: Vec<String>,
+        /// Variables as a JSON object, from a file or - for stdin
+        #[arg(long, value_name = "FILE")]
+        variables: Option<String>,
+        /// Follow the first connection's pageInfo and print all of its nodes
+        #[arg(long)]
+        paginate: bool,
+        /// Print the response compactly, exactly as received
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Fetch and browse the Linear API schema
+    #[command(after_help = r#"EXAMPLES:
+    linear schema fetch                     # Introspect the API and cache the result
+    linear schema show                      # Print the whole schema as SDL
+    linear schema show Issue                # Fields of one type
+    linear schema show IssueFilter -o json  # The introspection data for a type"#)]
+    Schema {
+        #[command(subcommand)]
+        action: schema::SchemaCommands,
+    },
     /// Manage cache - clear cached data or view status
     #[command(alias = "ca")]
     #[command(after_help = r#"EXAMPLES:
@@ -211,11 +340,25 @@ enum Commands {
     #[command(alias = "tm")]
     #[command(after_help = r#"EXAMPLES:
     linear time log LIN-123 2h              # Log 2 hours on issue
-    linear tm list --issue LIN-123          # List time entries"#)]
+    linear tm log LIN-123 1h30m --comment   # Log and mirror as a comment
+    linear tm start LIN-123                 # Start a timer
+    linear tm stop                          # Stop it and log the elapsed time
+    linear tm list --by day                 # Totals per day"#)]
     Time {
         #[command(subcommand)]
         action: time::TimeCommands,
     },
+    /// Changes queued with --queue while Linear couldn't be reached
+    #[command(alias = "q")]
+    #[command(after_help = r#"EXAMPLES:
+    linear i update LIN-123 -s Done --queue # Queued if Linear can't be reached
+    linear queue status                     # What's waiting
+    linear q replay                         # Send it, checking for conflicts
+    linear q drop 3f2a9c1e                  # Forget one change"#)]
+    Queue {
+        #[command(subcommand)]
+        action: queue::QueueCommands,
+    },
     /// Fetch uploads from Linear with authentication
     #[command(alias = "up")]
     #[command(after_help = r#"EXAMPLES:
@@ -231,8 +374,27 @@ enum Commands {
     #[command(after_help = r#"EXAMPLES:
     linear interactive                      # Launch interactive mode
 
-Use arrow keys to navigate, Enter to select, q to quit."#)]
+Opens on the default team (default.team) when one is set.
+
+KEYS:
+    j/k, arrows    Move through issues
+    /              Filter the list by typing
+    s / a / p      Change state, assignee or priority
+    n              Create an issue
+    t              Switch team
+    r              Refresh (also every 30s)
+    ?              Show all keys
+    q              Quit"#)]
     Interactive,
+    /// Sign in with OAuth, check who you're signed in as, sign out
+    #[command(after_help = r#"EXAMPLES:
+    linear auth login --client-id abc123    # Sign in through the browser
+    linear auth status                      # Who requests are made as
+    linear auth logout                      # Revoke and forget the token"#)]
+    Auth {
+        #[command(subcommand)]
+        action: auth::AuthCommands,
+    },
     /// Configure CLI settings - API keys and workspaces
     #[command(after_help = r#"EXAMPLES:
     linear config set-key YOUR_API_KEY      # Set API key
@@ -254,6 +416,83 @@ enum ConfigCommands {
         /// Your Linear API key
         key: String,
     },
+    /// Choose where API keys are kept and move existing keys there
+    #[command(after_help = r#"EXAMPLES:
+    linear config set-credential-store keyring          # OS keyring (default)
+    linear config set-credential-store encrypted-file   # credentials.enc, passphrase protected
+    linear config set-credential-store plaintext        # config.toml, mode 0600
+
+The encrypted file asks for its passphrase, or reads LINEAR_CLI_PASSPHRASE.
+LINEAR_CREDENTIAL_STORE overrides the configured store for new keys."#)]
+    SetCredentialStore {
+        /// Where to keep keys
+        store: credentials::CredentialStore,
+    },
+    /// Read the current workspace's key from a command's output instead
+    #[command(after_help = r#"EXAMPLES:
+    linear config set-credential-command 'pass show linear/work'
+    linear config set-credential-command 'op read op://Private/Linear/credential'
+    linear config set-credential-command --reset"#)]
+    SetCredentialCommand {
+        /// Shell command that prints the API key
+        #[arg(required_unless_present = "reset")]
+        command: Option<String>,
+        /// Stop using a command
+        #[arg(long, conflicts_with = "command")]
+        reset: bool,
+    },
+    /// Set a per-workspace default, e.g. the team used when -t is omitted
+    #[command(after_help = r#"EXAMPLES:
+    linear config set default.team ENG              # Team when -t/--team is omitted
+    linear config set default.project "Q1 Roadmap"  # Project for new issues and documents
+    linear config set default.assignee me           # Assignee for new issues
+    linear config set default.cycle current         # New issues and the board use the active cycle
+    linear config set default.output json           # Output format when -o is omitted
+    linear config set default.branch '{team}/{number}-{title}'
+
+Branch patterns can use {identifier}, {team}, {number} and {title}.
+
+A flag on the command line always wins. Next come the environment variables
+LINEAR_TEAM, LINEAR_PROJECT, LINEAR_ASSIGNEE, LINEAR_CYCLE, LINEAR_OUTPUT and
+LINEAR_BRANCH_FORMAT, then the current workspace's defaults."#)]
+    Set {
+        /// Setting name, e.g. default.team
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Clear a per-workspace default
+    #[command(after_help = r#"EXAMPLE:
+    linear config unset default.team"#)]
+    Unset {
+        /// Setting name, e.g. default.team
+        key: String,
+    },
+    /// Set the GraphQL endpoint for the current workspace
+    #[command(after_help = r#"EXAMPLES:
+    linear config set-api-url http://localhost:4000/graphql
+    linear config set-api-url --reset          # Back to api.linear.app"#)]
+    SetApiUrl {
+        /// GraphQL endpoint URL
+        #[arg(required_unless_present = "reset")]
+        url: Option<String>,
+        /// Remove the override and use the default endpoint
+        #[arg(long, conflicts_with = "url")]
+        reset: bool,
+    },
+    /// Set how many times an API request is attempted before giving up
+    #[command(after_help = r#"EXAMPLES:
+    linear config set-max-attempts 6           # Retry transient failures up to 5 times
+    linear config set-max-attempts 1           # Never retry
+    linear config set-max-attempts --reset     # Back to the default (4)"#)]
+    SetMaxAttempts {
+        /// Attempts per request, including the first
+        #[arg(required_unless_present = "reset", value_parser = clap::value_parser!(u32).range(1..))]
+        attempts: Option<u32>,
+        /// Use the default number of attempts
+        #[arg(long, conflicts_with = "attempts")]
+        reset: bool,
+    },
     /// Show current configuration
     Show,
     /// Add a new workspace
@@ -265,6 +504,9 @@ enum ConfigCommands {
         name: String,
         /// API key for this workspace
         api_key: String,
+        /// Custom GraphQL endpoint for this workspace
+        #[arg(long)]
+        api_url: Option<String>,
     },
     /// List all workspaces
     #[command(alias = "list")]
@@ -289,40 +531,203 @@ enum ConfigCommands {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let output = cli.output;
+async fn main() {
+    let mut cli = Cli::parse();
 
+    if let Err(err) = config::load_repo_config() {
+        Cli::command()
+            .error(ErrorKind::InvalidValue, format!("{:#}", err))
+            .exit();
+    }
+    if let Some(name) = cli.workspace.take() {
+        if let Err(err) = config::set_workspace_override(&name) {
+            Cli::command()
+                .error(ErrorKind::InvalidValue, format!("{:#}", err))
+                .exit();
+        }
+    }
+
+    let mut format = match cli.output {
+        Some(format) => format,
+        None => config::default_output()
+            .unwrap_or_else(|err| Cli::command().error(ErrorKind::InvalidValue, err).exit())
+            .unwrap_or_default(),
+    };
+
+    if let Some(template) = cli.template.take() {
+        output::set_template(template);
+        format = OutputFormat::Template;
+    } else if matches!(format, OutputFormat::Template) {
+        Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--output template needs a --template string, e.g. --template '{{identifier}}'",
+            )
+            .exit();
+    }
+
+    if cli.fields.is_some() || cli.jq.is_some() {
+        if let Some(fields) = cli.fields.take() {
+            fields::set(fields);
+        }
+        if let Some(filter) = cli.jq.take() {
+            output::set_query(filter);
+        }
+        if format.is_table() {
+            format = OutputFormat::Json;
+        }
+    }
+
+    let output = format;
+    if let Err(err) = run(cli, output).await {
+        std::process::exit(report_error(&err, output));
+    }
+}
+
+fn parse_fields(spec: &str) -> Result<fields::Fields, String> {
+    fields::Fields::parse(spec).map_err(|e| e.to_string())
+}
+
+fn parse_query(source: &str) -> Result<jq::Filter, String> {
+    jq::Filter::parse(source).map_err(|e| e.to_string())
+}
+
+/// Print an error the way the output format expects and return the exit code.
+/// API failures carry their own exit code; anything else exits with 1.
+fn report_error(err: &anyhow::Error, output: OutputFormat) -> i32 {
+    let linear_error = err.chain().find_map(|e| e.downcast_ref::<LinearError>());
+
+    if matches!(output, OutputFormat::Json | OutputFormat::Ndjson) {
+        let value = match linear_error {
+            Some(e) => e.to_json(),
+            None => serde_json::json!({
+                "error": { "kind": "error", "message": format!("{:#}", err), "exitCode": 1 }
+            }),
+        };
+        if matches!(output, OutputFormat::Ndjson) {
+            eprintln!("{}", value);
+        } else {
+            eprintln!(
+                "{}",
+                serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+            );
+        }
+    } else {
+        eprintln!("Error: {:?}", err);
+    }
+
+    linear_error.map_or(1, |e| e.exit_code())
+}
+
+async fn run(cli: Cli, output: OutputFormat) -> Result<()> {
     match cli.command {
         Commands::Projects { action } => projects::handle(action, output).await?,
         Commands::Issues { action } => issues::handle(action, output).await?,
+        Commands::Board {
+            team,
+            cycle,
+            hide_done,
+            limit,
+            interactive,
+        } => {
+            let cycle = cycle.or_else(|| config::default_value("cycle"));
+            board::run(
+                &config::team_or_default(team)?,
+                cycle.as_deref().filter(|c| !c.eq_ignore_ascii_case("none")),
+                hide_done,
+                limit,
+                interactive,
+                output,
+            )
+            .await?
+        }
+        Commands::Api {
+            query,
+            input,
+            field,
+            raw_field,
+            variables,
+            paginate,
+            raw,
+        } => {
+            graphql::run(
+                query, input, field, raw_field, variables, paginate, raw, output,
+            )
+            .await?
+        }
+        Commands::Schema { action } => schema::handle(action, output).await?,
         Commands::Labels { action } => labels::handle(action, output).await?,
         Commands::Teams { action } => teams::handle(action, output).await?,
-        Commands::Users { action } => users::handle(action).await?,
-        Commands::Cycles { action } => cycles::handle(action).await?,
+        Commands::Users { action } => users::handle(action, output).await?,
+        Commands::Cycles { action } => cycles::handle(action, output).await?,
         Commands::Comments { action } => comments::handle(action, output).await?,
-        Commands::Documents { action } => documents::handle(action).await?,
-        Commands::Search { action } => search::handle(action).await?,
-        Commands::Sync { action } => sync::handle(action).await?,
-        Commands::Statuses { action } => statuses::handle(action).await?,
+        Commands::Documents { action } => documents::handle(action, output).await?,
+        Commands::Search { action } => search::handle(action, output).await?,
+        Commands::Sync { action } => sync::handle(action, output).await?,
+        Commands::Statuses { action } => statuses::handle(action, output).await?,
         Commands::Git { action } => git::handle(action).await?,
-        Commands::Bulk { action } => bulk::handle(action).await?,
-        Commands::Cache { action } => commands::cache::handle(action).await?,
-        Commands::Notifications { action } => notifications::handle(action).await?,
-        Commands::Templates { action } => templates::handle(action).await?,
-        Commands::Time { action } => time::handle(action).await?,
+        Commands::Bulk { action } => bulk::handle(action, output).await?,
+        Commands::Cache { action } => commands::cache::handle(action, output).await?,
+        Commands::Notifications { action } => notifications::handle(action, output).await?,
+        Commands::Templates { action } => templates::handle(action, output).await?,
+        Commands::Time { action } => time::handle(action, output).await?,
+        Commands::Queue { action } => queue::handle(action, output).await?,
         Commands::Uploads { action } => uploads::handle(action).await?,
         Commands::Interactive => interactive::run().await?,
+        Commands::Auth { action } => auth::handle(action, output).await?,
         Commands::Config { action } => match action {
             ConfigCommands::SetKey { key } => {
                 config::set_api_key(&key)?;
                 println!("API key saved successfully!");
             }
+            ConfigCommands::Set { key, value } => {
+                config::set_value(&key, Some(&value))?;
+                println!("{} = {}", key, value);
+            }
+            ConfigCommands::Unset { key } => {
+                config::set_value(&key, None)?;
+                println!("{} cleared", key);
+            }
+            ConfigCommands::SetCredentialStore { store } => {
+                config::set_credential_store(store)?;
+                println!("API keys are now kept in: {}", store.name());
+            }
+            ConfigCommands::SetCredentialCommand { command, reset } => {
+                if reset {
+                    config::set_credential_command(None)?;
+                    println!("Credential command removed.");
+                } else if let Some(command) = command {
+                    config::set_credential_command(Some(&command))?;
+                    println!("API key will be read from: {}", command);
+                }
+            }
+            ConfigCommands::SetApiUrl { url, reset } => {
+                if reset {
+                    config::set_api_url(None)?;
+                    println!("API URL reset to default.");
+                } else if let Some(url) = url {
+                    config::set_api_url(Some(&url))?;
+                    println!("API URL set to {}", url);
+                }
+            }
+            ConfigCommands::SetMaxAttempts { attempts, reset } => {
+                if reset {
+                    config::set_max_attempts(None)?;
+                    println!("Max attempts reset to default.");
+                } else if let Some(attempts) = attempts {
+                    config::set_max_attempts(Some(attempts))?;
+                    println!("Max attempts set to {}", attempts);
+                }
+            }
             ConfigCommands::Show => {
-                config::show_config()?;
+                config::show_config(output)?;
             }
-            ConfigCommands::WorkspaceAdd { name, api_key } => {
-                config::workspace_add(&name, &api_key)?;
+            ConfigCommands::WorkspaceAdd {
+                name,
+                api_key,
+                api_url,
+            } => {
+                config::workspace_add(&name, &api_key, api_url.as_deref())?;
             }
             ConfigCommands::WorkspaceList => {
                 config::workspace_list()?;
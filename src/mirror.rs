@@ -0,0 +1,533 @@
+//! Local replica of a workspace's issues, projects, cycles, comments and
+//! documents, for `--offline` commands.
+//!
+//! `linear sync pull` fills a SQLite database next to the organization's cache
+//! (`cache/<organization id>/mirror.db`). Each entity keeps an `updatedAt`
+//! watermark, so later pulls only ask for what changed since. Rows hold the
+//! node as JSON, in the shape the online queries return, so offline commands
+//! filter and print them with the same code.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::api::{LinearClient, PageOptions};
+use crate::cache::Cache;
+use crate::config;
+use crate::filter;
+
+/// Bumped when the tables change; an older database is rebuilt by the next pull
+const SCHEMA_VERSION: i32 = 1;
+
+const DATABASE_FILE: &str = "mirror.db";
+
+const ISSUES_QUERY: &str = r#"
+    query($filter: IssueFilter, $first: Int, $after: String) {
+        issues(filter: $filter, first: $first, after: $after, includeArchived: true, orderBy: updatedAt) {
+            nodes {
+                id
+                identifier
+                number
+                title
+                description
+                priority
+                estimate
+                url
+                branchName
+                dueDate
+                createdAt
+                updatedAt
+                archivedAt
+                startedAt
+                completedAt
+                canceledAt
+                team { id key name }
+                state { id name type }
+                assignee { id name displayName email }
+                creator { id name displayName email }
+                project { id name }
+                cycle { id number name startsAt endsAt }
+                parent { id identifier number team { key } }
+                labels { nodes { id name } }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const PROJECTS_QUERY: &str = r#"
+    query($filter: ProjectFilter, $first: Int, $after: String) {
+        projects(filter: $filter, first: $first, after: $after, includeArchived: true, orderBy: updatedAt) {
+            nodes {
+                id
+                name
+                description
+                url
+                progress
+                startDate
+                targetDate
+                createdAt
+                updatedAt
+                archivedAt
+                status { id name }
+                lead { id name }
+                labels { nodes { id name } }
+                teams { nodes { id key name } }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const CYCLES_QUERY: &str = r#"
+    query($filter: CycleFilter, $first: Int, $after: String) {
+        cycles(filter: $filter, first: $first, after: $after, includeArchived: true, orderBy: updatedAt) {
+            nodes {
+                id
+                number
+                name
+                startsAt
+                endsAt
+                completedAt
+                progress
+                createdAt
+                updatedAt
+                archivedAt
+                team { id key name }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const COMMENTS_QUERY: &str = r#"
+    query($filter: CommentFilter, $first: Int, $after: String) {
+        comments(filter: $filter, first: $first, after: $after, includeArchived: true, orderBy: updatedAt) {
+            nodes {
+                id
+                body
+                url
+                createdAt
+                updatedAt
+                archivedAt
+                user { id name }
+                issue { id identifier }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const DOCUMENTS_QUERY: &str = r#"
+    query($filter: DocumentFilter, $first: Int, $after: String) {
+        documents(filter: $filter, first: $first, after: $after, includeArchived: true, orderBy: updatedAt) {
+            nodes {
+                id
+                title
+                content
+                url
+                createdAt
+                updatedAt
+                archivedAt
+                project { id name }
+                creator { id name }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const VIEWER_QUERY: &str = r#"
+    query {
+        viewer { id name }
+    }
+"#;
+
+/// What the mirror keeps a copy of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Issues,
+    Projects,
+    Cycles,
+    Comments,
+    Documents,
+}
+
+impl Entity {
+    pub fn all() -> &'static [Entity] {
+        &[
+            Entity::Issues,
+            Entity::Projects,
+            Entity::Cycles,
+            Entity::Comments,
+            Entity::Documents,
+        ]
+    }
+
+    /// Table name, which is also the name of the query's root field
+    pub fn name(self) -> &'static str {
+        match self {
+            Entity::Issues => "issues",
+            Entity::Projects => "projects",
+            Entity::Cycles => "cycles",
+            Entity::Comments => "comments",
+            Entity::Documents => "documents",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Entity::all()
+            .iter()
+            .copied()
+            .find(|e| e.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    fn query(self) -> &'static str {
+        match self {
+            Entity::Issues => ISSUES_QUERY,
+            Entity::Projects => PROJECTS_QUERY,
+            Entity::Cycles => CYCLES_QUERY,
+            Entity::Comments => COMMENTS_QUERY,
+            Entity::Documents => DOCUMENTS_QUERY,
+        }
+    }
+}
+
+/// What `sync pull` fetched for one entity
+pub struct Pulled {
+    pub fetched: usize,
+    pub total: usize,
+    pub watermark: Option<String>,
+}
+
+/// The local replica of one organization
+pub struct Mirror {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl Mirror {
+    /// The mirror of the organization `client` signs in to, created if needed
+    pub async fn open(client: &LinearClient) -> Result<Self> {
+        let cache = Cache::open(client).await?;
+        Self::at(cache.dir().join(DATABASE_FILE))
+    }
+
+    /// The mirror to read without the network: the current key's organization
+    /// if it has been seen before, else the one last pulled from this workspace
+    pub async fn open_offline() -> Result<Self> {
+        let cache = match LinearClient::new() {
+            Ok(client) => Cache::known(&client).await?,
+            Err(_) => None,
+        };
+        let cache = match cache {
+            Some(cache) => Some(cache),
+            None => Cache::of_workspace(&config::current_workspace_name()?)?,
+        };
+        let path = cache
+            .map(|c| c.dir().join(DATABASE_FILE))
+            .filter(|p| p.exists())
+            .context("No offline copy of this workspace yet. Run: linear sync pull")?;
+        Self::at(path)
+    }
+
+    fn at(path: PathBuf) -> Result<Self> {
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != SCHEMA_VERSION {
+            for entity in Entity::all() {
+                conn.execute_batch(&format!("DROP TABLE IF EXISTS {};", entity.name()))?;
+            }
+            conn.execute_batch("DROP TABLE IF EXISTS watermarks; DROP TABLE IF EXISTS meta;")?;
+        }
+        for entity in Entity::all() {
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id TEXT PRIMARY KEY,
+                    updated_at TEXT NOT NULL,
+                    data TEXT NOT NULL
+                );",
+                entity.name()
+            ))?;
+        }
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS watermarks (
+                entity TEXT PRIMARY KEY,
+                updated_at TEXT NOT NULL,
+                pulled_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            PRAGMA user_version = {};",
+            SCHEMA_VERSION
+        ))?;
+        Ok(Self { conn, path })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Fetch everything updated since the last pull (or everything, with
+    /// `full`) and store it
+    pub async fn pull(
+        &mut self,
+        client: &LinearClient,
+        entity: Entity,
+        full: bool,
+    ) -> Result<Pulled> {
+        let since = if full { None } else { self.watermark(entity)? };
+        let variables = match &since {
+            Some(since) => json!({ "filter": { "updatedAt": { "gt": since } } }),
+            None => json!({}),
+        };
+
+        let mut fetched = 0;
+        let mut latest = since.clone();
+        let conn = &mut self.conn;
+        client
+            .paginate(
+                entity.query(),
+                Some(variables),
+                &[entity.name()],
+                PageOptions::all(),
+                |nodes| {
+                    let tx = conn.transaction()?;
+                    for node in &nodes {
+                        let id = node["id"].as_str().context("Node without an id")?;
+                        let updated = node["updatedAt"].as_str().unwrap_or_default();
+                        tx.execute(
+                            &format!(
+                                "INSERT INTO {} (id, updated_at, data) VALUES (?1, ?2, ?3)
+                                 ON CONFLICT(id) DO UPDATE SET updated_at = ?2, data = ?3",
+                                entity.name()
+                            ),
+                            params![id, updated, node.to_string()],
+                        )?;
+                        if latest.as_deref().is_none_or(|l| is_later(updated, l)) {
+                            latest = Some(updated.to_string());
+                        }
+                    }
+                    tx.commit()?;
+                    fetched += nodes.len();
+                    Ok(())
+                },
+            )
+            .await?;
+
+        // Only move the watermark once every page is stored, so an interrupted
+        // pull picks up where the last complete one ended
+        if let Some(latest) = &latest {
+            self.conn.execute(
+                "INSERT INTO watermarks (entity, updated_at, pulled_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(entity) DO UPDATE SET updated_at = ?2, pulled_at = ?3",
+                params![entity.name(), latest, Utc::now().to_rfc3339()],
+            )?;
+        }
+
+        Ok(Pulled {
+            fetched,
+            total: self.count(entity)?,
+            watermark: latest,
+        })
+    }
+
+    /// Remember who the mirror was pulled as, for `assignee:me` offline
+    pub async fn pull_viewer(&self, client: &LinearClient) -> Result<()> {
+        let result = client.query(VIEWER_QUERY, None).await?;
+        if let Some(id) = result["data"]["viewer"]["id"].as_str() {
+            self.set_meta("viewer_id", id)?;
+        }
+        Ok(())
+    }
+
+    fn watermark(&self, entity: Entity) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT updated_at FROM watermarks WHERE entity = ?1",
+                params![entity.name()],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn count(&self, entity: Entity) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM {}", entity.name()),
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = ?2",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    fn meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Every stored node of `entity`, most recently updated first
+    pub fn all(&self, entity: Entity) -> Result<Vec<Value>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT data FROM {} ORDER BY updated_at DESC",
+            entity.name()
+        ))?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|row| Ok(serde_json::from_str(&row?)?)).collect()
+    }
+
+    /// One stored issue, by UUID or identifier
+    pub fn issue(&self, id: &str) -> Result<Option<Value>> {
+        let data: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT data FROM issues WHERE id = ?1 OR json_extract(data, '$.identifier') = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    /// Issues with the fields the online filter relies on filled in from the
+    /// rest of the mirror: `isMe` on people and `isActive`, `isNext` and
+    /// `isPrevious` on cycles
+    pub fn issues(&self) -> Result<Vec<Value>> {
+        let viewer = self.meta("viewer_id")?;
+        let flags = cycle_flags(&self.all(Entity::Cycles)?, Utc::now());
+        let mut issues = self.all(Entity::Issues)?;
+        for issue in &mut issues {
+            for person in ["assignee", "creator"] {
+                if let Some(obj) = issue[person].as_object_mut() {
+                    let is_me = viewer.is_some()
+                        && obj.get("id").and_then(Value::as_str) == viewer.as_deref();
+                    obj.insert("isMe".to_string(), json!(is_me));
+                }
+            }
+            if let Some(cycle) = issue["cycle"].as_object_mut() {
+                let id = cycle.get("id").and_then(Value::as_str).unwrap_or_default();
+                let (active, next, previous) = flags.get(id).copied().unwrap_or_default();
+                cycle.insert("isActive".to_string(), json!(active));
+                cycle.insert("isNext".to_string(), json!(next));
+                cycle.insert("isPrevious".to_string(), json!(previous));
+            }
+        }
+        Ok(issues)
+    }
+}
+
+/// The nodes matching an online filter, at most `limit` of them
+pub fn select(nodes: Vec<Value>, filter: &Value, limit: Option<usize>) -> Vec<Value> {
+    let now = Utc::now();
+    nodes
+        .into_iter()
+        .filter(|node| filter::matches(filter, node, now))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Whether timestamp `a` is later than `b`
+pub fn is_later(a: &str, b: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(a),
+        DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+/// (active, next, previous) for each cycle ID, judged per team at `now`
+fn cycle_flags(cycles: &[Value], now: DateTime<Utc>) -> HashMap<String, (bool, bool, bool)> {
+    let time = |cycle: &Value, field: &str| {
+        cycle[field]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    };
+
+    let mut by_team: HashMap<&str, Vec<&Value>> = HashMap::new();
+    for cycle in cycles {
+        let team = cycle["team"]["id"].as_str().unwrap_or_default();
+        by_team.entry(team).or_default().push(cycle);
+    }
+
+    let mut flags = HashMap::new();
+    for cycles in by_team.values() {
+        let next = cycles
+            .iter()
+            .filter(|c| time(c, "startsAt").is_some_and(|s| s > now))
+            .min_by_key(|c| time(c, "startsAt"))
+            .map(|c| &c["id"]);
+        let previous = cycles
+            .iter()
+            .filter(|c| time(c, "endsAt").is_some_and(|e| e <= now))
+            .max_by_key(|c| time(c, "endsAt"))
+            .map(|c| &c["id"]);
+        for cycle in cycles {
+            let active = time(cycle, "startsAt").is_some_and(|s| s <= now)
+                && time(cycle, "endsAt").is_some_and(|e| now < e);
+            let id = cycle["id"].as_str().unwrap_or_default().to_string();
+            let is = |other: Option<&Value>| other == Some(&cycle["id"]);
+            flags.insert(id, (active, is(next), is(previous)));
+        }
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_flags() {
+        let now = DateTime::parse_from_rfc3339("2024-06-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let cycle = |id: &str, starts: &str, ends: &str| json!({ "id": id, "startsAt": starts, "endsAt": ends, "team": { "id": "t" } });
+        let cycles = vec![
+            cycle("c1", "2024-05-20T00:00:00Z", "2024-06-03T00:00:00Z"),
+            cycle("c2", "2024-06-03T00:00:00Z", "2024-06-17T00:00:00Z"),
+            cycle("c3", "2024-06-17T00:00:00Z", "2024-07-01T00:00:00Z"),
+            cycle("c4", "2024-07-01T00:00:00Z", "2024-07-15T00:00:00Z"),
+        ];
+        let flags = cycle_flags(&cycles, now);
+        assert_eq!(flags["c1"], (false, false, true));
+        assert_eq!(flags["c2"], (true, false, false));
+        assert_eq!(flags["c3"], (false, true, false));
+        assert_eq!(flags["c4"], (false, false, false));
+    }
+
+    #[test]
+    fn test_is_later() {
+        assert!(is_later(
+            "2024-06-02T00:00:00.000Z",
+            "2024-06-01T23:59:59.999Z"
+        ));
+        assert!(!is_later(
+            "2024-06-01T00:00:00Z",
+            "2024-06-01T00:00:00.000Z"
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+//! Typed views of Linear API responses.
+//!
+//! Identity fields (`id` and the display name or title) are required, so a query
+//! that forgets to select them, or a response that drifts from the schema, fails
+//! with an error naming the missing field instead of rendering as "-". Everything
+//! else is optional because queries select different subsets of fields.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use colored::Colorize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Deserialize a response node, naming the expected type if it doesn't fit
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T> {
+    T::deserialize(value).with_context(|| {
+        format!(
+            "Unexpected {} in API response",
+            short_type_name::<T>().to_lowercase()
+        )
+    })
+}
+
+/// Deserialize a page of connection nodes
+pub fn from_nodes<T: DeserializeOwned>(nodes: &[Value]) -> Result<Vec<T>> {
+    nodes.iter().map(from_value).collect()
+}
+
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// A GraphQL connection's `nodes`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Connection<T> {
+    pub nodes: Vec<T>,
+}
+
+impl<T> Default for Connection<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub id: String,
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<i64>,
+    pub estimate: Option<f64>,
+    pub url: Option<String>,
+    pub branch_name: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub state: Option<WorkflowState>,
+    pub assignee: Option<User>,
+    pub team: Option<Team>,
+    pub project: Option<Project>,
+    pub cycle: Option<Cycle>,
+    pub parent: Option<Box<Issue>>,
+    pub labels: Option<Connection<Label>>,
+}
+
+impl Issue {
+    pub fn state_name(&self) -> &str {
+        self.state.as_ref().map_or("-", |s| s.name.as_str())
+    }
+
+    pub fn assignee_name(&self) -> &str {
+        self.assignee.as_ref().map_or("-", |u| u.name.as_str())
+    }
+
+    pub fn label_names(&self) -> Vec<&str> {
+        self.labels
+            .as_ref()
+            .map(|l| l.nodes.iter().map(|l| l.name.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Human-readable priority, colored by urgency
+pub fn priority_label(priority: Option<i64>) -> String {
+    let name = priority_name(priority);
+    match priority {
+        Some(1) => name.red().to_string(),
+        Some(2) => name.yellow().to_string(),
+        Some(4) => name.dimmed().to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Priority name without styling ("-" for no priority)
+pub fn priority_name(priority: Option<i64>) -> &'static str {
+    match priority {
+        Some(1) => "Urgent",
+        Some(2) => "High",
+        Some(3) => "Normal",
+        Some(4) => "Low",
+        _ => "-",
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Legacy project state ("planned", "started", ...)
+    pub state: Option<String>,
+    pub status: Option<ProjectStatus>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub url: Option<String>,
+    pub progress: Option<f64>,
+    pub start_date: Option<NaiveDate>,
+    pub target_date: Option<NaiveDate>,
+    pub lead: Option<User>,
+    pub labels: Option<Connection<Label>>,
+    pub teams: Option<Connection<Team>>,
+}
+
+impl Project {
+    /// The project's status name, falling back to the legacy state
+    pub fn status_name(&self) -> &str {
+        self.status
+            .as_ref()
+            .map(|s| s.name.as_str())
+            .or(self.state.as_deref())
+            .unwrap_or("-")
+    }
+
+    pub fn label_names(&self) -> Vec<&str> {
+        self.labels
+            .as_ref()
+            .map(|l| l.nodes.iter().map(|l| l.name.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStatus {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    pub id: String,
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub private: Option<bool>,
+    pub timezone: Option<String>,
+    pub issue_count: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub states: Option<Connection<WorkflowState>>,
+    pub members: Option<Connection<User>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+    pub admin: Option<bool>,
+    pub active: Option<bool>,
+    pub url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cycle {
+    pub id: String,
+    pub number: i64,
+    pub name: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub progress: Option<f64>,
+    pub issues: Option<Connection<Issue>>,
+}
+
+impl Cycle {
+    /// The cycle's name, or "Cycle N" for unnamed cycles
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) if !name.is_empty() => name.clone(),
+            _ => format!("Cycle {}", self.number),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    pub id: String,
+    pub title: String,
+    pub content: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub creator: Option<User>,
+    pub project: Option<Project>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub body: String,
+    pub url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// `None` for comments posted by integrations
+    pub user: Option<User>,
+    pub issue: Option<Issue>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    /// The label group this label belongs to
+    pub parent: Option<Box<Label>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowState {
+    pub id: String,
+    pub name: String,
+    /// backlog, unstarted, started, completed or canceled
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub color: Option<String>,
+    pub position: Option<f64>,
+    pub description: Option<String>,
+}
+
+impl WorkflowState {
+    /// Color a piece of text according to this state's type
+    pub fn paint(&self, text: &str) -> String {
+        match self.kind.as_deref() {
+            Some("completed") => text.green().to_string(),
+            Some("started") => text.yellow().to_string(),
+            Some("canceled") | Some("cancelled") => text.red().to_string(),
+            Some("backlog") => text.dimmed().to_string(),
+            Some("unstarted") => text.cyan().to_string(),
+            _ => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_issue_deserializes_nested_models() {
+        let issue: Issue = from_value(&json!({
+            "id": "uuid",
+            "identifier": "ENG-1",
+            "title": "Fix login",
+            "priority": 2,
+            "createdAt": "2024-03-01T12:00:00.000Z",
+            "state": { "id": "s1", "name": "In Progress", "type": "started" },
+            "assignee": null,
+            "labels": { "nodes": [{ "id": "l1", "name": "Bug" }] }
+        }))
+        .unwrap();
+
+        assert_eq!(issue.state_name(), "In Progress");
+        assert_eq!(issue.assignee_name(), "-");
+        assert_eq!(issue.label_names(), vec!["Bug"]);
+        assert_eq!(
+            issue.created_at.unwrap().date_naive().to_string(),
+            "2024-03-01"
+        );
+    }
+
+    #[test]
+    fn test_missing_required_field_is_an_error() {
+        let err =
+            from_value::<Issue>(&json!({ "id": "uuid", "title": "No identifier" })).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Unexpected issue in API response"));
+        assert!(message.contains("missing field `identifier`"));
+    }
+
+    #[test]
+    fn test_display_helpers() {
+        let cycle: Cycle = from_value(&json!({ "id": "c", "number": 7, "name": null })).unwrap();
+        assert_eq!(cycle.display_name(), "Cycle 7");
+
+        let project: Project = from_value(&json!({
+            "id": "p", "name": "Roadmap", "state": "started"
+        }))
+        .unwrap();
+        assert_eq!(project.status_name(), "started");
+    }
+}
@@ -0,0 +1,426 @@
+//! OAuth2 sign-in for `linear auth login`: the authorization-code flow with
+//! PKCE, a one-shot localhost listener for the redirect, and token refresh.
+//!
+//! `LINEAR_OAUTH_URL` points every endpoint at another server, so the flow can
+//! run against a local fake authorization server.
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::credentials::CredentialStore;
+
+/// Scopes asked for when `--scope` isn't given
+pub const DEFAULT_SCOPES: &[&str] = &["read", "write"];
+
+/// Port the callback listener binds by default; register
+/// `http://127.0.0.1:8976/callback` as the app's redirect URI
+pub const DEFAULT_PORT: u16 = 8976;
+
+/// How long to wait for the browser to come back
+const CALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Refresh this long before the access token actually expires
+const REFRESH_MARGIN: Duration = Duration::seconds(60);
+
+/// Where the authorize, token and revoke endpoints live
+pub struct Endpoints {
+    pub authorize: String,
+    pub token: String,
+    pub revoke: String,
+}
+
+impl Endpoints {
+    /// Linear's endpoints, or all three under `LINEAR_OAUTH_URL` when set
+    pub fn get() -> Self {
+        match std::env::var("LINEAR_OAUTH_URL") {
+            Ok(base) if !base.is_empty() => {
+                let base = base.trim_end_matches('/');
+                Self {
+                    authorize: format!("{}/oauth/authorize", base),
+                    token: format!("{}/oauth/token", base),
+                    revoke: format!("{}/oauth/revoke", base),
+                }
+            }
+            _ => Self {
+                authorize: "https://linear.app/oauth/authorize".to_string(),
+                token: "https://api.linear.app/oauth/token".to_string(),
+                revoke: "https://api.linear.app/oauth/revoke".to_string(),
+            },
+        }
+    }
+}
+
+/// A workspace's OAuth login, as kept in config.toml
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OAuthLogin {
+    /// The OAuth application the tokens were issued to
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// When the access token stops working; unset for tokens that don't expire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Where the tokens are kept
+    pub token_store: CredentialStore,
+    /// The tokens themselves, only when kept in plaintext
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Tokens>,
+}
+
+impl OAuthLogin {
+    /// Whether the access token has expired or is about to
+    pub fn expires_soon(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at - REFRESH_MARGIN <= Utc::now())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Tokens {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+/// A token endpoint response
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+    /// Space- or comma-separated string, or an array
+    #[serde(default)]
+    pub scope: Option<Value>,
+}
+
+impl TokenResponse {
+    /// Apply to `login`, keeping the old refresh token when the server doesn't rotate it
+    pub fn update(self, login: &mut OAuthLogin, previous: Option<&Tokens>) -> Tokens {
+        login.expires_at = self
+            .expires_in
+            .map(|seconds| Utc::now() + Duration::seconds(seconds));
+        if let Some(scopes) = self.scope.as_ref().map(parse_scopes) {
+            if !scopes.is_empty() {
+                login.scopes = scopes;
+            }
+        }
+        Tokens {
+            access_token: self.access_token,
+            refresh_token: self
+                .refresh_token
+                .or_else(|| previous.and_then(|t| t.refresh_token.clone())),
+        }
+    }
+}
+
+fn parse_scopes(scope: &Value) -> Vec<String> {
+    match scope {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|s| s.as_str().map(|s| s.to_string()))
+            .collect(),
+        Value::String(s) => s
+            .split([' ', ','])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A PKCE verifier and its S256 challenge
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn new() -> Self {
+        let verifier = random_token(32);
+        let challenge = BASE64_URL.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// URL-safe random string from `bytes` random bytes
+pub fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    BASE64_URL.encode(buf)
+}
+
+/// The URL the user opens to approve access
+pub fn authorize_url(
+    endpoints: &Endpoints,
+    client_id: &str,
+    redirect_uri: &str,
+    scopes: &[String],
+    state: &str,
+    pkce: &Pkce,
+) -> Result<String> {
+    let url = Url::parse_with_params(
+        &endpoints.authorize,
+        &[
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("response_type", "code"),
+            ("scope", &scopes.join(",")),
+            ("state", state),
+            ("code_challenge", &pkce.challenge),
+            ("code_challenge_method", "S256"),
+            ("prompt", "consent"),
+        ],
+    )
+    .context("Invalid authorize URL")?;
+    Ok(url.to_string())
+}
+
+/// Wait for the browser to hit the redirect URI and return the authorization code
+pub async fn wait_for_code(listener: TcpListener, state: &str) -> Result<String> {
+    tokio::time::timeout(CALLBACK_TIMEOUT, accept_callback(listener, state))
+        .await
+        .context("Timed out waiting for the browser to finish signing in")?
+}
+
+async fn accept_callback(listener: TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 16 * 1024 {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let target = request.split_whitespace().nth(1).unwrap_or("/");
+        let url = Url::parse(&format!("http://localhost{}", target))?;
+
+        // Browsers also ask for /favicon.ico and the like
+        if url.path() != "/callback" {
+            respond(&mut stream, "404 Not Found", "Not found").await;
+            continue;
+        }
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        if let Some(error) = param("error") {
+            respond(&mut stream, "400 Bad Request", "Sign-in was cancelled.").await;
+            let description = param("error_description").unwrap_or_default();
+            bail!("Authorization failed: {} {}", error, description);
+        }
+        if param("state").as_deref() != Some(state) {
+            respond(
+                &mut stream,
+                "400 Bad Request",
+                "Sign-in state didn't match.",
+            )
+            .await;
+            bail!("Authorization response had the wrong state; try again");
+        }
+        let Some(code) = param("code") else {
+            respond(&mut stream, "400 Bad Request", "No authorization code.").await;
+            bail!("Authorization response had no code");
+        };
+
+        respond(
+            &mut stream,
+            "200 OK",
+            "Signed in to linear-cli. You can close this tab.",
+        )
+        .await;
+        return Ok(code);
+    }
+}
+
+async fn respond(stream: &mut tokio::net::TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><html><body style=\"font-family: sans-serif\"><p>{}</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Trade the authorization code for tokens
+pub async fn exchange_code(
+    client: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    code: &str,
+    redirect_uri: &str,
+    pkce: &Pkce,
+) -> Result<TokenResponse> {
+    token_request(
+        client,
+        &endpoints.token,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", client_id),
+            ("code_verifier", &pkce.verifier),
+        ],
+    )
+    .await
+}
+
+/// Get a new access token with the refresh token
+pub async fn refresh(
+    client: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    refresh_token: &str,
+) -> Result<TokenResponse> {
+    token_request(
+        client,
+        &endpoints.token,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", client_id),
+        ],
+    )
+    .await
+    .context("Could not refresh the OAuth token; run: linear auth login")
+}
+
+async fn token_request(client: &Client, url: &str, form: &[(&str, &str)]) -> Result<TokenResponse> {
+    let response = client
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", url))?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|v| {
+                v["error_description"]
+                    .as_str()
+                    .or(v["error"].as_str())
+                    .map(|s| s.to_string())
+            })
+            .unwrap_or(text);
+        bail!(
+            "Token request failed (HTTP {}): {}",
+            status.as_u16(),
+            message
+        );
+    }
+    serde_json::from_str(&text).context("Invalid response from the token endpoint")
+}
+
+/// Tell the server to forget the token
+pub async fn revoke(client: &Client, endpoints: &Endpoints, access_token: &str) -> Result<()> {
+    let response = client
+        .post(&endpoints.revoke)
+        .bearer_auth(access_token)
+        .form(&[("token", access_token), ("token_type_hint", "access_token")])
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", endpoints.revoke))?;
+    if !response.status().is_success() {
+        bail!(
+            "Revoking the token failed (HTTP {})",
+            response.status().as_u16()
+        );
+    }
+    Ok(())
+}
+
+/// Try to open `url` in the default browser
+pub fn open_browser(url: &str) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pkce_challenge_is_s256_of_verifier() {
+        let pkce = Pkce::new();
+        assert!(pkce.verifier.len() >= 43);
+        assert_eq!(
+            BASE64_URL.decode(&pkce.challenge).unwrap(),
+            Sha256::digest(pkce.verifier.as_bytes()).to_vec()
+        );
+        assert_ne!(Pkce::new().verifier, pkce.verifier);
+    }
+
+    #[test]
+    fn test_token_response_keeps_old_refresh_token() {
+        let mut login = OAuthLogin {
+            client_id: "app".to_string(),
+            scopes: Vec::new(),
+            expires_at: None,
+            token_store: CredentialStore::Plaintext,
+            tokens: None,
+        };
+        let previous = Tokens {
+            access_token: "old".to_string(),
+            refresh_token: Some("refresh-1".to_string()),
+        };
+        let response: TokenResponse = serde_json::from_value(json!({
+            "access_token": "new",
+            "expires_in": 3600,
+            "scope": "read write"
+        }))
+        .unwrap();
+
+        let tokens = response.update(&mut login, Some(&previous));
+        assert_eq!(tokens.access_token, "new");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("refresh-1"));
+        assert_eq!(login.scopes, vec!["read", "write"]);
+        assert!(!login.expires_soon());
+
+        login.expires_at = Some(Utc::now() + Duration::seconds(30));
+        assert!(login.expires_soon());
+    }
+}
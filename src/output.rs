@@ -0,0 +1,679 @@
+//! Printers for command results: tables for people, and the data formats
+//! selected with `--output` (JSON, YAML, CSV, TSV, Markdown, NDJSON, templates),
+//! shaped by `--fields` and `--query`.
+
+use anyhow::{Context, Result};
+use apollo_compiler::ast::{Definition, Document, Field, Selection};
+use apollo_compiler::Node;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::sync::OnceLock;
+use tabled::settings::object::Rows;
+use tabled::settings::Disable;
+use tabled::{Table, Tabled};
+
+use crate::fields;
+use crate::jq::Filter;
+use crate::OutputFormat;
+
+/// Shorten `s` to at most `max` characters, ending in "..." when cut
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let kept: String = s.chars().take(max.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    }
+}
+
+/// Width of the terminal in columns. Falls back to `$COLUMNS`, then 120, when
+/// stdout isn't a terminal.
+pub fn terminal_width() -> usize {
+    if std::io::stdout().is_terminal() {
+        if let Ok((width, _)) = ratatui::crossterm::terminal::size() {
+            return width as usize;
+        }
+    }
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .filter(|&c| c > 0)
+        .unwrap_or(120)
+}
+
+/// Prints a pretty JSON array one page at a time.
+///
+/// The output is byte-for-byte what `serde_json::to_string_pretty` would produce
+/// for the whole array, but items appear as soon as their page is fetched.
+#[derive(Default)]
+pub struct JsonArrayStream {
+    count: usize,
+}
+
+impl JsonArrayStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print a page of items
+    pub fn push(&mut self, items: &[Value]) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        for item in items {
+            let separator = if self.count == 0 { "[\n" } else { ",\n" };
+            let pretty = serde_json::to_string_pretty(item)?;
+            let indented: Vec<String> = pretty.lines().map(|l| format!("  {}", l)).collect();
+            write!(stdout, "{}{}", separator, indented.join("\n"))?;
+            self.count += 1;
+        }
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Close the array
+    pub fn finish(self) -> Result<()> {
+        if self.count == 0 {
+            println!("[]");
+        } else {
+            println!("\n]");
+        }
+        Ok(())
+    }
+}
+
+/// The `--template` string, set once at startup
+static TEMPLATE: OnceLock<String> = OnceLock::new();
+
+/// Set the template used by `--output template`
+pub fn set_template(template: String) {
+    let _ = TEMPLATE.set(template);
+}
+
+/// The `--query` filter, set once at startup
+static QUERY: OnceLock<Filter> = OnceLock::new();
+
+/// Set the jq filter applied to data output
+pub fn set_query(filter: Filter) {
+    let _ = QUERY.set(filter);
+}
+
+/// Print a value in one of the data formats, after `--fields` and `--query`.
+/// Arrays print one item per row or record; anything else prints as a single
+/// record.
+pub fn print_value<T: Serialize + ?Sized>(value: &T, format: OutputFormat) -> Result<()> {
+    let mut value = serde_json::to_value(value)?;
+    if let Some(fields) = fields::selected() {
+        value = fields.project(&value);
+    }
+    if let Some(query) = QUERY.get() {
+        return write_results(&query.run(&value)?, format);
+    }
+    write_value(&value, format)
+}
+
+/// Print `--query`'s results the way jq does, however many there are: each
+/// one whole in JSON and YAML, and all of them as the rows of one list in the
+/// other formats
+fn write_results(results: &[Value], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table | OutputFormat::Json => {
+            for result in results {
+                println!("{}", serde_json::to_string_pretty(result)?);
+            }
+            Ok(())
+        }
+        OutputFormat::Yaml => {
+            for (i, result) in results.iter().enumerate() {
+                if i > 0 {
+                    println!("---");
+                }
+                print!("{}", serde_yaml::to_string(result)?);
+            }
+            Ok(())
+        }
+        _ => {
+            let mut stream = ValueStream::unshaped(format);
+            for result in results {
+                match result {
+                    Value::Array(items) => stream.push(items)?,
+                    item => stream.push(std::slice::from_ref(item))?,
+                }
+            }
+            stream.finish()
+        }
+    }
+}
+
+fn write_value(value: &Value, format: OutputFormat) -> Result<()> {
+    match (value, format) {
+        (_, OutputFormat::Table | OutputFormat::Json) => {
+            println!("{}", serde_json::to_string_pretty(value)?);
+            Ok(())
+        }
+        (_, OutputFormat::Yaml) => {
+            print!("{}", serde_yaml::to_string(value)?);
+            Ok(())
+        }
+        (Value::Array(items), _) => {
+            let mut stream = ValueStream::unshaped(format);
+            stream.push(items)?;
+            stream.finish()
+        }
+        (item, _) => {
+            let mut stream = ValueStream::unshaped(format);
+            stream.push(std::slice::from_ref(item))?;
+            stream.finish()
+        }
+    }
+}
+
+/// Prints a list of values one page at a time in any of the data formats.
+///
+/// CSV, TSV and Markdown columns are the flattened fields of the query's
+/// selection set (see [`ValueStream::columns`]), or else of the first page,
+/// e.g. `state.name`; connections and lists become comma-separated names.
+/// Items are cut down to `--fields` as they arrive; with `--query` the whole
+/// list is collected first, since the filter sees it as one array.
+pub struct ValueStream {
+    format: OutputFormat,
+    count: usize,
+    columns: Vec<String>,
+    json: JsonArrayStream,
+    shaped: bool,
+    collected: Option<Vec<Value>>,
+}
+
+impl ValueStream {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            collected: QUERY.get().map(|_| Vec::new()),
+            shaped: true,
+            ..Self::unshaped(format)
+        }
+    }
+
+    /// Take the CSV, TSV and Markdown columns from the selection set of the
+    /// items at `path` in `query` (nodes of a connection included), so a
+    /// relation that is null, or a field that first appears on a later page,
+    /// still gets its column. Without it the first page decides.
+    pub fn columns(mut self, query: &str, path: &[&str]) -> Self {
+        if let Some(mut columns) = selection_columns(query, path) {
+            if let Some(fields) = fields::selected().filter(|_| self.shaped) {
+                columns.sort_by_key(|column| fields.rank(column));
+            }
+            self.columns = columns;
+        }
+        self
+    }
+
+    /// A stream that prints items as given, ignoring `--fields` and `--query`
+    fn unshaped(format: OutputFormat) -> Self {
+        Self {
+            format,
+            count: 0,
+            columns: Vec::new(),
+            json: JsonArrayStream::new(),
+            shaped: false,
+            collected: None,
+        }
+    }
+
+    /// Print a page of items
+    pub fn push(&mut self, items: &[Value]) -> Result<()> {
+        let projected: Vec<Value>;
+        let items = match fields::selected().filter(|_| self.shaped) {
+            Some(fields) => {
+                projected = items.iter().map(|item| fields.project(item)).collect();
+                &projected
+            }
+            None => items,
+        };
+        if let Some(collected) = &mut self.collected {
+            collected.extend_from_slice(items);
+            return Ok(());
+        }
+        if matches!(self.format, OutputFormat::Table | OutputFormat::Json) {
+            return self.json.push(items);
+        }
+        let text = self.render(items)?;
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Finish the output; an empty list still prints as one in JSON and YAML
+    pub fn finish(self) -> Result<()> {
+        if let (Some(collected), Some(query)) = (self.collected, QUERY.get()) {
+            return write_results(&query.run(&Value::Array(collected))?, self.format);
+        }
+        match self.format {
+            OutputFormat::Table | OutputFormat::Json => self.json.finish(),
+            OutputFormat::Yaml if self.count == 0 => {
+                println!("[]");
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn render(&mut self, items: &[Value]) -> Result<String> {
+        let mut out = String::new();
+        match self.format {
+            OutputFormat::Table | OutputFormat::Json => {
+                unreachable!("JSON is printed by JsonArrayStream")
+            }
+            OutputFormat::Yaml => {
+                if !items.is_empty() {
+                    out.push_str(&serde_yaml::to_string(items)?);
+                }
+            }
+            OutputFormat::Ndjson => {
+                for item in items {
+                    out.push_str(&serde_json::to_string(item)?);
+                    out.push('\n');
+                }
+            }
+            OutputFormat::Template => {
+                let template = TEMPLATE
+                    .get()
+                    .context("--output template needs a --template string")?;
+                for item in items {
+                    out.push_str(&render_template(template, item));
+                    out.push('\n');
+                }
+            }
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Markdown => {
+                if self.count == 0 {
+                    if items.is_empty() {
+                        return Ok(out);
+                    }
+                    if self.columns.is_empty() {
+                        self.columns = page_columns(items);
+                    }
+                    out.push_str(&self.record(self.columns.clone()));
+                    if matches!(self.format, OutputFormat::Markdown) {
+                        out.push_str(&self.record(vec!["---".to_string(); self.columns.len()]));
+                    }
+                }
+                for item in items {
+                    let cells = self
+                        .columns
+                        .iter()
+                        .map(|column| match item {
+                            Value::Object(_) => cell_text(lookup(item, column)),
+                            other => cell_text(other),
+                        })
+                        .collect();
+                    out.push_str(&self.record(cells));
+                }
+            }
+        }
+        self.count += items.len();
+        Ok(out)
+    }
+
+    /// One line of CSV, TSV or Markdown
+    fn record(&self, cells: Vec<String>) -> String {
+        match self.format {
+            OutputFormat::Csv => {
+                let cells: Vec<String> = cells.iter().map(|c| csv_escape(c)).collect();
+                format!("{}\n", cells.join(","))
+            }
+            OutputFormat::Tsv => {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .map(|c| c.replace(['\t', '\n', '\r'], " "))
+                    .collect();
+                format!("{}\n", cells.join("\t"))
+            }
+            _ => {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .map(|c| {
+                        c.replace('|', "\\|")
+                            .replace("\r\n", "<br>")
+                            .replace('\n', "<br>")
+                    })
+                    .collect();
+                format!("| {} |\n", cells.join(" | "))
+            }
+        }
+    }
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// The columns of a page without a selection set: every item's flattened
+/// fields, in the order they first appear. A relation that is null in one
+/// item and an object in another is only its fields' columns.
+fn page_columns(items: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for item in items {
+        let mut paths = Vec::new();
+        match item {
+            Value::Object(map) => flatten_into("", map, &mut paths),
+            _ => paths.push("value".to_string()),
+        }
+        for path in paths {
+            if !columns.contains(&path) {
+                columns.push(path);
+            }
+        }
+    }
+    let parents: Vec<String> = columns
+        .iter()
+        .filter_map(|c| c.rsplit_once('.').map(|(parent, _)| parent.to_string()))
+        .collect();
+    columns.retain(|c| {
+        !parents
+            .iter()
+            .any(|p| p == c || p.starts_with(&format!("{}.", c)))
+    });
+    columns
+}
+
+/// An object's fields as dotted paths, with nested objects flattened
+fn flatten_into(prefix: &str, map: &Map<String, Value>, out: &mut Vec<String>) {
+    for (key, value) in map {
+        let path = join_path(prefix, key);
+        match value {
+            Value::Object(inner) if !inner.is_empty() && !is_connection(inner) => {
+                flatten_into(&path, inner, out)
+            }
+            _ => out.push(path),
+        }
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// The columns for the items at `path` (response keys) in `query`, named the
+/// way items are flattened: `state.name` for an object's field, and one
+/// column for a connection. `None` if the query doesn't parse or has no
+/// selection there.
+fn selection_columns(query: &str, path: &[&str]) -> Option<Vec<String>> {
+    let document = Document::parse(query, "query.graphql").ok()?;
+    let mut fragments = HashMap::new();
+    let mut operation = None;
+    for definition in &document.definitions {
+        match definition {
+            Definition::FragmentDefinition(fragment) => {
+                fragments.insert(fragment.name.as_str(), &fragment.selection_set[..]);
+            }
+            Definition::OperationDefinition(op) if operation.is_none() => operation = Some(op),
+            _ => {}
+        }
+    }
+
+    let mut selection = fields_of(&operation?.selection_set, &fragments);
+    for key in path {
+        let field = selection.into_iter().find(|f| response_key(f) == *key)?;
+        selection = fields_of(&field.selection_set, &fragments);
+    }
+    if let Some(nodes) = selection.iter().find(|f| response_key(f) == "nodes") {
+        selection = fields_of(&nodes.selection_set, &fragments);
+    }
+
+    let mut columns = Vec::new();
+    push_columns("", &selection, &fragments, &mut columns);
+    (!columns.is_empty()).then_some(columns)
+}
+
+fn push_columns(
+    prefix: &str,
+    selection: &[&Node<Field>],
+    fragments: &HashMap<&str, &[Selection]>,
+    out: &mut Vec<String>,
+) {
+    for field in selection {
+        let path = join_path(prefix, response_key(field));
+        let children = fields_of(&field.selection_set, fragments);
+        let connection = children.len() == 1 && response_key(children[0]) == "nodes";
+        if children.is_empty() || connection {
+            if !out.contains(&path) {
+                out.push(path);
+            }
+        } else {
+            push_columns(&path, &children, fragments, out);
+        }
+    }
+}
+
+/// The fields of a selection set, with fragments spread in
+fn fields_of<'a>(
+    selection: &'a [Selection],
+    fragments: &HashMap<&str, &'a [Selection]>,
+) -> Vec<&'a Node<Field>> {
+    let mut fields: Vec<&Node<Field>> = Vec::new();
+    for item in selection {
+        let spread = match item {
+            Selection::Field(field) => vec![field],
+            Selection::InlineFragment(fragment) => fields_of(&fragment.selection_set, fragments),
+            Selection::FragmentSpread(spread) => fragments
+                .get(spread.fragment_name.as_str())
+                .map(|set| fields_of(set, fragments))
+                .unwrap_or_default(),
+        };
+        for field in spread {
+            if !fields
+                .iter()
+                .any(|f| response_key(f) == response_key(field))
+            {
+                fields.push(field);
+            }
+        }
+    }
+    fields
+}
+
+fn response_key(field: &Field) -> &str {
+    field.alias.as_ref().unwrap_or(&field.name).as_str()
+}
+
+/// A GraphQL connection, `{ "nodes": [...] }`
+fn is_connection(map: &Map<String, Value>) -> bool {
+    map.len() == 1 && map.get("nodes").is_some_and(Value::is_array)
+}
+
+/// A value as plain text for a single cell or template field
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Array(items) => items.iter().map(item_name).collect::<Vec<_>>().join(", "),
+        Value::Object(map) if is_connection(map) => cell_text(&map["nodes"]),
+        Value::Object(_) => value.to_string(),
+    }
+}
+
+/// How a list item reads in a cell: its name, identifier, key or id if it has one
+fn item_name(item: &Value) -> String {
+    ["name", "identifier", "key", "id"]
+        .iter()
+        .find_map(|field| item.get(field).and_then(Value::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| cell_text(item))
+}
+
+/// Fill in `{{field}}` and `{{nested.field}}` placeholders from `value`.
+/// `\t` and `\n` in the template become a tab and a newline.
+pub fn render_template(template: &str, value: &Value) -> String {
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    let mut out = String::new();
+    let mut rest = template.as_str();
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let path = rest[start + 2..start + len].trim();
+        out.push_str(&cell_text(lookup(value, path)));
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> &'a Value {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .fold(value, |current, segment| match current {
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get(i))
+                .unwrap_or(&Value::Null),
+            _ => current.get(segment).unwrap_or(&Value::Null),
+        })
+}
+
+/// Prints table rows one page at a time, with the header only on the first page.
+#[derive(Default)]
+pub struct TableStream {
+    rows: usize,
+}
+
+impl TableStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print a page of rows
+    pub fn push<T: Tabled>(&mut self, rows: Vec<T>) {
+        if rows.is_empty() {
+            return;
+        }
+        let count = rows.len();
+        let mut table = Table::new(rows);
+        if self.rows > 0 {
+            table.with(Disable::row(Rows::first()));
+        }
+        println!("{}", table);
+        self.rows += count;
+    }
+
+    /// Number of rows printed so far
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn issues() -> Vec<Value> {
+        vec![
+            json!({
+                "identifier": "ENG-1",
+                "title": "Fix login, again",
+                "state": { "name": "Todo" },
+                "labels": { "nodes": [{ "name": "bug" }, { "name": "ui" }] }
+            }),
+            json!({
+                "identifier": "ENG-2",
+                "title": "Say \"hi\"\nnicely",
+                "state": null,
+                "labels": { "nodes": [] }
+            }),
+        ]
+    }
+
+    fn render(format: OutputFormat, items: &[Value]) -> String {
+        ValueStream::new(format).render(items).unwrap()
+    }
+
+    #[test]
+    fn test_csv_flattens_and_quotes() {
+        assert_eq!(
+            render(OutputFormat::Csv, &issues()),
+            "identifier,title,state.name,labels\n\
+             ENG-1,\"Fix login, again\",Todo,\"bug, ui\"\n\
+             ENG-2,\"Say \"\"hi\"\"\nnicely\",,\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_and_markdown() {
+        let tsv = render(OutputFormat::Tsv, &issues());
+        assert_eq!(
+            tsv.lines().next(),
+            Some("identifier\ttitle\tstate.name\tlabels")
+        );
+        assert_eq!(tsv.lines().nth(2), Some("ENG-2\tSay \"hi\" nicely\t\t"));
+
+        let markdown = render(OutputFormat::Markdown, &[json!({ "a": "x|y", "b": 1 })]);
+        assert_eq!(markdown, "| a | b |\n| --- | --- |\n| x\\|y | 1 |\n");
+    }
+
+    #[test]
+    fn test_columns_come_from_the_selection_set() {
+        let query = r#"
+            query($after: String) {
+                issues(after: $after) {
+                    nodes { ...Fields state { name } number: priority labels { nodes { name } } }
+                    pageInfo { hasNextPage endCursor }
+                }
+            }
+            fragment Fields on Issue { identifier dueDate }
+        "#;
+        let mut stream = ValueStream::new(OutputFormat::Csv).columns(query, &["issues"]);
+        let first = stream
+            .render(&[json!({ "identifier": "ENG-1", "state": null, "number": 1 })])
+            .unwrap();
+        assert_eq!(
+            first,
+            "identifier,dueDate,state.name,number,labels\nENG-1,,,1,\n"
+        );
+        let later = stream
+            .render(&[json!({
+                "identifier": "ENG-2", "dueDate": "2026-10-01", "state": { "name": "Todo" },
+                "labels": { "nodes": [{ "name": "bug" }] }
+            })])
+            .unwrap();
+        assert_eq!(later, "ENG-2,2026-10-01,Todo,,bug\n");
+    }
+
+    #[test]
+    fn test_ndjson_and_yaml() {
+        let items = [json!({ "id": "1" }), json!({ "id": "2" })];
+        assert_eq!(
+            render(OutputFormat::Ndjson, &items),
+            "{\"id\":\"1\"}\n{\"id\":\"2\"}\n"
+        );
+        assert_eq!(render(OutputFormat::Yaml, &items), "- id: '1'\n- id: '2'\n");
+    }
+
+    #[test]
+    fn test_render_template() {
+        let issue = &issues()[0];
+        assert_eq!(
+            render_template(
+                "{{identifier}}\\t{{ state.name }} [{{labels}}] {{missing}}",
+                issue
+            ),
+            "ENG-1\tTodo [bug, ui] "
+        );
+        assert_eq!(
+            render_template("{{labels.nodes.1.name}} {{oops", issue),
+            "ui {{oops"
+        );
+    }
+}
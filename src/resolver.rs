@@ -0,0 +1,504 @@
+//! Resolution of team, user, workflow state and label names to IDs.
+//!
+//! Lookups are answered from the local cache when possible, so repeated commands
+//! like `-t ENG -s "In Progress" -a alice` work without touching the network. A
+//! cache miss, or a name the cached data doesn't know (it may have been created
+//! since), refreshes that cache from the API before giving up.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::api::{LinearClient, LinearError, PageOptions};
+use crate::cache::{Cache, CacheType};
+
+const TEAMS_QUERY: &str = r#"
+    query($first: Int, $after: String) {
+        teams(first: $first, after: $after) {
+            nodes {
+                id
+                key
+                name
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const USERS_QUERY: &str = r#"
+    query($first: Int, $after: String) {
+        users(first: $first, after: $after) {
+            nodes {
+                id
+                name
+                displayName
+                email
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const LABELS_QUERY: &str = r#"
+    query($first: Int, $after: String) {
+        issueLabels(first: $first, after: $after) {
+            nodes {
+                id
+                name
+                team { id }
+            }
+            pageInfo { hasNextPage endCursor }
+        }
+    }
+"#;
+
+const TEAM_STATES_QUERY: &str = r#"
+    query($teamId: String!) {
+        team(id: $teamId) {
+            id
+            name
+            states {
+                nodes {
+                    id
+                    name
+                    type
+                    color
+                    position
+                    description
+                }
+            }
+        }
+    }
+"#;
+
+const PROJECT_QUERY: &str = r#"
+    query($name: String!) {
+        projects(filter: { name: { eqIgnoreCase: $name } }, first: 1) {
+            nodes {
+                id
+                name
+            }
+        }
+    }
+"#;
+
+const VIEWER_QUERY: &str = r#"
+    query {
+        viewer {
+            id
+            name
+            email
+        }
+    }
+"#;
+
+/// Check if a string looks like a UUID (contains dashes and is 36 characters)
+pub fn is_uuid(s: &str) -> bool {
+    s.len() == 36 && s.chars().filter(|c| *c == '-').count() == 4
+}
+
+/// Resolves names to IDs, consulting the cache before the API
+pub struct Resolver<'a> {
+    client: &'a LinearClient,
+    /// Opened on first use. `None` if the cache is unavailable; every lookup
+    /// then hits the API
+    cache: tokio::sync::OnceCell<Option<Cache>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(client: &'a LinearClient) -> Self {
+        Self {
+            client,
+            cache: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    async fn cache(&self) -> Option<&Cache> {
+        self.cache
+            .get_or_init(|| async { Cache::open(self.client).await.ok() })
+            .await
+            .as_ref()
+    }
+
+    /// Resolve a team key (like "ENG"), name or UUID to a team UUID
+    pub async fn team_id(&self, team: &str) -> Result<String> {
+        if is_uuid(team) {
+            return Ok(team.to_string());
+        }
+
+        let (found, teams) = self
+            .lookup(CacheType::Teams, TEAMS_QUERY, "teams", |teams| {
+                find_team(teams, team)
+            })
+            .await?;
+
+        found.ok_or_else(|| {
+            let keys = teams.iter().filter_map(|t| t["key"].as_str());
+            let candidates: Vec<&str> = keys.chain(names(&teams)).collect();
+            not_found("team", team, None, &candidates)
+        })
+    }
+
+    /// Resolve "me", a UUID, a name, display name or email to a user UUID
+    pub async fn user_id(&self, user: &str) -> Result<String> {
+        if user.eq_ignore_ascii_case("me") {
+            return self.viewer_id().await;
+        }
+        if is_uuid(user) {
+            return Ok(user.to_string());
+        }
+
+        let (found, users) = self
+            .lookup(CacheType::Users, USERS_QUERY, "users", |users| {
+                find_user(users, user)
+            })
+            .await?;
+
+        found.ok_or_else(|| not_found("user", user, None, &names(&users)))
+    }
+
+    /// Resolve a workflow state name or UUID to a state UUID within a team
+    pub async fn state_id(&self, team_id: &str, state: &str) -> Result<String> {
+        if is_uuid(state) {
+            return Ok(state.to_string());
+        }
+
+        if let Some((_, states)) = self.cached_team_states(team_id).await {
+            if let Some(id) = find_by_name(&states, state) {
+                return Ok(id);
+            }
+        }
+
+        let (team_name, states) = self.fetch_team_states(team_id).await?;
+        find_by_name(&states, state).ok_or_else(|| {
+            let scope = format!("team {}", team_name);
+            let scope = (!team_name.is_empty()).then_some(scope.as_str());
+            not_found("state", state, scope, &names(&states))
+        })
+    }
+
+    /// Resolve an issue label name or UUID to a label UUID. With a team, only
+    /// that team's labels and workspace-wide labels are considered.
+    pub async fn label_id(&self, label: &str, team_id: Option<&str>) -> Result<String> {
+        if is_uuid(label) {
+            return Ok(label.to_string());
+        }
+
+        let in_scope = |l: &Value| match (team_id, l["team"]["id"].as_str()) {
+            (Some(team_id), Some(label_team)) => label_team == team_id,
+            _ => true,
+        };
+        let (found, labels) = self
+            .lookup(CacheType::Labels, LABELS_QUERY, "issueLabels", |labels| {
+                labels
+                    .iter()
+                    .filter(|l| in_scope(l))
+                    .find(|l| field_matches(l, "name", label))
+                    .and_then(node_id)
+            })
+            .await?;
+
+        found.ok_or_else(|| {
+            let labels: Vec<Value> = labels.into_iter().filter(|l| in_scope(l)).collect();
+            not_found("label", label, None, &names(&labels))
+        })
+    }
+
+    /// Resolve a project name or UUID to a project UUID. Projects come and go
+    /// too often to cache, so names are always looked up.
+    pub async fn project_id(&self, project: &str) -> Result<String> {
+        if is_uuid(project) {
+            return Ok(project.to_string());
+        }
+
+        let result = self
+            .client
+            .query(PROJECT_QUERY, Some(json!({ "name": project })))
+            .await?;
+        result["data"]["projects"]["nodes"]
+            .as_array()
+            .and_then(|nodes| nodes.first())
+            .and_then(node_id)
+            .ok_or_else(|| not_found("project", project, None, &[]))
+    }
+
+    /// The current user's UUID
+    pub async fn viewer_id(&self) -> Result<String> {
+        let cached = self.cache().await.and_then(|c| c.get(CacheType::Viewer));
+        if let Some(id) = cached.as_ref().and_then(|v| v["id"].as_str()) {
+            return Ok(id.to_string());
+        }
+
+        let result = self.client.query(VIEWER_QUERY, None).await?;
+        let viewer = &result["data"]["viewer"];
+        let id = viewer["id"]
+            .as_str()
+            .context("Could not fetch current user ID")?
+            .to_string();
+        self.store(CacheType::Viewer, viewer.clone()).await;
+        Ok(id)
+    }
+
+    /// All teams, from the cache if it is fresh
+    pub async fn teams(&self) -> Result<Vec<Value>> {
+        match self.cached_nodes(CacheType::Teams).await {
+            Some(teams) => Ok(teams),
+            None => self.refresh(CacheType::Teams, TEAMS_QUERY, "teams").await,
+        }
+    }
+
+    /// A team's name and workflow states, from the cache if it is fresh
+    pub async fn team_states(&self, team_id: &str) -> Result<(String, Vec<Value>)> {
+        match self.cached_team_states(team_id).await {
+            Some(cached) => Ok(cached),
+            None => self.fetch_team_states(team_id).await,
+        }
+    }
+
+    /// Search the cached nodes for `cache_type`, refreshing them from the API if
+    /// they are missing or nothing matches. Returns the match along with the
+    /// nodes that were searched last.
+    async fn lookup<F>(
+        &self,
+        cache_type: CacheType,
+        query: &str,
+        path: &str,
+        find: F,
+    ) -> Result<(Option<String>, Vec<Value>)>
+    where
+        F: Fn(&[Value]) -> Option<String>,
+    {
+        if let Some(nodes) = self.cached_nodes(cache_type).await {
+            if let Some(id) = find(&nodes) {
+                return Ok((Some(id), nodes));
+            }
+        }
+
+        let nodes = self.refresh(cache_type, query, path).await?;
+        Ok((find(&nodes), nodes))
+    }
+
+    async fn cached_nodes(&self, cache_type: CacheType) -> Option<Vec<Value>> {
+        self.cache().await?.get(cache_type)?.as_array().cloned()
+    }
+
+    async fn refresh(&self, cache_type: CacheType, query: &str, path: &str) -> Result<Vec<Value>> {
+        let nodes = self
+            .client
+            .fetch_all(query, None, &[path], PageOptions::all())
+            .await?;
+        self.store(cache_type, json!(nodes)).await;
+        Ok(nodes)
+    }
+
+    async fn cached_team_states(&self, team_id: &str) -> Option<(String, Vec<Value>)> {
+        let cached = self
+            .cache()
+            .await?
+            .get_keyed(CacheType::Statuses, team_id)?;
+        let name = cached["team_name"].as_str().unwrap_or("").to_string();
+        let states = cached["states"].as_array().cloned().unwrap_or_default();
+        Some((name, states))
+    }
+
+    async fn fetch_team_states(&self, team_id: &str) -> Result<(String, Vec<Value>)> {
+        let result = self
+            .client
+            .query(TEAM_STATES_QUERY, Some(json!({ "teamId": team_id })))
+            .await?;
+        let team = &result["data"]["team"];
+
+        if team.is_null() {
+            anyhow::bail!("Team not found: {}", team_id);
+        }
+
+        let name = team["name"].as_str().unwrap_or("").to_string();
+        let states = team["states"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        if let Some(cache) = self.cache().await {
+            let _ = cache.set_keyed(
+                CacheType::Statuses,
+                team_id,
+                json!({ "team_name": name, "states": states }),
+            );
+        }
+
+        Ok((name, states))
+    }
+
+    /// Best-effort cache write; a failure only costs a network round trip later
+    async fn store(&self, cache_type: CacheType, data: Value) {
+        if let Some(cache) = self.cache().await {
+            let _ = cache.set(cache_type, data);
+        }
+    }
+}
+
+fn node_id(node: &Value) -> Option<String> {
+    node["id"].as_str().map(|id| id.to_string())
+}
+
+fn field_matches(node: &Value, field: &str, value: &str) -> bool {
+    node[field]
+        .as_str()
+        .is_some_and(|v| v.eq_ignore_ascii_case(value))
+}
+
+/// Find a team by key, then by name (both case-insensitive)
+fn find_team(teams: &[Value], team: &str) -> Option<String> {
+    teams
+        .iter()
+        .find(|t| field_matches(t, "key", team))
+        .or_else(|| teams.iter().find(|t| field_matches(t, "name", team)))
+        .and_then(node_id)
+}
+
+/// Find a user by name, display name or email (case-insensitive)
+fn find_user(users: &[Value], user: &str) -> Option<String> {
+    users
+        .iter()
+        .find(|u| {
+            ["name", "displayName", "email"]
+                .iter()
+                .any(|field| field_matches(u, field, user))
+        })
+        .and_then(node_id)
+}
+
+fn names(nodes: &[Value]) -> Vec<&str> {
+    nodes.iter().filter_map(|n| n["name"].as_str()).collect()
+}
+
+/// A not-found error that suggests the closest candidates, or lists them all
+/// when none are close
+fn not_found(kind: &str, input: &str, scope: Option<&str>, candidates: &[&str]) -> anyhow::Error {
+    // Displayed as "Not found: {message}"
+    let mut message = match scope {
+        Some(scope) => format!("{} '{}' in {}.", kind, input, scope),
+        None => format!("{} '{}'.", kind, input),
+    };
+
+    let suggestions = suggest(input, candidates);
+    if !suggestions.is_empty() {
+        let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+        message.push_str(&format!(" Did you mean {}?", quoted.join(" or ")));
+    } else if !candidates.is_empty() && candidates.len() <= 20 {
+        message.push_str(&format!(" Available: {}", candidates.join(", ")));
+    }
+
+    LinearError::NotFound { message }.into()
+}
+
+/// Up to three candidates close to `input`: within a small edit distance, or
+/// containing it (or contained in it), closest first
+fn suggest<'c>(input: &str, candidates: &[&'c str]) -> Vec<&'c str> {
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = edit_distance(&input, &lower);
+            let related = lower.contains(&input) || (input.len() >= 3 && input.contains(&lower));
+            (distance <= max_distance || related).then_some((distance, *candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
+/// Levenshtein distance between two strings, by chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev_diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev_diagonal + usize::from(ca != *cb);
+            prev_diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Find a node by name (case-insensitive)
+fn find_by_name(nodes: &[Value], name: &str) -> Option<String> {
+    nodes
+        .iter()
+        .find(|n| field_matches(n, "name", name))
+        .and_then(node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_team_prefers_key_over_name() {
+        let teams = vec![
+            json!({ "id": "t1", "key": "OPS", "name": "ENG" }),
+            json!({ "id": "t2", "key": "ENG", "name": "Engineering" }),
+        ];
+        assert_eq!(find_team(&teams, "eng").as_deref(), Some("t2"));
+        assert_eq!(find_team(&teams, "engineering").as_deref(), Some("t2"));
+        assert_eq!(find_team(&teams, "Design"), None);
+    }
+
+    #[test]
+    fn test_find_user_matches_name_display_name_or_email() {
+        let users = vec![
+            json!({ "id": "u1", "name": "Alice Smith", "displayName": "alice", "email": "alice@example.com" }),
+            json!({ "id": "u2", "name": "Bob", "email": null }),
+        ];
+        assert_eq!(find_user(&users, "alice").as_deref(), Some("u1"));
+        assert_eq!(
+            find_user(&users, "ALICE@example.com").as_deref(),
+            Some("u1")
+        );
+        assert_eq!(find_user(&users, "bob").as_deref(), Some("u2"));
+        assert_eq!(find_user(&users, "carol"), None);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let states = ["Backlog", "Todo", "In Progress", "In Review", "Done"];
+        assert_eq!(suggest("Bakclog", &states), vec!["Backlog"]);
+        assert_eq!(suggest("progress", &states), vec!["In Progress"]);
+        assert_eq!(suggest("in", &states), vec!["In Review", "In Progress"]);
+        assert!(suggest("Shipped", &states).is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_not_found_message() {
+        let err = not_found(
+            "state",
+            "Bakclog",
+            Some("team Engineering"),
+            &["Backlog", "Done"],
+        );
+        assert_eq!(
+            err.to_string(),
+            "Not found: state 'Bakclog' in team Engineering. Did you mean 'Backlog'?"
+        );
+        let err = not_found("label", "Shipped", None, &["bug", "feature"]);
+        assert_eq!(
+            err.to_string(),
+            "Not found: label 'Shipped'. Available: bug, feature"
+        );
+    }
+
+    #[test]
+    fn test_is_uuid() {
+        assert!(is_uuid("8f2e1c3a-0000-4000-8000-000000000001"));
+        assert!(!is_uuid("ENG"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Retry policy for Linear API requests.
+//!
+//! Failures are classified as transient (rate limits, connection problems, 5xx)
+//! or permanent. Transient failures are retried with exponential backoff and
+//! jitter, honouring any reset time the server sends back.
+
+use reqwest::header::HeaderMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default number of attempts per request, including the first
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+
+/// Why a request failed, as far as retrying is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// HTTP 429 or a `RATELIMITED` GraphQL error
+    RateLimited,
+    /// The request never reached the server
+    Connect,
+    /// Timeouts and 5xx responses
+    Server,
+    /// Anything retrying won't fix (bad input, auth, not found)
+    Permanent,
+}
+
+impl FailureKind {
+    pub fn is_transient(self) -> bool {
+        !matches!(self, FailureKind::Permanent)
+    }
+
+    /// Whether a mutation can be resent without risking it being applied twice.
+    /// Rate-limited and unconnected requests were never processed.
+    pub fn is_safe_to_resend(self) -> bool {
+        matches!(self, FailureKind::RateLimited | FailureKind::Connect)
+    }
+
+    /// Classify an HTTP status code
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            429 => FailureKind::RateLimited,
+            408 | 500 | 502 | 503 | 504 => FailureKind::Server,
+            _ => FailureKind::Permanent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles with each attempt
+    pub base_delay: Duration,
+    /// Upper bound on any single wait. If the server asks for longer, give up.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_max_attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Exponential backoff with "equal jitter": half the delay is fixed, the other
+    /// half is scaled by `jitter` (0.0..=1.0). `retry` is 1 for the first retry.
+    pub fn backoff(&self, retry: u32, jitter: f64) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = exp / 2;
+        half + half.mul_f64(jitter.clamp(0.0, 1.0))
+    }
+
+    /// How long to wait before the next attempt, or `None` to give up.
+    /// A server-provided reset time takes precedence over backoff.
+    pub fn next_delay(
+        &self,
+        attempt: u32,
+        kind: FailureKind,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if !kind.is_transient() || attempt >= self.max_attempts {
+            return None;
+        }
+        match retry_after {
+            Some(wait) if wait > self.max_delay => None,
+            Some(wait) => Some(wait),
+            None => Some(self.backoff(attempt, jitter())),
+        }
+    }
+}
+
+/// How long the server asked us to wait, from `Retry-After` (seconds) or
+/// Linear's `X-RateLimit-*-Reset` headers (epoch milliseconds).
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(secs) = header_u64(headers, "retry-after") {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    // Only the limits that are exhausted matter; wait for the latest of them
+    ["requests", "complexity", "endpoint-requests"]
+        .iter()
+        .filter(|kind| header_u64(headers, &format!("x-ratelimit-{}-remaining", kind)) == Some(0))
+        .filter_map(|kind| header_u64(headers, &format!("x-ratelimit-{}-reset", kind)))
+        .map(|reset_ms| Duration::from_millis(reset_ms.saturating_sub(now_ms)))
+        .max()
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// A pseudo-random fraction in 0.0..1.0, good enough to spread out retries
+fn jitter() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1_000_000) / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1, 1.0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2, 1.0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3, 0.0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(30, 1.0), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_next_delay_respects_classification_and_attempts() {
+        let policy = RetryPolicy::with_max_attempts(3);
+        assert!(policy.next_delay(1, FailureKind::Permanent, None).is_none());
+        assert!(policy.next_delay(1, FailureKind::Server, None).is_some());
+        assert!(policy.next_delay(3, FailureKind::Server, None).is_none());
+        assert_eq!(
+            policy.next_delay(1, FailureKind::RateLimited, Some(Duration::from_secs(2))),
+            Some(Duration::from_secs(2))
+        );
+        // Resets further out than max_delay aren't worth waiting for
+        assert!(policy
+            .next_delay(1, FailureKind::RateLimited, Some(Duration::from_secs(3600)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_classification() {
+        assert_eq!(FailureKind::from_status(429), FailureKind::RateLimited);
+        assert_eq!(FailureKind::from_status(503), FailureKind::Server);
+        assert_eq!(FailureKind::from_status(400), FailureKind::Permanent);
+        assert_eq!(FailureKind::from_status(401), FailureKind::Permanent);
+        assert!(FailureKind::RateLimited.is_safe_to_resend());
+        assert!(!FailureKind::Server.is_safe_to_resend());
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("retry-after", HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ratelimit-requests-remaining",
+            HeaderValue::from_static("0"),
+        );
+        headers.insert(
+            "x-ratelimit-requests-reset",
+            HeaderValue::from_str(&(now_ms + 5_000).to_string()).unwrap(),
+        );
+        // Not exhausted, so its reset is ignored
+        headers.insert(
+            "x-ratelimit-complexity-remaining",
+            HeaderValue::from_static("100"),
+        );
+        headers.insert(
+            "x-ratelimit-complexity-reset",
+            HeaderValue::from_str(&(now_ms + 50_000).to_string()).unwrap(),
+        );
+        let wait = retry_after(&headers).unwrap();
+        assert!(wait <= Duration::from_secs(5) && wait > Duration::from_secs(4));
+    }
+}
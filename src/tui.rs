@@ -0,0 +1,179 @@
+//! Shared plumbing for the full-screen terminal UIs (`interactive`, `board -i`).
+//!
+//! Screens keep their state in a plain struct with a `draw(&self, frame)` method
+//! and a key handler, so they can be rendered to a `TestBackend` in unit tests.
+//! Only [`enter`], [`leave`] and [`terminal_events`] touch the real terminal.
+
+use anyhow::Result;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+use ratatui::{Frame, Terminal};
+use std::io::{self, Stdout};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// A full-screen terminal
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+/// Switch the terminal to raw mode on the alternate screen. The previous state
+/// is restored by [`leave`], or by the panic hook if the UI crashes.
+pub fn enter() -> Result<Tui> {
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        hook(info);
+    }));
+
+    Ok(Terminal::new(CrosstermBackend::new(io::stdout()))?)
+}
+
+/// Restore the terminal to the state it was in before [`enter`]
+pub fn leave(tui: &mut Tui) -> Result<()> {
+    restore()?;
+    tui.show_cursor()?;
+    Ok(())
+}
+
+fn restore() -> io::Result<()> {
+    terminal::disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)
+}
+
+/// Forward terminal events from a background thread, which exits once the
+/// receiver is dropped
+pub fn terminal_events() -> UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || loop {
+        match event::poll(Duration::from_millis(100)) {
+            Ok(true) => match event::read() {
+                Ok(ev) => {
+                    if tx.send(ev).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            },
+            Ok(false) if tx.is_closed() => break,
+            Ok(false) => {}
+            Err(_) => break,
+        }
+    });
+    rx
+}
+
+/// Text style for a workflow state type, matching `WorkflowState::paint`
+pub fn state_style(kind: Option<&str>) -> Style {
+    match kind {
+        Some("completed") => Style::new().fg(Color::Green),
+        Some("started") => Style::new().fg(Color::Yellow),
+        Some("canceled") | Some("cancelled") => Style::new().fg(Color::Red),
+        Some("backlog") => Style::new().add_modifier(Modifier::DIM),
+        Some("unstarted") => Style::new().fg(Color::Cyan),
+        _ => Style::new(),
+    }
+}
+
+/// Style for the highlighted row of a list
+pub fn highlight_style() -> Style {
+    Style::new()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD)
+}
+
+/// A pop-up list of choices, such as the states an issue can move to
+#[derive(Debug, Clone)]
+pub struct Picker<T> {
+    pub title: String,
+    pub options: Vec<(String, T)>,
+    pub selected: usize,
+}
+
+/// What a key press did to a [`Picker`]
+#[derive(Debug, PartialEq)]
+pub enum PickerOutcome<T> {
+    Pending,
+    Chosen(T),
+    Cancelled,
+}
+
+impl<T: Clone> Picker<T> {
+    pub fn new(title: impl Into<String>, options: Vec<(String, T)>, selected: usize) -> Self {
+        let selected = selected.min(options.len().saturating_sub(1));
+        Self {
+            title: title.into(),
+            options,
+            selected,
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> PickerOutcome<T> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.options.len() => {
+                self.selected += 1;
+            }
+            KeyCode::Enter => {
+                if let Some((_, value)) = self.options.get(self.selected) {
+                    return PickerOutcome::Chosen(value.clone());
+                }
+                return PickerOutcome::Cancelled;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return PickerOutcome::Cancelled,
+            _ => {}
+        }
+        PickerOutcome::Pending
+    }
+
+    /// Draw the picker as a pop-up centred over `area`
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let width = self
+            .options
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .chain([self.title.chars().count()])
+            .max()
+            .unwrap_or(0) as u16
+            + 6;
+        let height = self.options.len() as u16 + 2;
+        let popup = centered(area, width, height);
+
+        let items: Vec<ListItem> = self
+            .options
+            .iter()
+            .map(|(label, _)| ListItem::new(label.as_str()))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ", self.title)),
+            )
+            .highlight_style(highlight_style())
+            .highlight_symbol("> ");
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(list, popup, &mut state);
+    }
+}
+
+/// A `width` x `height` rectangle centred in `area`, clamped to fit
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [row] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [rect] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(row);
+    rect
+}
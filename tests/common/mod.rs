@@ -0,0 +1,346 @@
+//! Shared helpers for integration tests that run the CLI against a local
+//! stand-in for the Linear GraphQL API.
+
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Organization every mock server reports unless a test routes `CacheOrganization`
+pub const MOCK_ORGANIZATION_ID: &str = "org-mock";
+
+/// A canned HTTP response returned by the mock server
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Value,
+}
+
+impl MockResponse {
+    pub fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn status(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// A request received by the mock server
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    pub fn query(&self) -> &str {
+        self.body["query"].as_str().unwrap_or("")
+    }
+
+    pub fn variables(&self) -> &Value {
+        &self.body["variables"]
+    }
+}
+
+type Responder = Box<dyn FnMut(&RecordedRequest) -> MockResponse + Send>;
+
+struct Route {
+    needle: String,
+    responder: Responder,
+}
+
+/// Builder for a mock GraphQL server.
+///
+/// Each route matches when the request's GraphQL document contains the given
+/// needle (e.g. `"issueCreate"`). Routes are tried in registration order, so
+/// register more specific needles first.
+#[derive(Default)]
+pub struct MockServerBuilder {
+    routes: Vec<Route>,
+}
+
+impl MockServerBuilder {
+    /// Respond to matching requests with `{"data": data}`
+    pub fn on(self, needle: &str, data: Value) -> Self {
+        let body = serde_json::json!({ "data": data });
+        self.respond(needle, move |_| MockResponse::ok(body.clone()))
+    }
+
+    /// Respond to matching requests with a custom responder
+    pub fn respond<F>(mut self, needle: &str, responder: F) -> Self
+    where
+        F: FnMut(&RecordedRequest) -> MockResponse + Send + 'static,
+    {
+        self.routes.push(Route {
+            needle: needle.to_string(),
+            responder: Box::new(responder),
+        });
+        self
+    }
+
+    /// Respond to matching requests with each response in turn, repeating the last one
+    pub fn sequence(self, needle: &str, responses: Vec<MockResponse>) -> Self {
+        let mut remaining = responses.into_iter();
+        let mut last = None;
+        self.respond(needle, move |_| {
+            if let Some(next) = remaining.next() {
+                last = Some(next.clone());
+                next
+            } else {
+                last.clone().expect("sequence needs at least one response")
+            }
+        })
+    }
+
+    /// Start serving. Unless a test routes it itself, the organization lookup
+    /// the cache makes is answered with `MOCK_ORGANIZATION_ID`.
+    pub fn start(self) -> MockServer {
+        let this = self.on(
+            "CacheOrganization",
+            serde_json::json!({
+                "organization": { "id": MOCK_ORGANIZATION_ID, "name": "Mock Org" }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let routes = Arc::new(Mutex::new(this.routes));
+
+        let thread_requests = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                let requests = Arc::clone(&thread_requests);
+                let routes = Arc::clone(&routes);
+                thread::spawn(move || handle_connection(stream, &requests, &routes));
+            }
+        });
+
+        MockServer {
+            url: format!("http://{}", addr),
+            requests,
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    requests: &Mutex<Vec<RecordedRequest>>,
+    routes: &Mutex<Vec<Route>>,
+) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() || request_line.is_empty() {
+        return;
+    }
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("/")
+        .to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            return;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let body: Value = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).to_string()));
+    let request = RecordedRequest {
+        path,
+        headers,
+        body,
+    };
+
+    let response = {
+        let mut routes = routes.lock().unwrap();
+        let document = request.query().to_string();
+        let search = if document.is_empty() {
+            request.body.to_string()
+        } else {
+            document
+        };
+        match routes.iter_mut().find(|r| search.contains(&r.needle)) {
+            Some(route) => (route.responder)(&request),
+            None => MockResponse::status(
+                400,
+                serde_json::json!({
+                    "errors": [{ "message": format!("mock: no route for request: {}", search) }]
+                }),
+            ),
+        }
+    };
+
+    requests.lock().unwrap().push(request);
+
+    let body = response.body.to_string();
+    let mut out = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        body.len()
+    );
+    for (name, value) in &response.headers {
+        out.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    out.push_str("\r\n");
+    out.push_str(&body);
+
+    let mut stream = stream;
+    let _ = stream.write_all(out.as_bytes());
+    let _ = stream.flush();
+}
+
+/// A running mock server. The listener thread lives until the test process exits.
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    /// The GraphQL endpoint to point the CLI at
+    pub fn graphql_url(&self) -> String {
+        format!("{}/graphql", self.url)
+    }
+
+    /// All requests received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Requests whose GraphQL document contains `needle`
+    pub fn requests_matching(&self, needle: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.query().contains(needle))
+            .collect()
+    }
+}
+
+/// Output of a CLI invocation
+pub struct CliOutput {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// An isolated environment for running the CLI: a private config/home
+/// directory and (optionally) a mock API endpoint.
+pub struct TestEnv {
+    home: tempfile::TempDir,
+    api_url: Option<String>,
+    env: Vec<(String, String)>,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        Self {
+            home: tempfile::tempdir().expect("create temp home"),
+            api_url: None,
+            env: Vec::new(),
+        }
+    }
+
+    pub fn with_server(server: &MockServer) -> Self {
+        let mut env = Self::new();
+        env.api_url = Some(server.graphql_url());
+        env
+    }
+
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    /// The CLI's config directory inside the isolated home
+    pub fn config_dir(&self) -> PathBuf {
+        self.home.path().join(".config").join("linear-cli")
+    }
+
+    pub fn command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_linear-cli"));
+        // Run from the temp home so no `.linear.toml` above the checkout leaks in
+        cmd.args(args)
+            .current_dir(self.home.path())
+            .env_clear()
+            .env("PATH", std::env::var("PATH").unwrap_or_default())
+            .env("HOME", self.home.path())
+            .env("XDG_CONFIG_HOME", self.home.path().join(".config"))
+            .env("NO_COLOR", "1")
+            .env("LINEAR_API_KEY", "lin_api_test")
+            // Never touch the developer's OS keyring; tests of the other
+            // stores pick theirs with `.env(...)`
+            .env("LINEAR_CREDENTIAL_STORE", "plaintext");
+        if let Some(url) = &self.api_url {
+            cmd.env("LINEAR_API_URL", url);
+        }
+        for (key, value) in &self.env {
+            cmd.env(key, value);
+        }
+        cmd
+    }
+
+    pub fn run(&self, args: &[&str]) -> CliOutput {
+        let output = self.command(args).output().expect("run linear-cli");
+        CliOutput {
+            code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
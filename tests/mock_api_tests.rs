@@ -0,0 +1,2215 @@
+mod common;
+
+use common::{MockServer, TestEnv, MOCK_ORGANIZATION_ID};
+use serde_json::json;
+use std::fs;
+
+const TEAM_ID: &str = "8f2e1c3a-0000-4000-8000-000000000001";
+
+fn teams_response() -> serde_json::Value {
+    json!({
+        "teams": {
+            "nodes": [
+                { "id": TEAM_ID, "key": "ENG", "name": "Engineering" }
+            ]
+        }
+    })
+}
+
+#[test]
+fn test_issues_list_against_mock() {
+    let server = MockServer::builder()
+        .on(
+            "issues(",
+            json!({
+                "issues": {
+                    "nodes": [
+                        {
+                            "id": "issue-1",
+                            "identifier": "ENG-1",
+                            "title": "Fix login",
+                            "priority": 2,
+                            "state": { "id": "state-todo", "name": "Todo" },
+                            "assignee": { "id": "user-alice", "name": "Alice" }
+                        }
+                    ]
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "list", "-t", "ENG"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("ENG-1"));
+    assert!(out.stdout.contains("Fix login"));
+    assert!(out.stdout.contains("Alice"));
+
+    let requests = server.requests_matching("issues(");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/graphql");
+    assert_eq!(requests[0].headers["authorization"], "lin_api_test");
+    assert_eq!(requests[0].variables()["filter"][0], team_filter("ENG"));
+}
+
+/// The issue filter `-t` adds, matching the team's key or name
+fn team_filter(team: &str) -> serde_json::Value {
+    json!({ "team": { "or": [
+        { "key": { "eqIgnoreCase": team } },
+        { "name": { "eqIgnoreCase": team } }
+    ] } })
+}
+
+#[test]
+fn test_issues_create_resolves_team() {
+    let server = MockServer::builder()
+        .on(
+            "issueCreate",
+            json!({
+                "issueCreate": {
+                    "success": true,
+                    "issue": {
+                        "id": "issue-2",
+                        "identifier": "ENG-2",
+                        "title": "New thing",
+                        "url": "https://linear.app/test/issue/ENG-2"
+                    }
+                }
+            }),
+        )
+        .on("teams(", teams_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&[
+        "--output",
+        "json",
+        "issues",
+        "create",
+        "New thing",
+        "-t",
+        "ENG",
+        "-p",
+        "2",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let created: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(created["identifier"], "ENG-2");
+
+    let requests = server.requests_matching("issueCreate");
+    assert_eq!(requests.len(), 1);
+    let input = &requests[0].variables()["input"];
+    assert_eq!(input["teamId"], TEAM_ID);
+    assert_eq!(input["title"], "New thing");
+    assert_eq!(input["priority"], 2);
+}
+
+#[test]
+fn test_projects_list_json_against_mock() {
+    let server = MockServer::builder()
+        .on(
+            "projects(",
+            json!({
+                "projects": {
+                    "nodes": [
+                        { "id": "p1", "name": "Roadmap", "state": "started", "url": null, "startDate": null, "targetDate": null }
+                    ]
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["--output", "json", "projects", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let projects: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(projects[0]["name"], "Roadmap");
+}
+
+#[test]
+fn test_bulk_update_state_against_mock() {
+    let server = MockServer::builder()
+        .respond("issueUpdate", |req| {
+            let id = req.variables()["id"].as_str().unwrap_or("").to_string();
+            common::MockResponse::ok(json!({
+                "data": {
+                    "issueUpdate": {
+                        "success": true,
+                        "issue": { "identifier": id.replace("uuid-", "ENG-"), "title": "t" }
+                    }
+                }
+            }))
+        })
+        .on(
+            "states",
+            json!({
+                "team": {
+                    "states": {
+                        "nodes": [
+                            { "id": "state-todo", "name": "Todo" },
+                            { "id": "state-done", "name": "Done" }
+                        ]
+                    }
+                }
+            }),
+        )
+        .respond("issue(id", |req| {
+            let id = req.variables()["id"].as_str().unwrap_or("").to_string();
+            common::MockResponse::ok(json!({
+                "data": {
+                    "issue": {
+                        "id": id.replace("ENG-", "uuid-"),
+                        "identifier": id,
+                        "team": { "id": TEAM_ID }
+                    }
+                }
+            }))
+        })
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["bulk", "update-state", "Done", "-i", "ENG-1,ENG-2"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("2 succeeded"), "stdout: {}", out.stdout);
+
+    let updates = server.requests_matching("issueUpdate");
+    assert_eq!(updates.len(), 2);
+    for update in updates {
+        assert_eq!(update.variables()["input"]["stateId"], "state-done");
+    }
+
+    // Data formats get the per-issue results instead of the summary
+    let out = env.run(&["-o", "json", "bulk", "state", "Done", "-i", "ENG-3"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let results: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(results[0]["issueId"], "ENG-3");
+    assert_eq!(results[0]["identifier"], "ENG-3");
+    assert_eq!(results[0]["success"], true);
+}
+
+#[test]
+fn test_workspace_api_url_from_config() {
+    let server = MockServer::builder().on("teams(", teams_response()).start();
+    let env = TestEnv::new();
+
+    fs::create_dir_all(env.config_dir()).unwrap();
+    fs::write(
+        env.config_dir().join("config.toml"),
+        format!(
+            "current = \"mock\"\n\n[workspaces.mock]\napi_key = \"lin_api_config\"\napi_url = \"{}\"\n",
+            server.graphql_url()
+        ),
+    )
+    .unwrap();
+
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("Engineering"));
+
+    assert_eq!(server.requests_matching("teams(").len(), 1);
+    for request in server.requests() {
+        assert_eq!(request.headers["authorization"], "lin_api_config");
+    }
+}
+
+#[test]
+fn test_workspace_flag_targets_another_workspace() {
+    let work = MockServer::builder().on("teams(", teams_response()).start();
+    let personal = MockServer::builder()
+        .on(
+            "CacheOrganization",
+            json!({ "organization": { "id": "org-personal", "name": "Personal" } }),
+        )
+        .on(
+            "teams(",
+            json!({ "teams": { "nodes": [{ "id": "team-home", "key": "HOME", "name": "Home" }] } }),
+        )
+        .start();
+    let env = TestEnv::new().env("LINEAR_CREDENTIAL_STORE", "plaintext");
+
+    fs::create_dir_all(env.config_dir()).unwrap();
+    let config_path = env.config_dir().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "current = \"work\"\n\n[workspaces.work]\napi_key = \"lin_api_work\"\napi_url = \"{}\"\n\n\
+             [workspaces.personal]\napi_key = \"lin_api_personal\"\napi_url = \"{}\"\n",
+            work.graphql_url(),
+            personal.graphql_url()
+        ),
+    )
+    .unwrap();
+    let before = fs::read_to_string(&config_path).unwrap();
+
+    let teams = |args: &[&str]| {
+        let out = env
+            .command(args)
+            .env_remove("LINEAR_API_KEY")
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+
+    let stdout = teams(&[
+        "--workspace",
+        "personal",
+        "--output",
+        "json",
+        "teams",
+        "list",
+    ]);
+    assert!(stdout.contains("Home"), "{}", stdout);
+    assert_eq!(
+        personal.requests()[0].headers["authorization"],
+        "lin_api_personal"
+    );
+
+    // An explicit --workspace beats the environment's key and endpoint
+    let out = env
+        .command(&["--workspace", "personal", "-o", "json", "teams", "list"])
+        .env("LINEAR_API_URL", work.graphql_url())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Home"));
+    assert!(personal
+        .requests()
+        .iter()
+        .all(|r| r.headers["authorization"] == "lin_api_personal"));
+
+    // The current workspace is untouched, and its cache is its own
+    let stdout = teams(&["--output", "json", "teams", "list"]);
+    assert!(stdout.contains("Engineering"), "{}", stdout);
+    assert_eq!(work.requests_matching("teams(").len(), 1);
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), before);
+    let cache = env.config_dir().join("cache");
+    assert!(cache.join(MOCK_ORGANIZATION_ID).join("teams.json").exists());
+    assert!(cache.join("org-personal").join("teams.json").exists());
+
+    let out = env.run(&["--workspace", "nope", "teams", "list"]);
+    assert_ne!(out.code, 0);
+    assert!(
+        out.stderr
+            .contains("Workspace 'nope' not found. Workspaces: personal, work"),
+        "{}",
+        out.stderr
+    );
+}
+
+#[test]
+fn test_cache_is_dropped_when_the_key_changes() {
+    let server = MockServer::builder().on("teams(", teams_response()).start();
+    let env = TestEnv::with_server(&server);
+
+    for _ in 0..2 {
+        let out = env.run(&["--output", "json", "teams", "list"]);
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    }
+    assert_eq!(server.requests_matching("teams(").len(), 1);
+    assert_eq!(server.requests_matching("CacheOrganization").len(), 1);
+
+    // Same organization, different key: the cached teams aren't trusted
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env("LINEAR_API_KEY", "lin_api_rotated")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(server.requests_matching("teams(").len(), 2);
+
+    let out = env.run(&["cache", "status", "--output", "json"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let status: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(status[0]["organization"]["id"], MOCK_ORGANIZATION_ID);
+    assert_eq!(status[0]["organization"]["name"], "Mock Org");
+    assert_eq!(status[0]["workspaces"], json!(["default"]));
+    let teams = status[0]["caches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["type"] == "Teams")
+        .unwrap();
+    assert_eq!(teams["valid"], true);
+    assert_eq!(teams["items"], 1);
+
+    let entry = env
+        .config_dir()
+        .join("cache")
+        .join(MOCK_ORGANIZATION_ID)
+        .join("teams.json");
+    let stamped: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(entry).unwrap()).unwrap();
+    assert_eq!(stamped["version"], 2);
+    assert_eq!(stamped["key"].as_str().unwrap().len(), 16);
+}
+
+#[test]
+fn test_plaintext_keys_move_to_the_encrypted_file() {
+    let server = MockServer::builder().on("teams(", teams_response()).start();
+    let env = TestEnv::new()
+        .env("LINEAR_CREDENTIAL_STORE", "encrypted-file")
+        .env("LINEAR_CLI_PASSPHRASE", "correct horse");
+
+    fs::create_dir_all(env.config_dir()).unwrap();
+    let config_path = env.config_dir().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "current = \"mock\"\n\n[workspaces.mock]\napi_key = \"lin_api_secret\"\napi_url = \"{}\"\n",
+            server.graphql_url()
+        ),
+    )
+    .unwrap();
+
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(
+        server.requests()[0].headers["authorization"],
+        "lin_api_secret"
+    );
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(!config.contains("lin_api_secret"), "{}", config);
+    assert!(config.contains("credential_store = \"encrypted-file\""));
+    assert!(config.contains("keys_migrated = true"), "{}", config);
+    let encrypted = fs::read_to_string(env.config_dir().join("credentials.enc")).unwrap();
+    assert!(!encrypted.contains("lin_api_secret"));
+
+    // The move happens once; a plaintext key added later is left where it is
+    fs::write(
+        &config_path,
+        format!(
+            "{}\n[workspaces.other]\napi_key = \"lin_api_other\"\n",
+            config
+        ),
+    )
+    .unwrap();
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("lin_api_other"), "{}", config);
+
+    // Without the passphrase the key can't be read
+    let out = env
+        .command(&["api", "{ viewer { id } }"])
+        .env_remove("LINEAR_API_KEY")
+        .env_remove("LINEAR_CLI_PASSPHRASE")
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("LINEAR_CLI_PASSPHRASE"));
+}
+
+#[test]
+fn test_credential_command_supplies_the_key() {
+    let server = MockServer::builder().on("teams(", teams_response()).start();
+    let env = TestEnv::new();
+
+    fs::create_dir_all(env.config_dir()).unwrap();
+    fs::write(
+        env.config_dir().join("config.toml"),
+        format!(
+            "current = \"mock\"\n\n[workspaces.mock]\ncredential_command = \"echo lin_api_cmd\"\napi_url = \"{}\"\n",
+            server.graphql_url()
+        ),
+    )
+    .unwrap();
+
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(server.requests()[0].headers["authorization"], "lin_api_cmd");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_plaintext_config_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new().env("LINEAR_CREDENTIAL_STORE", "plaintext");
+    let out = env.run(&["config", "set-key", "lin_api_plain"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    let config_path = env.config_dir().join("config.toml");
+    assert!(fs::read_to_string(&config_path)
+        .unwrap()
+        .contains("lin_api_plain"));
+    let mode = fs::metadata(&config_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // A config written by an older version is tightened on the next run
+    fs::set_permissions(&config_path, fs::Permissions::from_mode(0o644)).unwrap();
+    let out = env.run(&["config", "show"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("lin_api_...lain (plaintext)"));
+    let mode = fs::metadata(&config_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn test_schema_drift_is_a_clear_error() {
+    // `identifier` is missing from the node, as if the API renamed it
+    let server = MockServer::builder()
+        .on(
+            "issues(",
+            json!({
+                "issues": {
+                    "nodes": [{ "id": "issue-1", "title": "Fix login" }]
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "list"]);
+    assert_eq!(out.code, 1);
+    assert!(
+        out.stderr.contains("Unexpected issue in API response"),
+        "stderr: {}",
+        out.stderr
+    );
+    assert!(out.stderr.contains("missing field `identifier`"));
+}
+
+fn issue_page(identifiers: &[&str], end_cursor: Option<&str>) -> common::MockResponse {
+    let nodes: Vec<_> = identifiers
+        .iter()
+        .map(|id| {
+            json!({
+                "id": format!("uuid-{}", id),
+                "identifier": id,
+                "title": format!("Issue {}", id),
+                "priority": 0,
+                "state": { "id": "state-todo", "name": "Todo" },
+                "assignee": null
+            })
+        })
+        .collect();
+    common::MockResponse::ok(json!({
+        "data": {
+            "issues": {
+                "nodes": nodes,
+                "pageInfo": { "hasNextPage": end_cursor.is_some(), "endCursor": end_cursor }
+            }
+        }
+    }))
+}
+
+#[test]
+fn test_issues_list_all_follows_cursors() {
+    let server = MockServer::builder()
+        .respond("issues(", |req| match req.variables()["after"].as_str() {
+            None => issue_page(&["ENG-1", "ENG-2"], Some("cursor-1")),
+            Some("cursor-1") => issue_page(&["ENG-3"], None),
+            Some(other) => panic!("unexpected cursor {}", other),
+        })
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["--output", "json", "issues", "list", "--all"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let issues: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    let identifiers: Vec<&str> = issues
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["identifier"].as_str().unwrap())
+        .collect();
+    assert_eq!(identifiers, vec!["ENG-1", "ENG-2", "ENG-3"]);
+
+    let requests = server.requests_matching("issues(");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].variables()["after"], "cursor-1");
+
+    // CSV columns come from the query, not the first page, which has no
+    // state and no due date
+    let server = MockServer::builder()
+        .respond("issues(", |req| {
+            let (node, page_info) = match req.variables()["after"].as_str() {
+                None => (
+                    json!({ "identifier": "ENG-1", "state": null }),
+                    json!({ "hasNextPage": true, "endCursor": "cursor-1" }),
+                ),
+                _ => (
+                    json!({ "identifier": "ENG-2", "state": { "name": "Todo" }, "dueDate": "2026-11-01" }),
+                    json!({ "hasNextPage": false, "endCursor": null }),
+                ),
+            };
+            common::MockResponse::ok(json!({ "data": { "issues": {
+                "nodes": [node], "pageInfo": page_info
+            } } }))
+        })
+        .start();
+    let env = TestEnv::with_server(&server);
+    let out = env.run(&[
+        "-o",
+        "csv",
+        "issues",
+        "list",
+        "--all",
+        "--fields",
+        "identifier,state.name,dueDate",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(
+        out.stdout,
+        "identifier,state.name,dueDate\nENG-1,,\nENG-2,Todo,2026-11-01\n"
+    );
+}
+
+#[test]
+fn test_issues_list_limit_stops_paging() {
+    let server = MockServer::builder()
+        .respond("issues(", |_| issue_page(&["ENG-1", "ENG-2"], Some("more")))
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "list", "--limit", "2"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("2 issues"), "stdout: {}", out.stdout);
+
+    let requests = server.requests_matching("issues(");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].variables()["first"], 2);
+}
+
+#[test]
+fn test_time_log_and_list() {
+    let server = MockServer::builder()
+        .on(
+            "commentCreate",
+            json!({ "commentCreate": { "success": true, "comment": { "id": "comment-1" } } }),
+        )
+        .on(
+            "issue(id:",
+            json!({
+                "issue": {
+                    "id": "issue-7",
+                    "identifier": "ENG-7",
+                    "title": "Profile page",
+                    "cycle": { "id": "cycle-3", "number": 3, "name": null }
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&[
+        "time",
+        "log",
+        "eng-7",
+        "1h30m",
+        "--date",
+        "2024-03-01",
+        "-m",
+        "Layout",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&[
+        "time",
+        "log",
+        "ENG-7",
+        "45m",
+        "--date",
+        "2024-03-02",
+        "--comment",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    let comments = server.requests_matching("commentCreate");
+    assert_eq!(comments.len(), 1);
+    let input = &comments[0].variables()["input"];
+    assert_eq!(input["issueId"], "issue-7");
+    assert!(input["body"]
+        .as_str()
+        .unwrap()
+        .contains("<!-- linear-cli:time {"));
+
+    let out = env.run(&["--output", "json", "time", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let totals: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(
+        totals,
+        json!([{ "group": "ENG-7", "entries": 2, "minutes": 135 }])
+    );
+
+    let out = env.run(&["time", "list", "--by", "cycle"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("ENG Cycle 3"));
+    assert!(out.stdout.contains("2h 15m"));
+
+    let ledger = fs::read_to_string(env.config_dir().join("time_ledger.json")).unwrap();
+    assert!(ledger.contains("\"comment_id\": \"comment-1\""));
+}
+
+#[test]
+fn test_timer_start_stop() {
+    let server = MockServer::builder()
+        .on(
+            "issue(id:",
+            json!({
+                "issue": {
+                    "id": "issue-8",
+                    "identifier": "ENG-8",
+                    "title": "Search",
+                    "cycle": null
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["time", "start", "ENG-8"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    // Only one timer at a time
+    let out = env.run(&["time", "start", "ENG-9"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("already running on ENG-8"));
+
+    // Pretend the timer has been running for 90 minutes
+    let timer_path = env.config_dir().join("timer.json");
+    let mut timer: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&timer_path).unwrap()).unwrap();
+    let started = chrono::Utc::now() - chrono::Duration::minutes(90);
+    timer["started_at"] = json!(started);
+    fs::write(&timer_path, timer.to_string()).unwrap();
+
+    let out = env.run(&["time", "status"]);
+    assert!(out.stdout.contains("1h 30m"), "stdout: {}", out.stdout);
+
+    // The comment can't be posted (the mock has no route for it), so nothing
+    // is logged and the timer keeps running
+    let out = env.run(&["time", "stop", "--comment"]);
+    assert_ne!(out.code, 0);
+    assert!(
+        out.stderr.contains("Time not logged"),
+        "stderr: {}",
+        out.stderr
+    );
+    assert!(timer_path.exists());
+    let ledger_path = env.config_dir().join("time_ledger.json");
+    assert!(!fs::read_to_string(&ledger_path).unwrap().contains("ENG-8"));
+
+    let out = env.run(&["time", "stop"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Logged 1h 30m on ENG-8"));
+    assert!(!timer_path.exists());
+
+    let out = env.run(&["time", "stop"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("No timer is running"));
+
+    // Discarding a timer doesn't need the API, or a key
+    let out = env.run(&["time", "start", "ENG-8"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env
+        .command(&["time", "stop", "--discard"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(!timer_path.exists());
+}
+
+fn issue_update_ok() -> common::MockResponse {
+    common::MockResponse::ok(json!({
+        "data": {
+            "issueUpdate": {
+                "success": true,
+                "issue": { "id": "issue-1", "identifier": "ENG-1", "title": "Fix login" }
+            }
+        }
+    }))
+}
+
+#[test]
+fn test_query_retries_rate_limit_and_server_errors() {
+    let server = MockServer::builder()
+        .sequence(
+            "teams(",
+            vec![
+                common::MockResponse::status(
+                    429,
+                    json!({ "errors": [{ "message": "Rate limit exceeded" }] }),
+                )
+                .header("Retry-After", "0"),
+                common::MockResponse::ok(json!({
+                    "errors": [{
+                        "message": "Rate limited",
+                        "extensions": { "code": "RATELIMITED" }
+                    }]
+                }))
+                .header("Retry-After", "0"),
+                common::MockResponse::ok(json!({ "data": teams_response() })),
+            ],
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["--output", "json", "teams", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Engineering"));
+    assert_eq!(server.requests_matching("teams(").len(), 3);
+    assert!(out.stderr.contains("retrying"));
+}
+
+#[test]
+fn test_retries_stop_at_max_attempts() {
+    let server = MockServer::builder()
+        .sequence(
+            "teams(",
+            vec![common::MockResponse::status(429, json!({})).header("Retry-After", "0")],
+        )
+        .start();
+    let env = TestEnv::with_server(&server).env("LINEAR_MAX_ATTEMPTS", "2");
+
+    let out = env.run(&["teams", "list"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("Rate limited"));
+    assert_eq!(server.requests_matching("teams(").len(), 2);
+}
+
+#[test]
+fn test_permanent_errors_are_not_retried() {
+    let server = MockServer::builder()
+        .sequence(
+            "teams(",
+            vec![common::MockResponse::status(
+                400,
+                json!({ "errors": [{ "message": "Field 'nope' doesn't exist" }] }),
+            )],
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["teams", "list"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("doesn't exist"));
+    assert_eq!(server.requests_matching("teams(").len(), 1);
+}
+
+#[test]
+fn test_mutations_only_resent_when_rate_limited() {
+    let server = MockServer::builder()
+        .sequence(
+            "issueUpdate",
+            vec![
+                common::MockResponse::status(429, json!({})).header("Retry-After", "0"),
+                issue_update_ok(),
+            ],
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "update", "ENG-1", "--title", "Fix login"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(server.requests_matching("issueUpdate").len(), 2);
+
+    // A 503 may have been applied server-side, so the mutation isn't resent
+    let server = MockServer::builder()
+        .sequence(
+            "issueUpdate",
+            vec![
+                common::MockResponse::status(503, json!({})),
+                issue_update_ok(),
+            ],
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "update", "ENG-1", "--title", "Fix login"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("503"));
+    assert_eq!(server.requests_matching("issueUpdate").len(), 1);
+}
+
+#[test]
+fn test_api_errors_map_to_exit_codes() {
+    let server = MockServer::builder()
+        .sequence(
+            "teams(",
+            vec![common::MockResponse::status(
+                401,
+                json!({ "errors": [{
+                    "message": "Authentication required",
+                    "extensions": { "code": "AUTHENTICATION_ERROR" }
+                }] }),
+            )],
+        )
+        .sequence(
+            "issue(id:",
+            vec![common::MockResponse::ok(json!({
+                "errors": [{ "message": "Entity not found: Issue", "path": ["issue"] }],
+                "data": null
+            }))],
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["teams", "list"]);
+    assert_eq!(out.code, 3, "stderr: {}", out.stderr);
+    assert!(out.stderr.contains("Authentication error"));
+
+    let out = env.run(&["--output", "json", "issues", "get", "ENG-404"]);
+    assert_eq!(out.code, 4, "stderr: {}", out.stderr);
+    let error: serde_json::Value = serde_json::from_str(&out.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "not_found");
+    assert_eq!(error["error"]["exitCode"], 4);
+    assert_eq!(error["error"]["message"], "Entity not found: Issue");
+}
+
+#[test]
+fn test_network_errors_exit_with_network_code() {
+    // Nothing listens on the discard port
+    let env = TestEnv::new()
+        .env("LINEAR_API_URL", "http://127.0.0.1:9/graphql")
+        .env("LINEAR_MAX_ATTEMPTS", "1");
+
+    let out = env.run(&["--output", "json", "teams", "list"]);
+    assert_eq!(out.code, 7, "stderr: {}", out.stderr);
+    let error: serde_json::Value = serde_json::from_str(&out.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "network");
+}
+
+#[test]
+fn test_names_resolve_from_cache_after_first_run() {
+    let server = MockServer::builder()
+        .respond("issueUpdate", |_| issue_update_ok())
+        .on(
+            "users(",
+            json!({
+                "users": {
+                    "nodes": [
+                        { "id": "user-alice", "name": "Alice Smith", "displayName": "alice", "email": "alice@example.com" }
+                    ]
+                }
+            }),
+        )
+        .on(
+            "issue(id",
+            json!({ "issue": { "id": "uuid-ENG-1", "identifier": "ENG-1", "team": { "id": TEAM_ID } } }),
+        )
+        .on(
+            "states",
+            json!({ "team": { "id": TEAM_ID, "name": "Engineering", "states": { "nodes": [] } } }),
+        )
+        .on("teams(", teams_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    for _ in 0..2 {
+        let out = env.run(&["bulk", "assign", "alice", "-i", "ENG-1"]);
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert!(out.stdout.contains("1 succeeded"), "stdout: {}", out.stdout);
+    }
+    assert_eq!(server.requests_matching("users(").len(), 1);
+    for update in server.requests_matching("issueUpdate") {
+        assert_eq!(update.variables()["input"]["assigneeId"], "user-alice");
+    }
+
+    // A name the cache doesn't know refreshes it once before failing
+    let out = env.run(&["bulk", "assign", "carol", "-i", "ENG-1"]);
+    assert!(
+        out.stdout
+            .contains("Not found: user 'carol'. Available: Alice Smith"),
+        "stdout: {}",
+        out.stdout
+    );
+    assert_eq!(server.requests_matching("users(").len(), 2);
+
+    // Team lookups share the teams cache with `teams list`
+    let out = env.run(&["teams", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&["statuses", "list", "-t", "eng"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(server.requests_matching("teams(").len(), 1);
+}
+
+fn team_states_response() -> serde_json::Value {
+    json!({
+        "team": {
+            "id": TEAM_ID,
+            "name": "Engineering",
+            "states": {
+                "nodes": [
+                    { "id": "state-backlog", "name": "Backlog", "type": "backlog" },
+                    { "id": "state-progress", "name": "In Progress", "type": "started" }
+                ]
+            }
+        }
+    })
+}
+
+#[test]
+fn test_issues_create_resolves_state_assignee_and_labels_by_name() {
+    let server = MockServer::builder()
+        .on(
+            "issueCreate",
+            json!({
+                "issueCreate": {
+                    "success": true,
+                    "issue": { "id": "issue-3", "identifier": "ENG-3", "title": "Crash", "url": null }
+                }
+            }),
+        )
+        .on(
+            "issueLabels(",
+            json!({
+                "issueLabels": {
+                    "nodes": [
+                        { "id": "label-other-bug", "name": "Bug", "team": { "id": "other-team" } },
+                        { "id": "label-bug", "name": "Bug", "team": { "id": TEAM_ID } },
+                        { "id": "label-urgent", "name": "Urgent", "team": null }
+                    ]
+                }
+            }),
+        )
+        .on(
+            "users(",
+            json!({ "users": { "nodes": [{ "id": "user-alice", "name": "Alice", "email": "alice@example.com" }] } }),
+        )
+        .on("states", team_states_response())
+        .on("teams(", teams_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&[
+        "issues",
+        "create",
+        "Crash",
+        "-t",
+        "ENG",
+        "-s",
+        "backlog",
+        "-a",
+        "alice@example.com",
+        "-l",
+        "bug",
+        "-l",
+        "urgent",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    let requests = server.requests_matching("issueCreate");
+    let input = &requests[0].variables()["input"];
+    assert_eq!(input["stateId"], "state-backlog");
+    assert_eq!(input["assigneeId"], "user-alice");
+    assert_eq!(input["labelIds"], json!(["label-bug", "label-urgent"]));
+}
+
+#[test]
+fn test_workspace_defaults_fill_in_omitted_flags() {
+    let server = MockServer::builder()
+        .on(
+            "issueCreate",
+            json!({
+                "issueCreate": {
+                    "success": true,
+                    "issue": { "id": "issue-4", "identifier": "ENG-4", "title": "Crash", "url": null }
+                }
+            }),
+        )
+        .on(
+            "projects(",
+            json!({ "projects": { "nodes": [{ "id": "project-q1", "name": "Q1 Roadmap" }] } }),
+        )
+        .on(
+            "cycles(",
+            json!({ "cycles": { "nodes": [{ "id": "cycle-7", "number": 7 }] } }),
+        )
+        .on(
+            "viewer",
+            json!({ "viewer": { "id": "user-me", "name": "Me", "email": "me@example.com" } }),
+        )
+        .on("teams(", teams_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    for (key, value) in [
+        ("default.team", "ENG"),
+        ("default.project", "Q1 Roadmap"),
+        ("default.assignee", "me"),
+        ("default.cycle", "current"),
+        ("default.output", "json"),
+    ] {
+        let out = env.run(&["config", "set", key, value]);
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    }
+    let out = env.run(&["config", "set", "default.output", "template"]);
+    assert_ne!(out.code, 0);
+    let out = env.run(&["config", "set", "default.colour", "red"]);
+    assert!(out.stderr.contains("Unknown setting 'default.colour'"));
+
+    let out = env.run(&["issues", "create", "Crash"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let created: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(created["identifier"], "ENG-4");
+
+    let creates = server.requests_matching("issueCreate");
+    let input = &creates[0].variables()["input"];
+    assert_eq!(input["teamId"], TEAM_ID);
+    assert_eq!(input["projectId"], "project-q1");
+    assert_eq!(input["assigneeId"], "user-me");
+    assert_eq!(input["cycleId"], "cycle-7");
+    let cycles = server.requests_matching("cycles(");
+    let cycle_filter = &cycles[0].variables()["filter"];
+    assert_eq!(cycle_filter["isActive"]["eq"], true);
+    assert_eq!(cycle_filter["team"]["id"]["eq"], TEAM_ID);
+
+    // Environment variables beat the workspace defaults, and flags beat both
+    let env = env.env("LINEAR_TEAM", "nope").env("LINEAR_OUTPUT", "table");
+    let out = env.run(&["issues", "create", "Crash", "--cycle", "none"]);
+    assert_ne!(out.code, 0);
+    assert!(out.stderr.contains("team 'nope'"), "{}", out.stderr);
+
+    let out = env.run(&["issues", "create", "Crash", "-t", "ENG", "--cycle", "none"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(
+        out.stdout.contains("Created issue: ENG-4"),
+        "{}",
+        out.stdout
+    );
+    let creates = server.requests_matching("issueCreate");
+    assert!(creates[1].variables()["input"].get("cycleId").is_none());
+
+    let out = env.run(&["config", "show"]);
+    assert!(out
+        .stdout
+        .contains("default.team = nope (from LINEAR_TEAM)"));
+    assert!(out.stdout.contains("default.project = Q1 Roadmap"));
+}
+
+#[test]
+fn test_default_team_narrows_lists_and_search() {
+    let server = MockServer::builder()
+        .respond("issues(", |_| issue_page(&["ENG-1"], None))
+        .on("projects(", json!({ "projects": { "nodes": [] } }))
+        .on("teams(", teams_response())
+        .on(
+            "members(",
+            json!({ "team": { "members": { "nodes": [{ "id": "user-alice", "name": "Alice" }] } } }),
+        )
+        .on(
+            "users(",
+            json!({ "users": { "nodes": [{ "id": "user-bob", "name": "Bob" }] } }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server).env("LINEAR_TEAM", "ENG");
+
+    let out = env.run(&["issues", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&["search", "issues", "login"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&["issues", "list", "--all-teams"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let requests = server.requests_matching("issues(");
+    assert_eq!(
+        requests[0].variables()["filter"],
+        json!([team_filter("ENG")])
+    );
+    assert_eq!(
+        requests[1].variables()["filter"]["team"],
+        team_filter("ENG")["team"]
+    );
+    assert!(requests[2].variables().get("filter").is_none());
+
+    let out = env.run(&["projects", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let projects = server.requests_matching("projects(");
+    assert_eq!(
+        projects[0].variables()["filter"]["accessibleTeams"]["some"],
+        team_filter("ENG")["team"]
+    );
+
+    let out = env.run(&["users", "list"]);
+    assert!(out.stdout.contains("Alice"), "{}", out.stdout);
+    let out = env.run(&["users", "list", "--all-teams"]);
+    assert!(out.stdout.contains("Bob"), "{}", out.stdout);
+
+    let out = env.run(&["issues", "list", "-t", "ENG", "--all-teams"]);
+    assert_eq!(out.code, 2, "--team and --all-teams conflict");
+}
+
+#[test]
+fn test_repo_config_layers_over_workspace_defaults() {
+    let server = MockServer::builder()
+        .on(
+            "issueCreate",
+            json!({
+                "issueCreate": {
+                    "success": true,
+                    "issue": { "id": "issue-5", "identifier": "ENG-5", "title": "Crash", "url": null }
+                }
+            }),
+        )
+        .on(
+            "issueLabels(",
+            json!({ "issueLabels": { "nodes": [{ "id": "label-backend", "name": "Backend", "team": null }] } }),
+        )
+        .on("teams(", teams_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+    let out = env.run(&["config", "set", "default.team", "OTHER"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    let repo = env.home().join("repo");
+    let nested = repo.join("src").join("deep");
+    std::fs::create_dir_all(&nested).unwrap();
+    let repo_config = repo.join(".linear.toml");
+    std::fs::write(
+        &repo_config,
+        "team = \"ENG\"\nlabels = [\"backend\"]\nbranch = \"{team}/{number}\"\n",
+    )
+    .unwrap();
+
+    // Found from a subdirectory, and it beats the workspace's default team
+    let out = env
+        .command(&["issues", "create", "Crash"])
+        .current_dir(&nested)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let creates = server.requests_matching("issueCreate");
+    let input = &creates[0].variables()["input"];
+    assert_eq!(input["teamId"], TEAM_ID);
+    assert_eq!(input["labelIds"], json!(["label-backend"]));
+
+    let out = env
+        .command(&["config", "show"])
+        .current_dir(&nested)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let from_repo = format!("(from {})", repo_config.display());
+    assert!(
+        stdout.contains(&format!("default.team = ENG {}", from_repo)),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains(&format!("default.labels = backend {}", from_repo)));
+    assert!(
+        stdout.contains("max_attempts = 4 (from built-in)"),
+        "{}",
+        stdout
+    );
+
+    // Outside the repository only the workspace default applies
+    let out = env.run(&["config", "show", "--output", "json"]);
+    let shown: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert!(shown["repoConfig"].is_null());
+    assert_eq!(shown["settings"]["default.team"]["value"], "OTHER");
+    assert_eq!(
+        shown["settings"]["default.team"]["source"],
+        "workspace 'default'"
+    );
+
+    // Typos are errors rather than silently ignored
+    std::fs::write(&repo_config, "teem = \"ENG\"\n").unwrap();
+    let out = env
+        .command(&["config", "show"])
+        .current_dir(&nested)
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Unknown key 'teem'"));
+}
+
+#[test]
+fn test_issues_update_suggests_close_state_names() {
+    let server = MockServer::builder()
+        .respond("issueUpdate", |_| issue_update_ok())
+        .on(
+            "issue(id",
+            json!({
+                "issue": {
+                    "id": "issue-1",
+                    "identifier": "ENG-1",
+                    "title": "Fix login",
+                    "team": { "id": TEAM_ID, "key": "ENG", "name": "Engineering" }
+                }
+            }),
+        )
+        .on("states", team_states_response())
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "update", "ENG-1", "-s", "Bakclog"]);
+    assert_eq!(out.code, 4, "stderr: {}", out.stderr);
+    assert!(
+        out.stderr
+            .contains("state 'Bakclog' in team Engineering. Did you mean 'Backlog'?"),
+        "stderr: {}",
+        out.stderr
+    );
+    assert!(server.requests_matching("issueUpdate").is_empty());
+
+    let out = env.run(&["issues", "update", "ENG-1", "-s", "in progress"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let updates = server.requests_matching("issueUpdate");
+    assert_eq!(updates[0].variables()["input"]["stateId"], "state-progress");
+}
+
+#[test]
+fn test_issues_list_filter_expression() {
+    let server = MockServer::builder()
+        .respond("issues(", |_| issue_page(&["ENG-1"], None))
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&[
+        "issues",
+        "list",
+        "-t",
+        "ENG",
+        "--filter",
+        "label:bug and not assignee:none",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    let requests = server.requests_matching("issues(");
+    assert_eq!(
+        requests[0].variables()["filter"],
+        json!([team_filter("ENG"), { "and": [
+            { "labels": { "some": { "name": { "eqIgnoreCase": "bug" } } } },
+            { "assignee": { "null": false } }
+        ] }])
+    );
+
+    // Invalid expressions fail before any request is sent
+    let out = env.run(&["issues", "list", "--filter", "priority<=soon"]);
+    assert_eq!(out.code, 1);
+    assert!(
+        out.stderr.contains("Invalid priority 'soon'"),
+        "stderr: {}",
+        out.stderr
+    );
+    assert_eq!(server.requests_matching("issues(").len(), 1);
+}
+
+#[test]
+fn test_board_groups_issues_by_state() {
+    let server = MockServer::builder()
+        .on("teams(", teams_response())
+        .on("states {", team_states_response())
+        .on(
+            "issues(",
+            json!({
+                "issues": {
+                    "nodes": [
+                        {
+                            "id": "issue-1",
+                            "identifier": "ENG-1",
+                            "title": "Fix the login page redirect loop",
+                            "state": { "id": "state-progress", "name": "In Progress" }
+                        },
+                        {
+                            "id": "issue-2",
+                            "identifier": "ENG-2",
+                            "title": "Dark mode",
+                            "state": { "id": "state-backlog", "name": "Backlog" }
+                        }
+                    ],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null }
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server).env("COLUMNS", "40");
+
+    let out = env.run(&["board", "-t", "ENG", "--cycle", "current"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let lines: Vec<&str> = out.stdout.lines().collect();
+    // Two 18-character columns, with cards cut to fit
+    assert_eq!(lines[0], "Backlog (1)        | In Progress (1)");
+    assert_eq!(lines[2], "ENG-2 Dark mode    | ENG-1 Fix the l...");
+    assert!(out.stdout.contains("2 issues in Engineering"));
+
+    let requests = server.requests_matching("issues(");
+    assert_eq!(requests[0].variables()["teamId"], TEAM_ID);
+    assert_eq!(
+        requests[0].variables()["cycle"],
+        json!({ "isActive": { "eq": true } })
+    );
+
+    let out = env.run(&["board", "-t", "ENG", "--cycle", "soon"]);
+    assert_eq!(out.code, 1);
+    assert!(out.stderr.contains("Invalid cycle 'soon'"));
+}
+
+#[test]
+fn test_output_formats_for_list_commands() {
+    let server = MockServer::builder()
+        .on(
+            "users(",
+            json!({
+                "users": {
+                    "nodes": [
+                        { "id": "user-alice", "name": "Alice Smith", "email": "alice@example.com" },
+                        { "id": "user-bob", "name": "Bob, Jr.", "email": null }
+                    ],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null }
+                }
+            }),
+        )
+        .respond("issues(", |_| issue_page(&["ENG-1", "ENG-2"], None))
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["users", "list", "-o", "csv"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(
+        out.stdout,
+        "id,name,email\nuser-alice,Alice Smith,alice@example.com\nuser-bob,\"Bob, Jr.\",\n"
+    );
+
+    let out = env.run(&["users", "list", "-o", "md"]);
+    assert!(out
+        .stdout
+        .starts_with("| id | name | email |\n| --- | --- | --- |\n"));
+
+    let out = env.run(&["-o", "ndjson", "issues", "list"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let lines: Vec<&str> = out.stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(lines[1]).unwrap()["identifier"],
+        "ENG-2"
+    );
+
+    let out = env.run(&[
+        "issues",
+        "list",
+        "--template",
+        "{{identifier}}\\t{{state.id}}",
+    ]);
+    assert_eq!(out.stdout, "ENG-1\tstate-todo\nENG-2\tstate-todo\n");
+
+    let out = env.run(&["issues", "list", "-o", "template"]);
+    assert_eq!(out.code, 2);
+    assert!(out.stderr.contains("--template"), "stderr: {}", out.stderr);
+}
+
+#[test]
+fn test_fields_narrow_the_query_and_the_output() {
+    let server = MockServer::builder()
+        .respond("issues(", |_| issue_page(&["ENG-1", "ENG-2"], None))
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["issues", "list", "--fields", "identifier,state.name"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let value: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(
+        value,
+        json!([
+            { "identifier": "ENG-1", "state": { "name": "Todo" } },
+            { "identifier": "ENG-2", "state": { "name": "Todo" } }
+        ])
+    );
+
+    let requests = server.requests_matching("issues(");
+    let query = requests[0].query();
+    assert!(
+        query.contains("nodes { identifier state { name } }"),
+        "{}",
+        query
+    );
+    assert!(query.contains("pageInfo { hasNextPage endCursor }"));
+    assert!(!query.contains("assignee { id name }"));
+
+    let out = env.run(&[
+        "issues",
+        "list",
+        "-o",
+        "ndjson",
+        "--query",
+        r#".[] | select(.identifier == "ENG-2") | {id: .identifier, state: .state.name}"#,
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(out.stdout, "{\"id\":\"ENG-2\",\"state\":\"Todo\"}\n");
+
+    let out = env.run(&[
+        "issues",
+        "list",
+        "--query",
+        "map(.identifier) | join(\",\")",
+    ]);
+    assert_eq!(out.stdout, "\"ENG-1,ENG-2\"\n");
+
+    // Like jq, each result prints on its own, whether there is one or several
+    let out = env.run(&["issues", "list", "--query", ".[].identifier"]);
+    assert_eq!(out.stdout, "\"ENG-1\"\n\"ENG-2\"\n");
+    let out = env.run(&["issues", "list", "--query", ".[0].identifier"]);
+    assert_eq!(out.stdout, "\"ENG-1\"\n");
+    let rows = env.run(&["issues", "list", "-o", "csv", "--query", ".[]"]);
+    let list = env.run(&["issues", "list", "-o", "csv", "--query", "map(.)"]);
+    assert_eq!(rows.stdout, list.stdout);
+    assert_eq!(rows.stdout.lines().count(), 3, "{}", rows.stdout);
+
+    let out = env.run(&["issues", "list", "--query", ".[] |"]);
+    assert_eq!(out.code, 2);
+    assert!(out.stderr.contains("--query"), "stderr: {}", out.stderr);
+}
+
+#[test]
+fn test_api_passthrough_with_variables() {
+    let server = MockServer::builder()
+        .on(
+            "viewer",
+            json!({ "viewer": { "id": "user-1", "name": "Alice" } }),
+        )
+        .respond("issues(", |request| {
+            let after = request.variables()["after"].as_str().map(str::to_string);
+            match after.as_deref() {
+                None => issue_page(&["ENG-1", "ENG-2"], Some("cursor-1")),
+                Some(_) => issue_page(&["ENG-3"], None),
+            }
+        })
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&[
+        "api",
+        "query($n: Int, $team: String) { viewer { id name } }",
+        "-F",
+        "n=3",
+        "-f",
+        "team=42",
+        "--raw",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(
+        out.stdout,
+        "{\"data\":{\"viewer\":{\"id\":\"user-1\",\"name\":\"Alice\"}}}\n"
+    );
+    let requests = server.requests_matching("viewer");
+    assert_eq!(requests[0].variables(), &json!({ "n": 3, "team": "42" }));
+
+    let query_file = env.home().join("issues.graphql");
+    std::fs::write(
+        &query_file,
+        "query($after: String, $first: Int) { issues(first: $first, after: $after) \
+         { nodes { identifier } pageInfo { hasNextPage endCursor } } }",
+    )
+    .unwrap();
+    let mut cmd = env.command(&[
+        "api",
+        "--input",
+        query_file.to_str().unwrap(),
+        "--variables",
+        "-",
+        "--paginate",
+        "--query",
+        "map(.identifier)",
+        "-o",
+        "ndjson",
+    ]);
+    cmd.stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped());
+    let mut child = cmd.spawn().unwrap();
+    {
+        use std::io::Write;
+        let mut stdin = child.stdin.take().unwrap();
+        stdin.write_all(br#"{"first": 2}"#).unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\"ENG-1\"\n\"ENG-2\"\n\"ENG-3\"\n"
+    );
+    let pages = server.requests_matching("issues(");
+    assert_eq!(pages.len(), 2);
+    assert_eq!(
+        pages[1].variables(),
+        &json!({ "first": 2, "after": "cursor-1" })
+    );
+
+    let out = env.run(&["api"]);
+    assert_eq!(out.code, 1);
+    assert!(
+        out.stderr.contains("No query given"),
+        "stderr: {}",
+        out.stderr
+    );
+}
+
+#[test]
+fn test_schema_fetch_caches_and_prints_sdl() {
+    let named = |kind: &str, name: &str| json!({ "kind": kind, "name": name, "ofType": null });
+    let server = MockServer::builder()
+        .on(
+            "__schema",
+            json!({
+                "__schema": {
+                    "queryType": { "name": "Query" },
+                    "mutationType": null,
+                    "subscriptionType": null,
+                    "directives": [],
+                    "types": [
+                        named("SCALAR", "String"),
+                        {
+                            "kind": "OBJECT",
+                            "name": "Query",
+                            "description": null,
+                            "interfaces": [],
+                            "fields": [{
+                                "name": "team",
+                                "description": null,
+                                "args": [{ "name": "id", "description": null,
+                                           "type": { "kind": "NON_NULL", "name": null,
+                                                     "ofType": named("SCALAR", "String") },
+                                           "defaultValue": null }],
+                                "type": named("OBJECT", "Team"),
+                                "isDeprecated": false
+                            }]
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "Team",
+                            "description": "A team.",
+                            "interfaces": [],
+                            "fields": [{ "name": "key", "description": null, "args": [],
+                                         "type": named("SCALAR", "String"),
+                                         "isDeprecated": false }]
+                        }
+                    ]
+                }
+            }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["schema", "show"]);
+    assert_eq!(out.code, 1);
+    assert!(
+        out.stderr.contains("linear schema fetch"),
+        "stderr: {}",
+        out.stderr
+    );
+
+    let out = env.run(&["schema", "fetch"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("3 types, 1 queries, 0 mutations"));
+
+    let out = env.run(&["schema", "show"]);
+    assert_eq!(
+        out.stdout,
+        "type Query {\n  team(id: String!): Team\n}\n\n\
+         \"\"\"\nA team.\n\"\"\"\ntype Team {\n  key: String\n}\n"
+    );
+
+    let out = env.run(&["schema", "show", "team", "--fields", "name,kind"]);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&out.stdout).unwrap(),
+        json!({ "name": "Team", "kind": "OBJECT" })
+    );
+    assert_eq!(server.requests_matching("__schema").len(), 1);
+}
+
+fn connection(field: &str, nodes: serde_json::Value) -> serde_json::Value {
+    json!({ field: { "nodes": nodes, "pageInfo": { "hasNextPage": false, "endCursor": null } } })
+}
+
+fn mirrored_issue(identifier: &str, title: &str, state: &str, updated: &str) -> serde_json::Value {
+    json!({
+        "id": format!("id-{}", identifier),
+        "identifier": identifier,
+        "title": title,
+        "description": null,
+        "priority": 2,
+        "updatedAt": updated,
+        "archivedAt": null,
+        "team": { "id": "team-1", "key": "ENG", "name": "Engineering" },
+        "state": { "id": format!("state-{}", state), "name": state, "type": "started" },
+        "assignee": { "id": "user-me", "name": "Me", "displayName": "me", "email": "me@example.com" },
+        "labels": { "nodes": [] }
+    })
+}
+
+#[test]
+fn test_sync_pull_mirrors_for_offline_reads() {
+    let mut archived = mirrored_issue(
+        "ENG-3",
+        "Old login page",
+        "Done",
+        "2026-09-01T00:00:00.000Z",
+    );
+    archived["archivedAt"] = json!("2026-09-02T00:00:00.000Z");
+    let first = connection(
+        "issues",
+        json!([
+            mirrored_issue("ENG-2", "Write docs", "Todo", "2026-10-03T00:00:00.000Z"),
+            mirrored_issue(
+                "ENG-1",
+                "Fix login bug",
+                "In Progress",
+                "2026-10-01T00:00:00.000Z"
+            ),
+            archived
+        ]),
+    );
+    let second = connection(
+        "issues",
+        json!([mirrored_issue(
+            "ENG-2",
+            "Write docs",
+            "Done",
+            "2026-10-05T00:00:00.000Z"
+        )]),
+    );
+    let server = MockServer::builder()
+        .sequence(
+            "issues(filter",
+            vec![
+                common::MockResponse::ok(json!({ "data": first })),
+                common::MockResponse::ok(json!({ "data": second })),
+            ],
+        )
+        .on(
+            "projects(filter",
+            connection(
+                "projects",
+                json!([{
+                    "id": "project-1",
+                    "name": "Login revamp",
+                    "updatedAt": "2026-10-02T00:00:00.000Z",
+                    "archivedAt": null,
+                    "status": { "id": "status-1", "name": "Started" },
+                    "labels": { "nodes": [] },
+                    "teams": { "nodes": [{ "id": "team-1", "key": "ENG", "name": "Engineering" }] }
+                }]),
+            ),
+        )
+        .on(
+            "cycles(filter",
+            connection(
+                "cycles",
+                json!([{
+                    "id": "cycle-3",
+                    "number": 3,
+                    "name": null,
+                    "startsAt": "2026-10-01T00:00:00.000Z",
+                    "endsAt": "2026-10-15T00:00:00.000Z",
+                    "completedAt": null,
+                    "progress": 0.5,
+                    "updatedAt": "2026-10-02T00:00:00.000Z",
+                    "archivedAt": null,
+                    "team": { "id": "team-1", "key": "ENG", "name": "Engineering" }
+                }]),
+            ),
+        )
+        .on(
+            "comments(filter",
+            connection(
+                "comments",
+                json!([{
+                    "id": "comment-1",
+                    "body": "Reproduced on staging",
+                    "createdAt": "2026-10-02T00:00:00.000Z",
+                    "updatedAt": "2026-10-02T00:00:00.000Z",
+                    "archivedAt": null,
+                    "user": { "id": "user-me", "name": "Me" },
+                    "issue": { "id": "id-ENG-1", "identifier": "ENG-1" }
+                }]),
+            ),
+        )
+        .on(
+            "documents(filter",
+            connection(
+                "documents",
+                json!([{
+                    "id": "doc-1",
+                    "title": "Login spec",
+                    "updatedAt": "2026-10-02T00:00:00.000Z",
+                    "archivedAt": null,
+                    "project": { "id": "project-1", "name": "Login revamp" }
+                }]),
+            ),
+        )
+        .on(
+            "viewer {",
+            json!({ "viewer": { "id": "user-me", "name": "Me" } }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+
+    let out = env.run(&["--output", "json", "sync", "pull"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let pulled: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(pulled[0]["entity"], "issues");
+    assert_eq!(pulled[0]["fetched"], 3);
+    assert_eq!(pulled[0]["watermark"], "2026-10-03T00:00:00.000Z");
+    assert!(env
+        .config_dir()
+        .join("cache")
+        .join(MOCK_ORGANIZATION_ID)
+        .join("mirror.db")
+        .exists());
+
+    // The next pull only asks for what changed since the watermark
+    let out = env.run(&["sync", "pull", "--only", "issues"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let requests = server.requests_matching("issues(filter");
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].variables().get("filter").is_none());
+    assert_eq!(
+        requests[1].variables()["filter"],
+        json!({ "updatedAt": { "gt": "2026-10-03T00:00:00.000Z" } })
+    );
+
+    let sent = server.requests().len();
+    let out = env.run(&["--output", "json", "issues", "list", "--offline"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let issues: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    let identifiers: Vec<_> = issues
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["identifier"].as_str().unwrap())
+        .collect();
+    assert_eq!(identifiers, ["ENG-2", "ENG-1"]);
+    assert_eq!(issues[0]["state"]["name"], "Done");
+
+    let out = env.run(&[
+        "issues",
+        "list",
+        "--offline",
+        "--filter",
+        "assignee:me and state:\"In Progress\"",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("ENG-1"));
+    assert!(!out.stdout.contains("ENG-2"));
+
+    let out = env.run(&["search", "issues", "login", "--offline", "--archived"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("ENG-1"));
+    assert!(out.stdout.contains("ENG-3"));
+    assert!(!out.stdout.contains("ENG-2"));
+
+    // Without a key the mirror is found by workspace
+    let out = env
+        .command(&["search", "projects", "LOGIN", "--offline"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Login revamp"));
+
+    let out = env.run(&["projects", "list", "--offline", "-t", "eng"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Login revamp"));
+
+    let out = env.run(&[
+        "--output",
+        "json",
+        "cycles",
+        "list",
+        "--offline",
+        "-t",
+        "ENG",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let cycles: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(cycles[0]["number"], 3);
+
+    let out = env.run(&["comments", "list", "ENG-1", "--offline"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Reproduced on staging"));
+    let out = env.run(&["comments", "list", "ENG-2", "--offline"]);
+    assert!(out.stdout.contains("No comments found"));
+
+    let out = env.run(&["documents", "list", "--offline", "-p", "login revamp"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Login spec"));
+
+    assert_eq!(server.requests().len(), sent);
+}
+
+#[test]
+fn test_offline_reads_need_a_pull_first() {
+    let env = TestEnv::new();
+    let out = env.run(&["issues", "list", "--offline"]);
+    assert_ne!(out.code, 0);
+    assert!(
+        out.stderr.contains("linear sync pull"),
+        "stderr: {}",
+        out.stderr
+    );
+}
+
+#[test]
+fn test_queued_changes_replay_with_conflict_detection() {
+    // Nothing listens on the discard port
+    let env = TestEnv::new()
+        .env("LINEAR_API_URL", "http://127.0.0.1:9/graphql")
+        .env("LINEAR_MAX_ATTEMPTS", "1");
+
+    let out = env.run(&["issues", "update", "ENG-1", "-T", "Renamed"]);
+    assert_eq!(out.code, 7, "without --queue it fails: {}", out.stderr);
+
+    let out = env.run(&["issues", "update", "ENG-1", "-T", "Renamed", "--queue"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(
+        out.stdout.contains("Queued: update ENG-1"),
+        "{}",
+        out.stdout
+    );
+    let out = env.run(&[
+        "comments",
+        "create",
+        "ENG-1",
+        "-b",
+        "Done offline",
+        "--queue",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&["-o", "json", "bulk", "unassign", "-i", "ENG-2", "--queue"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let bulk: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(bulk[0]["success"], false);
+    let bulk_entry = bulk[0]["queued"]
+        .as_str()
+        .expect("queue entry id")
+        .to_string();
+
+    let out = env.run(&["--output", "json", "queue", "status"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let queued: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    let kinds: Vec<_> = queued
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["operation"]["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["issue-update", "comment-create", "assign"]);
+    assert_eq!(queued[2]["id"], bulk_entry.as_str());
+    let comment_id = queued[1]["operation"]["id"].as_str().unwrap().to_string();
+
+    // Both issues changed on Linear after the changes were queued: ENG-1 by an
+    // earlier attempt that went through, ENG-2 by someone else
+    let server = MockServer::builder()
+        .on(
+            "issueUpdate",
+            json!({ "issueUpdate": { "success": true, "issue": { "id": "id-1", "identifier": "ENG-1", "title": "Renamed" } } }),
+        )
+        .on(
+            "commentCreate",
+            json!({ "commentCreate": { "success": true, "comment": {
+                "id": comment_id, "body": "Done offline", "createdAt": "2026-10-01T00:00:00.000Z",
+                "user": { "id": "user-me", "name": "Me" },
+                "issue": { "id": "id-1", "identifier": "ENG-1", "title": "Renamed" }
+            } } }),
+        )
+        .on("comment(id", json!({ "comment": null }))
+        .respond("updatedAt", |request| {
+            let id = request.variables()["id"].as_str().unwrap().to_string();
+            common::MockResponse::ok(json!({ "data": { "issue": {
+                "id": format!("id-{}", id), "identifier": id,
+                "updatedAt": "2099-01-01T00:00:00.000Z",
+                "title": "Renamed",
+                "assignee": { "id": "user-bob" },
+                "team": { "id": "team-1" }
+            } } }))
+        })
+        .start();
+    let replay = |force: bool| {
+        let mut args = vec!["--output", "json", "queue", "replay"];
+        if force {
+            args.push("--force");
+        }
+        let out = env
+            .command(&args)
+            .env("LINEAR_API_URL", server.graphql_url())
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+
+    let results = replay(false);
+    let statuses: Vec<_> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["already-applied", "applied", "conflict"]);
+    assert_eq!(server.requests_matching("issueUpdate").len(), 0);
+    // Created comments keep the id they were queued with
+    let sent = server.requests_matching("commentCreate");
+    assert_eq!(sent[0].variables()["input"]["id"], comment_id.as_str());
+
+    let out = env.run(&["--output", "json", "queue", "status"]);
+    let queued: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(queued.as_array().unwrap().len(), 1);
+    assert!(queued[0]["lastError"]
+        .as_str()
+        .unwrap()
+        .contains("updated on Linear"));
+
+    let results = replay(true);
+    assert_eq!(results[0]["status"], "applied");
+    assert_eq!(
+        server
+            .requests_matching("issueUpdate")
+            .last()
+            .unwrap()
+            .variables()["input"],
+        json!({ "assigneeId": null })
+    );
+
+    let out = env.run(&["queue", "status"]);
+    assert!(out.stdout.contains("Nothing queued."), "{}", out.stdout);
+}
+
+#[test]
+fn test_replay_rebases_later_changes_to_the_same_issue() {
+    let env = TestEnv::new()
+        .env("LINEAR_API_URL", "http://127.0.0.1:9/graphql")
+        .env("LINEAR_MAX_ATTEMPTS", "1");
+    let out = env.run(&["issues", "update", "ENG-1", "-T", "Renamed", "--queue"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    let out = env.run(&["bulk", "unassign", "-i", "ENG-1", "--queue"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    // The rename moves ENG-1's updatedAt past the time both were queued
+    let renamed = "2099-06-01T00:00:00.000Z";
+    let issue = |updated: &str| {
+        common::MockResponse::ok(json!({ "data": { "issue": {
+            "id": "id-1", "identifier": "ENG-1", "updatedAt": updated,
+            "title": "Old", "assignee": { "id": "user-bob" }, "team": { "id": TEAM_ID }
+        } } }))
+    };
+    let server = MockServer::builder()
+        .on(
+            "issueUpdate",
+            json!({ "issueUpdate": { "success": true, "issue": {
+                "id": "id-1", "identifier": "ENG-1", "title": "Renamed", "updatedAt": renamed
+            } } }),
+        )
+        .sequence(
+            "updatedAt",
+            vec![issue("2000-01-01T00:00:00.000Z"), issue(renamed)],
+        )
+        .start();
+
+    let out = env
+        .command(&["--output", "json", "queue", "replay"])
+        .env("LINEAR_API_URL", server.graphql_url())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let results: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(results[0]["status"], "applied");
+    assert_eq!(results[1]["status"], "applied", "{}", results);
+    assert_eq!(server.requests_matching("issueUpdate").len(), 2);
+}
+
+#[test]
+fn test_replay_keeps_changes_queued_while_it_runs() {
+    let env = TestEnv::new()
+        .env("LINEAR_API_URL", "http://127.0.0.1:9/graphql")
+        .env("LINEAR_MAX_ATTEMPTS", "1");
+    let out = env.run(&["issues", "update", "ENG-1", "-T", "Renamed", "--queue"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+
+    // Another process queues a change while the replay is sending the first
+    let mut concurrent =
+        Some(env.command(&["issues", "update", "ENG-2", "-T", "Later", "--queue"]));
+    let server = MockServer::builder()
+        .respond("issueUpdate", move |_| {
+            if let Some(mut command) = concurrent.take() {
+                assert!(command.output().unwrap().status.success());
+            }
+            common::MockResponse::ok(
+                json!({ "data": { "issueUpdate": { "success": true, "issue": {
+                "id": "id-1", "identifier": "ENG-1", "title": "Renamed",
+                "updatedAt": "2099-06-01T00:00:00.000Z"
+            } } } }),
+            )
+        })
+        .on(
+            "updatedAt",
+            json!({ "issue": {
+                "id": "id-1", "identifier": "ENG-1", "updatedAt": "2000-01-01T00:00:00.000Z",
+                "title": "Old", "team": { "id": TEAM_ID }
+            } }),
+        )
+        .start();
+
+    let out = env
+        .command(&["--output", "json", "queue", "replay"])
+        .env("LINEAR_API_URL", server.graphql_url())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let results: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert_eq!(results[0]["status"], "applied");
+
+    let out = env.run(&["--output", "json", "queue", "status"]);
+    let queued: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+    assert_eq!(queued.as_array().unwrap().len(), 1, "{}", queued);
+    assert_eq!(queued[0]["operation"]["issue"], "ENG-2");
+}
+
+#[test]
+fn test_bulk_where_previews_and_confirms() {
+    let server = MockServer::builder()
+        .on(
+            "issues(filter",
+            json!({ "issues": {
+                "nodes": [
+                    { "id": "uuid-1", "identifier": "ENG-1", "title": "Review login",
+                      "state": { "id": "state-review", "name": "In Review" },
+                      "assignee": { "id": "user-alice", "name": "alice" },
+                      "labels": { "nodes": [{ "id": "label-bug", "name": "Bug" }] },
+                      "team": { "id": TEAM_ID, "key": "ENG", "name": "Engineering" } },
+                    { "id": "uuid-2", "identifier": "ENG-2", "title": "Review docs",
+                      "state": { "id": "state-review", "name": "In Review" },
+                      "assignee": null,
+                      "labels": { "nodes": [] },
+                      "team": { "id": TEAM_ID, "key": "ENG", "name": "Engineering" } }
+                ],
+                "pageInfo": { "hasNextPage": false, "endCursor": null }
+            } }),
+        )
+        .respond("issueUpdate", |req| {
+            let id = req.variables()["id"].as_str().unwrap_or("").to_string();
+            common::MockResponse::ok(json!({
+                "data": { "issueUpdate": {
+                    "success": true,
+                    "issue": { "identifier": id.replace("uuid-", "ENG-"), "title": "t" }
+                } }
+            }))
+        })
+        .on(
+            "states",
+            json!({ "team": { "states": { "nodes": [
+                { "id": "state-review", "name": "In Review" },
+                { "id": "state-done", "name": "Done" }
+            ] } } }),
+        )
+        .start();
+    let env = TestEnv::with_server(&server);
+    let filter = r#"team:ENG state:"In Review" updated<-14d"#;
+
+    let out = env.run(&[
+        "bulk",
+        "update-state",
+        "Done",
+        "--where",
+        filter,
+        "--dry-run",
+    ]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("ENG-1"), "{}", out.stdout);
+    assert!(
+        out.stdout.contains("state: In Review -> Done"),
+        "{}",
+        out.stdout
+    );
+    assert!(out.stdout.contains("Dry run: 2 issues would change."));
+    let selection = server.requests_matching("issues(filter");
+    assert_eq!(
+        selection[0].variables()["filter"]["and"][1],
+        json!({ "state": { "name": { "eqIgnoreCase": "In Review" } } })
+    );
+
+    // No terminal to confirm on, and no --force: nothing changes
+    let out = env.run(&["bulk", "update-state", "Done", "--where", filter]);
+    assert_ne!(out.code, 0);
+    assert!(server.requests_matching("issueUpdate").is_empty());
+
+    let out = env.run(&["bulk", "unassign", "-w", filter, "--dry-run"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(
+        out.stdout.contains("assignee: alice -> -"),
+        "{}",
+        out.stdout
+    );
+
+    // ENG-1 already has the label, so only ENG-2 would change
+    let out = env.run(&["bulk", "label", "bug", "-w", filter, "--dry-run"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("(no change)"), "{}", out.stdout);
+    assert!(out.stdout.contains("+ label bug"), "{}", out.stdout);
+    assert!(out.stdout.contains("Dry run: 1 issues would change."));
+
+    let out = env.run(&["bulk", "update-state", "Done", "--where", filter, "--force"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("2 succeeded"), "stdout: {}", out.stdout);
+    let updates = server.requests_matching("issueUpdate");
+    assert_eq!(updates.len(), 2);
+    for update in updates {
+        assert_eq!(update.variables()["input"]["stateId"], "state-done");
+    }
+    // The selection already fetched each issue's UUID and team
+    assert!(server.requests_matching("issue(id").is_empty());
+
+    let out = env.run(&["bulk", "label", "bug", "-i", "ENG-1", "-w", filter]);
+    assert_ne!(out.code, 0, "-i and --where conflict");
+}
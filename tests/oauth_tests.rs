@@ -0,0 +1,322 @@
+//! `linear auth` against a fake authorization server: the mock server answers
+//! the token and revoke endpoints as well as GraphQL, and the test plays the
+//! browser by calling the CLI's redirect URI itself.
+
+mod common;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use common::{MockResponse, MockServer, RecordedRequest, TestEnv};
+use reqwest::Url;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn viewer_response() -> serde_json::Value {
+    json!({
+        "viewer": { "id": "user-alice", "name": "Alice", "email": "alice@example.com" },
+        "organization": { "id": "org-1", "name": "Acme", "urlKey": "acme" }
+    })
+}
+
+fn token_response(access: &str, refresh: &str) -> MockResponse {
+    MockResponse::ok(json!({
+        "access_token": access,
+        "refresh_token": refresh,
+        "token_type": "Bearer",
+        "expires_in": 86399,
+        "scope": "read write"
+    }))
+}
+
+/// Form fields of a request to the token or revoke endpoint
+fn form(request: &RecordedRequest) -> HashMap<String, String> {
+    let body = request.body.as_str().unwrap_or_default();
+    Url::parse(&format!("http://localhost/?{}", body))
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
+
+/// A config whose current workspace "mock" is signed in with plaintext tokens
+fn write_oauth_config(env: &TestEnv, server: &MockServer, expires_at: &str) {
+    fs::create_dir_all(env.config_dir()).unwrap();
+    fs::write(
+        env.config_dir().join("config.toml"),
+        format!(
+            r#"current = "mock"
+
+[workspaces.mock]
+api_url = "{}"
+
+[workspaces.mock.oauth]
+client_id = "test-client"
+scopes = ["read", "write"]
+expires_at = "{}"
+token_store = "plaintext"
+
+[workspaces.mock.oauth.tokens]
+access_token = "oauth-access-1"
+refresh_token = "oauth-refresh-1"
+"#,
+            server.graphql_url(),
+            expires_at
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_auth_login_with_pkce() {
+    let server = MockServer::builder()
+        .respond("grant_type=authorization_code", |_| {
+            token_response("oauth-access-1", "oauth-refresh-1")
+        })
+        .on("viewer", viewer_response())
+        .on("teams(", json!({ "teams": { "nodes": [] } }))
+        .start();
+    let env = TestEnv::with_server(&server)
+        .env("LINEAR_OAUTH_URL", &server.url)
+        .env("LINEAR_CREDENTIAL_STORE", "plaintext");
+
+    let mut child = env
+        .command(&[
+            "auth",
+            "login",
+            "--client-id",
+            "test-client",
+            "--no-browser",
+            "--port",
+            "0",
+        ])
+        .env_remove("LINEAR_API_KEY")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let mut line = String::new();
+    while !line.contains("/oauth/authorize") {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "no authorize URL");
+    }
+    let authorize = Url::parse(line.trim()).unwrap();
+    let params: HashMap<String, String> = authorize.query_pairs().into_owned().collect();
+    assert_eq!(params["client_id"], "test-client");
+    assert_eq!(params["scope"], "read,write");
+    assert_eq!(params["code_challenge_method"], "S256");
+
+    // Play the browser: the authorization server redirects back with a code
+    let redirect = Url::parse(&params["redirect_uri"]).unwrap();
+    let mut stream =
+        TcpStream::connect((redirect.host_str().unwrap(), redirect.port().unwrap())).unwrap();
+    write!(
+        stream,
+        "GET {}?code=auth-code-1&state={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        redirect.path(),
+        params["state"]
+    )
+    .unwrap();
+    let mut page = String::new();
+    stream.read_to_string(&mut page).unwrap();
+    assert!(page.starts_with("HTTP/1.1 200"), "{}", page);
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    let status = child.wait().unwrap();
+    let mut stderr = String::new();
+    child.stderr.unwrap().read_to_string(&mut stderr).unwrap();
+    assert!(status.success(), "stderr: {}", stderr);
+    assert!(rest.contains("Signed in to Acme as Alice"), "{}", rest);
+
+    // The token request proves possession of the PKCE verifier
+    let token_request = server
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/oauth/token")
+        .unwrap();
+    let token_form = form(&token_request);
+    assert_eq!(token_form["grant_type"], "authorization_code");
+    assert_eq!(token_form["code"], "auth-code-1");
+    assert_eq!(token_form["client_id"], "test-client");
+    assert_eq!(token_form["redirect_uri"], params["redirect_uri"]);
+    assert_eq!(
+        BASE64_URL.encode(Sha256::digest(token_form["code_verifier"].as_bytes())),
+        params["code_challenge"]
+    );
+
+    // Later commands send the token as a Bearer header
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let teams = server.requests_matching("teams(");
+    assert_eq!(teams[0].headers["authorization"], "Bearer oauth-access-1");
+}
+
+#[test]
+fn test_auth_login_rejects_wrong_state() {
+    let server = MockServer::builder().start();
+    let env = TestEnv::with_server(&server).env("LINEAR_OAUTH_URL", &server.url);
+
+    let mut child = env
+        .command(&[
+            "auth",
+            "login",
+            "--client-id",
+            "test-client",
+            "--no-browser",
+            "--port",
+            "0",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("/oauth/authorize") {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "no authorize URL");
+    }
+    let authorize = Url::parse(line.trim()).unwrap();
+    let params: HashMap<String, String> = authorize.query_pairs().into_owned().collect();
+    let redirect = Url::parse(&params["redirect_uri"]).unwrap();
+
+    let mut stream =
+        TcpStream::connect((redirect.host_str().unwrap(), redirect.port().unwrap())).unwrap();
+    write!(
+        stream,
+        "GET /callback?code=stolen&state=forged HTTP/1.1\r\nHost: localhost\r\n\r\n"
+    )
+    .unwrap();
+    let mut page = String::new();
+    stream.read_to_string(&mut page).unwrap();
+    assert!(page.starts_with("HTTP/1.1 400"), "{}", page);
+
+    let out = child.wait_with_output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("wrong state"));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_expired_token_is_refreshed() {
+    let server = MockServer::builder()
+        .respond("grant_type=refresh_token", |_| {
+            token_response("oauth-access-2", "oauth-refresh-2")
+        })
+        .on("teams(", json!({ "teams": { "nodes": [] } }))
+        .start();
+    let env = TestEnv::new().env("LINEAR_OAUTH_URL", &server.url);
+    write_oauth_config(&env, &server, "2020-01-01T00:00:00Z");
+
+    let out = env
+        .command(&["--output", "json", "teams", "list"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/oauth/token");
+    let refresh = form(&requests[0]);
+    assert_eq!(refresh["refresh_token"], "oauth-refresh-1");
+    assert_eq!(refresh["client_id"], "test-client");
+    assert_eq!(
+        requests[1].headers["authorization"],
+        "Bearer oauth-access-2"
+    );
+
+    // The rotated tokens are saved for next time
+    let config = fs::read_to_string(env.config_dir().join("config.toml")).unwrap();
+    assert!(config.contains("oauth-refresh-2"), "{}", config);
+    assert!(!config.contains("oauth-access-1"));
+}
+
+#[test]
+fn test_rejected_token_is_refreshed_once() {
+    let refreshes = std::sync::Arc::new(AtomicUsize::new(0));
+    let counter = refreshes.clone();
+    let server = MockServer::builder()
+        .respond("grant_type=refresh_token", move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            token_response("oauth-access-2", "oauth-refresh-2")
+        })
+        .respond("viewer", |request| {
+            if request.headers["authorization"] == "Bearer oauth-access-2" {
+                MockResponse::ok(json!({ "data": viewer_response() }))
+            } else {
+                MockResponse::status(
+                    401,
+                    json!({ "errors": [{
+                        "message": "Authentication required, not authenticated",
+                        "extensions": { "code": "AUTHENTICATION_ERROR" }
+                    }] }),
+                )
+            }
+        })
+        .start();
+    let env = TestEnv::new().env("LINEAR_OAUTH_URL", &server.url);
+    write_oauth_config(&env, &server, "2999-01-01T00:00:00Z");
+
+    let out = env
+        .command(&["auth", "status", "--output", "json"])
+        .env_remove("LINEAR_API_KEY")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+
+    let status: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(status["method"], "oauth");
+    assert_eq!(status["user"]["name"], "Alice");
+    assert_eq!(status["organization"]["name"], "Acme");
+}
+
+#[test]
+fn test_auth_logout_revokes_and_forgets_the_token() {
+    let server = MockServer::builder()
+        .respond("token_type_hint", |_| MockResponse::ok(json!({})))
+        .start();
+    let env = TestEnv::new().env("LINEAR_OAUTH_URL", &server.url);
+    write_oauth_config(&env, &server, "2999-01-01T00:00:00Z");
+
+    let out = env.run(&["auth", "logout"]);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert!(out.stdout.contains("Signed out of workspace 'mock'"));
+
+    let revoke = &server.requests()[0];
+    assert_eq!(revoke.path, "/oauth/revoke");
+    assert_eq!(revoke.headers["authorization"], "Bearer oauth-access-1");
+    assert_eq!(form(revoke)["token"], "oauth-access-1");
+
+    let config = fs::read_to_string(env.config_dir().join("config.toml")).unwrap();
+    assert!(!config.contains("oauth"), "{}", config);
+
+    let out = env.run(&["auth", "logout"]);
+    assert_eq!(out.code, 0);
+    assert!(out.stdout.contains("has no OAuth login"));
+}
@@ -0,0 +1,96 @@
+//! Every GraphQL document embedded in `src/` is validated against the vendored
+//! schema snapshot in `schema/linear.graphql`, so a query that no longer
+//! matches Linear's API fails here instead of at runtime.
+//!
+//! Refresh the snapshot with `linear schema fetch && linear schema show > schema/linear.graphql`.
+
+use apollo_compiler::{ExecutableDocument, Schema};
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = include_str!("../schema/linear.graphql");
+
+/// A GraphQL document found in a source file
+struct Embedded {
+    location: String,
+    source: String,
+}
+
+fn rust_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// Raw string literals that hold a query or mutation, outside test modules
+fn embedded_documents() -> Vec<Embedded> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut files = Vec::new();
+    rust_files(&root.join("src"), &mut files);
+    files.sort();
+
+    let mut documents = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file).unwrap();
+        let code = text.split("#[cfg(test)]").next().unwrap();
+        let mut rest = code;
+        let mut offset = 0;
+        while let Some(start) = rest.find("r#\"") {
+            let body_start = start + 3;
+            let Some(len) = rest[body_start..].find("\"#") else {
+                break;
+            };
+            let body = &rest[body_start..body_start + len];
+            let trimmed = body.trim_start();
+            if trimmed.starts_with("query") || trimmed.starts_with("mutation") {
+                let line = code[..offset + start].lines().count() + 1;
+                documents.push(Embedded {
+                    location: format!("{}:{}", file.strip_prefix(root).unwrap().display(), line),
+                    source: body.to_string(),
+                });
+            }
+            offset += body_start + len + 2;
+            rest = &rest[body_start + len + 2..];
+        }
+    }
+    documents
+}
+
+#[test]
+fn test_schema_snapshot_is_valid() {
+    Schema::parse_and_validate(SCHEMA, "schema/linear.graphql")
+        .map_err(|e| e.errors.to_string())
+        .unwrap();
+}
+
+#[test]
+fn test_embedded_queries_match_schema() {
+    let schema = Schema::parse_and_validate(SCHEMA, "schema/linear.graphql").unwrap();
+    let documents = embedded_documents();
+    assert!(
+        documents.len() > 50,
+        "only found {} embedded documents; has the extraction broken?",
+        documents.len()
+    );
+
+    let failures: Vec<String> = documents
+        .iter()
+        .filter_map(|doc| {
+            ExecutableDocument::parse_and_validate(&schema, &doc.source, &doc.location)
+                .err()
+                .map(|e| format!("{}\n{}", doc.location, e.errors))
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} embedded GraphQL documents don't match schema/linear.graphql:\n\n{}",
+        failures.len(),
+        documents.len(),
+        failures.join("\n")
+    );
+}